@@ -0,0 +1,3 @@
+ALTER TABLE questions
+  DROP COLUMN max_selections,
+  DROP COLUMN min_selections;
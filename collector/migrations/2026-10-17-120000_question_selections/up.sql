@@ -0,0 +1,5 @@
+-- Cached copy of the number of candidates a voter can select:
+--   min_selections <= k <= max_selections
+ALTER TABLE questions
+  ADD COLUMN min_selections BIGINT NOT NULL DEFAULT 1,
+  ADD COLUMN max_selections BIGINT NOT NULL DEFAULT 1;
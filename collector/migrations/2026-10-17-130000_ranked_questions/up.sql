@@ -0,0 +1,4 @@
+-- Cached copy of the question type from the API server (See QuestionType enum)
+--   Choice = 0, Ranked = 1
+ALTER TABLE questions
+  ADD COLUMN question_type INTEGER NOT NULL DEFAULT 0;
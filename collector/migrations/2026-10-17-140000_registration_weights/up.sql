@@ -0,0 +1,4 @@
+-- Cached copy of the voter weights from the API server
+--   Only counter elections can have a weight other than 1
+ALTER TABLE registrations
+  ADD COLUMN weight BIGINT NOT NULL DEFAULT 1;
@@ -0,0 +1,3 @@
+ALTER TABLE elections
+  DROP COLUMN ballot_encoding,
+  DROP COLUMN counter_radix;
@@ -0,0 +1,5 @@
+-- Cached copy of the ballot encoding from the API server
+--   Counter questions use a single base-counter_radix digit for every candidate
+ALTER TABLE elections
+  ADD COLUMN ballot_encoding INTEGER NOT NULL DEFAULT 0,
+  ADD COLUMN counter_radix BIGINT NOT NULL DEFAULT 2;
@@ -0,0 +1,14 @@
+-- Cached copy of the group for each question from the API server
+--   Every question has its own group g^x (mod p), sized for the voting vector of that question
+ALTER TABLE questions
+  ADD COLUMN generator NUMERIC NOT NULL DEFAULT 0,
+  ADD COLUMN prime NUMERIC NOT NULL DEFAULT 0;
+
+UPDATE questions
+  SET generator = elections.generator, prime = elections.prime
+  FROM elections
+  WHERE elections.id = questions.election_id;
+
+ALTER TABLE elections
+  DROP COLUMN generator,
+  DROP COLUMN prime;
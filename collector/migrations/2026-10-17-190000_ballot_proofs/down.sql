@@ -0,0 +1,3 @@
+ALTER TABLE registrations
+  DROP COLUMN forward_blinding,
+  DROP COLUMN reverse_blinding;
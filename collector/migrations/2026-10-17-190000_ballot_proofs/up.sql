@@ -0,0 +1,5 @@
+-- Blinding factors for the share commitments D = g^(S~) * h^(rho)
+--   Only generated for elections that use non-interactive ballot proofs
+ALTER TABLE registrations
+  ADD COLUMN forward_blinding NUMERIC,
+  ADD COLUMN reverse_blinding NUMERIC;
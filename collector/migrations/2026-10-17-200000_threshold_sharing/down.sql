@@ -0,0 +1,3 @@
+ALTER TABLE registrations
+  DROP COLUMN forward_threshold_shares,
+  DROP COLUMN reverse_threshold_shares;
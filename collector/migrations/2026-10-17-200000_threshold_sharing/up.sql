@@ -0,0 +1,5 @@
+-- Sum of the Shamir shares of S~ - S dealt to this collector by every collector
+--   Only generated for elections that use (t,n)-threshold secret sharing
+ALTER TABLE registrations
+  ADD COLUMN forward_threshold_shares NUMERIC,
+  ADD COLUMN reverse_threshold_shares NUMERIC;
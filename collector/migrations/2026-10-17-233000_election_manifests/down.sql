@@ -0,0 +1,5 @@
+ALTER TABLE elections
+  DROP COLUMN threshold;
+
+ALTER TABLE elections
+  DROP COLUMN manifest_hash;
@@ -0,0 +1,9 @@
+-- Hash of the signed election manifest that was accepted when the election was created (Base64 SHA-256)
+--   NULL = Election created before manifests were signed
+ALTER TABLE elections
+  ADD COLUMN manifest_hash TEXT;
+
+-- Threshold t from the signed manifest, so the mediator cannot ask for threshold shares with a different t
+--   NULL = Election does not use threshold secret sharing
+ALTER TABLE elections
+  ADD COLUMN threshold BIGINT;
@@ -0,0 +1,2 @@
+ALTER TABLE elections
+  DROP COLUMN collectors;
@@ -0,0 +1,5 @@
+-- Collector IDs in index order from the signed manifest, so every verification key can be checked against
+--   the identity key pinned for that collector
+--   Empty = Election created before the collectors were stored
+ALTER TABLE elections
+  ADD COLUMN collectors UUID[] NOT NULL DEFAULT '{}';
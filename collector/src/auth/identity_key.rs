@@ -0,0 +1,96 @@
+use openssl::pkey::{Id, PKey, Private, Public};
+use openssl::sign::{Signer, Verifier};
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+
+use crate::protocol::SignedManifest;
+
+///
+/// Long-term Ed25519 key that identifies the collector
+///
+/// The public key is pinned by the mediator when the collector registers and by the other collectors in their
+///   configuration, and every websocket message sent during ballot verification is signed with the private key
+///
+#[derive(Clone)]
+pub struct IdentityKey {
+  private_key: PKey<Private>,
+}
+
+impl IdentityKey {
+  /// Load the private key from a PEM file, or generate a new key if the file does not exist yet
+  pub fn load_or_generate(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+    let path = path.as_ref();
+    if path.exists() {
+      let pem = fs::read(path)?;
+      let private_key = PKey::private_key_from_pem(&pem)?;
+      if private_key.id() != Id::ED25519 {
+        return Err(anyhow::anyhow!("{} is not an Ed25519 private key", path.display()));
+      }
+
+      return Ok(Self { private_key });
+    }
+
+    // Only the collector should be able to read the private key
+    log::info!("Generating a new identity key in {}", path.display());
+    let private_key = PKey::generate_ed25519()?;
+    fs::write(path, private_key.private_key_to_pem_pkcs8()?)?;
+    fs::set_permissions(path, fs::Permissions::from_mode(0o600))?;
+
+    Ok(Self { private_key })
+  }
+
+  /// Public key to publish, encoded as base64 DER
+  pub fn public_key(&self) -> String {
+    // Converting a valid key to DER should never fail
+    let der = self
+      .private_key
+      .public_key_to_der()
+      .expect("Error: Invalid identity key");
+    base64::encode(der)
+  }
+
+  /// Sign the message, returning the base64 signature
+  pub fn sign(&self, message: &[u8]) -> String {
+    // Ed25519 signs the whole message at once, and should never fail with a valid key
+    let mut signer = Signer::new_without_digest(&self.private_key).expect("Error: Invalid identity key");
+    let signature = signer
+      .sign_oneshot_to_vec(message)
+      .expect("Error: Failed to sign message");
+    base64::encode(signature)
+  }
+
+  /// Verify a signature from any collector using the base64 DER public key
+  ///   Returns false if either the public key or the signature is not valid
+  pub fn verify(public_key: &str, message: &[u8], signature: &str) -> bool {
+    let public_key = match parse_public_key(public_key) {
+      Some(public_key) => public_key,
+      None => return false,
+    };
+    let signature = match base64::decode(signature) {
+      Ok(signature) => signature,
+      Err(_) => return false,
+    };
+
+    Verifier::new_without_digest(&public_key)
+      .and_then(|mut verifier| verifier.verify_oneshot(&signature, message))
+      .unwrap_or(false)
+  }
+
+  /// Check the Ed25519 signature from the API server over the manifest hash
+  pub fn verify_manifest(server_public_key: &str, manifest: &SignedManifest) -> bool {
+    Self::verify(
+      server_public_key,
+      &manifest.manifest.compute_hash(),
+      &manifest.signature,
+    )
+  }
+}
+
+/// Parse a base64 DER public key, making sure it is an Ed25519 key
+fn parse_public_key(public_key: &str) -> Option<PKey<Public>> {
+  let der = base64::decode(public_key).ok()?;
+  PKey::public_key_from_der(&der)
+    .ok()
+    .filter(|public_key| public_key.id() == Id::ED25519)
+}
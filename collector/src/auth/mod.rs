@@ -3,12 +3,14 @@
 //
 pub mod audience;
 mod constants;
+mod identity_key;
 mod jwt_secret;
 mod jwt_token;
 mod permission;
 
 pub use audience::Audience;
 pub use constants::*;
+pub use identity_key::IdentityKey;
 pub use jwt_secret::JWTSecret;
 pub use jwt_token::*;
 pub use permission::{Permission, DEFAULT_PERMISSIONS};
@@ -2,6 +2,7 @@
 // Environment configuration functions
 //
 use dotenv::dotenv;
+use std::collections::HashMap;
 use std::env;
 use std::path::PathBuf;
 use std::str::FromStr;
@@ -62,6 +63,10 @@ pub struct Opt {
   #[structopt(long)]
   database_url: Option<String>,
 
+  /// Path for the Ed25519 identity key file, which is created if it does not exist [Default: "identity_c1.pem", ...]
+  #[structopt(long, parse(from_os_str))]
+  identity_key_file: Option<PathBuf>,
+
   /// JSON Web Token Secret
   #[structopt(short = "s", long, env, hide_env_values = true, default_value = DEFAULT_JWT_SECRET, hide_default_value(true))]
   jwt_secret: String,
@@ -73,6 +78,14 @@ pub struct Opt {
   /// Shared collector secret used to verify public keys
   #[structopt(long, env)]
   collector_secret: String,
+
+  /// Ed25519 public key of the API server (Base64 DER), used to verify the election manifests
+  #[structopt(long, env)]
+  manifest_public_key: String,
+
+  /// Identity keys of the other collectors (Base64 DER), as comma-separated "<collector id>=<public key>" pairs
+  #[structopt(long, env)]
+  collector_identity_keys: Option<String>,
 }
 
 impl Opt {
@@ -109,9 +122,17 @@ impl Opt {
       env::set_var(c.env_prefix("DATABASE_URL"), database_url);
     }
 
+    if let Some(ref identity_key_file) = self.identity_key_file {
+      env::set_var(c.env_prefix("IDENTITY_KEY_FILE"), identity_key_file);
+    }
+
     env::set_var("JWT_SECRET", &self.jwt_secret);
     env::set_var("MEDIATOR_URL", &self.mediator_url);
     env::set_var("COLLECTOR_SECRET", &self.collector_secret);
+    env::set_var("MANIFEST_PUBLIC_KEY", &self.manifest_public_key);
+    if let Some(ref collector_identity_keys) = self.collector_identity_keys {
+      env::set_var("COLLECTOR_IDENTITY_KEYS", collector_identity_keys);
+    }
   }
 }
 
@@ -192,6 +213,14 @@ pub fn get_database_url() -> Option<String> {
   env::var(c.env_prefix("DATABASE_URL")).ok()
 }
 
+//
+// Long-term identity key
+//
+pub fn get_identity_key_file() -> String {
+  let c = get_collector();
+  env::var(c.env_prefix("IDENTITY_KEY_FILE")).unwrap_or_else(|_| format!("identity_c{}.pem", c))
+}
+
 //
 // Authentication System Secrets
 //
@@ -209,3 +238,26 @@ pub fn get_mediator_url() -> Option<String> {
 pub fn get_collector_secret() -> String {
   env::var("COLLECTOR_SECRET").unwrap_or_else(|_| DEFAULT_COLLECTOR_SECRET.to_string())
 }
+
+///
+/// Identity keys pinned for the other collectors, by collector ID
+///   Entries without a valid collector ID are skipped, so that collector has no pinned key
+///
+pub fn get_collector_identity_keys() -> HashMap<Uuid, String> {
+  env::var("COLLECTOR_IDENTITY_KEYS")
+    .unwrap_or_default()
+    .split(',')
+    .filter_map(|entry| {
+      // Base64 keys can end with '=' padding, so only split at the first one
+      let (id, public_key) = entry.split_once('=')?;
+      Some((id.trim().parse().ok()?, public_key.trim().to_string()))
+    })
+    .collect()
+}
+
+//
+// API Server
+//
+pub fn get_manifest_public_key() -> Option<String> {
+  env::var("MANIFEST_PUBLIC_KEY").ok()
+}
@@ -32,6 +32,19 @@ pub enum ServiceError {
     question_id: Option<Uuid>,
   },
   VerificationError(WebsocketError),
+  ThresholdSharesInvalid {
+    election_id: Uuid,
+    question_id: Uuid,
+  },
+  ShuffleProofInvalid {
+    election_id: Uuid,
+  },
+  LocationKeyInvalid {
+    election_id: Uuid,
+  },
+  ManifestInvalid {
+    election_id: Uuid,
+  },
 }
 
 impl ServiceError {
@@ -148,6 +161,36 @@ impl ServiceError {
         GlobalErrorCode::VerificationError,
         format!("{:?}", error),
       ),
+
+      ServiceError::ThresholdSharesInvalid {
+        election_id,
+        question_id,
+      } => ErrorResponse::new(
+        StatusCode::BAD_REQUEST,
+        "Threshold shares do not match the registered users".into(),
+        GlobalErrorCode::ThresholdSharesInvalid,
+        format!("Election ID: {}, Question ID: {}", election_id, question_id),
+      ),
+      ServiceError::ShuffleProofInvalid { election_id } => ErrorResponse::new(
+        StatusCode::BAD_REQUEST,
+        "Location shuffle from the previous step could not be verified".into(),
+        GlobalErrorCode::ShuffleProofInvalid,
+        format!("Election ID: {}", election_id),
+      ),
+
+      ServiceError::LocationKeyInvalid { election_id } => ErrorResponse::new(
+        StatusCode::BAD_REQUEST,
+        "Only the key holder decrypts a round of location anonymization, and it never shuffles that round".into(),
+        GlobalErrorCode::LocationKeyInvalid,
+        format!("Election ID: {}", election_id),
+      ),
+
+      ServiceError::ManifestInvalid { election_id } => ErrorResponse::new(
+        StatusCode::BAD_REQUEST,
+        "Election parameters do not match the manifest signed by the API server".into(),
+        GlobalErrorCode::ManifestInvalid,
+        format!("Election ID: {}", election_id),
+      ),
     }
   }
 }
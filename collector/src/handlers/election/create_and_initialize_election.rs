@@ -1,19 +1,24 @@
 use actix_web::{web, HttpResponse};
-use curv_kzen::{arithmetic::BitManipulation, BigInt};
+use curv_kzen::arithmetic::{BitManipulation, Modulo, Samplable};
+use curv_kzen::BigInt;
 use diesel::prelude::*;
 use kzen_paillier::*;
-use rand::{seq::SliceRandom, thread_rng};
 use serde::{Deserialize, Serialize};
 use uuid_b64::UuidB64 as Uuid;
 use validator::{Validate, ValidationError};
 
-use crate::auth::MediatorToken;
+use crate::auth::{IdentityKey, MediatorToken};
+use crate::config;
 use crate::db::DbConnection;
 use crate::errors::ServiceError;
-use crate::models::{Election, EncryptedLocation, Question, Registration};
-use crate::protocol::{location_anonymization as loc_anon, SharesMatrix};
+use crate::models::{
+  count_choice_selection_sets, BallotEncoding, CommitmentGroup, Election, EncryptedLocation, Question, QuestionType,
+  Registration, VerificationMode, MAX_CHOICE_SELECTION_SETS, MAX_RANKED_CANDIDATES,
+};
+use crate::protocol::shuffle_proof::{self, ShuffleProof};
+use crate::protocol::{Group, SharesMatrix, SignedManifest};
 use crate::utils::ConvertBigInt;
-use crate::views::election::CreateElectionResponse;
+use crate::views::election::{CreateElectionResponse, QuestionCancelationCommitments, QuestionShareCommitments};
 
 #[derive(Debug, Serialize, Deserialize, Validate)]
 #[validate(schema(function = "validate_struct", skip_on_field_errors = false))]
@@ -21,18 +26,23 @@ use crate::views::election::CreateElectionResponse;
 pub struct CreateElectionData {
   id: Uuid,
 
-  #[serde(with = "kzen_paillier::serialize::bigint")]
-  generator: BigInt,
-
-  #[serde(with = "kzen_paillier::serialize::bigint")]
-  prime: BigInt,
-
   #[validate(length(min = 1))]
   #[validate]
   questions: Vec<CreateElectionQuestion>,
 
   #[validate(length(min = 2))]
   registered_users: Vec<Uuid>,
+  registered_weights: Vec<i64>,
+
+  ballot_encoding: BallotEncoding,
+  #[validate(range(min = 2))]
+  counter_radix: i64,
+  commitment_group: CommitmentGroup,
+  verification_mode: VerificationMode,
+
+  /// Threshold t for the (t,n)-threshold cancelation shares, which is checked again when we deal them
+  #[serde(default)]
+  threshold: Option<usize>,
 
   #[validate(range(min = 2))]
   num_collectors: usize,
@@ -44,15 +54,96 @@ pub struct CreateElectionData {
   encrypted_locations: Vec<BigInt>,
   #[serde(with = "crate::utils::serialize_option_bigint")]
   n: Option<BigInt>,
+
+  /// Proof for the previous step of location anonymization
+  ///   Not provided when the collectors shuffle the locations between themselves
+  #[serde(default)]
+  location_shuffle: Option<LocationShuffle>,
+  #[serde(default)]
+  distributed_locations: bool,
+
+  /// Every parameter above must match this manifest from the API server
+  manifest: SignedManifest,
+}
+
+///
+/// Previous step of location anonymization, which must be verified before we continue the chain
+///
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocationShuffle {
+  /// Paillier public key n used to encrypt the locations
+  #[serde(with = "kzen_paillier::serialize::bigint")]
+  public_key: BigInt,
+
+  /// Encrypted locations before and after the previous step (Input is empty for the first collector)
+  #[serde(with = "kzen_paillier::serialize::vecbigint")]
+  input: Vec<BigInt>,
+  #[serde(with = "kzen_paillier::serialize::vecbigint")]
+  output: Vec<BigInt>,
+  proof: ShuffleProof,
+
+  /// Randomness ρ_j of every ciphertext in the output, so the last collector can check the decryption
+  ///   Empty for every other collector
+  #[serde(default, with = "kzen_paillier::serialize::vecbigint")]
+  decryption_randomness: Vec<BigInt>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Validate)]
+#[validate(schema(function = "validate_question", skip_on_field_errors = false))]
 #[serde(rename_all = "camelCase")]
 pub struct CreateElectionQuestion {
   id: Uuid,
 
   #[validate(range(min = 2))]
   num_candidates: i64,
+  question_type: QuestionType,
+
+  #[validate(range(min = 1))]
+  min_selections: i64,
+  max_selections: i64,
+
+  #[serde(with = "kzen_paillier::serialize::bigint")]
+  generator: BigInt,
+
+  #[serde(with = "kzen_paillier::serialize::bigint")]
+  prime: BigInt,
+}
+
+///
+/// Make sure the question type is valid for the number of candidates:
+///   Choice questions must have 1 <= min_selections <= max_selections <= num_candidates,
+///     and at most MAX_CHOICE_SELECTION_SETS sets of selections
+///   Ranked questions must have num_candidates <= MAX_RANKED_CANDIDATES
+///
+fn validate_question(input: &CreateElectionQuestion) -> Result<(), ValidationError> {
+  match input.question_type {
+    QuestionType::Choice => {
+      if input.max_selections < input.min_selections || input.max_selections > input.num_candidates {
+        return Err(ValidationError::new(
+          "max_selections must be between min_selections and num_candidates",
+        ));
+      }
+
+      if count_choice_selection_sets(input.num_candidates, input.min_selections, input.max_selections)
+        > MAX_CHOICE_SELECTION_SETS
+      {
+        return Err(ValidationError::new(
+          "choice questions cannot have more than 5040 sets of selections",
+        ));
+      }
+    }
+
+    QuestionType::Ranked => {
+      if input.num_candidates > MAX_RANKED_CANDIDATES {
+        return Err(ValidationError::new(
+          "ranked questions cannot have more than 7 candidates",
+        ));
+      }
+    }
+  }
+
+  Ok(())
 }
 
 ///
@@ -60,6 +151,8 @@ pub struct CreateElectionQuestion {
 ///   1. The collector index is within the range for the number of collectors
 ///   2. There are enough users for the election
 ///   3. There is an encrypted location entry for every registered user
+///   4. There is a weight for every registered user, and only counter questions have weights
+///   5. The counter digits can store the total weight of every registered user
 ///
 fn validate_struct(input: &CreateElectionData) -> Result<(), ValidationError> {
   if input.collector_index >= input.num_collectors {
@@ -80,6 +173,33 @@ fn validate_struct(input: &CreateElectionData) -> Result<(), ValidationError> {
     ));
   }
 
+  if input.registered_users.len() != input.registered_weights.len() {
+    return Err(ValidationError::new(
+      "length(registered_users) not equal to length(registered_weights)",
+    ));
+  }
+
+  if input.registered_weights.iter().any(|w| *w < 1) {
+    return Err(ValidationError::new("registered_weights must be at least 1"));
+  }
+
+  // One-hot chunks are decoded one voter at a time, so a weight would identify the voter
+  let only_counter = input
+    .questions
+    .iter()
+    .all(|q| input.ballot_encoding.for_question(q.question_type) == BallotEncoding::Counter);
+  if !only_counter && input.registered_weights.iter().any(|w| *w != 1) {
+    return Err(ValidationError::new(
+      "registered_weights must all be 1 unless every question uses the counter encoding",
+    ));
+  }
+
+  if input.registered_weights.iter().sum::<i64>() >= input.counter_radix {
+    return Err(ValidationError::new(
+      "counter_radix must be larger than the sum of registered_weights",
+    ));
+  }
+
   Ok(())
 }
 
@@ -93,12 +213,28 @@ pub async fn create_and_initialize_election(
 
   let mut data = data.into_inner();
 
+  // The mediator forwards the election parameters, so they must match the manifest signed by the API server
+  log::debug!("Verify the election manifest");
+  if !verify_manifest(&data) {
+    return Err(ServiceError::ManifestInvalid { election_id: data.id });
+  }
+  let manifest_hash = base64::encode(data.manifest.manifest.compute_hash());
+
+  // Check the previous step of location anonymization before we build on top of it
+  //   A duplicated or replaced location would corrupt the tally without anybody noticing
+  if !data.distributed_locations {
+    log::debug!("Verify the previous step of location anonymization");
+    if !verify_location_shuffle(&data) {
+      return Err(ServiceError::ShuffleProofInvalid { election_id: data.id });
+    }
+  }
+
   // Handle the encrypted anonymous locations protocol
   log::debug!(
     "Computing value r{} for encrypted locations",
     ((data.collector_index + 1) % data.num_collectors) + 1
   );
-  let (encrypted_locations, encryption_result) = handle_encrypted_location(
+  let (encrypted_locations, encryption_result, shuffle_proof) = handle_encrypted_location(
     data.encrypted_locations.drain(..).collect(),
     &data.n,
     data.collector_index == 0,
@@ -106,14 +242,116 @@ pub async fn create_and_initialize_election(
 
   // Create the election if it does not already exist
   //  Otherwise, we update the locations on the existing election
-  if let Some(election) = Election::find_optional(&data.id, &conn)? {
-    update_encrypted_locations(&data, &election, &encrypted_locations, &conn)?;
+  let (share_commitments, cancelation_commitments) = if let Some(election) = Election::find_optional(&data.id, &conn)? {
+    update_encrypted_locations(&data, &election, &encrypted_locations, manifest_hash.clone(), &conn)?
   } else {
-    create_new_election(&data, &encrypted_locations, &conn)?;
-  }
+    create_new_election(&data, &encrypted_locations, manifest_hash.clone(), &conn)?
+  };
+
+  // Other collectors need our public key to send us threshold shares
+  let public_key = Election::find_resource(&data.id, &conn)?.get_public_key();
 
   // Done!
-  Ok(HttpResponse::Ok().json(CreateElectionResponse { encryption_result }))
+  Ok(HttpResponse::Ok().json(CreateElectionResponse {
+    encryption_result,
+    share_commitments,
+    cancelation_commitments,
+    public_key,
+    shuffle_proof,
+    manifest_hash,
+  }))
+}
+
+///
+/// Check the signature on the election manifest, then make sure the mediator sent us the same parameters
+///
+/// The manifest also lists the collectors in order, so our index must point to our own ID
+///
+fn verify_manifest(data: &CreateElectionData) -> bool {
+  let server_public_key = match config::get_manifest_public_key() {
+    Some(public_key) => public_key,
+    None => return false,
+  };
+  if !IdentityKey::verify_manifest(&server_public_key, &data.manifest) {
+    return false;
+  }
+
+  let manifest = &data.manifest.manifest;
+  let valid_questions = manifest.questions.len() == data.questions.len()
+    && manifest
+      .questions
+      .iter()
+      .zip(data.questions.iter())
+      .all(|(expected, question)| {
+        expected.id == question.id
+          && expected.num_candidates == question.num_candidates
+          && expected.question_type == question.question_type
+          && expected.min_selections == question.min_selections
+          && expected.max_selections == question.max_selections
+          && expected.generator == question.generator
+          && expected.prime == question.prime
+      });
+
+  manifest.election_id == data.id
+    && manifest.collectors.len() == data.num_collectors
+    && manifest.collectors.get(data.collector_index) == config::get_id().as_ref()
+    && manifest.registered_users == data.registered_users
+    && manifest.registered_weights == data.registered_weights
+    && manifest.ballot_encoding == data.ballot_encoding
+    && manifest.counter_radix == data.counter_radix
+    && manifest.commitment_group == data.commitment_group
+    && manifest.verification_mode == data.verification_mode
+    && manifest.threshold == data.threshold
+    && manifest.distributed_locations == data.distributed_locations
+    && valid_questions
+}
+
+///
+/// Verify the proof for the previous step of location anonymization
+///
+/// The first collector checks that the mediator only shuffled the locations 0 to N-1,
+///   starting from the trivial encryptions E(i; 1) = 1 + i*n (mod n^2).
+/// Every other collector checks the shuffle and masks from the collector before it.
+/// The last collector also checks that the mediator decrypted every location correctly.
+///
+fn verify_location_shuffle(data: &CreateElectionData) -> bool {
+  let location_shuffle = match data.location_shuffle {
+    Some(ref location_shuffle) => location_shuffle,
+    None => return false,
+  };
+  let n = &location_shuffle.public_key;
+
+  let valid_proof = if data.collector_index == 0 {
+    let input: Vec<BigInt> = (0u64..(data.registered_users.len() as u64))
+      .map(|i| shuffle_proof::encrypt_with_randomness(&BigInt::from(i), &BigInt::from(1), n))
+      .collect();
+    location_shuffle
+      .proof
+      .verify(&input, &location_shuffle.output, n, false)
+  } else {
+    location_shuffle
+      .proof
+      .verify(&location_shuffle.input, &location_shuffle.output, n, true)
+  };
+  if !valid_proof {
+    return false;
+  }
+
+  match data.n {
+    // Step i: We must continue from the same ciphertexts that were proven
+    Some(ref expected_n) => expected_n == n && location_shuffle.output == data.encrypted_locations,
+
+    // Last step: Every decrypted location must re-encrypt to the ciphertext it came from
+    None => {
+      location_shuffle.decryption_randomness.len() == data.encrypted_locations.len()
+        && data
+          .encrypted_locations
+          .iter()
+          .zip(location_shuffle.decryption_randomness.iter())
+          .zip(location_shuffle.output.iter())
+          .all(|((m, rho), c)| m < n && &shuffle_proof::encrypt_with_randomness(m, rho, n) == c)
+    }
+  }
 }
 
 /// Handle the encrypted anonymous location protocol
@@ -124,30 +362,29 @@ pub async fn create_and_initialize_election(
 /// The "should_shuffle" flag should ONLY be set for the first collector
 ///  in the list (index = 0) for the protocol to work correctly
 ///
-/// Returns: (r_i, results)
-///   -"results" is an empty vector if we are at step 3
+/// Returns: (r_i, results, proof)
+///   -"results" is an empty vector and there is no proof if we are at step 3
 fn handle_encrypted_location(
   encrypted_locations: Vec<BigInt>,
   n: &Option<BigInt>,
   should_shuffle: bool,
-) -> (Vec<BigInt>, Vec<BigInt>) {
+) -> (Vec<BigInt>, Vec<BigInt>, Option<ShuffleProof>) {
   if let Some(ref n) = n {
-    // Perform step i on all locations
-    let mut encrypted_locations: Vec<_> = encrypted_locations
-      .into_iter()
-      .map(|l| loc_anon::step_ith(&l, n))
-      .collect();
-
-    // Shuffle the order of locations for the first collector being initialized
+    // Perform step i on all locations: e_xj = e_x{π(j)} * E(-r_j)
+    //  Shuffle the order of locations for the first collector being initialized
     if should_shuffle {
       log::debug!("Shuffling the list of encrypted locations");
-      encrypted_locations.shuffle(&mut thread_rng());
     }
+    let (results, witness) = shuffle_proof::shuffle_and_mask(&encrypted_locations, n, should_shuffle, Some(n));
+
+    // Prove to the next collector that every location was kept
+    log::debug!("Generate proof of shuffle for encrypted locations");
+    let proof = ShuffleProof::prove(&encrypted_locations, &results, n, &witness, true);
 
-    encrypted_locations.into_iter().unzip()
+    (witness.offsets, results, Some(proof))
   } else {
     // We are at step 3, do nothing
-    (encrypted_locations, Vec::new())
+    (encrypted_locations, Vec::new(), None)
   }
 }
 
@@ -157,26 +394,58 @@ fn handle_encrypted_location(
 /// This initializes all shares and parameters within the collector.
 /// It also initializes the user locations.
 ///
+/// Returns the share commitments and cancelation commitments for every question
+///   (Share commitments are empty if the election uses interactive verification)
+///
 fn create_new_election(
   data: &CreateElectionData,
   encrypted_locations: &[BigInt],
+  manifest_hash: String,
   conn: &DbConnection,
-) -> Result<Election, ServiceError> {
+) -> Result<(Vec<QuestionShareCommitments>, Vec<QuestionCancelationCommitments>), ServiceError> {
   // Generate the STPM Paillier cryptosystem key pair
-  // Should have enough bits to store x1 * x2 without any modulus
-  let num_bits = 4 * data.prime.bit_length();
+  // Should have enough bits to store x1 * x2 without any modulus for every question
+  //   The shares are mod the group order, which is fixed for elliptic curves
+  let max_order_bits = data
+    .questions
+    .iter()
+    .map(|q| data.commitment_group.order(&q.prime).bit_length())
+    .max()
+    .unwrap_or(0);
+  let num_bits = 4 * max_order_bits;
   log::debug!("Generate Paillier keypair with {} bits", num_bits);
   let (_, decryption_key) = Paillier::keypair_safe_primes_with_modulus_size(num_bits).keys();
 
-  Ok(conn.get().transaction::<Election, ServiceError, _>(|| {
+  conn.get().transaction::<_, ServiceError, _>(|| {
     // Create the election
-    let election = Election::new(data.id, &data.generator, &data.prime, &decryption_key).insert(&conn)?;
+    let election = Election::new(
+      data.id,
+      &decryption_key,
+      data.ballot_encoding,
+      data.counter_radix,
+      data.commitment_group,
+      data.threshold.map(|threshold| threshold as i64),
+      manifest_hash,
+      data.manifest.manifest.collectors.clone(),
+    )
+    .insert(conn)?;
 
     // Create the questions for the election
     let _questions = data
       .questions
       .iter()
-      .map(|question| Question::new(question.id, election.id, question.num_candidates).insert(&conn))
+      .map(|question| {
+        Question::new(
+          question.id,
+          election.id,
+          question.num_candidates,
+          question.question_type,
+          question.min_selections..=question.max_selections,
+          &question.generator,
+          &question.prime,
+        )
+        .insert(conn)
+      })
       .collect::<Result<Vec<Question>, _>>()?;
 
     // Create all of the encrypted locations
@@ -188,22 +457,28 @@ fn create_new_election(
       .collect::<Result<Vec<_>, _>>()?;
 
     // Finally, create all of the registrations with the user shares
-    generate_question_n_n_secret_shares(data, conn)?;
-
-    Ok(election)
-  })?)
+    generate_question_n_n_secret_shares(data, conn)
+  })
 }
 
 ///
 /// Update the encrypted locations with the new values
 ///
+/// The manifest is replaced as well, since initialization might be retried with different collectors
+///
 fn update_encrypted_locations(
   data: &CreateElectionData,
   election: &Election,
   encrypted_locations: &[BigInt],
+  manifest_hash: String,
   conn: &DbConnection,
-) -> Result<(), ServiceError> {
+) -> Result<(Vec<QuestionShareCommitments>, Vec<QuestionCancelationCommitments>), ServiceError> {
   conn.get().transaction::<_, ServiceError, _>(|| {
+    let mut election = election.clone();
+    election.manifest_hash = Some(manifest_hash);
+    election.collectors = data.manifest.manifest.collectors.clone();
+    election.update(conn)?;
+
     data
       .registered_users
       .iter()
@@ -217,9 +492,7 @@ fn update_encrypted_locations(
       .collect::<Result<Vec<_>, ServiceError>>()?;
 
     // Also regenerate all of the user shares, as the collector order might have changed
-    generate_question_n_n_secret_shares(data, conn)?;
-
-    Ok(())
+    generate_question_n_n_secret_shares(data, conn)
   })
 }
 
@@ -228,32 +501,71 @@ fn update_encrypted_locations(
 ///
 /// This method should be called inside the database transaction to keep everything atomic.
 ///
-fn generate_question_n_n_secret_shares(data: &CreateElectionData, conn: &DbConnection) -> Result<(), ServiceError> {
+/// The cancelation shares are always committed as X_c,i = g^(S~c,i - S_c,i),
+///   so the mediator and the server can check the sums we return when voting is closed
+///
+/// Non-interactive ballot proofs also need a blinded commitment to the same shares for every user:
+///   D_c,i = g^(S~c,i - S_c,i) * h^(rho_c,i), where rho_c,i is a random blinding factor mod the group order
+///   The ballot is p_i = v_i - S_i + S~i, so dividing g^(p_i) by D_i leaves g^(v_i) * h^(-rho_i)
+///
+fn generate_question_n_n_secret_shares(
+  data: &CreateElectionData,
+  conn: &DbConnection,
+) -> Result<(Vec<QuestionShareCommitments>, Vec<QuestionCancelationCommitments>), ServiceError> {
+  let mut share_commitments = Vec::new();
+  let mut cancelation_commitments = Vec::new();
+
   // We create a separate table for each question
   for (question, question_number) in data.questions.iter().zip(1usize..) {
     // Clear the existing list of user registrations (We will replace them)
     Question::delete_all_registrations_from_id(&question.id, &conn)?;
 
     // Generate all of the shares for the voters for each question
-    //  Since our generator g^x (mod p) is order p - 1 (NOT order p), our shares are mod (p-1)
+    //  Our generator g^x (mod p) has the order of the commitment group (NOT order p), so the shares are mod that order
+    //  Every question has its own prime, so the shares use the modulus for that question
     log::debug!("Generate shares for question {}", question_number);
+    let modulus = data.commitment_group.order(&question.prime);
     let forward_shares = SharesMatrix::new(
       data.collector_index,
       data.num_collectors,
       data.registered_users.len(),
-      &data.prime - 1,
+      modulus.clone(),
     );
 
     let reverse_shares = SharesMatrix::new(
       data.collector_index,
       data.num_collectors,
       data.registered_users.len(),
-      &data.prime - 1,
+      modulus.clone(),
     );
 
+    let group = Group::new(
+      data.commitment_group,
+      question.generator.clone(),
+      question.prime.clone(),
+    );
+    // Counter, multi-select, and ranked ballots always carry a ballot proof,
+    //   since sub-protocol 1 cannot bind the forward and reverse vectors to a single valid vote
+    let multiple_selections = match question.question_type {
+      QuestionType::Choice => question.max_selections > 1,
+      QuestionType::Ranked => true,
+    };
+    let with_proofs = data.verification_mode == VerificationMode::NonInteractive
+      || data.ballot_encoding.for_question(question.question_type) == BallotEncoding::Counter
+      || multiple_selections;
+    let mut forward_commitments = Vec::new();
+    let mut reverse_commitments = Vec::new();
+    let mut forward_cancelation_commitments = Vec::new();
+    let mut reverse_cancelation_commitments = Vec::new();
+
     // Now register all of the users!
     log::debug!("Register users for question {}", question_number);
-    for (user_index, user_id) in data.registered_users.iter().enumerate() {
+    for (user_index, (user_id, weight)) in data
+      .registered_users
+      .iter()
+      .zip(data.registered_weights.iter())
+      .enumerate()
+    {
       // S_c,i
       let forward_verification_shares = forward_shares
         .get_verification_shares(user_index)
@@ -267,10 +579,53 @@ fn generate_question_n_n_secret_shares(data: &CreateElectionData, conn: &DbConne
         .to_bigdecimal();
 
       // S~c,i
-      let forward_ballot_shares = forward_shares.get_ballot_shares(user_index).unwrap().to_bigdecimal();
+      let forward_ballot_shares = forward_shares.get_ballot_shares(user_index).unwrap();
 
       // S~c,i'
-      let reverse_ballot_shares = reverse_shares.get_ballot_shares(user_index).unwrap().to_bigdecimal();
+      let reverse_ballot_shares = reverse_shares.get_ballot_shares(user_index).unwrap();
+
+      // g^(S~c,i - S_c,i) and g^(S~c,i' - S_c,i')
+      let forward_cancelation_commitment = group.pow_generator(&BigInt::mod_sub(
+        &forward_ballot_shares,
+        &forward_verification_shares.to_bigint(),
+        &modulus,
+      ));
+      let reverse_cancelation_commitment = group.pow_generator(&BigInt::mod_sub(
+        &reverse_ballot_shares,
+        &reverse_verification_shares.to_bigint(),
+        &modulus,
+      ));
+
+      // rho_c,i and rho_c,i'
+      let (forward_blinding, reverse_blinding) = if with_proofs {
+        let forward_blinding = BigInt::sample_below(&modulus);
+        let reverse_blinding = BigInt::sample_below(&modulus);
+
+        // Our own values are always valid group elements
+        forward_commitments.push(
+          group
+            .mul(
+              &forward_cancelation_commitment,
+              &group.pow_second_generator(&forward_blinding),
+            )
+            .unwrap(),
+        );
+        reverse_commitments.push(
+          group
+            .mul(
+              &reverse_cancelation_commitment,
+              &group.pow_second_generator(&reverse_blinding),
+            )
+            .unwrap(),
+        );
+
+        (Some(forward_blinding), Some(reverse_blinding))
+      } else {
+        (None, None)
+      };
+
+      forward_cancelation_commitments.push(forward_cancelation_commitment);
+      reverse_cancelation_commitments.push(reverse_cancelation_commitment);
 
       Registration {
         user_id: *user_id,
@@ -278,12 +633,31 @@ fn generate_question_n_n_secret_shares(data: &CreateElectionData, conn: &DbConne
         question_id: question.id,
         forward_verification_shares,
         reverse_verification_shares,
-        forward_ballot_shares,
-        reverse_ballot_shares,
+        forward_ballot_shares: forward_ballot_shares.to_bigdecimal(),
+        reverse_ballot_shares: reverse_ballot_shares.to_bigdecimal(),
+        weight: *weight,
+        forward_blinding: forward_blinding.map(|b| b.to_bigdecimal()),
+        reverse_blinding: reverse_blinding.map(|b| b.to_bigdecimal()),
+        forward_threshold_shares: None,
+        reverse_threshold_shares: None,
       }
       .insert(conn)?;
     }
+
+    if with_proofs {
+      share_commitments.push(QuestionShareCommitments {
+        question_id: question.id,
+        forward_commitments,
+        reverse_commitments,
+      });
+    }
+
+    cancelation_commitments.push(QuestionCancelationCommitments {
+      question_id: question.id,
+      forward_commitments: forward_cancelation_commitments,
+      reverse_commitments: reverse_cancelation_commitments,
+    });
   }
 
-  Ok(())
+  Ok((share_commitments, cancelation_commitments))
 }
@@ -0,0 +1,133 @@
+use actix_web::{web, HttpResponse};
+use curv_kzen::{arithmetic::Modulo, BigInt};
+use kzen_paillier::*;
+use serde::{Deserialize, Serialize};
+use uuid_b64::UuidB64 as Uuid;
+use validator::{Validate, ValidationError};
+
+use crate::auth::MediatorToken;
+use crate::db::DbConnection;
+use crate::errors::ServiceError;
+use crate::models::Election;
+use crate::protocol::shamir;
+use crate::utils::ConvertBigInt;
+use crate::views::election::{QuestionThresholdShares, ThresholdDealing};
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+#[validate(schema(function = "validate_struct", skip_on_field_errors = false))]
+#[serde(rename_all = "camelCase")]
+pub struct DealThresholdSharesData {
+  #[validate(range(min = 2))]
+  threshold: usize,
+
+  #[validate(length(min = 2))]
+  registered_users: Vec<Uuid>,
+
+  /// Paillier public key of every collector, in the same order as election initialization
+  #[serde(with = "kzen_paillier::serialize::vecbigint")]
+  #[validate(length(min = 2))]
+  public_keys: Vec<BigInt>,
+}
+
+///
+/// Make sure the threshold is not larger than the number of collectors
+///
+fn validate_struct(input: &DealThresholdSharesData) -> Result<(), ValidationError> {
+  if input.threshold > input.public_keys.len() {
+    return Err(ValidationError::new(
+      "threshold is greater than the number of collectors",
+    ));
+  }
+
+  Ok(())
+}
+
+///
+/// Split the cancelation shares S~c,i - S_c,i of every user into (t,n)-threshold shares
+///
+/// Returns one dealing for each collector, where collector j gets f_c,i(j + 1) encrypted with its public key.
+///   The mediator forwards the dealings, so it never sees any of the shares.
+///
+pub async fn deal_threshold_shares(
+  token: MediatorToken,
+  path: web::Path<Uuid>,
+  data: web::Json<DealThresholdSharesData>,
+  conn: DbConnection,
+) -> Result<HttpResponse, ServiceError> {
+  token.test_can_create_election()?;
+  data.validate()?;
+
+  let election = Election::find_resource(&path, &conn)?;
+
+  // The threshold must be the one from the signed manifest, or the mediator could recover the shares with fewer collectors
+  if election.threshold != Some(data.threshold as i64) {
+    return Err(ServiceError::ManifestInvalid {
+      election_id: election.id,
+    });
+  }
+
+  let num_collectors = data.public_keys.len();
+  let encryption_keys: Vec<EncryptionKey> = data.public_keys.iter().map(EncryptionKey::from).collect();
+
+  let mut dealings: Vec<ThresholdDealing> = (0..num_collectors)
+    .map(|_| ThresholdDealing { questions: Vec::new() })
+    .collect();
+
+  for question in election.get_questions(&conn)? {
+    let modulus = election.commitment_group.order(&question.prime.to_bigint());
+
+    // Shares for every collector, indexed by [collector][user]
+    let mut forward_shares = vec![Vec::new(); num_collectors];
+    let mut reverse_shares = vec![Vec::new(); num_collectors];
+
+    for user_id in data.registered_users.iter() {
+      let registration = election
+        .get_registration(&question.id, user_id, &conn)?
+        .ok_or_else(|| ServiceError::UserNotRegistered {
+          user_id: *user_id,
+          election_id: election.id,
+          question_id: Some(question.id),
+        })?;
+
+      // S~c,i - S_c,i and S~c,i' - S_c,i'
+      let forward_secret = BigInt::mod_sub(
+        &registration.forward_ballot_shares.to_bigint(),
+        &registration.forward_verification_shares.to_bigint(),
+        &modulus,
+      );
+      let reverse_secret = BigInt::mod_sub(
+        &registration.reverse_ballot_shares.to_bigint(),
+        &registration.reverse_verification_shares.to_bigint(),
+        &modulus,
+      );
+
+      let forward_split = shamir::split_secret(&forward_secret, data.threshold, num_collectors, &modulus);
+      let reverse_split = shamir::split_secret(&reverse_secret, data.threshold, num_collectors, &modulus);
+
+      for (j, (forward, reverse)) in forward_split.into_iter().zip(reverse_split).enumerate() {
+        let e_forward: RawCiphertext = Paillier::encrypt(&encryption_keys[j], RawPlaintext::from(forward));
+        let e_reverse: RawCiphertext = Paillier::encrypt(&encryption_keys[j], RawPlaintext::from(reverse));
+        forward_shares[j].push(e_forward.0.into_owned());
+        reverse_shares[j].push(e_reverse.0.into_owned());
+      }
+    }
+
+    for (dealing, (forward_shares, reverse_shares)) in
+      dealings.iter_mut().zip(forward_shares.into_iter().zip(reverse_shares))
+    {
+      dealing.questions.push(QuestionThresholdShares {
+        question_id: question.id,
+        forward_shares,
+        reverse_shares,
+      });
+    }
+  }
+
+  log::debug!(
+    "Dealt threshold shares for election {} to {} collectors",
+    election.id,
+    num_collectors
+  );
+
+  Ok(HttpResponse::Ok().json(dealings))
+}
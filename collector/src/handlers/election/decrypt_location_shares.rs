@@ -0,0 +1,82 @@
+use actix_web::{web, HttpResponse};
+use curv_kzen::BigInt;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid_b64::UuidB64 as Uuid;
+use validator::{Validate, ValidationError};
+
+use super::shuffle_location_shares::{validate_round, verify_round};
+use crate::auth::MediatorToken;
+use crate::db::DbConnection;
+use crate::errors::ServiceError;
+use crate::models::{Election, EncryptedLocation};
+use crate::protocol::location_anonymization as loc_anon;
+use crate::utils::ConvertBigInt;
+use crate::views::election::LocationRound;
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+#[validate(schema(function = "validate_struct", skip_on_field_errors = false))]
+#[serde(rename_all = "camelCase")]
+pub struct DecryptLocationSharesData {
+  #[validate(length(min = 2))]
+  registered_users: Vec<Uuid>,
+
+  #[serde(with = "kzen_paillier::serialize::bigint")]
+  modulus: BigInt,
+
+  /// Round that uses our public key, after every other collector shuffled it
+  round: LocationRound,
+}
+
+///
+/// Make sure the round has a ciphertext for every registered user, and at least one shuffle
+///
+fn validate_struct(input: &DecryptLocationSharesData) -> Result<(), ValidationError> {
+  if input.round.links.is_empty() {
+    return Err(ValidationError::new("round must have at least one shuffle"));
+  }
+
+  validate_round(&input.round, &input.registered_users, &input.modulus)
+}
+
+///
+/// Last step of a round as the key holder: verify every shuffle, then decrypt the locations
+///
+/// The decrypted value for user j is our new location share, since every other collector kept its random value r_j
+///
+pub async fn decrypt_location_shares(
+  token: MediatorToken,
+  path: web::Path<Uuid>,
+  data: web::Json<DecryptLocationSharesData>,
+  conn: DbConnection,
+) -> Result<HttpResponse, ServiceError> {
+  token.test_can_create_election()?;
+  data.validate()?;
+
+  let election = Election::find_resource(&path, &conn)?;
+  if data.round.public_key != election.get_public_key() {
+    return Err(ServiceError::LocationKeyInvalid {
+      election_id: election.id,
+    });
+  }
+
+  log::debug!("Verify the location shuffles before decrypting");
+  let encrypted_locations =
+    verify_round(&data.round, data.registered_users.len()).ok_or(ServiceError::ShuffleProofInvalid {
+      election_id: election.id,
+    })?;
+
+  let decryption_key = election.get_decryption_key();
+  conn.get().transaction::<_, ServiceError, _>(|| {
+    for (user_id, e_x) in data.registered_users.iter().zip(encrypted_locations.iter()) {
+      let mut location = EncryptedLocation::find_resource(user_id, &election.id, &conn)?;
+      let share = loc_anon::step_last_normalized(e_x, &decryption_key.p, &decryption_key.q, &data.modulus);
+      location.location = share.to_bigdecimal();
+      location.update(&conn)?;
+    }
+
+    Ok(())
+  })?;
+
+  Ok(HttpResponse::Ok().finish())
+}
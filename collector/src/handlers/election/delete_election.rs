@@ -0,0 +1,36 @@
+use actix_web::{web, HttpResponse};
+use diesel::prelude::*;
+use uuid_b64::UuidB64 as Uuid;
+
+use crate::auth::MediatorToken;
+use crate::db::DbConnection;
+use crate::errors::ServiceError;
+use crate::models::Election;
+
+///
+/// Purge the election when it is canceled, along with the private key and every share of the election
+///
+/// An election we never received is already purged, so it is not an error
+///
+pub async fn delete_election(
+  token: MediatorToken,
+  path: web::Path<Uuid>,
+  conn: DbConnection,
+) -> Result<HttpResponse, ServiceError> {
+  token.test_can_create_election()?;
+
+  if let Some(election) = Election::find_optional(&path, &conn)? {
+    conn.get().transaction::<_, ServiceError, _>(|| {
+      election.delete_all_registrations(&conn)?;
+      election.delete_all_encrypted_locations(&conn)?;
+      election.delete_all_questions(&conn)?;
+      election.delete(&conn)?;
+
+      Ok(())
+    })?;
+
+    log::info!("Purged election <{}>", election.id);
+  }
+
+  Ok(HttpResponse::Ok().finish())
+}
@@ -0,0 +1,53 @@
+use actix_web::{web, HttpResponse};
+use curv_kzen::BigInt;
+use serde::{Deserialize, Serialize};
+use uuid_b64::UuidB64 as Uuid;
+use validator::Validate;
+
+use crate::auth::MediatorToken;
+use crate::db::DbConnection;
+use crate::errors::ServiceError;
+use crate::models::{Election, EncryptedLocation};
+use crate::protocol::location_anonymization as loc_anon;
+use crate::utils::ConvertBigInt;
+use crate::views::election::EncryptedLocationShares;
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct EncryptLocationSharesData {
+  #[validate(length(min = 2))]
+  registered_users: Vec<Uuid>,
+
+  /// Paillier public key of the key holder for the next round
+  #[serde(with = "kzen_paillier::serialize::bigint")]
+  public_key: BigInt,
+}
+
+///
+/// Encrypt the location shares of every user with the public key of the key holder (Step 1),
+///   so the shares of every collector can be added together to start the next round
+///
+pub async fn encrypt_location_shares(
+  token: MediatorToken,
+  path: web::Path<Uuid>,
+  data: web::Json<EncryptLocationSharesData>,
+  conn: DbConnection,
+) -> Result<HttpResponse, ServiceError> {
+  token.test_can_create_election()?;
+  data.validate()?;
+
+  let election = Election::find_resource(&path, &conn)?;
+  let encrypted_locations = data
+    .registered_users
+    .iter()
+    .map(|user_id| {
+      let location = EncryptedLocation::find_resource(user_id, &election.id, &conn)?;
+      Ok(loc_anon::step_1(&location.location.to_bigint(), &data.public_key))
+    })
+    .collect::<Result<Vec<_>, ServiceError>>()?;
+
+  Ok(HttpResponse::Ok().json(EncryptedLocationShares {
+    public_key: data.public_key.clone(),
+    encrypted_locations,
+  }))
+}
@@ -7,7 +7,7 @@ use validator::Validate;
 use crate::auth::MediatorToken;
 use crate::db::DbConnection;
 use crate::errors::ServiceError;
-use crate::models::Election;
+use crate::models::{Election, Question};
 use crate::utils::ConvertBigInt;
 use crate::views::election::CancelationShares;
 
@@ -30,8 +30,9 @@ pub async fn get_cancelation_shares(
   let (election_id, question_id) = path.into_inner();
   let CancelationSharesData { user_ids } = data.into_inner();
 
-  // Make sure the election and all registrations exist
+  // Make sure the election, question, and all registrations exist
   let election = Election::find_resource(&election_id, &conn)?;
+  let question = Question::find_resource(&question_id, &election_id, &conn)?;
   let registrations = user_ids
     .into_iter()
     .map(|user_id| {
@@ -48,7 +49,29 @@ pub async fn get_cancelation_shares(
     .collect::<Result<Vec<_>, ServiceError>>()?;
 
   // Compute the sum of the shares for all users
-  let modulus = election.prime.to_bigint() - 1;
+  let modulus = election.commitment_group.order(&question.prime.to_bigint());
+
+  // Elections with threshold secret sharing also return the sum of the threshold shares y_j,i
+  //   Only provided if this collector holds a threshold share for every user
+  let forward_threshold_shares = registrations
+    .iter()
+    .map(|registration| registration.forward_threshold_shares.as_ref())
+    .collect::<Option<Vec<_>>>()
+    .map(|shares| {
+      shares.into_iter().fold(BigInt::from(0), |sum, y| {
+        BigInt::mod_add(&sum, &y.to_bigint(), &modulus)
+      })
+    });
+  let reverse_threshold_shares = registrations
+    .iter()
+    .map(|registration| registration.reverse_threshold_shares.as_ref())
+    .collect::<Option<Vec<_>>>()
+    .map(|shares| {
+      shares.into_iter().fold(BigInt::from(0), |sum, y| {
+        BigInt::mod_add(&sum, &y.to_bigint(), &modulus)
+      })
+    });
+
   let (forward_cancelation_shares, reverse_cancelation_shares) = registrations.into_iter().fold(
     (BigInt::from(0), BigInt::from(0)),
     |(forward, reverse), registration| {
@@ -70,5 +93,7 @@ pub async fn get_cancelation_shares(
   Ok(HttpResponse::Ok().json(CancelationShares {
     forward_cancelation_shares,
     reverse_cancelation_shares,
+    forward_threshold_shares,
+    reverse_threshold_shares,
   }))
 }
@@ -33,6 +33,8 @@ pub async fn get_question_parameters(
     reverse_verification_shares: registration.reverse_verification_shares.to_bigint(),
     forward_ballot_shares: registration.forward_ballot_shares.to_bigint(),
     reverse_ballot_shares: registration.reverse_ballot_shares.to_bigint(),
+    forward_blinding: registration.forward_blinding.map(|b| b.to_bigint()),
+    reverse_blinding: registration.reverse_blinding.map(|b| b.to_bigint()),
   };
 
   Ok(HttpResponse::Ok().json(result))
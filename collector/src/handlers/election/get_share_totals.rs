@@ -0,0 +1,61 @@
+use actix_web::{web, HttpResponse};
+use curv_kzen::{arithmetic::Modulo, BigInt};
+use uuid_b64::UuidB64 as Uuid;
+
+use crate::auth::MediatorToken;
+use crate::db::DbConnection;
+use crate::errors::ServiceError;
+use crate::models::{Election, Registration};
+use crate::protocol::Group;
+use crate::utils::ConvertBigInt;
+use crate::views::election::QuestionShareTotals;
+
+///
+/// Commit to the row and column totals of our shares matrix for every question:
+///   g^(Σ S_c,i) and g^(Σ S~c,i), and the same for the reverse shares
+///
+/// Every cell of the matrix is in exactly one row and one column, so both totals must be equal.
+///   The mediator audits the totals from every collector, without learning any of the shares.
+///
+pub async fn get_share_totals(
+  token: MediatorToken,
+  path: web::Path<Uuid>,
+  conn: DbConnection,
+) -> Result<HttpResponse, ServiceError> {
+  token.test_can_view_elections()?;
+
+  let election = Election::find_resource(&path, &conn)?;
+
+  let mut totals = Vec::new();
+  for question in election.get_questions(&conn)? {
+    let registrations: Vec<Registration> = question.get_registrations(&conn)?;
+    let modulus = election.commitment_group.order(&question.prime.to_bigint());
+    let group = Group::new(
+      election.commitment_group,
+      question.generator.to_bigint(),
+      question.prime.to_bigint(),
+    );
+
+    // Σ S_c,i, Σ S~c,i, Σ S_c,i' and Σ S~c,i'
+    let sum = |get_shares: fn(&Registration) -> BigInt| {
+      registrations.iter().fold(BigInt::from(0), |sum, registration| {
+        BigInt::mod_add(&sum, &get_shares(registration), &modulus)
+      })
+    };
+    let forward_row_total = sum(|r| r.forward_verification_shares.to_bigint());
+    let forward_column_total = sum(|r| r.forward_ballot_shares.to_bigint());
+    let reverse_row_total = sum(|r| r.reverse_verification_shares.to_bigint());
+    let reverse_column_total = sum(|r| r.reverse_ballot_shares.to_bigint());
+
+    totals.push(QuestionShareTotals {
+      question_id: question.id,
+      num_registrations: registrations.len(),
+      forward_row_total: group.pow_generator(&forward_row_total),
+      forward_column_total: group.pow_generator(&forward_column_total),
+      reverse_row_total: group.pow_generator(&reverse_row_total),
+      reverse_column_total: group.pow_generator(&reverse_column_total),
+    });
+  }
+
+  Ok(HttpResponse::Ok().json(totals))
+}
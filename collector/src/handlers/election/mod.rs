@@ -2,11 +2,25 @@
 // All API handlers for managing elections
 //
 mod create_and_initialize_election;
+mod deal_threshold_shares;
+mod decrypt_location_shares;
+mod delete_election;
+mod encrypt_location_shares;
 mod get_cancelation_shares;
 mod get_election_parameters;
 mod get_question_parameters;
+mod get_share_totals;
+mod receive_threshold_shares;
+mod shuffle_location_shares;
 
 pub use create_and_initialize_election::create_and_initialize_election;
+pub use deal_threshold_shares::deal_threshold_shares;
+pub use decrypt_location_shares::decrypt_location_shares;
+pub use delete_election::delete_election;
+pub use encrypt_location_shares::encrypt_location_shares;
 pub use get_cancelation_shares::get_cancelation_shares;
 pub use get_election_parameters::get_election_parameters;
 pub use get_question_parameters::get_question_parameters;
+pub use get_share_totals::get_share_totals;
+pub use receive_threshold_shares::receive_threshold_shares;
+pub use shuffle_location_shares::shuffle_location_shares;
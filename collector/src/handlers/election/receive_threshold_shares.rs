@@ -0,0 +1,108 @@
+use actix_web::{web, HttpResponse};
+use curv_kzen::{arithmetic::Modulo, BigInt};
+use diesel::prelude::*;
+use kzen_paillier::*;
+use serde::{Deserialize, Serialize};
+use uuid_b64::UuidB64 as Uuid;
+use validator::Validate;
+
+use crate::auth::MediatorToken;
+use crate::db::DbConnection;
+use crate::errors::ServiceError;
+use crate::models::Election;
+use crate::utils::ConvertBigInt;
+use crate::views::election::ThresholdDealing;
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct ReceiveThresholdSharesData {
+  #[validate(length(min = 2))]
+  registered_users: Vec<Uuid>,
+
+  /// Dealings sent to this collector from every collector (Including this one)
+  #[validate(length(min = 2))]
+  dealings: Vec<ThresholdDealing>,
+}
+
+///
+/// Decrypt the threshold shares from every collector, and store the sum for every user:
+///   y_j,i = Σ f_c,i(j) (mod q) over all collectors c
+///
+/// Any t collectors can then recover the cancelation shares using Lagrange interpolation,
+///   since the sum of the polynomials evaluated at 0 is Σ (S~c,i - S_c,i)
+///
+pub async fn receive_threshold_shares(
+  token: MediatorToken,
+  path: web::Path<Uuid>,
+  data: web::Json<ReceiveThresholdSharesData>,
+  conn: DbConnection,
+) -> Result<HttpResponse, ServiceError> {
+  token.test_can_create_election()?;
+  data.validate()?;
+
+  let election = Election::find_resource(&path, &conn)?;
+  let decryption_key = election.get_decryption_key();
+  let num_users = data.registered_users.len();
+
+  conn.get().transaction::<_, ServiceError, _>(|| {
+    for question in election.get_questions(&conn)? {
+      let modulus = election.commitment_group.order(&question.prime.to_bigint());
+      let mut forward_sums = vec![BigInt::from(0); num_users];
+      let mut reverse_sums = vec![BigInt::from(0); num_users];
+
+      for dealing in data.dealings.iter() {
+        // Every dealing must have a share for every user in this question
+        let shares = dealing
+          .questions
+          .iter()
+          .find(|shares| shares.question_id == question.id)
+          .filter(|shares| shares.forward_shares.len() == num_users && shares.reverse_shares.len() == num_users)
+          .ok_or(ServiceError::ThresholdSharesInvalid {
+            election_id: election.id,
+            question_id: question.id,
+          })?;
+
+        for (user_index, (forward, reverse)) in shares
+          .forward_shares
+          .iter()
+          .zip(shares.reverse_shares.iter())
+          .enumerate()
+        {
+          let forward: RawPlaintext = Paillier::decrypt(&decryption_key, RawCiphertext::from(forward));
+          let reverse: RawPlaintext = Paillier::decrypt(&decryption_key, RawCiphertext::from(reverse));
+          forward_sums[user_index] = BigInt::mod_add(&forward_sums[user_index], &forward.0, &modulus);
+          reverse_sums[user_index] = BigInt::mod_add(&reverse_sums[user_index], &reverse.0, &modulus);
+        }
+      }
+
+      // Store the sums with the user registrations
+      for (user_id, (forward, reverse)) in data
+        .registered_users
+        .iter()
+        .zip(forward_sums.into_iter().zip(reverse_sums))
+      {
+        let mut registration = election
+          .get_registration(&question.id, user_id, &conn)?
+          .ok_or_else(|| ServiceError::UserNotRegistered {
+            user_id: *user_id,
+            election_id: election.id,
+            question_id: Some(question.id),
+          })?;
+
+        registration.forward_threshold_shares = Some(forward.to_bigdecimal());
+        registration.reverse_threshold_shares = Some(reverse.to_bigdecimal());
+        registration.update(&conn)?;
+      }
+    }
+
+    Ok(())
+  })?;
+
+  log::debug!(
+    "Stored threshold shares from {} collectors for election {}",
+    data.dealings.len(),
+    election.id
+  );
+
+  Ok(HttpResponse::Ok().finish())
+}
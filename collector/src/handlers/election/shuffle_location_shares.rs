@@ -0,0 +1,150 @@
+use actix_web::{web, HttpResponse};
+use curv_kzen::arithmetic::{BitManipulation, Modulo};
+use curv_kzen::BigInt;
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid_b64::UuidB64 as Uuid;
+use validator::{Validate, ValidationError};
+
+use crate::auth::MediatorToken;
+use crate::db::DbConnection;
+use crate::errors::ServiceError;
+use crate::models::{Election, EncryptedLocation};
+use crate::protocol::location_anonymization as loc_anon;
+use crate::protocol::shuffle_proof::{self, ShuffleProof};
+use crate::utils::ConvertBigInt;
+use crate::views::election::{LocationLink, LocationRound};
+
+/// Extra bits for the random values ri, so x - ri statistically hides x from the key holder
+const MASK_SECURITY_BITS: usize = 128;
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+#[validate(schema(function = "validate_struct", skip_on_field_errors = false))]
+#[serde(rename_all = "camelCase")]
+pub struct ShuffleLocationSharesData {
+  #[validate(length(min = 2))]
+  registered_users: Vec<Uuid>,
+
+  #[serde(with = "kzen_paillier::serialize::bigint")]
+  modulus: BigInt,
+
+  round: LocationRound,
+}
+
+///
+/// Make sure the round has a ciphertext for every registered user,
+///   and the locations are reduced mod the number of users
+///
+fn validate_struct(input: &ShuffleLocationSharesData) -> Result<(), ValidationError> {
+  validate_round(&input.round, &input.registered_users, &input.modulus)
+}
+
+///
+/// Shared with the key holder, which gets the same round once every other collector has shuffled
+///
+pub fn validate_round(
+  round: &LocationRound,
+  registered_users: &[Uuid],
+  modulus: &BigInt,
+) -> Result<(), ValidationError> {
+  if modulus != &BigInt::from(registered_users.len() as u64) {
+    return Err(ValidationError::new("modulus not equal to length(registered_users)"));
+  }
+
+  if round
+    .encrypted_shares
+    .iter()
+    .any(|shares| shares.public_key != round.public_key || shares.encrypted_locations.len() != registered_users.len())
+  {
+    return Err(ValidationError::new(
+      "every encrypted share must use the round public key and have length(registered_users) locations",
+    ));
+  }
+
+  Ok(())
+}
+
+///
+/// Verify every shuffle in the round so far, then mask the locations again (Step i):
+///   D_j = C_π(j) * E(-r_j) (mod n^2), where n is the public key of the key holder
+///
+/// Only the first collector in the round picks a permutation π, since the other collectors keep their
+///   random values as plaintext shares, which would not follow a later permutation.
+///
+/// Our new location share for user j is r_j (mod modulus), since the key holder decrypts the rest.
+///   The random values are much smaller than n, so the key holder can decrypt without any modulus.
+///
+/// The key holder never shuffles its own round, otherwise it would know the permutation of the values it decrypts
+///
+pub async fn shuffle_location_shares(
+  token: MediatorToken,
+  path: web::Path<Uuid>,
+  data: web::Json<ShuffleLocationSharesData>,
+  conn: DbConnection,
+) -> Result<HttpResponse, ServiceError> {
+  token.test_can_create_election()?;
+  data.validate()?;
+
+  let election = Election::find_resource(&path, &conn)?;
+  let ShuffleLocationSharesData {
+    registered_users,
+    modulus,
+    round,
+  } = data.into_inner();
+
+  let n = &round.public_key;
+  if n == &election.get_public_key() {
+    return Err(ServiceError::LocationKeyInvalid {
+      election_id: election.id,
+    });
+  }
+
+  // Check the chain from the start of the round, so we only build on proven ciphertexts
+  log::debug!("Verify the location shuffles in the current round");
+  let input = verify_round(&round, registered_users.len()).ok_or(ServiceError::ShuffleProofInvalid {
+    election_id: election.id,
+  })?;
+
+  let mut bound = BigInt::from(0);
+  bound.set_bit(MASK_SECURITY_BITS, true);
+  let bound = &modulus * &bound;
+
+  let should_shuffle = round.links.is_empty();
+  if should_shuffle {
+    log::debug!("Shuffling the list of encrypted locations");
+  }
+  let (output, witness) = shuffle_proof::shuffle_and_mask(&input, n, should_shuffle, Some(&bound));
+  let proof = ShuffleProof::prove(&input, &output, n, &witness, true);
+
+  conn.get().transaction::<_, ServiceError, _>(|| {
+    for (user_id, r) in registered_users.iter().zip(witness.offsets.iter()) {
+      let mut location = EncryptedLocation::find_resource(user_id, &election.id, &conn)?;
+      location.location = BigInt::modulus(r, &modulus).to_bigdecimal();
+      location.update(&conn)?;
+    }
+
+    Ok(())
+  })?;
+
+  Ok(HttpResponse::Ok().json(LocationLink { output, proof }))
+}
+
+///
+/// Combine the encrypted shares into the start of the round, then verify every link
+///
+/// Returns the output of the last link, or None if any proof is invalid
+///
+pub fn verify_round(round: &LocationRound, num_users: usize) -> Option<Vec<BigInt>> {
+  let encrypted_shares: Vec<&[BigInt]> = round
+    .encrypted_shares
+    .iter()
+    .map(|shares| shares.encrypted_locations.as_slice())
+    .collect();
+  let start = loc_anon::round_start(&encrypted_shares, num_users, &round.public_key);
+
+  loc_anon::verify_round(
+    start,
+    round.links.iter().map(|link| (link.output.as_slice(), &link.proof)),
+    &round.public_key,
+  )
+}
@@ -1,33 +1,53 @@
 use actix::prelude::*;
 use actix_http::ws::{CloseCode, CloseReason};
 use actix_web_actors::ws;
-use curv_kzen::arithmetic::{Modulo, Samplable};
+use curv_kzen::arithmetic::Modulo;
 use curv_kzen::BigInt;
 use serde::Serialize;
 use std::collections::BTreeMap;
 use std::hash::Hash;
+use std::iter;
+use uuid::Uuid as UUID;
+use uuid_b64::UuidB64 as Uuid;
 
 use super::websocket_messages::*;
+use crate::auth::IdentityKey;
 use crate::config;
-use crate::models::{Election, Question, Registration};
-use crate::protocol::stpm;
+use crate::models::{BallotEncoding, Election, Question, QuestionType, Registration};
+use crate::protocol::{selections, stpm, Group};
 use crate::utils::ConvertBigInt;
 
 /// Actor used for managing the verification protocol
 ///
 /// This protocol verifies both sub-protocol 1 and sub-protocol 2 over websockets.
 pub struct VerificationWebsocketActor {
+  // Every signed message must belong to this verification
+  session: Session,
+
+  // Hash of the election manifest we accepted (The mediator must use the same manifest)
+  manifest_hash: Option<String>,
+
+  // Collector IDs in index order from the manifest (Used to look up the pinned identity keys)
+  collectors: Vec<Uuid>,
+
   // Collector details
   collector_index: usize,
   num_collectors: usize,
   public_keys: Vec<PublicKey>,
 
   // Election parameters:
-  //   g^x (mod p) is a cyclic group of order p-1
-  generator: BigInt,
-  prime: BigInt,
+  //   g^x is a cyclic group of order group_order (Each question has its own group)
+  group: Group,
+  group_order: BigInt,
   num_registered: i64,
   num_candidates: i64,
+  min_selections: i64,
+  max_selections: i64,
+  question_type: QuestionType,
+  chunk_size: i64,
+  weight: i64,
+  ballot_encoding: BallotEncoding,
+  counter_radix: i64,
 
   // Published ballots
   p_i: BigInt,       // Forward Ballot = p_i
@@ -38,12 +58,13 @@ pub struct VerificationWebsocketActor {
   g_s_prime: BigInt,
   g_s_s_prime: BigInt,
 
-  // Private key (Paillier cryptosystem and RSA)
+  // Private key (Paillier cryptosystem)
   n: BigInt, // p * q
   paillier_p: BigInt,
   paillier_q: BigInt,
-  rsa_a: BigInt,
-  rsa_b: BigInt,
+
+  // Long-term key used to sign every message
+  identity_key: IdentityKey,
 
   // Shares held by the collector
   s_i_cj: BigInt,           // S_i,Cj
@@ -73,30 +94,21 @@ struct SP1_STPM_Values {
 ///
 impl VerificationWebsocketActor {
   /// Create a new actor to handle websocket verification
-  pub fn new(election: Election, question: Question, num_registered: i64, registration: Registration) -> Self {
-    let generator = election.generator.to_bigint();
-    let prime = election.prime.to_bigint();
+  pub fn new(
+    election: Election,
+    question: Question,
+    num_registered: i64,
+    registration: Registration,
+    identity_key: IdentityKey,
+  ) -> Self {
+    let prime = question.prime.to_bigint();
+    let group_order = election.commitment_group.order(&prime);
+    let group = Group::new(election.commitment_group, question.generator.to_bigint(), prime);
 
     // Private key for paillier cryptosystem
     let paillier_p = election.paillier_p.to_bigint();
     let paillier_q = election.paillier_q.to_bigint();
 
-    // Generate public-private key pair for RSA:
-    //  Let n = p*q, where p and q are safe primes
-    //  Also Φ(n) = (p-1)(q-1) is the Euler totient function
-    //
-    //  Pick values a and b such that a*b ≡ 1 (mod Φ(n))
-    //
-    //  Public Key: (b, n)     Private key: (a)
-    //  E(x) = x^b (mod n)     D(x) = E(x)^a (mod n)
-    let totient = (&paillier_p - 1) * (&paillier_q - 1);
-    let (rsa_a, rsa_b) = loop {
-      let a = BigInt::sample_below(&totient);
-      if let Some(b) = BigInt::mod_inv(&a, &totient) {
-        break (a, b);
-      }
-    };
-
     // Extract the shares
     let s_i_cj = registration.forward_verification_shares.to_bigint(); // S_i,Cj
     let s_i_cj_prime = registration.reverse_verification_shares.to_bigint(); // S_i,Cj'
@@ -104,16 +116,33 @@ impl VerificationWebsocketActor {
     let stild_i_cj_prime = registration.reverse_ballot_shares.to_bigint(); // S~i,Cj'
 
     Self {
+      // Session nonce is chosen by the mediator (Don't have this right now)
+      session: Session {
+        nonce: UUID::nil().into(), // Initialized later
+        election_id: registration.election_id,
+        question_id: registration.question_id,
+        user_id: registration.user_id,
+      },
+      manifest_hash: election.manifest_hash.clone(),
+      collectors: election.collectors.clone(),
+
       // Collector details (Don't have these right now)
       collector_index: 0,      // Initialized later
       num_collectors: 0,       // Initialized later
       public_keys: Vec::new(), // Initialized later
 
       // Election Parameters
-      generator,
-      prime,
+      group,
+      group_order,
       num_registered,
       num_candidates: question.num_candidates,
+      min_selections: question.min_selections,
+      max_selections: question.max_selections,
+      question_type: question.question_type,
+      chunk_size: question.chunk_size(),
+      weight: registration.weight,
+      ballot_encoding: election.ballot_encoding.for_question(question.question_type),
+      counter_radix: election.counter_radix,
 
       // Ballots  (Don't have this right now)
       p_i: BigInt::from(0),       // Initialized later
@@ -128,8 +157,7 @@ impl VerificationWebsocketActor {
       n: &paillier_p * &paillier_q,
       paillier_p,
       paillier_q,
-      rsa_a,
-      rsa_b,
+      identity_key,
 
       // Shares held by the collector
       s_i_cj,           // S_i,Cj
@@ -150,6 +178,22 @@ impl VerificationWebsocketActor {
   ///
   /// Sends a error close message if the signature validation fails
   fn verify_signature<T: SignedMessage>(&self, msg: &T, ctx: &mut <Self as Actor>::Context) -> bool {
+    // Reject messages that were recorded during another verification
+    if msg.get_session() != &self.session {
+      Self::error_close(
+        (
+          CloseCode::Invalid,
+          format!(
+            "Message from collector {} belongs to a different verification session",
+            msg.get_from() + 1,
+          ),
+        ),
+        ctx,
+      );
+
+      return false;
+    }
+
     // Make sure we actually have the public key for this collector
     //  (This case SHOULD NOT happen in practice)
     let public_key = self.public_keys.get(msg.get_from());
@@ -169,7 +213,14 @@ impl VerificationWebsocketActor {
     }
 
     // Test the message signature
+    //   Report the other collector to the mediator first, since only we can check this signature
     if !msg.verify_signature(public_key.unwrap()) {
+      let report = Misbehavior_Report {
+        collector_index: msg.get_from(),
+        check: VerificationCheck::BadSignature,
+      };
+      self.send_mediator(report, ctx);
+
       Self::error_close(
         (
           CloseCode::Invalid,
@@ -189,7 +240,7 @@ impl VerificationWebsocketActor {
   /// Returns "false" if this failed due to an error, meaning the actor should stop any processing immediately
   fn send_mediator<T: Serialize + Hash>(&self, data: T, ctx: &mut <Self as Actor>::Context) -> bool {
     Self::send_json(
-      &SignedMediatorMessage::new_signed(self.collector_index, data, &self.rsa_a, &self.n),
+      &SignedMediatorMessage::new_signed(&self.session, self.collector_index, data, &self.identity_key),
       ctx,
     )
   }
@@ -199,7 +250,7 @@ impl VerificationWebsocketActor {
   /// Returns "false" if this failed due to an error, meaning the actor should stop any processing immediately
   fn send_unicast<T: Serialize + Hash>(&self, to: usize, data: T, ctx: &mut <Self as Actor>::Context) -> bool {
     Self::send_json(
-      &SignedUnicastMessage::new_signed(self.collector_index, to, data, &self.rsa_a, &self.n),
+      &SignedUnicastMessage::new_signed(&self.session, self.collector_index, to, data, &self.identity_key),
       ctx,
     )
   }
@@ -209,7 +260,7 @@ impl VerificationWebsocketActor {
   /// Returns "false" if this failed due to an error, meaning the actor should stop any processing immediately
   fn send_broadcast<T: Serialize + Hash>(&self, data: T, ctx: &mut <Self as Actor>::Context) -> bool {
     Self::send_json(
-      &SignedBroadcastMessage::new_signed(self.collector_index, data, &self.rsa_a, &self.n),
+      &SignedBroadcastMessage::new_signed(&self.session, self.collector_index, data, &self.identity_key),
       ctx,
     )
   }
@@ -254,7 +305,7 @@ impl Actor for VerificationWebsocketActor {
   fn started(&mut self, ctx: &mut Self::Context) {
     // Broadcast the public key back to the mediator
     let shared_secret = config::get_collector_secret();
-    Self::send_json(&PublicKey::new_signed(&self.n, &self.rsa_b, &shared_secret), ctx);
+    Self::send_json(&PublicKey::new_signed(&self.n, &self.identity_key, &shared_secret), ctx);
   }
 }
 
@@ -335,6 +386,65 @@ impl Handler<Initialize> for VerificationWebsocketActor {
   fn handle(&mut self, init: Initialize, ctx: &mut Self::Context) -> Self::Result {
     log::debug!("Initialize parameters for collector {}:", init.collector_index + 1);
 
+    // Every party must use the same protocol, otherwise the messages and signatures cannot be understood
+    if init.protocol_version != PROTOCOL_VERSION {
+      return Self::error_close(
+        (
+          CloseCode::Unsupported,
+          format!(
+            "Mediator uses protocol version {}, expected version {}",
+            init.protocol_version, PROTOCOL_VERSION
+          ),
+        ),
+        ctx,
+      );
+    }
+    if let Some((index, public_key)) = init
+      .public_keys
+      .iter()
+      .enumerate()
+      .find(|(_, public_key)| public_key.protocol_version != PROTOCOL_VERSION)
+    {
+      return Self::error_close(
+        (
+          CloseCode::Unsupported,
+          format!(
+            "Collector {} uses protocol version {}, expected version {}",
+            index + 1,
+            public_key.protocol_version,
+            PROTOCOL_VERSION
+          ),
+        ),
+        ctx,
+      );
+    }
+
+    // The mediator picks the nonce, but the session must be for the ballot we were asked to verify
+    let session = &init.session;
+    if session.election_id != self.session.election_id
+      || session.question_id != self.session.question_id
+      || session.user_id != self.session.user_id
+    {
+      return Self::error_close(
+        (
+          CloseCode::Invalid,
+          "Verification session does not match the requested ballot".to_string(),
+        ),
+        ctx,
+      );
+    }
+
+    // The mediator must not verify the ballot against a different set of election parameters
+    if init.manifest_hash != self.manifest_hash {
+      return Self::error_close(
+        (
+          CloseCode::Invalid,
+          "Election manifest does not match the manifest accepted for this election".to_string(),
+        ),
+        ctx,
+      );
+    }
+
     // Verify all public keys first to ensure they weren't tampered with
     //   Only the collectors know the shared secret, so the mediator cannot sign the keys
     let shared_secret = config::get_collector_secret();
@@ -350,7 +460,46 @@ impl Handler<Initialize> for VerificationWebsocketActor {
       }
     }
 
+    // The mediator must not swap out our own key
+    let own_identity_key = self.identity_key.public_key();
+    let own_key = init.public_keys.get(init.collector_index);
+    if own_key.is_none_or(|key| key.identity_key != own_identity_key) {
+      return Self::error_close(
+        (
+          CloseCode::Invalid,
+          "Public key for this collector was replaced".to_string(),
+        ),
+        ctx,
+      );
+    }
+
+    // The mediator must not swap out the keys of the other collectors either,
+    //   so every key must match the identity key pinned in our configuration for the collector at that index
+    let pinned_keys = config::get_collector_identity_keys();
+    if init.public_keys.len() != self.collectors.len() {
+      return Self::error_close(
+        (
+          CloseCode::Invalid,
+          "Public keys do not match the collectors in the election manifest".to_string(),
+        ),
+        ctx,
+      );
+    }
+
+    for (index, (public_key, collector_id)) in init.public_keys.iter().zip(self.collectors.iter()).enumerate() {
+      if index != init.collector_index && pinned_keys.get(collector_id) != Some(&public_key.identity_key) {
+        return Self::error_close(
+          (
+            CloseCode::Invalid,
+            format!("Identity key for collector {} does not match the pinned key", index + 1),
+          ),
+          ctx,
+        );
+      }
+    }
+
     // Collector details
+    self.session = init.session;
     self.collector_index = init.collector_index;
     self.num_collectors = init.num_collectors;
     self.public_keys = init.public_keys;
@@ -393,8 +542,8 @@ impl Handler<Initialize> for VerificationWebsocketActor {
     //   g^(S~i,Cj) and g^(S~i,Cj')
     // ===================================
     log::debug!("Sub-protocol 2 - Compute g values");
-    let g_stild = BigInt::mod_pow(&self.generator, &self.stild_i_cj, &self.prime); // g^(S~i,Cj)
-    let g_stild_prime = BigInt::mod_pow(&self.generator, &self.stild_i_cj_prime, &self.prime); // g^(S~i,Cj')
+    let g_stild = self.group.pow_generator(&self.stild_i_cj); // g^(S~i,Cj)
+    let g_stild_prime = self.group.pow_generator(&self.stild_i_cj_prime); // g^(S~i,Cj')
 
     log::debug!("g^(S~i,C{}) = {}", self.collector_index + 1, g_stild);
     log::debug!("g^(S~i,C{}') = {}", self.collector_index + 1, g_stild_prime);
@@ -530,25 +679,24 @@ impl VerificationWebsocketActor {
     );
 
     // Add together all r values from STPM
-    let modulus = &self.prime - 1;
     let r_r_prime = self.sp1_values.values().fold(BigInt::from(0), |acc, value| {
-      BigInt::mod_add(&acc, &(&value.r + &value.r_prime), &modulus)
+      BigInt::mod_add(&acc, &(&value.r + &value.r_prime), &self.group_order)
     });
 
     // Now compute the product
-    let product_j = BigInt::mod_mul(
-      &BigInt::mod_mul(
-        &BigInt::mod_pow(&self.g_s, &self.s_i_cj_prime, &self.prime),
-        &BigInt::mod_pow(&self.g_s_prime, &self.s_i_cj, &self.prime),
-        &self.prime,
-      ),
-      &BigInt::mod_mul(
-        &BigInt::mod_pow(&self.generator, &(&self.s_i_cj * &self.s_i_cj_prime), &self.prime),
-        &BigInt::mod_pow(&self.generator, &r_r_prime, &self.prime),
-        &self.prime,
-      ),
-      &self.prime,
-    );
+    //   The commitments come from the voter, so they might not be valid group elements
+    let product_j = match self.compute_product_j(&r_r_prime) {
+      Some(product_j) => product_j,
+      None => {
+        return Self::error_close(
+          (
+            CloseCode::Invalid,
+            "Commitments are not valid group elements".to_string(),
+          ),
+          ctx,
+        )
+      }
+    };
 
     log::debug!("P{} = {}", self.collector_index + 1, product_j);
 
@@ -564,6 +712,20 @@ impl VerificationWebsocketActor {
     // Possibly do the final verification if we have all the products
     self.maybe_publish_sp1_result(ctx);
   }
+
+  /// Compute P_j = g^(s_i * S_i,Cj') * g^(s_i' * S_i,Cj) * g^(S_i,Cj * S_i,Cj' + Σ r + r')
+  ///   Returns None if the commitments are not valid group elements
+  fn compute_product_j(&self, r_r_prime: &BigInt) -> Option<BigInt> {
+    let g_s_s_cj_prime = self.group.pow(&self.g_s, &self.s_i_cj_prime)?;
+    let g_s_prime_s_cj = self.group.pow(&self.g_s_prime, &self.s_i_cj)?;
+    let g_shares = self
+      .group
+      .pow_generator(&(&self.s_i_cj * &self.s_i_cj_prime + r_r_prime));
+
+    self
+      .group
+      .mul(&self.group.mul(&g_s_s_cj_prime, &g_s_prime_s_cj)?, &g_shares)
+  }
 }
 
 ///
@@ -594,32 +756,49 @@ impl VerificationWebsocketActor {
     log::debug!("Sub-protocol 1: Computing final combined product");
 
     // Multiply all products from all collectors
-    let products = self
-      .products
-      .values()
-      .fold(BigInt::from(1), |acc, x| BigInt::mod_mul(&acc, &x, &self.prime));
-
-    log::debug!("P1 * ... * P{} = {}", self.num_collectors, products);
-
-    // Compute the final combined product
-    let combined_product = BigInt::mod_mul(&self.g_s_s_prime, &products, &self.prime);
+    //   Also compute the final combined product (None if any value is not a valid group element)
+    let combined_product = self
+      .group
+      .product(iter::once(&self.g_s_s_prime).chain(self.products.values()));
     log::debug!(
-      "g^(s_i * s_i') * P1 * ... * P{} = {}",
+      "g^(s_i * s_i') * P1 * ... * P{} = {:?}",
       self.num_collectors,
       combined_product
     );
 
-    // Compute the expected product:
-    //   g^(2^(L - 1)), where L is the number of bits in the voting vector
-    let expected_product = BigInt::mod_pow(
-      &self.generator,
-      &(BigInt::from(1) << (self.num_registered * self.num_candidates - 1) as usize),
-      &self.prime,
-    );
-    log::debug!("g^(2^(L - 1)) = {}", expected_product);
+    // One-hot questions give every voter a chunk of single-bit fields,
+    //   while counter questions share a single chunk of base-counter_radix digits between all voters
+    let (radix, num_chunks) = match self.ballot_encoding {
+      BallotEncoding::OneHot => (BigInt::from(2), self.num_registered),
+      BallotEncoding::Counter => (BigInt::from(self.counter_radix as u64), 1),
+    };
+
+    // Compute the expected products:
+    //   For a single-choice question with unweighted voters, this is only g^(2^(L - 1)),
+    //   where L is the number of bits in the voting vector
+    //   Approval and ranked questions accept one product for every valid set of digits in the voter's chunk
+    let expected_products = match self.question_type {
+      QuestionType::Choice => selections::expected_products(
+        &self.group,
+        &radix,
+        num_chunks,
+        self.chunk_size,
+        self.weight,
+        selections::choice_positions(self.num_candidates, self.min_selections, self.max_selections),
+      ),
+      QuestionType::Ranked => selections::expected_products(
+        &self.group,
+        &radix,
+        num_chunks,
+        self.chunk_size,
+        self.weight,
+        selections::ranked_positions(self.num_candidates),
+      ),
+    };
+    log::debug!("Expected products = {:?}", expected_products);
 
     // Send the verification result
-    let sp1_ballot_valid = combined_product == expected_product;
+    let sp1_ballot_valid = combined_product.is_some_and(|product| expected_products.contains(&product));
     log::debug!(
       "Sub-protocol 1: ballot {}",
       if sp1_ballot_valid { "valid" } else { "invalid" }
@@ -658,48 +837,34 @@ impl VerificationWebsocketActor {
 
     log::debug!("Sub-protocol 2 - Compute the final products");
 
-    // Multiply all g products together
-    let (g_combined, g_prime_combined) =
-      self
-        .sp2_values
-        .values()
-        .fold((BigInt::from(1), BigInt::from(1)), |(forward, reverse), value| {
-          (
-            BigInt::mod_mul(&forward, &value.g_stild, &self.prime),
-            BigInt::mod_mul(&reverse, &value.g_stild_prime, &self.prime),
-          )
-        });
-
-    log::debug!("g^(S~i,C1) * ... * g^(S~i,C{}) = {}", self.num_collectors, g_combined);
-    log::debug!(
-      "g^(S~i,C1') * ... * g^(S~i,C{}') = {}",
-      self.num_collectors,
-      g_prime_combined
-    );
-
     // Verify the forward ballot
-    let g_p_i = BigInt::mod_pow(&self.generator, &self.p_i, &self.prime);
-    let g_p_i_combined = BigInt::mod_mul(&self.g_s, &g_combined, &self.prime);
-    let g_p_i_verified = &g_p_i_combined == &g_p_i;
+    //   The combined product is None if any value is not a valid group element
+    let g_p_i = self.group.pow_generator(&self.p_i);
+    let g_p_i_combined = self
+      .group
+      .product(iter::once(&self.g_s).chain(self.sp2_values.values().map(|value| &value.g_stild)));
+    let g_p_i_verified = g_p_i_combined.as_ref() == Some(&g_p_i);
 
     log::debug!("Sub-protocol 2: Forward ballot");
     log::debug!("g^(p_i) = {}", g_p_i);
     log::debug!(
-      "g^(s_i) * g^(S~i,C1) * ... * g^(S~i,C{}) = {}",
+      "g^(s_i) * g^(S~i,C1) * ... * g^(S~i,C{}) = {:?}",
       self.num_collectors,
       g_p_i_combined
     );
     log::debug!("Forward ballot {}", if g_p_i_verified { "valid" } else { "invalid" });
 
     // Verify the reverse ballot
-    let g_p_i_prime = BigInt::mod_pow(&self.generator, &self.p_i_prime, &self.prime);
-    let g_p_i_prime_combined = BigInt::mod_mul(&self.g_s_prime, &g_prime_combined, &self.prime);
-    let g_p_i_prime_verified = &g_p_i_prime_combined == &g_p_i_prime;
+    let g_p_i_prime = self.group.pow_generator(&self.p_i_prime);
+    let g_p_i_prime_combined = self
+      .group
+      .product(iter::once(&self.g_s_prime).chain(self.sp2_values.values().map(|value| &value.g_stild_prime)));
+    let g_p_i_prime_verified = g_p_i_prime_combined.as_ref() == Some(&g_p_i_prime);
 
     log::debug!("Sub-protocol 2: Reverse ballot");
     log::debug!("g^(p_i') = {}", g_p_i_prime);
     log::debug!(
-      "g^(s_i') * g^(S~i,C1') * ... * g^(S~i,C{}') = {}",
+      "g^(s_i') * g^(S~i,C1') * ... * g^(S~i,C{}') = {:?}",
       self.num_collectors,
       g_p_i_prime_combined
     );
@@ -3,7 +3,7 @@ use actix_web_actors::ws;
 use uuid_b64::UuidB64 as Uuid;
 
 use super::verification_websocket_actor::VerificationWebsocketActor;
-use crate::auth::MediatorToken;
+use crate::auth::{IdentityKey, MediatorToken};
 use crate::db::DbConnection;
 use crate::errors::{ServiceError, WebsocketError};
 use crate::models::{Election, Question};
@@ -12,6 +12,7 @@ pub async fn verify_ballot_websocket(
   token: MediatorToken,
   path: web::Path<(Uuid, Uuid, Uuid)>,
   conn: DbConnection,
+  identity_key: web::Data<IdentityKey>,
   req: HttpRequest,
   payload: web::Payload,
 ) -> Result<HttpResponse, ServiceError> {
@@ -35,7 +36,13 @@ pub async fn verify_ballot_websocket(
   log::debug!("Starting actor to serve verification websocket...");
   Ok(
     ws::start(
-      VerificationWebsocketActor::new(election, question, num_registered, registration),
+      VerificationWebsocketActor::new(
+        election,
+        question,
+        num_registered,
+        registration,
+        identity_key.get_ref().clone(),
+      ),
       &req,
       payload,
     )
@@ -1,12 +1,14 @@
 #![allow(non_camel_case_types)]
 use actix::prelude::*;
 use actix_http::ws::CloseCode;
-use curv_kzen::arithmetic::{Converter, Modulo};
+use curv_kzen::arithmetic::Converter;
 use curv_kzen::BigInt;
 use serde::{Deserialize, Serialize};
 use std::hash::{Hash, Hasher};
+use uuid_b64::UuidB64 as Uuid;
 
 use super::sha_hasher::SHAHasher;
+use crate::auth::IdentityKey;
 
 /// Close the connection due to an error
 #[derive(Message)]
@@ -28,6 +30,21 @@ where
   }
 }
 
+///
+/// Version of the verification protocol, which must be the same for every collector and the mediator
+///
+/// Increment this whenever a message format or signature scheme changes
+///   Version 1 = Textbook RSA signatures using the Paillier modulus (No version field)
+///   Version 2 = Ed25519 signatures using the collector identity keys
+///   Version 3 = Signed messages are bound to the verification session
+///
+pub const PROTOCOL_VERSION: u32 = 3;
+
+/// Messages without a version field are from the first version of the protocol
+fn legacy_protocol_version() -> u32 {
+  1
+}
+
 /// Top level structure for any type of JSON value that can be received
 #[derive(Debug, Clone, Deserialize)]
 #[serde(untagged)]
@@ -39,20 +56,51 @@ pub enum WebsocketMessage {
   SP2_Shares_Response(SignedBroadcastMessage<SP2_Shares_Response>),
 }
 
+///
+/// Identifies a single verification run
+///
+/// Every signed message includes the session, so a recorded message cannot be replayed into
+///   the verification of a different ballot, or into a later verification of the same ballot
+///
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Session {
+  pub nonce: Uuid, // Chosen by the mediator for every verification
+  pub election_id: Uuid,
+  pub question_id: Uuid,
+  pub user_id: Uuid,
+}
+
+impl Hash for Session {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    self.nonce.to_string().hash(state);
+    self.election_id.to_string().hash(state);
+    self.question_id.to_string().hash(state);
+    self.user_id.to_string().hash(state);
+  }
+}
+
 /// Represents a received message that is signed
 pub trait SignedMessage {
   /// Extract the signature stored in the struct
-  fn get_signature(&self) -> &BigInt;
+  fn get_signature(&self) -> &str;
 
   /// Extract the source of the signature
   fn get_from(&self) -> usize;
 
+  /// Extract the session the message is bound to
+  fn get_session(&self) -> &Session;
+
   /// Hash the message to get the signature
   fn compute_hash(&self) -> BigInt;
 
-  /// Verify the message signature using RSA from the public key
+  /// Verify the message signature using the identity key from the public key
   fn verify_signature(&self, public_key: &PublicKey) -> bool {
-    &self.compute_hash() == &BigInt::mod_pow(self.get_signature(), &public_key.b, &public_key.n)
+    IdentityKey::verify(
+      &public_key.identity_key,
+      &self.compute_hash().to_bytes(),
+      self.get_signature(),
+    )
   }
 }
 
@@ -62,31 +110,33 @@ pub trait SignedMessage {
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct SignedMediatorMessage<T> {
+  pub session: Session,
   pub from: usize,
   pub data: T,
 
-  #[serde(with = "kzen_paillier::serialize::bigint")]
-  pub collector_signature: BigInt,
+  /// Ed25519 signature (base64)
+  pub collector_signature: String,
 }
 
 impl<T: Hash> SignedMediatorMessage<T> {
-  pub fn new_signed(from: usize, data: T, private_key: &BigInt, n: &BigInt) -> Self {
+  pub fn new_signed(session: &Session, from: usize, data: T, identity_key: &IdentityKey) -> Self {
     // Start by building the message
     let mut message = Self {
+      session: session.clone(),
       from,
       data,
-      collector_signature: BigInt::from(0),
+      collector_signature: String::new(),
     };
 
     // Then compute the signature from the hash
-    message.collector_signature = BigInt::mod_pow(&message.compute_hash(), private_key, n);
+    message.collector_signature = identity_key.sign(&message.compute_hash().to_bytes());
     message
   }
 }
 
 impl<T: Hash> SignedMessage for SignedMediatorMessage<T> {
   #[inline]
-  fn get_signature(&self) -> &BigInt {
+  fn get_signature(&self) -> &str {
     &self.collector_signature
   }
 
@@ -95,8 +145,15 @@ impl<T: Hash> SignedMessage for SignedMediatorMessage<T> {
     self.from
   }
 
+  #[inline]
+  fn get_session(&self) -> &Session {
+    &self.session
+  }
+
   fn compute_hash(&self) -> BigInt {
     let mut hasher = SHAHasher::new();
+    PROTOCOL_VERSION.hash(&mut hasher);
+    self.session.hash(&mut hasher);
     self.from.hash(&mut hasher);
     self.data.hash(&mut hasher);
     hasher.get_sha_hash()
@@ -110,34 +167,35 @@ impl<T: Hash> SignedMessage for SignedMediatorMessage<T> {
 #[serde(rename_all = "camelCase")]
 #[rtype(result = "()")]
 pub struct SignedUnicastMessage<T> {
+  pub session: Session,
   pub from: usize,
   pub to: usize,
   pub data: T,
 
-  /// RSA Signature
-  #[serde(with = "kzen_paillier::serialize::bigint")]
-  pub signature: BigInt,
+  /// Ed25519 signature (base64)
+  pub signature: String,
 }
 
 impl<T: Hash> SignedUnicastMessage<T> {
-  pub fn new_signed(from: usize, to: usize, data: T, private_key: &BigInt, n: &BigInt) -> Self {
+  pub fn new_signed(session: &Session, from: usize, to: usize, data: T, identity_key: &IdentityKey) -> Self {
     // Start by building the message
     let mut message = Self {
+      session: session.clone(),
       from,
       to,
       data,
-      signature: BigInt::from(0),
+      signature: String::new(),
     };
 
     // Then compute the signature from the hash
-    message.signature = BigInt::mod_pow(&message.compute_hash(), private_key, n);
+    message.signature = identity_key.sign(&message.compute_hash().to_bytes());
     message
   }
 }
 
 impl<T: Hash> SignedMessage for SignedUnicastMessage<T> {
   #[inline]
-  fn get_signature(&self) -> &BigInt {
+  fn get_signature(&self) -> &str {
     &self.signature
   }
 
@@ -146,8 +204,15 @@ impl<T: Hash> SignedMessage for SignedUnicastMessage<T> {
     self.from
   }
 
+  #[inline]
+  fn get_session(&self) -> &Session {
+    &self.session
+  }
+
   fn compute_hash(&self) -> BigInt {
     let mut hasher = SHAHasher::new();
+    PROTOCOL_VERSION.hash(&mut hasher);
+    self.session.hash(&mut hasher);
     self.from.hash(&mut hasher);
     self.to.hash(&mut hasher);
     self.data.hash(&mut hasher);
@@ -162,32 +227,33 @@ impl<T: Hash> SignedMessage for SignedUnicastMessage<T> {
 #[serde(rename_all = "camelCase")]
 #[rtype(result = "()")]
 pub struct SignedBroadcastMessage<T> {
+  pub session: Session,
   pub from: usize,
   pub data: T,
 
-  /// RSA Signature
-  #[serde(with = "kzen_paillier::serialize::bigint")]
-  pub signature: BigInt,
+  /// Ed25519 signature (base64)
+  pub signature: String,
 }
 
 impl<T: Hash> SignedBroadcastMessage<T> {
-  pub fn new_signed(from: usize, data: T, private_key: &BigInt, n: &BigInt) -> Self {
+  pub fn new_signed(session: &Session, from: usize, data: T, identity_key: &IdentityKey) -> Self {
     // Start by building the message
     let mut message = Self {
+      session: session.clone(),
       from,
       data,
-      signature: BigInt::from(0),
+      signature: String::new(),
     };
 
     // Then compute the signature from the hash
-    message.signature = BigInt::mod_pow(&message.compute_hash(), private_key, n);
+    message.signature = identity_key.sign(&message.compute_hash().to_bytes());
     message
   }
 }
 
 impl<T: Hash> SignedMessage for SignedBroadcastMessage<T> {
   #[inline]
-  fn get_signature(&self) -> &BigInt {
+  fn get_signature(&self) -> &str {
     &self.signature
   }
 
@@ -196,8 +262,15 @@ impl<T: Hash> SignedMessage for SignedBroadcastMessage<T> {
     self.from
   }
 
+  #[inline]
+  fn get_session(&self) -> &Session {
+    &self.session
+  }
+
   fn compute_hash(&self) -> BigInt {
     let mut hasher = SHAHasher::new();
+    PROTOCOL_VERSION.hash(&mut hasher);
+    self.session.hash(&mut hasher);
     self.from.hash(&mut hasher);
     self.data.hash(&mut hasher);
     hasher.get_sha_hash()
@@ -208,46 +281,71 @@ impl<T: Hash> SignedMessage for SignedBroadcastMessage<T> {
 // Define all data structures from the mediator
 // =============================================
 
+///
 /// Publish the public key for a collector
+///
+/// The Paillier modulus is new for every verification, but it is signed with the long-term identity key.
+///   The identity key is pinned by the mediator when the collector registers, and the hash with the shared
+///   collector secret makes sure only a collector could have published it.
+///
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PublicKey {
-  /// Modulus for both RSA and Paillier cryptosystem
+  /// Checked before anything else, so collectors on different versions can reject each other
+  #[serde(default = "legacy_protocol_version")]
+  pub protocol_version: u32,
+
+  /// Modulus for the Paillier cryptosystem
   #[serde(with = "kzen_paillier::serialize::bigint")]
   pub n: BigInt,
 
-  /// Public exponent b for RSA
-  #[serde(with = "kzen_paillier::serialize::bigint")]
-  pub b: BigInt,
+  /// Ed25519 identity key of the collector (base64 DER)
+  pub identity_key: String,
 
-  /// Signature to ensure public key has been faithfully published
+  /// Hash to ensure public key has been faithfully published
   #[serde(with = "kzen_paillier::serialize::bigint")]
-  pub signature: BigInt,
+  pub secret_hash: BigInt,
+
+  /// Identity key signature over the modulus and identity key
+  pub signature: String,
 }
 
 impl PublicKey {
-  pub fn new_signed(n: &BigInt, b: &BigInt, shared_secret: &str) -> Self {
+  pub fn new_signed(n: &BigInt, identity_key: &IdentityKey, shared_secret: &str) -> Self {
     // Start by building the public key
     let mut public_key = PublicKey {
+      protocol_version: PROTOCOL_VERSION,
       n: n.clone(),
-      b: b.clone(),
-      signature: BigInt::from(0),
+      identity_key: identity_key.public_key(),
+      secret_hash: BigInt::from(0),
+      signature: String::new(),
     };
 
-    // Then compute the signature from the hash
-    public_key.signature = public_key.compute_hash(shared_secret);
+    // Then compute the hash and the signature
+    public_key.secret_hash = public_key.compute_secret_hash(shared_secret);
+    public_key.signature = identity_key.sign(&public_key.compute_hash().to_bytes());
     public_key
   }
 
-  /// Verify the signature using the shared collector secret
+  /// Verify the hash using the shared collector secret, and the signature using the identity key
   pub fn verify_signature(&self, shared_secret: &str) -> bool {
-    self.signature == self.compute_hash(shared_secret)
+    self.secret_hash == self.compute_secret_hash(shared_secret)
+      && IdentityKey::verify(&self.identity_key, &self.compute_hash().to_bytes(), &self.signature)
+  }
+
+  fn compute_hash(&self) -> BigInt {
+    let mut hasher = SHAHasher::new();
+    self.protocol_version.hash(&mut hasher);
+    self.n.to_bytes().hash(&mut hasher);
+    self.identity_key.hash(&mut hasher);
+    hasher.get_sha_hash()
   }
 
-  fn compute_hash(&self, shared_secret: &str) -> BigInt {
+  fn compute_secret_hash(&self, shared_secret: &str) -> BigInt {
     let mut hasher = SHAHasher::new();
+    self.protocol_version.hash(&mut hasher);
     self.n.to_bytes().hash(&mut hasher);
-    self.b.to_bytes().hash(&mut hasher);
+    self.identity_key.hash(&mut hasher);
     shared_secret.hash(&mut hasher);
     hasher.get_sha_hash()
   }
@@ -258,6 +356,14 @@ impl PublicKey {
 #[serde(rename_all = "camelCase")]
 #[rtype(result = "()")]
 pub struct Initialize {
+  #[serde(default = "legacy_protocol_version")]
+  pub protocol_version: u32,
+  pub session: Session,
+
+  // Election manifest accepted when the election was created (None for older elections)
+  #[serde(default)]
+  pub manifest_hash: Option<String>,
+
   // Collector details
   pub collector_index: usize,
   pub num_collectors: usize,
@@ -276,7 +382,7 @@ pub struct Initialize {
   #[serde(with = "kzen_paillier::serialize::bigint")]
   pub g_s_s_prime: BigInt, // g^(s_i * s_i')
 
-  // STPM Encryption Key and RSA signatures
+  // STPM Encryption Key and identity keys for signatures
   pub public_keys: Vec<PublicKey>,
 }
 
@@ -386,3 +492,22 @@ impl Hash for SP2_Shares_Response {
 pub struct SP2_Result_Response {
   pub sp2_ballot_valid: bool,
 }
+
+/// Report a collector that sent an invalid message to the mediator
+#[derive(Debug, Clone, Hash, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Misbehavior_Report {
+  pub collector_index: usize,
+  pub check: VerificationCheck,
+}
+
+/// Checks that can fail during verification (Same as the mediator)
+///   Only the mediator can check the products and commitments, so the collector never reports those
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, Hash, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum VerificationCheck {
+  ProductMismatch,
+  CommitmentMismatch,
+  BadSignature,
+}
@@ -9,7 +9,7 @@ use std::str::FromStr;
 use structopt::StructOpt;
 use uuid_b64::UuidB64 as Uuid;
 
-use evoting_collector::auth::{CollectorToken, JWTSecret, Permission};
+use evoting_collector::auth::{CollectorToken, IdentityKey, JWTSecret, Permission};
 use evoting_collector::config::{self, EnvPrefix};
 use evoting_collector::db;
 use evoting_collector::errors::{ClientRequestError, ServiceError};
@@ -29,8 +29,14 @@ async fn main() -> anyhow::Result<()> {
     log::set_max_level(LevelFilter::Info);
   }
 
+  // Long-term key used to sign all verification messages
+  let identity_key = IdentityKey::load_or_generate(config::get_identity_key_file())?;
+
+  // The other collectors must pin this public key to accept our verification messages
+  log::info!("Collector identity key: {}", identity_key.public_key());
+
   // Let the mediator know about this collector
-  register_collector_with_mediator().await?;
+  register_collector_with_mediator(&identity_key).await?;
 
   // Database connection pool and web server
   let connection_pool = db::establish_new_connection_pool()?;
@@ -40,6 +46,8 @@ async fn main() -> anyhow::Result<()> {
       .data(connection_pool.clone())
       // Encryption secret for JSON Web Token
       .data(JWTSecret::new(config::get_jwt_secret()))
+      // Identity key for signing verification messages
+      .data(identity_key.clone())
       // Enable logger
       .wrap(middleware::Logger::default())
       // Configure error handlers
@@ -56,10 +64,23 @@ async fn main() -> anyhow::Result<()> {
               .route("", web::post().to(handlers::election::create_and_initialize_election))
               .service(
                 web::scope("/{election_id}")
+                  .route("", web::delete().to(handlers::election::delete_election))
                   .route(
                     "/parameters",
                     web::get().to(handlers::election::get_election_parameters),
                   )
+                  .route("/shares/totals", web::get().to(handlers::election::get_share_totals))
+                  .service(
+                    web::scope("/locations")
+                      .route("/encrypt", web::post().to(handlers::election::encrypt_location_shares))
+                      .route("/shuffle", web::post().to(handlers::election::shuffle_location_shares))
+                      .route("/decrypt", web::post().to(handlers::election::decrypt_location_shares)),
+                  )
+                  .service(
+                    web::scope("/threshold")
+                      .route("/deal", web::post().to(handlers::election::deal_threshold_shares))
+                      .route("/receive", web::post().to(handlers::election::receive_threshold_shares)),
+                  )
                   .service(
                     web::scope("/questions").service(
                       web::scope("/{question_id}")
@@ -99,7 +120,7 @@ async fn main() -> anyhow::Result<()> {
 /// Register the collector with the mediator
 ///  -This is done EVERY TIME the program is run
 ///
-async fn register_collector_with_mediator() -> anyhow::Result<()> {
+async fn register_collector_with_mediator(identity_key: &IdentityKey) -> anyhow::Result<()> {
   // Data needed to register an election
   let c = config::get_collector();
   let id = config::get_id().ok_or_else(|| anyhow::anyhow!("{} environment variable not set", c.env_prefix("ID")))?;
@@ -122,6 +143,7 @@ async fn register_collector_with_mediator() -> anyhow::Result<()> {
     #[serde(with = "http_serde::uri")]
     private_base_uri: Uri,
     is_secure: bool,
+    identity_key: String,
   }
 
   // Build thr request
@@ -130,6 +152,7 @@ async fn register_collector_with_mediator() -> anyhow::Result<()> {
     name,
     private_base_uri,
     is_secure,
+    identity_key: identity_key.public_key(),
   };
 
   // Encode the JSON web token (Requires special permissions to manage collector)
@@ -0,0 +1,33 @@
+use crate::models::QuestionType;
+
+// Every election uses one of the following encodings for the voting vector:
+//
+//   [OneHot]  - Every voter has their own chunk of the voting vector (Chosen by their location)
+//               Each field is a single bit, since a weight in the chunk would identify the voter
+//
+//   [Counter] - Every candidate is a single digit in base counter_radix, shared by all voters
+//
+// Note: Ranked questions always use the one-hot encoding
+sql_enum!(
+  pub BallotEncoding {
+    OneHot = 0,
+    Counter
+  }
+);
+
+impl BallotEncoding {
+  pub fn get_name(&self) -> &'static str {
+    match self {
+      BallotEncoding::OneHot => "One-Hot",
+      BallotEncoding::Counter => "Counter",
+    }
+  }
+
+  /// Encoding actually used for a given question in the election
+  pub fn for_question(&self, question_type: QuestionType) -> Self {
+    match question_type {
+      QuestionType::Choice => *self,
+      QuestionType::Ranked => BallotEncoding::OneHot,
+    }
+  }
+}
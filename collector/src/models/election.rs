@@ -6,7 +6,7 @@ use uuid_b64::UuidB64 as Uuid;
 
 use crate::db::DbConnection;
 use crate::errors::{NamedResourceType, ServiceError};
-use crate::models::{EncryptedLocation, Registration};
+use crate::models::{BallotEncoding, CommitmentGroup, EncryptedLocation, Registration};
 use crate::schema::elections;
 use crate::utils::ConvertBigInt;
 
@@ -15,32 +15,59 @@ use crate::utils::ConvertBigInt;
 #[serde(rename_all = "camelCase")]
 pub struct Election {
   pub id: Uuid,
-  pub generator: BigDecimal,
-  pub prime: BigDecimal,
+
+  // STPM Paillier private key, sized for the largest prime of any question
   pub paillier_p: BigDecimal,
   pub paillier_q: BigDecimal,
+
+  // Encoding of the voting vector (Counter digits use base counter_radix)
+  pub ballot_encoding: BallotEncoding,
+  pub counter_radix: i64,
+
+  // Group for the commitments g^s (All shares are mod the order of this group)
+  pub commitment_group: CommitmentGroup,
+
+  // Hash of the election manifest signed by the API server (None for older elections)
+  pub manifest_hash: Option<String>,
+
+  // Threshold t from the manifest (None if the election does not use threshold secret sharing)
+  pub threshold: Option<i64>,
+
+  // Collector IDs in index order from the manifest (Empty for older elections)
+  pub collectors: Vec<Uuid>,
 }
 
 impl Election {
   model_base!();
 
+  has_many!(Question);
   has_many!(Registration);
   has_many!(EncryptedLocation);
 
-  pub fn new(id: Uuid, generator: &BigInt, prime: &BigInt, paillier: &DecryptionKey) -> Self {
+  pub fn new(
+    id: Uuid,
+    paillier: &DecryptionKey,
+    ballot_encoding: BallotEncoding,
+    counter_radix: i64,
+    commitment_group: CommitmentGroup,
+    threshold: Option<i64>,
+    manifest_hash: String,
+    collectors: Vec<Uuid>,
+  ) -> Self {
     // Convert from BigInt to BigDecimal
-    let generator = generator.to_bigdecimal();
-    let prime = prime.to_bigdecimal();
-
     let paillier_p = paillier.p.to_bigdecimal();
     let paillier_q = paillier.q.to_bigdecimal();
 
     Self {
       id,
-      generator,
-      prime,
       paillier_p,
       paillier_q,
+      ballot_encoding,
+      counter_radix,
+      commitment_group,
+      manifest_hash: Some(manifest_hash),
+      threshold,
+      collectors,
     }
   }
 
@@ -49,6 +76,19 @@ impl Election {
     Self::find_optional(id, conn)?.ok_or_else(|| NamedResourceType::election(*id).into_error())
   }
 
+  /// STPM Paillier private key for the election
+  pub fn get_decryption_key(&self) -> DecryptionKey {
+    DecryptionKey {
+      p: self.paillier_p.to_bigint(),
+      q: self.paillier_q.to_bigint(),
+    }
+  }
+
+  /// STPM Paillier public key n = p*q, which other collectors use to send threshold shares
+  pub fn get_public_key(&self) -> BigInt {
+    self.paillier_p.to_bigint() * self.paillier_q.to_bigint()
+  }
+
   /// Get a user registration for an election
   pub fn get_registration(
     &self,
@@ -1,12 +1,20 @@
 //
 // Database tables (ORM models)
 //
+mod ballot_encoding;
+mod commitment_group;
 mod election;
 mod encrypted_location;
 mod question;
+mod question_type;
 mod registration;
+mod verification_mode;
 
+pub use ballot_encoding::BallotEncoding;
+pub use commitment_group::CommitmentGroup;
 pub use election::Election;
 pub use encrypted_location::EncryptedLocation;
 pub use question::Question;
+pub use question_type::{count_choice_selection_sets, QuestionType, MAX_CHOICE_SELECTION_SETS, MAX_RANKED_CANDIDATES};
 pub use registration::Registration;
+pub use verification_mode::VerificationMode;
@@ -1,10 +1,14 @@
+use bigdecimal::BigDecimal;
+use curv_kzen::BigInt;
 use serde::Serialize;
+use std::ops::RangeInclusive;
 use uuid_b64::UuidB64 as Uuid;
 
 use crate::db::DbConnection;
 use crate::errors::{NamedResourceType, ServiceError};
-use crate::models::Election;
+use crate::models::{Election, QuestionType};
 use crate::schema::questions;
+use crate::utils::ConvertBigInt;
 
 #[derive(Debug, Clone, Serialize, Queryable, Insertable, Identifiable, AsChangeset, Associations)]
 #[belongs_to(Election)]
@@ -14,6 +18,13 @@ pub struct Question {
   pub id: Uuid,
   pub election_id: Uuid,
   pub num_candidates: i64,
+  pub min_selections: i64,
+  pub max_selections: i64,
+  pub question_type: QuestionType,
+
+  // Cached copy of the group g^x (mod p) for this question from the API server
+  pub generator: BigDecimal,
+  pub prime: BigDecimal,
 }
 
 impl Question {
@@ -22,14 +33,32 @@ impl Question {
   belongs_to!(Election);
   has_many!(Registration);
 
-  pub fn new(id: Uuid, election_id: Uuid, num_candidates: i64) -> Self {
+  pub fn new(
+    id: Uuid,
+    election_id: Uuid,
+    num_candidates: i64,
+    question_type: QuestionType,
+    selections: RangeInclusive<i64>,
+    generator: &BigInt,
+    prime: &BigInt,
+  ) -> Self {
     Self {
       id,
       election_id,
       num_candidates,
+      min_selections: *selections.start(),
+      max_selections: *selections.end(),
+      question_type,
+      generator: generator.to_bigdecimal(),
+      prime: prime.to_bigdecimal(),
     }
   }
 
+  /// Number of bits used by each voter in the voting vector
+  pub fn chunk_size(&self) -> i64 {
+    self.question_type.chunk_size(self.num_candidates)
+  }
+
   /// Search for an election question in the database, and return a ServiceError (not a Diesel error)
   pub fn find_resource(id: &Uuid, election_id: &Uuid, conn: &DbConnection) -> Result<Self, ServiceError> {
     let question =
@@ -21,6 +21,17 @@ pub struct Registration {
 
   pub forward_ballot_shares: BigDecimal,
   pub reverse_ballot_shares: BigDecimal,
+
+  // Number of votes the ballot counts for
+  pub weight: i64,
+
+  // rho and rho' for the share commitments (Only used with non-interactive ballot proofs)
+  pub forward_blinding: Option<BigDecimal>,
+  pub reverse_blinding: Option<BigDecimal>,
+
+  // Shamir shares of the cancelation shares held by this collector (Only used with threshold secret sharing)
+  pub forward_threshold_shares: Option<BigDecimal>,
+  pub reverse_threshold_shares: Option<BigDecimal>,
 }
 
 impl Registration {
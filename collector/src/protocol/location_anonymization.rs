@@ -1,6 +1,8 @@
 use curv_kzen::arithmetic::{Modulo, Samplable};
 use kzen_paillier::*;
 
+use crate::protocol::shuffle_proof::{self, ShuffleProof};
+
 /// First step of the location anonymization scheme
 ///   C1 computes e_x1 = E(x1) and sends it to C2
 ///
@@ -63,3 +65,74 @@ pub fn step_last(e_xn_1: &BigInt, p: &BigInt, q: &BigInt) -> BigInt {
 
   r1.0.into_owned()
 }
+
+/// Last step of distributed location anonymization:
+///   The key holder decrypts the locations after every other collector subtracted its random values rj
+///
+/// Since x - rj is much smaller than n, any value above n/2 is negative
+///   This recovers x - rj with NO modulus, which is then reduced mod the location modulus
+///
+/// e_x      = E(x - rj)
+/// p, q     = Private key for Paillier cryptosystem
+/// modulus  = Location modulus shared by every collector
+///
+/// Returns x - rj (mod modulus)
+pub fn step_last_normalized(e_x: &BigInt, p: &BigInt, q: &BigInt, modulus: &BigInt) -> BigInt {
+  let n = p * q;
+  let mut x = step_last(e_x, p, q);
+  if 2 * &x >= n {
+    x = &x - &n;
+  }
+
+  BigInt::modulus(&x, modulus)
+}
+
+///
+/// Starting ciphertexts for a round of distributed location anonymization (step 1)
+///
+/// The first round starts from the trivial encryptions E(i; 1) = 1 + i*n (mod n^2) of the locations 0 to N-1,
+///   so every collector can check the input and nobody is handed a location in plaintext.
+/// Every later round adds up the encrypted location shares of every collector:
+///   E(s_1,j) * E(s_2,j) * ... * E(s_c,j) (mod n^2) = E(s_1,j + ... + s_c,j)
+///
+/// encrypted_shares = Location shares of every collector encrypted with n (Empty for the first round)
+/// num_users        = Number of registered users N
+/// n                = Public key of the key holder for this round
+///
+pub fn round_start(encrypted_shares: &[&[BigInt]], num_users: usize, n: &BigInt) -> Vec<BigInt> {
+  if encrypted_shares.is_empty() {
+    return (0u64..(num_users as u64))
+      .map(|i| shuffle_proof::encrypt_with_randomness(&BigInt::from(i), &BigInt::from(1), n))
+      .collect();
+  }
+
+  let nn = n * n;
+  (0..num_users)
+    .map(|j| {
+      encrypted_shares.iter().fold(BigInt::from(1), |product, shares| {
+        BigInt::mod_mul(&product, &shares[j], &nn)
+      })
+    })
+    .collect()
+}
+
+///
+/// Check every shuffle in the round so far (step i), starting from the known input of the round
+///
+/// Checking the whole chain means the mediator cannot swap in a different list of ciphertexts between two collectors
+///
+/// Returns the output of the last shuffle, or None if any proof is invalid
+///
+pub fn verify_round<'a>(
+  start: Vec<BigInt>,
+  mut links: impl Iterator<Item = (&'a [BigInt], &'a ShuffleProof)>,
+  n: &BigInt,
+) -> Option<Vec<BigInt>> {
+  links.try_fold(start, |input, (output, proof)| {
+    if proof.verify(&input, output, n, true) {
+      Some(output.to_vec())
+    } else {
+      None
+    }
+  })
+}
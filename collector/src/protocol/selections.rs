@@ -0,0 +1,98 @@
+use curv_kzen::BigInt;
+use itertools::Itertools;
+use std::collections::BTreeSet;
+
+use crate::protocol::Group;
+
+/// Compute every value of g^(v * v') that sub-protocol 1 should accept for a question
+///
+/// The voting vector is read as L digits in base R, where every digit stores the voter weight w.
+///   One-hot questions use R = 2^B (B bits per field), and give every voter their own chunk of m digits.
+///   Counter questions use R = N + 1 (N is the total weight), and share a single chunk of m digits between all voters.
+///
+/// Say a voter sets digits r_1, ..., r_k within their chunk of m digits.
+/// Then the forward and reverse voting vectors are v = w * Σ R^(l + r_a) and v' = w * Σ R^(L - 1 - l - r_b),
+/// where l is the offset of the voter's chunk:
+///
+///   v * v' = w^2 * Σ R^(L - 1 + r_a - r_b) = R^(L - m) * w^2 * t,  where t = Σ R^(m - 1 + r_a - r_b)
+///
+/// The multiplier t does NOT depend on the voter location, so we can enumerate it
+///   for every valid set of positions. For a single-choice question with R = 2 and w = 1, this is just 2^(L - 1).
+///
+/// Since g^(R^(L - m) * w^2 * t) only depends on t, every product is computed directly from the generator.
+///
+/// radix      = Base of every digit in the voting vector (R)
+/// num_chunks = Number of chunks in the voting vector (Number of voters for one-hot, 1 for counter)
+/// chunk_size = Number of digits in each chunk (m)
+/// weight     = Weight of the voter (w)
+/// positions  = Every valid set of digit positions within a chunk
+pub fn expected_products(
+  group: &Group,
+  radix: &BigInt,
+  num_chunks: i64,
+  chunk_size: i64,
+  weight: i64,
+  positions: impl Iterator<Item = Vec<usize>>,
+) -> BTreeSet<BigInt> {
+  let m = chunk_size as usize;
+
+  // R^(L - m) * w^2
+  let base =
+    pow(radix, ((num_chunks - 1) * chunk_size) as usize) * BigInt::from(weight as u64) * BigInt::from(weight as u64);
+
+  positions
+    .map(|positions| {
+      // t = (Σ R^(r_a)) * (Σ R^(m - 1 - r_b))
+      let (forward, reverse) = positions
+        .iter()
+        .fold((BigInt::from(0), BigInt::from(0)), |(forward, reverse), r| {
+          (forward + pow(radix, *r), reverse + pow(radix, m - 1 - *r))
+        });
+
+      forward * reverse
+    })
+    .collect::<BTreeSet<BigInt>>()
+    .into_iter()
+    .map(|t| group.pow_generator(&(&base * &t)))
+    .collect()
+}
+
+/// Compute base^exponent without any modulus (Square-and-multiply)
+fn pow(base: &BigInt, exponent: usize) -> BigInt {
+  let mut result = BigInt::from(1);
+  let mut square = base.clone();
+  let mut exponent = exponent;
+
+  while exponent > 0 {
+    if exponent & 1 == 1 {
+      result = &result * &square;
+    }
+    square = &square * &square;
+    exponent >>= 1;
+  }
+
+  result
+}
+
+/// Every valid set of positions for a choice question:
+///   Select between min_selections and max_selections (inclusive) of the candidates
+pub fn choice_positions(
+  num_candidates: i64,
+  min_selections: i64,
+  max_selections: i64,
+) -> impl Iterator<Item = Vec<usize>> {
+  (min_selections as usize..=max_selections as usize).flat_map(move |k| (0..num_candidates as usize).combinations(k))
+}
+
+/// Every valid set of positions for a ranked question:
+///   Candidate c with rank r sets bit (c * num_candidates + r), where the ranks are a permutation
+pub fn ranked_positions(num_candidates: i64) -> impl Iterator<Item = Vec<usize>> {
+  let m = num_candidates as usize;
+  (0..m).permutations(m).map(move |ranks| {
+    ranks
+      .into_iter()
+      .enumerate()
+      .map(|(candidate, rank)| candidate * m + rank)
+      .collect()
+  })
+}
@@ -0,0 +1,80 @@
+use curv_kzen::arithmetic::{Modulo, Samplable};
+use curv_kzen::BigInt;
+
+///
+/// Split a secret into Shamir shares, so any `threshold` of the shares can recover it
+///
+/// Picks a random polynomial f(x) = secret + a_1*x + ... + a_(t-1)*x^(t-1) (mod q),
+///   and returns [f(1), f(2), ..., f(num_shares)]. Collector j (0-indexed) gets the share f(j + 1).
+///
+/// The modulus q must be prime, otherwise the mediator cannot use Lagrange interpolation to recover f(0)
+///
+pub fn split_secret(secret: &BigInt, threshold: usize, num_shares: usize, modulus: &BigInt) -> Vec<BigInt> {
+  let mut coefficients = vec![BigInt::modulus(secret, modulus)];
+  coefficients.extend((1..threshold).map(|_| BigInt::sample_below(modulus)));
+
+  (1..=num_shares)
+    .map(|x| {
+      // Evaluate f(x) using Horner's method
+      let x = BigInt::from(x as u64);
+      coefficients.iter().rev().fold(BigInt::from(0), |acc, coefficient| {
+        BigInt::mod_add(&BigInt::mod_mul(&acc, &x, modulus), coefficient, modulus)
+      })
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use itertools::Itertools;
+
+  use super::*;
+
+  /// Mersenne prime 2^61 - 1
+  const MODULUS: u64 = 2_305_843_009_213_693_951;
+
+  /// Recover f(0) with Lagrange interpolation, the same way as the mediator
+  fn interpolate_at_zero(points: &[(BigInt, BigInt)], modulus: &BigInt) -> BigInt {
+    points.iter().fold(BigInt::from(0), |sum, (x_j, y_j)| {
+      let (numerator, denominator) = points.iter().filter(|(x_k, _)| x_k != x_j).fold(
+        (BigInt::from(1), BigInt::from(1)),
+        |(numerator, denominator), (x_k, _)| {
+          (
+            BigInt::mod_mul(&numerator, x_k, modulus),
+            BigInt::mod_mul(&denominator, &BigInt::mod_sub(x_k, x_j, modulus), modulus),
+          )
+        },
+      );
+
+      let lambda = BigInt::mod_mul(&numerator, &BigInt::mod_inv(&denominator, modulus).unwrap(), modulus);
+      BigInt::mod_add(&sum, &BigInt::mod_mul(y_j, &lambda, modulus), modulus)
+    })
+  }
+
+  #[test]
+  fn reconstructs_from_any_threshold_shares() {
+    let modulus = BigInt::from(MODULUS);
+    let secret = BigInt::sample_below(&modulus);
+
+    for threshold in 1..=5 {
+      let shares = split_secret(&secret, threshold, 5, &modulus);
+      let points: Vec<(BigInt, BigInt)> = (1u64..).map(BigInt::from).zip(shares.into_iter()).collect();
+
+      for subset in points.iter().cloned().combinations(threshold) {
+        assert_eq!(interpolate_at_zero(&subset, &modulus), secret);
+      }
+    }
+  }
+
+  #[test]
+  fn fewer_shares_do_not_reconstruct() {
+    let modulus = BigInt::from(MODULUS);
+    let secret = BigInt::sample_below(&modulus);
+    let shares = split_secret(&secret, 3, 5, &modulus);
+    let points: Vec<(BigInt, BigInt)> = (1u64..).map(BigInt::from).zip(shares.into_iter()).collect();
+
+    for subset in points.into_iter().combinations(2) {
+      assert_ne!(interpolate_at_zero(&subset, &modulus), secret);
+    }
+  }
+}
@@ -0,0 +1,298 @@
+//
+// Copied into the mediator and collector crates
+//   Both copies must stay byte-identical (pinned by a test in protocol/mod.rs)
+//
+use curv_kzen::arithmetic::{Converter, Modulo, Samplable};
+use curv_kzen::BigInt;
+use rand::{seq::SliceRandom, thread_rng};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// Number of cut-and-choose rounds (A cheating prover passes with probability 2^-rounds)
+pub const SHUFFLE_PROOF_ROUNDS: usize = 128;
+
+/// Domain separator for the Fiat-Shamir challenge
+const CHALLENGE_DOMAIN: &[u8] = b"TruBallot location shuffle";
+
+///
+/// Non-interactive proof that the output ciphertexts are a shuffle of the input ciphertexts,
+///   where the prover may also subtract a known value from every ciphertext:
+///
+///   D_j = C_π(j) * E(-r_j; ρ_j) (mod n^2)
+///
+/// The prover must know π, r_j and ρ_j. Copying a ciphertext (Which creates a duplicate location)
+///   would need the difference between two plaintexts, which nobody but the key owner knows.
+///
+/// Every round picks an intermediate shuffle B_j = C_σ(j) * E(-t_j; τ_j), then opens one of the two halves:
+///   [Challenge 0] σ, t_j and τ_j, so the verifier can recompute B from C
+///   [Challenge 1] φ = σ^-1 ∘ π, u_j = r_j - t_φ(j) and v_j = ρ_j / τ_φ(j), so the verifier can recompute D from B
+///
+/// The challenge bits are a hash of every ciphertext in the proof (Fiat-Shamir)
+///
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShuffleProof {
+  pub rounds: Vec<ShuffleProofRound>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShuffleProofRound {
+  #[serde(with = "kzen_paillier::serialize::vecbigint")]
+  pub commitments: Vec<BigInt>, // B_j
+
+  pub permutation: Vec<usize>, // σ or φ
+
+  #[serde(with = "kzen_paillier::serialize::vecbigint")]
+  pub offsets: Vec<BigInt>, // t_j or u_j
+
+  #[serde(with = "kzen_paillier::serialize::vecbigint")]
+  pub randomness: Vec<BigInt>, // τ_j or v_j
+}
+
+/// Secret values used to shuffle the ciphertexts, which are needed to build the proof
+#[derive(Debug, Clone)]
+pub struct ShuffleWitness {
+  pub permutation: Vec<usize>, // π
+  pub offsets: Vec<BigInt>,    // r_j
+  pub randomness: Vec<BigInt>, // ρ_j
+}
+
+///
+/// Shuffle and mask the ciphertexts: D_j = C_π(j) * E(-r_j; ρ_j) (mod n^2)
+///
+/// should_shuffle = Pick a random permutation π (Otherwise the order is kept)
+/// mask_bound     = Pick random values 0 <= r_j < mask_bound (Otherwise r_j = 0, which only re-encrypts the ciphertexts)
+///
+/// The bound is normally n, but it can be smaller so the key owner can decrypt x - r_j without any modulus
+///
+/// Returns (D, witness)
+///
+pub fn shuffle_and_mask(
+  input: &[BigInt],
+  n: &BigInt,
+  should_shuffle: bool,
+  mask_bound: Option<&BigInt>,
+) -> (Vec<BigInt>, ShuffleWitness) {
+  let mut permutation: Vec<usize> = (0..input.len()).collect();
+  if should_shuffle {
+    permutation.shuffle(&mut thread_rng());
+  }
+
+  let offsets: Vec<BigInt> = input
+    .iter()
+    .map(|_| match mask_bound {
+      Some(bound) => BigInt::sample_below(bound),
+      None => BigInt::from(0),
+    })
+    .collect();
+  let randomness: Vec<BigInt> = input.iter().map(|_| sample_randomness(n)).collect();
+
+  let output = permutation
+    .iter()
+    .zip(offsets.iter().zip(randomness.iter()))
+    .map(|(&i, (r, rho))| apply_offset(&input[i], r, rho, n))
+    .collect();
+
+  (
+    output,
+    ShuffleWitness {
+      permutation,
+      offsets,
+      randomness,
+    },
+  )
+}
+
+///
+/// Encrypt m with the chosen randomness ρ: E(m; ρ) = (1 + m*n) * ρ^n (mod n^2)
+///   This is the same encryption as the Paillier library, which uses the generator g = n + 1
+///
+pub fn encrypt_with_randomness(m: &BigInt, rho: &BigInt, n: &BigInt) -> BigInt {
+  let nn = n * n;
+  let g_m = BigInt::mod_add(&BigInt::from(1), &BigInt::mod_mul(&BigInt::modulus(m, n), n, &nn), &nn);
+  BigInt::mod_mul(&g_m, &BigInt::mod_pow(rho, n, &nn), &nn)
+}
+
+impl ShuffleProof {
+  ///
+  /// Build the proof for the shuffle D = shuffle_and_mask(C)
+  ///
+  /// If the shuffle was not masked, then every offset in the proof is 0 as well
+  ///
+  /// The intermediate offsets are always picked mod n, even if the shuffle used smaller offsets,
+  ///   so u_j = r_j - t_φ(j) (mod n) does not leak anything about r_j
+  ///
+  pub fn prove(input: &[BigInt], output: &[BigInt], n: &BigInt, witness: &ShuffleWitness, masked: bool) -> Self {
+    // Pick every intermediate shuffle B = shuffle_and_mask(C)
+    let intermediate: Vec<(Vec<BigInt>, ShuffleWitness)> = (0..SHUFFLE_PROOF_ROUNDS)
+      .map(|_| shuffle_and_mask(input, n, true, if masked { Some(n) } else { None }))
+      .collect();
+
+    let challenge = fiat_shamir_challenge(input, output, n, intermediate.iter().map(|(b, _)| b.as_slice()));
+
+    let rounds = intermediate
+      .into_iter()
+      .enumerate()
+      .map(|(round, (commitments, sigma))| {
+        if !challenge_bit(&challenge, round) {
+          // Open B directly
+          return ShuffleProofRound {
+            commitments,
+            permutation: sigma.permutation,
+            offsets: sigma.offsets,
+            randomness: sigma.randomness,
+          };
+        }
+
+        // Open the link from B to D: φ = σ^-1 ∘ π
+        let mut sigma_inverse = vec![0; sigma.permutation.len()];
+        for (j, &i) in sigma.permutation.iter().enumerate() {
+          sigma_inverse[i] = j;
+        }
+
+        let permutation: Vec<usize> = witness.permutation.iter().map(|&i| sigma_inverse[i]).collect();
+        let (offsets, randomness) = permutation
+          .iter()
+          .enumerate()
+          .map(|(j, &k)| {
+            // u_j = r_j - t_φ(j) and v_j = ρ_j / τ_φ(j)
+            //  Every randomness value should be invertible, so we should never panic when finding inverse
+            let u = BigInt::mod_sub(&witness.offsets[j], &sigma.offsets[k], n);
+            let v = BigInt::mod_mul(
+              &witness.randomness[j],
+              &BigInt::mod_inv(&sigma.randomness[k], n).expect("Error: No Inverse"),
+              n,
+            );
+            (u, v)
+          })
+          .unzip();
+
+        ShuffleProofRound {
+          commitments,
+          permutation,
+          offsets,
+          randomness,
+        }
+      })
+      .collect();
+
+    Self { rounds }
+  }
+
+  ///
+  /// Verify the proof that D is a shuffle of C
+  ///
+  /// masked = Allow the prover to subtract values from the ciphertexts
+  ///   Otherwise every offset must be 0, so D has the exact same plaintexts as C
+  ///
+  pub fn verify(&self, input: &[BigInt], output: &[BigInt], n: &BigInt, masked: bool) -> bool {
+    let num_values = input.len();
+    if output.len() != num_values || self.rounds.len() != SHUFFLE_PROOF_ROUNDS {
+      return false;
+    }
+
+    // Every round must open a valid permutation with values in the correct range
+    let nn = n * n;
+    let valid_format = self.rounds.iter().all(|round| {
+      round.commitments.len() == num_values
+        && round.offsets.len() == num_values
+        && round.randomness.len() == num_values
+        && is_permutation(&round.permutation, num_values)
+        && round.commitments.iter().all(|b| b < &nn)
+        && round.offsets.iter().all(|t| t < n && (masked || t == &BigInt::from(0)))
+        && round.randomness.iter().all(|rho| rho > &BigInt::from(0) && rho < n)
+    });
+    if !valid_format {
+      return false;
+    }
+
+    let challenge = fiat_shamir_challenge(input, output, n, self.rounds.iter().map(|r| r.commitments.as_slice()));
+
+    self.rounds.iter().enumerate().all(|(round_number, round)| {
+      // Recompute B from C, or D from B
+      let (source, target) = if !challenge_bit(&challenge, round_number) {
+        (input, round.commitments.as_slice())
+      } else {
+        (round.commitments.as_slice(), output)
+      };
+
+      round
+        .permutation
+        .iter()
+        .zip(round.offsets.iter().zip(round.randomness.iter()))
+        .zip(target.iter())
+        .all(|((&i, (offset, rho)), expected)| &apply_offset(&source[i], offset, rho, n) == expected)
+    })
+  }
+}
+
+/// Compute c * E(-r; ρ) (mod n^2)
+fn apply_offset(c: &BigInt, r: &BigInt, rho: &BigInt, n: &BigInt) -> BigInt {
+  let nn = n * n;
+  let negative_r = BigInt::mod_sub(&BigInt::from(0), r, n);
+  BigInt::mod_mul(c, &encrypt_with_randomness(&negative_r, rho, n), &nn)
+}
+
+/// Pick random ρ with 1 <= ρ < n
+fn sample_randomness(n: &BigInt) -> BigInt {
+  loop {
+    let rho = BigInt::sample_below(n);
+    if rho != BigInt::from(0) {
+      return rho;
+    }
+  }
+}
+
+/// Make sure the list contains every index from 0 to length - 1 exactly once
+fn is_permutation(permutation: &[usize], length: usize) -> bool {
+  let mut seen = vec![false; length];
+  permutation.len() == length
+    && permutation.iter().all(|&i| {
+      if i >= length || seen[i] {
+        return false;
+      }
+
+      seen[i] = true;
+      true
+    })
+}
+
+///
+/// Hash every ciphertext into the challenge bits
+///
+/// Every integer is hashed as an 8-byte big-endian length followed by its big-endian bytes
+///
+fn fiat_shamir_challenge<'a>(
+  input: &[BigInt],
+  output: &[BigInt],
+  n: &BigInt,
+  commitments: impl Iterator<Item = &'a [BigInt]>,
+) -> Vec<u8> {
+  let mut hasher = Sha256::new();
+  hasher.update(CHALLENGE_DOMAIN);
+  update_bigint(&mut hasher, n);
+
+  for value in input.iter().chain(output.iter()) {
+    update_bigint(&mut hasher, value);
+  }
+  for round in commitments {
+    for value in round {
+      update_bigint(&mut hasher, value);
+    }
+  }
+
+  hasher.finalize().to_vec()
+}
+
+/// Get the challenge bit for a single round
+fn challenge_bit(challenge: &[u8], round: usize) -> bool {
+  (challenge[round / 8] >> (round % 8)) & 1 == 1
+}
+
+/// Hash an integer with its length, so the boundaries between values are not ambiguous
+fn update_bigint(hasher: &mut Sha256, value: &BigInt) {
+  let bytes = value.to_bytes();
+  hasher.update((bytes.len() as u64).to_be_bytes());
+  hasher.update(&bytes);
+}
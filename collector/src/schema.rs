@@ -1,10 +1,14 @@
 table! {
     elections (id) {
         id -> Uuid,
-        generator -> Numeric,
-        prime -> Numeric,
         paillier_p -> Numeric,
         paillier_q -> Numeric,
+        ballot_encoding -> Int4,
+        counter_radix -> Int8,
+        commitment_group -> Int4,
+        manifest_hash -> Nullable<Text>,
+        threshold -> Nullable<Int8>,
+        collectors -> Array<Uuid>,
     }
 }
 
@@ -21,6 +25,11 @@ table! {
         id -> Uuid,
         election_id -> Uuid,
         num_candidates -> Int8,
+        min_selections -> Int8,
+        max_selections -> Int8,
+        question_type -> Int4,
+        generator -> Numeric,
+        prime -> Numeric,
     }
 }
 
@@ -33,6 +42,11 @@ table! {
         reverse_verification_shares -> Numeric,
         forward_ballot_shares -> Numeric,
         reverse_ballot_shares -> Numeric,
+        weight -> Int8,
+        forward_blinding -> Nullable<Numeric>,
+        reverse_blinding -> Nullable<Numeric>,
+        forward_threshold_shares -> Nullable<Numeric>,
+        reverse_threshold_shares -> Nullable<Numeric>,
     }
 }
 
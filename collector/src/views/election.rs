@@ -1,5 +1,8 @@
 use curv_kzen::BigInt;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
+use uuid_b64::UuidB64 as Uuid;
+
+use crate::protocol::shuffle_proof::ShuffleProof;
 
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -7,6 +10,146 @@ pub struct CreateElectionResponse {
   // Vector might be empty when returning from the second collector
   #[serde(with = "kzen_paillier::serialize::vecbigint")]
   pub encryption_result: Vec<BigInt>,
+
+  // Only provided for elections that use non-interactive ballot proofs
+  pub share_commitments: Vec<QuestionShareCommitments>,
+
+  // Used to check the cancelation shares when voting is closed
+  pub cancelation_commitments: Vec<QuestionCancelationCommitments>,
+
+  // Paillier public key n, used to encrypt the threshold shares sent to this collector
+  #[serde(with = "kzen_paillier::serialize::bigint")]
+  pub public_key: BigInt,
+
+  // Proof that the encryption result is a shuffle of the encrypted locations we received (Not on the last step)
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub shuffle_proof: Option<ShuffleProof>,
+
+  // Hash of the election manifest we accepted, which the mediator must send back with every verification
+  pub manifest_hash: String,
+}
+
+///
+/// Blinded commitments to the cancelation shares of every registered user (Same order as the registered users)
+///   D_c,i = g^(S~c,i - S_c,i) * h^(rho_c,i) and D_c,i' = g^(S~c,i' - S_c,i') * h^(rho_c,i')
+///
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuestionShareCommitments {
+  pub question_id: Uuid,
+
+  #[serde(with = "kzen_paillier::serialize::vecbigint")]
+  pub forward_commitments: Vec<BigInt>,
+
+  #[serde(with = "kzen_paillier::serialize::vecbigint")]
+  pub reverse_commitments: Vec<BigInt>,
+}
+
+///
+/// Commitments to the cancelation shares of every registered user (Same order as the registered users)
+///   X_c,i = g^(S~c,i - S_c,i) and X_c,i' = g^(S~c,i' - S_c,i')
+///
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuestionCancelationCommitments {
+  pub question_id: Uuid,
+
+  #[serde(with = "kzen_paillier::serialize::vecbigint")]
+  pub forward_commitments: Vec<BigInt>,
+
+  #[serde(with = "kzen_paillier::serialize::vecbigint")]
+  pub reverse_commitments: Vec<BigInt>,
+}
+
+///
+/// Commitments to the totals of the shares matrix for a single question
+///   Row totals g^(Σ S_c,i) come from the verification shares, column totals g^(Σ S~c,i) from the ballot shares
+///
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuestionShareTotals {
+  pub question_id: Uuid,
+  pub num_registrations: usize,
+
+  #[serde(with = "kzen_paillier::serialize::bigint")]
+  pub forward_row_total: BigInt,
+  #[serde(with = "kzen_paillier::serialize::bigint")]
+  pub forward_column_total: BigInt,
+
+  #[serde(with = "kzen_paillier::serialize::bigint")]
+  pub reverse_row_total: BigInt,
+  #[serde(with = "kzen_paillier::serialize::bigint")]
+  pub reverse_column_total: BigInt,
+}
+
+///
+/// Threshold shares for a single recipient collector, encrypted with the Paillier public key of that collector
+///
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ThresholdDealing {
+  pub questions: Vec<QuestionThresholdShares>,
+}
+
+///
+/// Encrypted Shamir shares f_c,i(j) of S~c,i - S_c,i for every registered user (Same order as the registered users)
+///
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuestionThresholdShares {
+  pub question_id: Uuid,
+
+  #[serde(with = "kzen_paillier::serialize::vecbigint")]
+  pub forward_shares: Vec<BigInt>,
+
+  #[serde(with = "kzen_paillier::serialize::vecbigint")]
+  pub reverse_shares: Vec<BigInt>,
+}
+
+///
+/// Location shares of every registered user (Same order as the registered users),
+///   encrypted with the Paillier public key of the key holder for the round
+///
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EncryptedLocationShares {
+  #[serde(with = "kzen_paillier::serialize::bigint")]
+  pub public_key: BigInt,
+
+  #[serde(with = "kzen_paillier::serialize::vecbigint")]
+  pub encrypted_locations: Vec<BigInt>,
+}
+
+///
+/// Round of distributed location anonymization, which is decrypted by the key holder at the end
+///
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocationRound {
+  /// Paillier public key of the key holder
+  #[serde(with = "kzen_paillier::serialize::bigint")]
+  pub public_key: BigInt,
+
+  /// Location shares of every collector, which are added together to start the round
+  ///   Empty for the first round, which starts from the locations 0 to N-1
+  #[serde(default)]
+  pub encrypted_shares: Vec<EncryptedLocationShares>,
+
+  /// Every shuffle in the round so far, in order
+  #[serde(default)]
+  pub links: Vec<LocationLink>,
+}
+
+///
+/// One shuffle in a round of distributed location anonymization,
+///   with the proof that the output is a shuffle of the output from the previous link
+///
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocationLink {
+  #[serde(with = "kzen_paillier::serialize::vecbigint")]
+  pub output: Vec<BigInt>,
+  pub proof: ShuffleProof,
 }
 
 #[derive(Debug, Serialize)]
@@ -33,6 +176,18 @@ pub struct QuestionParameters {
 
   #[serde(with = "kzen_paillier::serialize::bigint")]
   pub reverse_ballot_shares: BigInt,
+
+  // Needed to build a non-interactive ballot proof
+  #[serde(
+    skip_serializing_if = "Option::is_none",
+    with = "crate::utils::serialize_option_bigint"
+  )]
+  pub forward_blinding: Option<BigInt>,
+  #[serde(
+    skip_serializing_if = "Option::is_none",
+    with = "crate::utils::serialize_option_bigint"
+  )]
+  pub reverse_blinding: Option<BigInt>,
 }
 
 #[derive(Debug, Serialize)]
@@ -43,4 +198,16 @@ pub struct CancelationShares {
 
   #[serde(with = "kzen_paillier::serialize::bigint")]
   pub reverse_cancelation_shares: BigInt,
+
+  // Sum of the threshold shares for all users (Only used with threshold secret sharing)
+  #[serde(
+    skip_serializing_if = "Option::is_none",
+    with = "crate::utils::serialize_option_bigint"
+  )]
+  pub forward_threshold_shares: Option<BigInt>,
+  #[serde(
+    skip_serializing_if = "Option::is_none",
+    with = "crate::utils::serialize_option_bigint"
+  )]
+  pub reverse_threshold_shares: Option<BigInt>,
 }
@@ -53,6 +53,30 @@ export enum GlobalErrorCode {
   NoSubscribePermission,
   NotificationError,
   InvalidCollectorURI,
+  VotingVectorTooLarge,
+  VerificationModeNotSupported,
+  ShareCommitmentsInvalid,
+  BallotProofMissing,
+  BallotProofInvalid,
+  ThresholdSharingNotSupported,
+  ThresholdSharesInvalid,
+  NotEnoughCollectors,
+  ShuffleProofInvalid,
+  CancelationSharesInvalid,
+  CollectorMisbehaved,
+  IdentityKeyInvalid,
+  IdentityKeyMismatch,
+  ManifestInvalid,
+  SharesAuditError,
+  SharesAuditFailed,
+  PurgeElectionError,
+  WeightsRequireCounterEncoding,
+  InvalidTransition,
+  SaveCheckpointError,
+  BallotProofNotSupported,
+  LocationKeyInvalid,
+  BallotProofTooLarge,
+  BlockingTaskCanceled,
 }
 
 /**
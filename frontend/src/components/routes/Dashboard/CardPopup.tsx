@@ -5,9 +5,17 @@ export interface CardPopupProps {
   election: PublicElectionList;
 }
 
-const REGISTRATION_STATUS: ElectionStatus[] = [ElectionStatus.Registration, ElectionStatus.InitFailed];
+const REGISTRATION_STATUS: ElectionStatus[] = [
+  ElectionStatus.Registration,
+  ElectionStatus.Initializing,
+  ElectionStatus.InitFailed,
+];
 const VOTING_STATUS: ElectionStatus[] = [ElectionStatus.Voting];
-const CLOSED_STATUS: ElectionStatus[] = [ElectionStatus.CollectionFailed, ElectionStatus.Finished];
+const CLOSED_STATUS: ElectionStatus[] = [
+  ElectionStatus.Collecting,
+  ElectionStatus.CollectionFailed,
+  ElectionStatus.Finished,
+];
 
 export const CardPopup = ({ election }: CardPopupProps) => {
   if (!election.isRegistered) {
@@ -58,10 +58,15 @@ export enum DashboardFilter {
 
 type FilterFunction = (input: AllElectionsResult) => PublicElectionList[];
 
-const DRAFT_STATUS: ElectionStatus[] = [ElectionStatus.Draft, ElectionStatus.InitFailed];
+const DRAFT_STATUS: ElectionStatus[] = [ElectionStatus.Draft, ElectionStatus.Initializing, ElectionStatus.InitFailed];
 const OPEN_STATUS: ElectionStatus[] = [ElectionStatus.Registration];
 const VOTING_STATUS: ElectionStatus[] = [ElectionStatus.Voting];
-const CLOSED_STATUS: ElectionStatus[] = [ElectionStatus.Finished, ElectionStatus.CollectionFailed];
+const CLOSED_STATUS: ElectionStatus[] = [
+  ElectionStatus.Finished,
+  ElectionStatus.Collecting,
+  ElectionStatus.CollectionFailed,
+  ElectionStatus.Canceled,
+];
 
 const filterMyElectionsAll: FilterFunction = (input) => input.userElections;
 const filterMyElectionsDraft: FilterFunction = (input) =>
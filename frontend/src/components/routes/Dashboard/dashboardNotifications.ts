@@ -19,6 +19,7 @@ import {
   VoteReceivedEvent,
   VotingClosedEvent,
   ResultsPublishedEvent,
+  ElectionCanceledEvent,
   NameChangedEvent,
 } from 'notifications';
 import { mergeNestedState } from 'redux/helpers';
@@ -66,6 +67,7 @@ const onMessageElectionList = buildNotificationHandler({
   [ElectionEvents.VoteReceived]: handleVoteReceived,
   [ElectionEvents.VotingClosed]: handleVotingClosed,
   [ElectionEvents.ResultsPublished]: handleResultsPublished,
+  [ElectionEvents.ElectionCanceled]: handleElectionCanceled,
 });
 
 /**
@@ -144,7 +146,7 @@ function handleUserUnregistered(event: UserUnregisteredEvent): void {
 }
 
 function handleRegistrationClosed(event: RegistrationClosedEvent): void {
-  mergeElection(event.electionId, { status: ElectionStatus.InitFailed });
+  mergeElection(event.electionId, { status: ElectionStatus.Initializing });
 }
 
 function handleVotingOpened(event: VotingOpenedEvent): void {
@@ -159,13 +161,17 @@ function handleVoteReceived(event: VoteReceivedEvent): void {
 }
 
 function handleVotingClosed(event: VotingClosedEvent): void {
-  mergeElection(event.electionId, { status: ElectionStatus.CollectionFailed });
+  mergeElection(event.electionId, { status: ElectionStatus.Collecting });
 }
 
 function handleResultsPublished(event: ResultsPublishedEvent): void {
   mergeElection(event.electionId, { status: ElectionStatus.Finished });
 }
 
+function handleElectionCanceled(event: ElectionCanceledEvent): void {
+  mergeElection(event.electionId, { status: ElectionStatus.Canceled });
+}
+
 /**
  * Fetch the election details from the API backend
  *
@@ -179,7 +179,12 @@ export const ManageElection = () => {
                               {election.registered.map((user) => (
                                 <List.Item key={user.id} className={styles['question-item']}>
                                   <Flex justify="space-between" alignItems="center">
-                                    {user.name}
+                                    <span>
+                                      {user.name}
+                                      {user.weight !== 1 && (
+                                        <Label basic size="tiny" content={`Weight ${user.weight}`} />
+                                      )}
+                                    </span>
                                     {user.hasVotedStatus === HasVotedStatus.Yes ? (
                                       <Label color="green" icon="check square outline" content="Voted" />
                                     ) : (
@@ -234,6 +239,9 @@ export const ManageElection = () => {
                 [ElectionStatus.Voting]: <VotingControls election={election} />,
                 [ElectionStatus.CollectionFailed]: <CollectionFailedControls election={election} />,
                 [ElectionStatus.Finished]: <FinishedControls election={election} />,
+                [ElectionStatus.Canceled]: null,
+                [ElectionStatus.Initializing]: null,
+                [ElectionStatus.Collecting]: null,
               }[election.status]
             }
           </Segment>
@@ -177,9 +177,10 @@ export const register = async (electionId: string): Promise<void> => {
         const registered =
           registeredIndex > -1
             ? props.registered
-            : [...props.registered, { id: userId, name, hasVotedStatus: HasVotedStatus.No }].sort((a, b) =>
-                a.name.localeCompare(b.name),
-              );
+            : [
+                ...props.registered,
+                { id: userId, name, hasVotedStatus: HasVotedStatus.No, weight: 1 },
+              ].sort((a, b) => a.name.localeCompare(b.name));
 
         return {
           isRegistered: true,
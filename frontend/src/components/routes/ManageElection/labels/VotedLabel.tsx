@@ -9,6 +9,7 @@ export interface VotedLabelProps {
 const HIDE_LABEL_STATUS: ElectionStatus[] = [
   ElectionStatus.Draft,
   ElectionStatus.Registration,
+  ElectionStatus.Initializing,
   ElectionStatus.InitFailed,
 ];
 
@@ -60,9 +60,12 @@ export async function tryReFetchElection(electionId: string): Promise<void> {
   if (electionCollectors.loading || !electionCollectors.success) {
     // Only certain election statuses should fetch the election collectors
     if (
-      [ElectionStatus.Voting, ElectionStatus.CollectionFailed, ElectionStatus.Finished].includes(
-        electionDetails.data.status,
-      )
+      [
+        ElectionStatus.Voting,
+        ElectionStatus.Collecting,
+        ElectionStatus.CollectionFailed,
+        ElectionStatus.Finished,
+      ].includes(electionDetails.data.status)
     ) {
       electionCollectors = await axiosApi
         .get<PublicCollectorList[]>(`/mediator/elections/${electionId}/collectors`)
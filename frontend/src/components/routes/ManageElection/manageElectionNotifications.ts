@@ -18,6 +18,8 @@ import {
   VoteReceivedEvent,
   VotingClosedEvent,
   ResultsPublishedEvent,
+  CollectionProgressEvent,
+  ElectionCanceledEvent,
 } from 'notifications';
 import { getNestedState, mergeNestedState } from 'redux/helpers';
 import { isDev } from 'env';
@@ -44,6 +46,8 @@ const onMessageManageElection = buildNotificationHandler({
   [ElectionEvents.VoteReceived]: handleVoteReceived,
   [ElectionEvents.VotingClosed]: handleVotingClosed,
   [ElectionEvents.ResultsPublished]: handleResultsPublished,
+  [ElectionEvents.CollectionProgress]: handleCollectionProgress,
+  [ElectionEvents.ElectionCanceled]: handleElectionCanceled,
 });
 
 /**
@@ -123,9 +127,10 @@ function handleUserRegistered(event: UserRegisteredEvent): void {
     const registered =
       userIndex > -1
         ? election.registered
-        : [...election.registered, { id: event.userId, name: event.userName, hasVotedStatus: HasVotedStatus.No }].sort(
-            (a, b) => a.name.localeCompare(b.name),
-          );
+        : [
+            ...election.registered,
+            { id: event.userId, name: event.userName, hasVotedStatus: HasVotedStatus.No, weight: 1 },
+          ].sort((a, b) => a.name.localeCompare(b.name));
 
     return { registered, isRegistered: event.userId === userId ? true : election.isRegistered };
   });
@@ -143,7 +148,7 @@ function handleUserUnregistered(event: UserUnregisteredEvent): void {
 }
 
 function handleRegistrationClosed(event: RegistrationClosedEvent): void {
-  mergeElection({ status: ElectionStatus.InitFailed, accessCode: undefined, isPublic: event.isPublic });
+  mergeElection({ status: ElectionStatus.Initializing, accessCode: undefined, isPublic: event.isPublic });
 }
 
 function handleVotingOpened(event: VotingOpenedEvent): void {
@@ -198,13 +203,26 @@ function handleVoteReceived(event: VoteReceivedEvent): void {
 }
 
 function handleVotingClosed(event: VotingClosedEvent): void {
-  mergeElection({ status: ElectionStatus.CollectionFailed });
+  mergeElection({ status: ElectionStatus.Collecting });
 }
 
 function handleResultsPublished(event: ResultsPublishedEvent): void {
   mergeElection({ status: ElectionStatus.Finished });
 }
 
+function handleCollectionProgress(event: CollectionProgressEvent): void {
+  mergeElection((election) => ({
+    questions: election.questions.map((question) => {
+      const progress = event.questions.find((q) => q.questionId === question.id);
+      return progress ? { ...question, collectionStatus: progress.collectionStatus } : question;
+    }),
+  }));
+}
+
+function handleElectionCanceled(event: ElectionCanceledEvent): void {
+  mergeElection({ status: ElectionStatus.Canceled });
+}
+
 /**
  * Fetch the election details from the API backend
  *
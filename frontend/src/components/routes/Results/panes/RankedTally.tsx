@@ -0,0 +1,73 @@
+/* eslint-disable react/no-array-index-key */
+import pluralize from 'pluralize';
+import { Header, Card, Table, Icon, Divider } from 'semantic-ui-react';
+import { nestedSelectorHook } from 'redux/helpers';
+
+const useSelector = nestedSelectorHook('results');
+
+/**
+ * Show every round of the instant-runoff count, followed by the Borda points of every candidate
+ *   Candidates eliminated in an earlier round have an empty cell in the later rounds
+ */
+export const RankedTally = () => {
+  const currentIndex = useSelector((state) => state.currentQuestionIndex);
+  const question = useSelector((state) => state.questions[currentIndex]);
+
+  const rounds = question.instantRunoff ?? [];
+  const bordaCount = question.bordaCount ?? {};
+  const mostPoints = Object.values(bordaCount).reduce((max, points) => Math.max(points, max), 0);
+
+  return (
+    <>
+      <Header>Instant Runoff:</Header>
+      <div style={{ overflowX: 'auto' }}>
+        <Table celled definition>
+          <Table.Header>
+            <Table.Row>
+              <Table.HeaderCell />
+              {rounds.map((_, round) => (
+                <Table.HeaderCell key={round} textAlign="center" singleLine>
+                  Round {round + 1}
+                </Table.HeaderCell>
+              ))}
+            </Table.Row>
+          </Table.Header>
+
+          <Table.Body>
+            {question.candidates.map((candidate, i) => (
+              <Table.Row key={i}>
+                <Table.Cell singleLine>{candidate.name}</Table.Cell>
+                {rounds.map((round, r) => (
+                  <Table.Cell
+                    key={r}
+                    textAlign="center"
+                    singleLine
+                    positive={round.winner === i}
+                    negative={round.eliminated === i}
+                  >
+                    {round.candidateVotes[i] && pluralize('Vote', round.candidateVotes[i].numVotes, true)}
+                    {round.winner === i && <Icon name="trophy" color="green" style={{ marginLeft: '0.5em' }} />}
+                    {round.eliminated === i && <Icon name="close" color="red" style={{ marginLeft: '0.5em' }} />}
+                  </Table.Cell>
+                ))}
+              </Table.Row>
+            ))}
+          </Table.Body>
+        </Table>
+      </div>
+
+      <Divider hidden />
+      <Header>Borda Count:</Header>
+      <Card.Group stackable itemsPerRow="3" centered={question.candidates.length < 3}>
+        {question.candidates.map((candidate, i) => (
+          <Card key={`${i}-${candidate.name}`} raised={bordaCount[i] === mostPoints}>
+            <Card.Content>
+              <Card.Header content={candidate.name} />
+              <Card.Description content={pluralize('Point', bordaCount[i] ?? 0, true)} />
+            </Card.Content>
+          </Card>
+        ))}
+      </Card.Group>
+    </>
+  );
+};
@@ -10,9 +10,6 @@ export const RawValuesPane = () => {
   const questionIndex = useSelector((state) => state.currentQuestionIndex);
   const question = useSelector((state) => state.questions[questionIndex]);
 
-  const generator = useSelector((state) => state.generator);
-  const prime = useSelector((state) => state.prime);
-
   const rawTab = useSelector((state) => state.questions[questionIndex].rawTab);
   const currentUserId = useUserId();
   const tabAnimation = useTabAnimation();
@@ -106,14 +103,14 @@ export const RawValuesPane = () => {
                               <Table.Cell textAlign="center">
                                 <Latex>$g$</Latex>
                               </Table.Cell>
-                              <Table.Cell>{generator.toString(10)}</Table.Cell>
+                              <Table.Cell>{question.generator.toString(10)}</Table.Cell>
                             </Table.Row>
 
                             <Table.Row>
                               <Table.Cell textAlign="center">
                                 <Latex>$p$</Latex>
                               </Table.Cell>
-                              <Table.Cell>{prime.toString(10)}</Table.Cell>
+                              <Table.Cell>{question.prime.toString(10)}</Table.Cell>
                             </Table.Row>
 
                             {question.forwardBallots && (
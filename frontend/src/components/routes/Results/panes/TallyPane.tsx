@@ -3,6 +3,7 @@ import pluralize from 'pluralize';
 import { Header, Card, Tab, Label, Popup, Transition } from 'semantic-ui-react';
 import { nestedSelectorHook } from 'redux/helpers';
 import { useTabAnimation } from './panesActions';
+import { RankedTally } from './RankedTally';
 
 const useSelector = nestedSelectorHook('results');
 
@@ -17,41 +18,47 @@ export const TallyPane = () => {
   return (
     <Transition animation={tabAnimation} duration={300} transitionOnMount>
       <Tab.Pane>
-        <Header>
-          Final Tally:
-          {!question.ballotValid && <Label color="red" icon="exclamation" content="Invalid!" />}
-        </Header>
-        <Card.Group stackable itemsPerRow="3" centered={question.candidates.length < 3}>
-          {question.candidates.map((candidate, i) => (
-            <Card key={`${i}-${candidate}`} raised={candidate.numVotes === winningVotes}>
-              <Card.Content>
-                <Card.Header content={candidate.name} />
-                <Card.Description
-                  content={question.ballotValid ? pluralize('Vote', candidate.numVotes ?? 0, true) : '{Invalid}'}
-                />
-
-                {candidate.numVotes === winningVotes &&
-                  (isTied ? (
-                    <Popup
-                      on="hover"
-                      size="mini"
-                      content={<Label color="yellow" content="Tied" />}
-                      position="right center"
-                      trigger={<Label corner="right" color="yellow" icon="trophy" />}
-                    />
-                  ) : (
-                    <Popup
-                      on="hover"
-                      size="mini"
-                      content={<Label color="green" content="Winner" />}
-                      position="right center"
-                      trigger={<Label corner="right" color="green" icon="trophy" />}
+        {question.instantRunoff ? (
+          <RankedTally />
+        ) : (
+          <>
+            <Header>
+              Final Tally:
+              {!question.ballotValid && <Label color="red" icon="exclamation" content="Invalid!" />}
+            </Header>
+            <Card.Group stackable itemsPerRow="3" centered={question.candidates.length < 3}>
+              {question.candidates.map((candidate, i) => (
+                <Card key={`${i}-${candidate}`} raised={candidate.numVotes === winningVotes}>
+                  <Card.Content>
+                    <Card.Header content={candidate.name} />
+                    <Card.Description
+                      content={question.ballotValid ? pluralize('Vote', candidate.numVotes ?? 0, true) : '{Invalid}'}
                     />
-                  ))}
-              </Card.Content>
-            </Card>
-          ))}
-        </Card.Group>
+
+                    {candidate.numVotes === winningVotes &&
+                      (isTied ? (
+                        <Popup
+                          on="hover"
+                          size="mini"
+                          content={<Label color="yellow" content="Tied" />}
+                          position="right center"
+                          trigger={<Label corner="right" color="yellow" icon="trophy" />}
+                        />
+                      ) : (
+                        <Popup
+                          on="hover"
+                          size="mini"
+                          content={<Label color="green" content="Winner" />}
+                          position="right center"
+                          trigger={<Label corner="right" color="green" icon="trophy" />}
+                        />
+                      ))}
+                  </Card.Content>
+                </Card>
+              ))}
+            </Card.Group>
+          </>
+        )}
       </Tab.Pane>
     </Transition>
   );
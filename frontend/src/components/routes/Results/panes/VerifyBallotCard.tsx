@@ -1,6 +1,7 @@
 import { Button, Card, Icon, Message } from 'semantic-ui-react';
 import { getErrorInformation } from 'api';
 import { UserBallotResult } from 'models/election';
+import { FailureCause } from 'models/verification';
 import { nestedSelectorHook } from 'redux/helpers';
 import { clearVerifyResult, verifyBallot } from './panesActions';
 
@@ -40,7 +41,18 @@ export const VerifyBallotCard = ({
     }
 
     if (verifying.data !== undefined) {
-      const allPositive = verifying.data.subProtocol1 && verifying.data.subProtocol2;
+      const { failure } = verifying.data;
+      const allPositive = verifying.data.subProtocol1 && verifying.data.subProtocol2 && failure === undefined;
+
+      // A collector failure means the ballot was never fully checked
+      const collectorFailure =
+        failure?.cause === FailureCause.Collector
+          ? [
+              `${failure.collectorIndex !== null ? `Collector ${failure.collectorIndex + 1}` : 'A collector'} ` +
+                `misbehaved (${failure.check})`,
+            ]
+          : [];
+
       return (
         <Message
           positive={allPositive}
@@ -49,6 +61,7 @@ export const VerifyBallotCard = ({
           list={[
             `Sub-Protocol 1: ${verifying.data.subProtocol1 ? 'Valid' : 'Invalid'}`,
             `Sub-Protocol 2: ${verifying.data.subProtocol2 ? 'Valid' : 'Invalid'}`,
+            ...collectorFailure,
           ]}
           onDismiss={() => clearVerifyResult(questionIndex, ballotIndex)}
         />
@@ -1,6 +1,9 @@
 /* eslint-disable react/no-array-index-key */
-import { parseVotingVector } from 'protocol';
+import pluralize from 'pluralize';
+import { BallotEncoding, QuestionType } from 'models/election';
+import { parseCounterVector, parseRankedVotingVector, parseVotingVector } from 'protocol';
 import { nestedSelectorHook } from 'redux/helpers';
+import { ExtendedCandidatesResult } from 'redux/state/results';
 import { Header, Card, Tab, Divider, Button, Transition } from 'semantic-ui-react';
 import { setVectorTab, toggleShowVote, useTabAnimation, verifySum } from './panesActions';
 import { VerifySumModal } from './VerifySumModal';
@@ -9,26 +12,79 @@ const useSelector = nestedSelectorHook('results');
 
 const VOTE_STYLE = { outline: '5px solid red' };
 
+interface CounterCardsProps {
+  votes?: number[];
+  candidates: ExtendedCandidatesResult[];
+}
+
+/**
+ * Show the digit of every candidate in a counter-encoded voting vector
+ */
+const CounterCards = ({ votes, candidates }: CounterCardsProps) => {
+  if (votes === undefined) {
+    return (
+      <Card.Group stackable itemsPerRow="4">
+        <Card>
+          <Card.Content>
+            <Card.Header content="{Invalid}" />
+          </Card.Content>
+        </Card>
+      </Card.Group>
+    );
+  }
+
+  return (
+    <Card.Group stackable itemsPerRow="4">
+      {candidates.map((candidate, i) => (
+        <Card key={i}>
+          <Card.Content>
+            <Card.Header content={candidate.name} />
+          </Card.Content>
+          <Card.Content extra>{pluralize('Vote', votes[i], true)}</Card.Content>
+        </Card>
+      ))}
+    </Card.Group>
+  );
+};
+
 export const VotingVectorPane = () => {
   const currentIndex = useSelector((state) => state.currentQuestionIndex);
   const question = useSelector((state) => state.questions[currentIndex]);
   const numRegistered = useSelector((state) =>
     state.electionParams.loading || !state.electionParams.success ? 0 : state.electionParams.data.numRegistered,
   );
+  const counterRadix = useSelector((state) =>
+    state.electionParams.loading || !state.electionParams.success ? 2 : state.electionParams.data.counterRadix,
+  );
 
   const showVote = useSelector((state) => state.questions[currentIndex].showVote);
   const encryptedLocation = useSelector((state) => state.encryptedLocation);
 
-  const parsedForwardVector = parseVotingVector(
-    BigInt(question.forwardBallots!),
-    question.candidates.length,
-    numRegistered,
-  );
+  // Ranked chunks list the candidates from first to last preference
+  const isRanked = question.questionType === QuestionType.Ranked;
+  const separator = isRanked ? ' > ' : ', ';
+  const parseVector = (vector: bigint, reverse: boolean) =>
+    isRanked
+      ? parseRankedVotingVector(vector, question.candidates.length, numRegistered, reverse)
+      : parseVotingVector(
+          vector,
+          question.candidates.length,
+          numRegistered,
+          question.minSelections,
+          question.maxSelections,
+          reverse,
+        );
+
+  const parsedForwardVector = parseVector(BigInt(question.forwardBallots!), false);
+  const parsedReverseVector = parseVector(BigInt(question.reverseBallots!), true);
 
-  const parsedReverseVector = parseVotingVector(
+  // Counter questions mix every ballot into a single digit for each candidate, so there is no chunk for each voter
+  const isCounter = question.ballotEncoding === BallotEncoding.Counter;
+  const forwardCounter = parseCounterVector(BigInt(question.forwardBallots!), question.candidates.length, counterRadix);
+  const reverseCounter = parseCounterVector(
     BigInt(question.reverseBallots!),
     question.candidates.length,
-    numRegistered,
+    counterRadix,
     true,
   );
 
@@ -49,27 +105,31 @@ export const VotingVectorPane = () => {
                 <Tab.Pane attached={false} secondary raised>
                   <Header>Forward Voting Vector:</Header>
                   <Divider />
-                  <Card.Group stackable itemsPerRow="4">
-                    {parsedForwardVector.map(({ candidatePicked, bits }, i) => (
-                      <Card
-                        key={i}
-                        style={
-                          showVote && encryptedLocation === BigInt(numRegistered - (i + 1)) ? VOTE_STYLE : undefined
-                        }
-                      >
-                        <Card.Content>
-                          <Card.Header
-                            content={
-                              typeof candidatePicked === 'number'
-                                ? question.candidates[candidatePicked].name
-                                : `{${candidatePicked === null ? 'Empty' : 'Invalid'}}`
-                            }
-                          />
-                        </Card.Content>
-                        <Card.Content extra>{bits}</Card.Content>
-                      </Card>
-                    ))}
-                  </Card.Group>
+                  {isCounter ? (
+                    <CounterCards votes={forwardCounter} candidates={question.candidates} />
+                  ) : (
+                    <Card.Group stackable itemsPerRow="4">
+                      {parsedForwardVector.map(({ candidatesPicked, bits }, i) => (
+                        <Card
+                          key={i}
+                          style={
+                            showVote && encryptedLocation === BigInt(numRegistered - (i + 1)) ? VOTE_STYLE : undefined
+                          }
+                        >
+                          <Card.Content>
+                            <Card.Header
+                              content={
+                                candidatesPicked
+                                  ? candidatesPicked.map((c) => question.candidates[c].name).join(separator)
+                                  : `{${candidatesPicked === null ? 'Empty' : 'Invalid'}}`
+                              }
+                            />
+                          </Card.Content>
+                          <Card.Content extra>{bits}</Card.Content>
+                        </Card>
+                      ))}
+                    </Card.Group>
+                  )}
                 </Tab.Pane>
               ),
             },
@@ -80,22 +140,26 @@ export const VotingVectorPane = () => {
                 <Tab.Pane attached={false} secondary raised>
                   <Header>Reverse Voting Vector:</Header>
                   <Divider />
-                  <Card.Group stackable itemsPerRow="4">
-                    {parsedReverseVector.map(({ candidatePicked, bits }, i) => (
-                      <Card key={i} style={showVote && encryptedLocation === BigInt(i) ? VOTE_STYLE : undefined}>
-                        <Card.Content>
-                          <Card.Header
-                            content={
-                              typeof candidatePicked === 'number'
-                                ? question.candidates[candidatePicked].name
-                                : `{${candidatePicked === null ? 'Empty' : 'Invalid'}}`
-                            }
-                          />
-                        </Card.Content>
-                        <Card.Content extra>{bits}</Card.Content>
-                      </Card>
-                    ))}
-                  </Card.Group>
+                  {isCounter ? (
+                    <CounterCards votes={reverseCounter} candidates={question.candidates} />
+                  ) : (
+                    <Card.Group stackable itemsPerRow="4">
+                      {parsedReverseVector.map(({ candidatesPicked, bits }, i) => (
+                        <Card key={i} style={showVote && encryptedLocation === BigInt(i) ? VOTE_STYLE : undefined}>
+                          <Card.Content>
+                            <Card.Header
+                              content={
+                                candidatesPicked
+                                  ? candidatesPicked.map((c) => question.candidates[c].name).join(separator)
+                                  : `{${candidatesPicked === null ? 'Empty' : 'Invalid'}}`
+                              }
+                            />
+                          </Card.Content>
+                          <Card.Content extra>{bits}</Card.Content>
+                        </Card>
+                      ))}
+                    </Card.Group>
+                  )}
                 </Tab.Pane>
               ),
             },
@@ -103,7 +167,7 @@ export const VotingVectorPane = () => {
         />
 
         <Divider horizontal />
-        {encryptedLocation !== null && (
+        {encryptedLocation !== null && !isCounter && (
           <Button
             primary
             size="large"
@@ -55,9 +55,9 @@ export const verifySum = (): void => {
 
   // Add a bit of delay to make the program "feel" like it is chugging away
   setTimeout(() => {
-    const { questions, currentQuestionIndex, prime } = getState();
+    const { questions, currentQuestionIndex } = getState();
     const question = questions[currentQuestionIndex];
-    const modulus = BigInt(prime) - BigInt(1);
+    const modulus = question.groupOrder;
 
     const forwardSum =
       question.ballots.reduce(
@@ -159,12 +159,13 @@ export const setRawTab = (event: React.MouseEvent<HTMLDivElement>, { activeIndex
  * Export the election results as a JSON file
  */
 export const exportResultsJSON = (): void => {
-  const { prime, generator, questions, currentQuestionIndex } = getState();
+  const { questions, currentQuestionIndex } = getState();
   const question = questions[currentQuestionIndex];
 
   const data = {
-    prime: prime.toString(10),
-    generator: generator.toString(10),
+    prime: question.prime.toString(10),
+    generator: question.generator.toString(10),
+    groupOrder: question.groupOrder.toString(10),
     forwardBallotSum: question.forwardBallots ?? '0',
     reverseBallotSum: question.reverseBallots ?? '0',
     forwardCancelationShares: question.forwardCancelationShares ?? '0',
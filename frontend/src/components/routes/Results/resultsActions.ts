@@ -80,8 +80,9 @@ export async function tryReFetchData(electionId: string): Promise<void> {
   // Build out the list of questions
   const allQuestionResults = electionResults.data.questionResults;
   const tab = electionDetails.data.status !== ElectionStatus.Finished ? 2 : 0;
-  const questions: ExtendedQuestionResult[] = electionDetails.data.questions.map((question) => {
+  const questions: ExtendedQuestionResult[] = electionDetails.data.questions.map((question, questionIndex) => {
     const questionResults = allQuestionResults[question.id];
+    const questionParams = electionParams.data.questions[questionIndex];
 
     return {
       ...questionResults,
@@ -91,6 +92,13 @@ export async function tryReFetchData(electionId: string): Promise<void> {
         name: candidate,
         numVotes: questionResults?.candidateVotes?.[i].numVotes,
       })),
+      questionType: questionParams.questionType,
+      ballotEncoding: questionParams.ballotEncoding,
+      minSelections: questionParams.minSelections,
+      maxSelections: questionParams.maxSelections,
+      generator: BigInt(questionParams.generator),
+      prime: BigInt(questionParams.prime),
+      groupOrder: BigInt(questionParams.groupOrder),
       ballots: questionResults.userBallots.map((ballot) => ({ ...ballot, verifying: apiSuccess(undefined) })),
       showVote: false,
       currentTab: tab,
@@ -99,7 +107,7 @@ export async function tryReFetchData(electionId: string): Promise<void> {
       rawTab: 0,
     };
   });
-  mergeState({ questions, generator: BigInt(electionParams.data.generator), prime: BigInt(electionParams.data.prime) });
+  mergeState({ questions });
 
   // Try to fetch the list of election collectors if they failed to fetch
   let { electionCollectors } = getState();
@@ -224,6 +232,7 @@ const FATAL_ERROR_CODES: GlobalErrorCode[] = [
 
 const VOTING_STARTED_STATUS: ElectionStatus[] = [
   ElectionStatus.Voting,
+  ElectionStatus.Collecting,
   ElectionStatus.CollectionFailed,
   ElectionStatus.Finished,
 ];
@@ -245,7 +254,7 @@ export const getFatalError = (input: APIResult<PublicElectionDetails>): string |
   // Run through the errors that keep us from voting
   //  These all come from the backend logic
   const election = input.data;
-  if (election.status === ElectionStatus.InitFailed) {
+  if (election.status === ElectionStatus.Initializing || election.status === ElectionStatus.InitFailed) {
     return 'Election parameters have not been initialized';
   }
 
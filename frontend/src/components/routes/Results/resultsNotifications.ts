@@ -56,7 +56,15 @@ function handleVotingOpened(event: VotingOpenedEvent): void {
     name: `Collector ${index + 1}`,
   }));
 
-  mergeElectionParams({ prime: event.prime, generator: event.generator, locationModulus: event.locationModulus });
+  mergeElectionParams((params) => ({
+    // Questions are sent in the same order as the election parameters
+    questions: params.questions.map((question, i) => ({
+      ...question,
+      prime: event.questions[i]?.prime ?? question.prime,
+      generator: event.questions[i]?.generator ?? question.generator,
+    })),
+    locationModulus: event.locationModulus,
+  }));
   mergeState({ electionCollectors: apiSuccess(collectorList) });
   mergeElection({ status: ElectionStatus.Voting });
 }
@@ -99,7 +107,7 @@ function handleVoteReceived(event: VoteReceivedEvent): void {
 }
 
 function handleVotingClosed(event: VotingClosedEvent): void {
-  mergeElection({ status: ElectionStatus.CollectionFailed });
+  mergeElection({ status: ElectionStatus.Collecting });
 }
 
 function handleResultsPublished(event: ResultsPublishedEvent): void {
@@ -1,8 +1,21 @@
-import { Segment, Header, Form, Radio, Divider, Checkbox, Popup, Message } from 'semantic-ui-react';
+import { Segment, Header, Form, Radio, Divider, Checkbox, Popup, Message, Button, Label } from 'semantic-ui-react';
 import { useState } from 'react';
-import { setChoice, toggleChoice, useQuestion } from './voteActions';
+import { QuestionType } from 'models/election';
+import { setChoice, toggleChoice, toggleRank, useQuestion, useQuestionParameters } from './voteActions';
 import styles from './vote.module.scss';
 
+/**
+ * Describe how many candidates can be selected for a multiple-choice question
+ *
+ * @param minSelections Fewest candidates to select
+ * @param maxSelections Most candidates to select
+ * @returns Hint to show above the candidates
+ */
+const getSelectionHint = (minSelections: number, maxSelections: number): string =>
+  minSelections === maxSelections
+    ? `Select ${maxSelections} candidates`
+    : `Select between ${minSelections} and ${maxSelections} candidates`;
+
 export interface QuestionBoxProps {
   questionIndex: number;
   cheatMode?: boolean;
@@ -12,7 +25,57 @@ export interface QuestionBoxProps {
 export const QuestionBox = ({ questionIndex, cheatMode, disabled }: QuestionBoxProps): JSX.Element => {
   const [popupOpen, setPopupOpen] = useState(false);
   const question = useQuestion(questionIndex);
+  const questionParams = useQuestionParameters(questionIndex);
   const formDisabled = disabled || question.hasVoted;
+  const isRanked = questionParams?.questionType === QuestionType.Ranked;
+  const minSelections = questionParams?.minSelections ?? 1;
+  const maxSelections = questionParams?.maxSelections ?? 1;
+  const isMultipleChoice = !isRanked && maxSelections > 1;
+
+  // Ranked questions show the rank of every candidate, while choice questions use radio buttons or checkboxes
+  const candidateInput = (candidate: string, i: number): JSX.Element => {
+    if (isRanked) {
+      const rank = question.ranking.indexOf(i);
+      return (
+        <Button
+          basic
+          fluid
+          type="button"
+          className={styles['rank-button']}
+          active={rank >= 0}
+          onClick={() => toggleRank(questionIndex, i)}
+          disabled={formDisabled}
+        >
+          <Label circular color={rank >= 0 ? 'blue' : undefined} content={rank >= 0 ? rank + 1 : '-'} />
+          {candidate}
+        </Button>
+      );
+    }
+
+    if (cheatMode || isMultipleChoice) {
+      // Outside of "cheat" mode, stop selecting candidates once the maximum is reached
+      const limitReached = !cheatMode && !question.choices.has(i) && question.choices.size >= maxSelections;
+      return (
+        <Checkbox
+          className={styles['radio-overflow']}
+          label={candidate}
+          checked={question.choices.has(i)}
+          onChange={() => toggleChoice(questionIndex, i)}
+          disabled={formDisabled || limitReached}
+        />
+      );
+    }
+
+    return (
+      <Radio
+        className={styles['radio-overflow']}
+        label={candidate}
+        checked={question.choices.has(i)}
+        onChange={() => setChoice(questionIndex, i)}
+        disabled={formDisabled}
+      />
+    );
+  };
 
   const formComponent = (
     <Segment raised padded textAlign="left" disabled={formDisabled}>
@@ -22,29 +85,19 @@ export const QuestionBox = ({ questionIndex, cheatMode, disabled }: QuestionBoxP
 
       <Divider />
 
+      {isRanked && !question.hasVoted && (
+        <Message size="small" content="Select every candidate in order of preference, starting with your top choice" />
+      )}
+
+      {isMultipleChoice && !question.hasVoted && (
+        <Message size="small" content={getSelectionHint(minSelections, maxSelections)} />
+      )}
+
       <Form>
         {question.candidates.map((candidate, i) => (
           // This is intended behavior, as the cnadidate order will NOT change
           // eslint-disable-next-line react/no-array-index-key
-          <Form.Field key={`${i}-${candidate}`}>
-            {cheatMode ? (
-              <Checkbox
-                className={styles['radio-overflow']}
-                label={candidate}
-                checked={question.choices.has(i)}
-                onChange={() => toggleChoice(questionIndex, i)}
-                disabled={formDisabled}
-              />
-            ) : (
-              <Radio
-                className={styles['radio-overflow']}
-                label={candidate}
-                checked={question.choices.has(i)}
-                onChange={() => setChoice(questionIndex, i)}
-                disabled={formDisabled}
-              />
-            )}
-          </Form.Field>
+          <Form.Field key={`${i}-${candidate}`}>{candidateInput(candidate, i)}</Form.Field>
         ))}
       </Form>
     </Segment>
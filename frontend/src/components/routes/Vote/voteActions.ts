@@ -21,11 +21,14 @@ import {
   ElectionStatus,
   HasVotedStatus,
   PublicElectionDetails,
+  QuestionParameters,
+  QuestionType,
 } from 'models/election';
+import { getUserId } from 'redux/auth';
 import { clearNestedState, getNestedState, mergeNestedState, nestedSelectorHook } from 'redux/helpers';
 import { QuestionDetails, VoteState, VotingStatus } from 'redux/state';
 import { showConfirm } from 'showConfirm';
-import { getVotingVector, computeBallot } from 'protocol';
+import { getVotingVector, getRankedVotingVector, computeBallot, computeBallotProof } from 'protocol';
 import { PublicCollectorList } from 'models/mediator';
 
 const getState = getNestedState('vote');
@@ -139,6 +142,7 @@ function setElection(electionDetails: APISuccess<PublicElectionDetails> | APIErr
     candidates: question.candidates,
     hasVoted: question.hasVoted,
     choices: new Set(),
+    ranking: [],
     voting: apiSuccess(false),
   }));
 
@@ -228,7 +232,7 @@ export const getFatalError = (input: APIResult<PublicElectionDetails>): string |
   // Run through the errors that keep us from voting
   //  These all come from the backend logic
   const election = input.data;
-  if (election.status === ElectionStatus.InitFailed) {
+  if (election.status === ElectionStatus.Initializing || election.status === ElectionStatus.InitFailed) {
     return 'Election parameters have not been initialized';
   }
 
@@ -262,16 +266,36 @@ export const useSetVoteTitle = (electionDetails: APIResult<PublicElectionDetails
 export const useQuestion = (questionIndex: number): QuestionDetails =>
   useSelector((state) => state.questions[questionIndex]);
 
+/**
+ * Hook to get the parameters for a question, which are undefined until the election parameters are loaded
+ */
+export const useQuestionParameters = (questionIndex: number): QuestionParameters | undefined =>
+  useSelector((state) => getQuestionParameters(state, questionIndex));
+
+/**
+ * Get the parameters for a question from the state
+ *
+ * @param state Current voting state
+ * @param questionIndex Question to find
+ * @returns Parameters, or undefined if they are not loaded yet
+ */
+function getQuestionParameters(state: VoteState, questionIndex: number): QuestionParameters | undefined {
+  const { electionParams } = state;
+  return electionParams.loading || !electionParams.success ? undefined : electionParams.data.questions[questionIndex];
+}
+
 /**
  * Switch between "cheat" mode and normal mode
  */
 export const toggleCheatMode = (): void => {
-  const { cheatMode, questions } = getState();
+  const state = getState();
+  const { cheatMode, questions } = state;
 
-  const newQuestions: QuestionDetails[] = questions.map((question) => ({
-    ...question,
-    choices: question.choices.size <= 1 ? question.choices : new Set(),
-  }));
+  // Leaving "cheat" mode clears any choices that select too many candidates
+  const newQuestions: QuestionDetails[] = questions.map((question, index) => {
+    const maxSelections = getQuestionParameters(state, index)?.maxSelections ?? 1;
+    return { ...question, choices: question.choices.size <= maxSelections ? question.choices : new Set() };
+  });
 
   mergeState({ cheatMode: !cheatMode, questions: newQuestions });
 };
@@ -298,7 +322,7 @@ export const setChoice = (questionIndex: number, choice: number): void => {
 };
 
 /**
- * Toggle a choice on or off for "cheat" mode
+ * Toggle a choice on or off for multiple-choice questions or "cheat" mode
  *
  * @param questionIndex Question to update
  * @param choice Which candidate to toggle
@@ -324,12 +348,41 @@ export const toggleChoice = (questionIndex: number, choice: number): void => {
   mergeState({ questions: newQuestions });
 };
 
+/**
+ * Add a candidate to the end of the ranking, or remove them if they are already ranked
+ *   Removing a candidate moves every candidate after them up by one rank
+ *
+ * @param questionIndex Question to update
+ * @param choice Which candidate to rank
+ */
+export const toggleRank = (questionIndex: number, choice: number): void =>
+  mergeQuestion(questionIndex, ({ ranking }) => ({
+    ranking: ranking.includes(choice) ? ranking.filter((candidate) => candidate !== choice) : [...ranking, choice],
+  }));
+
 /**
  * Test if the given input is valid, depending on the state of "cheat" mode
+ *   Choice questions must select between minSelections and maxSelections candidates,
+ *   and ranked questions must rank every candidate
  */
 export const useIsFormValid = (): boolean =>
   useSelector(
-    (state) => state.cheatMode || state.questions.every((question) => question.hasVoted || question.choices.size === 1),
+    (state) =>
+      state.cheatMode ||
+      state.questions.every((question, index) => {
+        if (question.hasVoted) {
+          return true;
+        }
+
+        const questionParams = getQuestionParameters(state, index);
+        if (questionParams?.questionType === QuestionType.Ranked) {
+          return question.ranking.length === question.candidates.length;
+        }
+
+        const minSelections = questionParams?.minSelections ?? 1;
+        const maxSelections = questionParams?.maxSelections ?? 1;
+        return question.choices.size >= minSelections && question.choices.size <= maxSelections;
+      }),
   );
 
 /**
@@ -341,8 +394,10 @@ export const vote = (electionId: string, override?: true): void => {
     message: 'You will not be able to change your reponses later',
     override,
     onConfirm: async () => {
-      const { questions, encryptedLocation, electionParams, electionCollectors } = getState();
+      const { questions, encryptedLocation, electionDetails, electionParams, electionCollectors } = getState();
       if (
+        electionDetails.loading ||
+        !electionDetails.success ||
         electionParams.loading ||
         !electionParams.success ||
         electionCollectors.loading ||
@@ -359,6 +414,10 @@ export const vote = (electionId: string, override?: true): void => {
 
       mergeState({ votingStatus: VotingStatus.Voting, questions: newQuestions });
 
+      // Counter ballots add the registration weight of the voter to every selected candidate
+      const userId = getUserId();
+      const weight = electionDetails.data.registered.find((user) => user.id === userId)?.weight ?? 1;
+
       // Run all requests in parallel
       const results = await Promise.all(
         [...questions.entries()]
@@ -368,6 +427,8 @@ export const vote = (electionId: string, override?: true): void => {
               electionId,
               question,
               questionIndex,
+              userId,
+              weight,
               encryptedLocation,
               electionParams.data,
               electionCollectors.data,
@@ -392,6 +453,8 @@ export const vote = (electionId: string, override?: true): void => {
  * @param electionId ID of the election
  * @param question Question details
  * @param questionIndex Question index in the array
+ * @param userId ID of the voter, which the ballot proof is bound to
+ * @param weight Registration weight of the voter
  *
  * @returns Result
  */
@@ -399,6 +462,8 @@ async function voteForQuestion(
   electionId: string,
   question: QuestionDetails,
   questionIndex: number,
+  userId: string,
+  weight: number,
   encryptedLocation: bigint,
   electionParams: ElectionParameters,
   electionCollectors: PublicCollectorList[],
@@ -425,21 +490,49 @@ async function voteForQuestion(
   const collectorParams = allResults.map((result) => (result as APISuccess<CollectorQuestionParameters>).data);
 
   // Get the binary voting vector
-  const { forwardVector, reverseVector } = getVotingVector({
-    candidates: [...question.choices],
-    encryptedLocation,
-    electionParams,
-    questionIndex,
-  });
+  const { forwardVector, reverseVector } =
+    electionParams.questions[questionIndex].questionType === QuestionType.Ranked
+      ? getRankedVotingVector({
+          ranking: question.ranking,
+          encryptedLocation,
+          electionParams,
+          questionIndex,
+        })
+      : getVotingVector({
+          candidates: [...question.choices],
+          weight,
+          encryptedLocation,
+          electionParams,
+          questionIndex,
+        });
 
   // Compute the ballots
   const ballot = computeBallot({
     forwardVector,
     reverseVector,
     electionParams,
+    questionIndex,
     collectorParams,
   });
 
+  // Questions with a second generator need a proof that the ballot is valid
+  const proof =
+    electionParams.questions[questionIndex].secondGenerator !== undefined
+      ? await computeBallotProof({
+          electionId,
+          questionId: question.id,
+          userId,
+          forwardVector,
+          reverseVector,
+          forwardBallot: ballot.forwardBallot,
+          reverseBallot: ballot.reverseBallot,
+          weight,
+          electionParams,
+          questionIndex,
+          collectorParams,
+        })
+      : undefined;
+
   // Submit the vote!!!
   const result = await axiosApi
     .post(`/elections/${electionId}/questions/${question.id}/vote`, {
@@ -448,6 +541,11 @@ async function voteForQuestion(
       gS: ballot.gS.toString(10),
       gSPrime: ballot.gSPrime.toString(10),
       gSSPrime: ballot.gSSPrime.toString(10),
+      proof: proof && {
+        challenges: proof.challenges.map((c) => c.toString(10)),
+        forwardResponses: proof.forwardResponses.map((z) => z.toString(10)),
+        reverseResponses: proof.reverseResponses.map((z) => z.toString(10)),
+      },
     })
     .then(...resolveResult);
 
@@ -457,7 +555,7 @@ async function voteForQuestion(
   }
 
   // We are done!
-  mergeQuestion(questionIndex, { hasVoted: true, voting: apiSuccess(true), choices: new Set() });
+  mergeQuestion(questionIndex, { hasVoted: true, voting: apiSuccess(true), choices: new Set(), ranking: [] });
   return apiSuccess({});
 }
 
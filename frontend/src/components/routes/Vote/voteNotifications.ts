@@ -10,6 +10,7 @@ import {
   VoteReceivedEvent,
   VotingClosedEvent,
   ResultsPublishedEvent,
+  ElectionCanceledEvent,
 } from 'notifications';
 import { getNestedState, mergeNestedState } from 'redux/helpers';
 import { getUserId } from 'redux/auth';
@@ -27,6 +28,7 @@ const onMessageVote = buildNotificationHandler({
   [ElectionEvents.VoteReceived]: handleVoteReceived,
   [ElectionEvents.VotingClosed]: handleVotingClosed,
   [ElectionEvents.ResultsPublished]: handleResultsPublished,
+  [ElectionEvents.ElectionCanceled]: handleElectionCanceled,
 });
 
 const ELECTION_EVENTS: ElectionEvents[] = [
@@ -34,6 +36,7 @@ const ELECTION_EVENTS: ElectionEvents[] = [
   ElectionEvents.VoteReceived,
   ElectionEvents.VotingClosed,
   ElectionEvents.ResultsPublished,
+  ElectionEvents.ElectionCanceled,
 ];
 
 /**
@@ -56,7 +59,15 @@ function handleVotingOpened(event: VotingOpenedEvent): void {
     name: `Collector ${index + 1}`,
   }));
 
-  mergeElectionParams({ prime: event.prime, generator: event.generator, locationModulus: event.locationModulus });
+  mergeElectionParams((params) => ({
+    // Questions are sent in the same order as the election parameters
+    questions: params.questions.map((question, i) => ({
+      ...question,
+      prime: event.questions[i]?.prime ?? question.prime,
+      generator: event.questions[i]?.generator ?? question.generator,
+    })),
+    locationModulus: event.locationModulus,
+  }));
   mergeState({ electionCollectors: apiSuccess(collectorList) });
   mergeElection({ status: ElectionStatus.Voting });
 }
@@ -86,13 +97,17 @@ function handleVoteReceived(event: VoteReceivedEvent): void {
 }
 
 function handleVotingClosed(event: VotingClosedEvent): void {
-  mergeElection({ status: ElectionStatus.CollectionFailed });
+  mergeElection({ status: ElectionStatus.Collecting });
 }
 
 function handleResultsPublished(event: ResultsPublishedEvent): void {
   mergeElection({ status: ElectionStatus.Finished });
 }
 
+function handleElectionCanceled(event: ElectionCanceledEvent): void {
+  mergeElection({ status: ElectionStatus.Canceled });
+}
+
 /**
  * Helpful utility function to update specific election properties
  *
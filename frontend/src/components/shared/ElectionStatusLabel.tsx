@@ -9,17 +9,23 @@ export interface ElectionStatusLabelProps {
 const DRAFT_LABEL = <Label basic icon="edit" color="yellow" content="Draft" />;
 const REGISTRATION_LABEL = <Label basic icon="clipboard outline" color="brown" content="Open for Registration" />;
 const VOTING_INIT_LABEL = <Label basic icon="check square outline" color="olive" content="Initializing Voting" />;
+const INIT_FAILED_LABEL = <Label basic icon="warning sign" color="olive" content="Initialization Failed" />;
 const VOTING_LABEL = <Label basic icon="check square outline" color="green" content="Voting" />;
 const COLLECTION_LABEL = <Label basic icon="clock outline" color="orange" content="Collecting Votes" />;
+const COLLECTION_FAILED_LABEL = <Label basic icon="warning sign" color="orange" content="Collection Failed" />;
 const FINISHED_LABEL = <Label basic icon="clock outline" color="red" content="Closed" />;
+const CANCELED_LABEL = <Label basic icon="ban" color="grey" content="Canceled" />;
 
 const ALL_LABELS: Record<ElectionStatus, JSX.Element> = {
   [ElectionStatus.Draft]: DRAFT_LABEL,
   [ElectionStatus.Registration]: REGISTRATION_LABEL,
-  [ElectionStatus.InitFailed]: VOTING_INIT_LABEL,
+  [ElectionStatus.InitFailed]: INIT_FAILED_LABEL,
   [ElectionStatus.Voting]: VOTING_LABEL,
-  [ElectionStatus.CollectionFailed]: COLLECTION_LABEL,
+  [ElectionStatus.CollectionFailed]: COLLECTION_FAILED_LABEL,
   [ElectionStatus.Finished]: FINISHED_LABEL,
+  [ElectionStatus.Canceled]: CANCELED_LABEL,
+  [ElectionStatus.Initializing]: VOTING_INIT_LABEL,
+  [ElectionStatus.Collecting]: COLLECTION_LABEL,
 };
 
 export const ElectionStatusLabel = ({ status }: ElectionStatusLabelProps): JSX.Element => ALL_LABELS[status];
@@ -5,6 +5,34 @@ export enum ElectionStatus {
   Voting,
   CollectionFailed,
   Finished,
+  Canceled,
+  Initializing,
+  Collecting,
+}
+
+export enum QuestionType {
+  Choice = 0,
+  Ranked,
+}
+
+// Counter questions share a single digit for each candidate between all voters (Ranked questions are always one-hot)
+export enum BallotEncoding {
+  OneHot = 0,
+  Counter,
+}
+
+// Elliptic curves use the base point of the curve as the generator, so their generator and prime are 0
+export enum CommitmentGroup {
+  Full = 0,
+  PrimeOrder,
+  Secp256k1,
+  Ristretto,
+}
+
+// Non-interactive elections check a zero-knowledge proof with every ballot, instead of running the sub-protocols
+export enum VerificationMode {
+  Interactive = 0,
+  NonInteractive,
 }
 
 export enum HasVotedStatus {
@@ -13,6 +41,12 @@ export enum HasVotedStatus {
   Yes,
 }
 
+export enum CollectionStatus {
+  Pending = 0,
+  Collected,
+  Failed,
+}
+
 export interface NewElectionResult {
   id: string;
 }
@@ -51,6 +85,15 @@ export interface PublicElectionDetails {
   hasVotedStatus: HasVotedStatus;
   registered: RegisteredUserDetails[];
   questions: PublicElectionQuestion[];
+  statusHistory: StatusChangeDetails[];
+}
+
+export interface StatusChangeDetails {
+  fromStatus: ElectionStatus;
+  toStatus: ElectionStatus;
+  actorId?: string; // Not provided if the scheduler changed the status
+  reason: string;
+  changedAt: string; // RFC 3339 timestamp
 }
 
 export interface UserDetails {
@@ -62,6 +105,7 @@ export interface RegisteredUserDetails {
   id: string;
   name: string;
   hasVotedStatus: HasVotedStatus;
+  weight: number; // Number of votes the ballot counts for (Only counter-encoded elections allow a weight other than 1)
 }
 
 export interface PublicElectionQuestion {
@@ -70,6 +114,8 @@ export interface PublicElectionQuestion {
   hasVoted: boolean;
   numVotesReceived: number;
   candidates: string[];
+  collectionStatus: CollectionStatus;
+  collectionError?: string; // Only provided to the election creator
 }
 
 export interface PublishElectionResult {
@@ -79,14 +125,24 @@ export interface PublishElectionResult {
 export interface ElectionParameters {
   numRegistered: number;
   questions: QuestionParameters[];
+  counterRadix: number; // Total weight of all registrations + 1
+  commitmentGroup: CommitmentGroup;
+  verificationMode: VerificationMode;
 
-  generator: string; // BigInt
-  prime: string; // BigInt
   locationModulus: string; // BigInt
 }
 
 export interface QuestionParameters {
   numCandidates: number;
+  questionType: QuestionType;
+  ballotEncoding: BallotEncoding;
+  minSelections: number; // Only used by choice questions
+  maxSelections: number;
+
+  generator: string; // BigInt
+  prime: string; // BigInt
+  groupOrder: string; // BigInt
+  secondGenerator?: string; // BigInt, only set for questions that require a ballot proof
 }
 
 export interface ElectionResult {
@@ -103,7 +159,11 @@ export interface QuestionResult {
 
   userBallots: UserBallotResult[];
   noVotes: UserDetails[];
-  candidateVotes?: Record<string, CandidateResult>;
+  candidateVotes?: Record<string, CandidateResult>; // Ranked questions use the votes from the final round
+
+  // Only provided for ranked questions
+  instantRunoff?: InstantRunoffRound[];
+  bordaCount?: Record<string, number>;
 }
 
 export interface UserBallotResult {
@@ -116,12 +176,29 @@ export interface UserBallotResult {
   gS: string; // BigInt
   gSPrime: string; // BigInt
   gSSPrime: string; // BigInt
+
+  proof?: BallotProofResult; // Only set for questions that require a ballot proof
+}
+
+export interface BallotProofResult {
+  forwardShareCommitment: string; // BigInt
+  reverseShareCommitment: string; // BigInt
+
+  challenges: string[]; // BigInt
+  forwardResponses: string[]; // BigInt
+  reverseResponses: string[]; // BigInt
 }
 
 export interface CandidateResult {
   numVotes: number;
 }
 
+export interface InstantRunoffRound {
+  candidateVotes: Record<string, CandidateResult>; // Only the candidates still in the running
+  eliminated?: number;
+  winner?: number;
+}
+
 export interface CollectorElectionParameters {
   encryptedLocation?: string; // BigInt
 }
@@ -131,4 +208,8 @@ export interface CollectorQuestionParameters {
   reverseVerificationShares: string; // BigInt
   forwardBallotShares: string; // BigInt
   reverseBallotShares: string; // BigInt
+
+  // Only set for questions that require a ballot proof
+  forwardBlinding?: string; // BigInt
+  reverseBlinding?: string; // BigInt
 }
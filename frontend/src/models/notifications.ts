@@ -1,4 +1,4 @@
-import { HasVotedStatus } from './election';
+import { CollectionStatus, HasVotedStatus } from './election';
 
 // Events not attached to any specific election
 export enum GlobalEvents {
@@ -20,6 +20,8 @@ export enum ElectionEvents {
   VoteReceived = 'voteReceived',
   VotingClosed = 'votingClosed',
   ResultsPublished = 'resultsPublished',
+  CollectionProgress = 'collectionProgress',
+  ElectionCanceled = 'electionCanceled',
 }
 
 /**
@@ -92,7 +94,9 @@ export type WebsocketNotificationEvent =
   | VotingOpenedEvent
   | VoteReceivedEvent
   | VotingClosedEvent
-  | ResultsPublishedEvent;
+  | ResultsPublishedEvent
+  | CollectionProgressEvent
+  | ElectionCanceledEvent;
 
 export interface ElectionCreatedEvent {
   type: GlobalEvents.ElectionCreated;
@@ -156,12 +160,17 @@ export interface VotingOpenedEvent {
   type: ElectionEvents.VotingOpened;
   electionId: string;
   collectors: string[];
+  questions: VotingOpenedQuestion[];
 
-  prime: string;
-  generator: string;
   locationModulus: string;
 }
 
+export interface VotingOpenedQuestion {
+  questionId: string;
+  prime: string; // BigInt
+  generator: string; // BigInt
+}
+
 export interface VoteReceivedEvent {
   type: ElectionEvents.VoteReceived;
   electionId: string;
@@ -190,3 +199,20 @@ export interface ResultsPublishedEvent {
   type: ElectionEvents.ResultsPublished;
   electionId: string;
 }
+
+export interface CollectionProgressEvent {
+  type: ElectionEvents.CollectionProgress;
+  electionId: string;
+  questions: QuestionCollectionProgress[];
+}
+
+export interface ElectionCanceledEvent {
+  type: ElectionEvents.ElectionCanceled;
+  electionId: string;
+  reason: string;
+}
+
+export interface QuestionCollectionProgress {
+  questionId: string;
+  collectionStatus: CollectionStatus;
+}
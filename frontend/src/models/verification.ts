@@ -14,4 +14,25 @@ export interface VerifyBallotData {
 export interface VerificationResult {
   subProtocol1: boolean;
   subProtocol2: boolean;
+  transcriptId: string;
+  failure?: VerificationFailure;
+}
+
+export enum FailureCause {
+  Ballot = 'ballot',
+  Collector = 'collector',
+}
+
+export enum VerificationCheck {
+  ProductMismatch = 'productMismatch',
+  CommitmentMismatch = 'commitmentMismatch',
+  BadSignature = 'badSignature',
+  UnknownIdentityKey = 'unknownIdentityKey',
+}
+
+export interface VerificationFailure {
+  cause: FailureCause;
+  check: VerificationCheck;
+  collectorIndex: number | null; // Null if the mediator cannot tell which collector misbehaved
+  reportedBy?: number;
 }
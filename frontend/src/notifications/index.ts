@@ -20,4 +20,6 @@ export type {
   VoteReceivedEvent,
   VotingClosedEvent,
   ResultsPublishedEvent,
+  CollectionProgressEvent,
+  ElectionCanceledEvent,
 } from 'models/notifications';
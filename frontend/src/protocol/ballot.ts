@@ -1,11 +1,13 @@
 import { CollectorQuestionParameters, ElectionParameters } from 'models/election';
-import { modPow, toZn } from 'bigint-mod-arith';
+import { toZn } from 'bigint-mod-arith';
+import { getGroup } from './group';
 
 export interface ComputeBallotInput {
   forwardVector: bigint;
   reverseVector: bigint;
 
   electionParams: ElectionParameters;
+  questionIndex: number; // 0...(m-1)
   collectorParams: CollectorQuestionParameters[];
 }
 
@@ -28,10 +30,13 @@ export const computeBallot = ({
   forwardVector,
   reverseVector,
   electionParams,
+  questionIndex,
   collectorParams,
 }: ComputeBallotInput): ComputeBallotOutput => {
-  const prime = BigInt(electionParams.prime);
-  const modulus = prime - BigInt(1);
+  // Every question has its own group, and all shares are computed modulo the order of that group
+  const questionParams = electionParams.questions[questionIndex];
+  const group = getGroup(electionParams.commitmentGroup, questionParams);
+  const modulus = BigInt(questionParams.groupOrder);
 
   // Sum together all the shares
   const forwardVerificationShares = collectorParams.reduce(
@@ -59,10 +64,9 @@ export const computeBallot = ({
   const secretPrime = toZn(reverseVector - reverseVerificationShares, modulus);
 
   // Compute the commitments
-  const g = BigInt(electionParams.generator);
-  const gS = modPow(g, secret, prime);
-  const gSPrime = modPow(g, secretPrime, prime);
-  const gSSPrime = modPow(g, secret * secretPrime, prime);
+  const gS = group.powGenerator(secret);
+  const gSPrime = group.powGenerator(secretPrime);
+  const gSSPrime = group.powGenerator(toZn(secret * secretPrime, modulus));
 
   // Compute the ballots
   const forwardBallot = toZn(secret + forwardBallotShares, modulus);
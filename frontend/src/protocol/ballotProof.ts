@@ -0,0 +1,203 @@
+import { CollectorQuestionParameters, ElectionParameters } from 'models/election';
+import { toZn } from 'bigint-mod-arith';
+import { getGroup } from './group';
+import { getValidVotingVectors } from './votingVector';
+
+/** Domain separator for the Fiat-Shamir challenge, which must match the server */
+const CHALLENGE_DOMAIN = 'TruBallot ballot proof';
+
+export interface ComputeBallotProofInput {
+  electionId: string;
+  questionId: string;
+  userId: string;
+
+  forwardVector: bigint;
+  reverseVector: bigint;
+  forwardBallot: bigint;
+  reverseBallot: bigint;
+  weight: number; // Registration weight of the voter
+
+  electionParams: ElectionParameters;
+  questionIndex: number; // 0...(m-1)
+  collectorParams: CollectorQuestionParameters[];
+}
+
+export interface ComputeBallotProofOutput {
+  challenges: bigint[]; // c_k
+  forwardResponses: bigint[]; // z_k
+  reverseResponses: bigint[]; // z_k'
+}
+
+/**
+ * Compute the non-interactive proof that the ballot encodes one of the valid voting vectors
+ *
+ * Every collector commits to D_c,i = g^(S~c,i - S_c,i) * h^(rho_c,i), so the server checks the ballot against
+ *   D_i = g^(S~i - S_i) * h^(rho_i). Then C_i = g^(p_i) / D_i = g^(v_i) * h^(-rho_i), and the voter proves that
+ *   Y_k = C_i / g^(v_k) is a power of h for one of the branches k (Disjunctive Chaum-Pedersen proof):
+ *
+ *   1. Every simulated branch picks c_k, z_k, z_k' first: A_k = h^(z_k) / Y_k^(c_k)
+ *   2. The real branch uses random a, a': A_k = h^a, A_k' = h^(a')
+ *   3. c = Hash(election, question, user, p_i, p_i', D_i, D_i', A_1, A_1', ..., A_K, A_K') (mod q)
+ *   4. The real branch gets c_k = c - Σ (simulated c_k), z_k = a - c_k * rho_i, z_k' = a' - c_k * rho_i'
+ *
+ * If the voting vector is not valid ("cheat" mode), the first branch is used as the real branch,
+ *   so the proof is built anyway and the server rejects it.
+ *
+ * @param input Parameters
+ * @returns Challenge and responses for every branch
+ */
+export const computeBallotProof = async ({
+  electionId,
+  questionId,
+  userId,
+  forwardVector,
+  reverseVector,
+  forwardBallot,
+  reverseBallot,
+  weight,
+  electionParams,
+  questionIndex,
+  collectorParams,
+}: ComputeBallotProofInput): Promise<ComputeBallotProofOutput> => {
+  const questionParams = electionParams.questions[questionIndex];
+  const group = getGroup(electionParams.commitmentGroup, questionParams);
+  const modulus = BigInt(questionParams.groupOrder);
+  const secondGenerator = BigInt(questionParams.secondGenerator ?? 0);
+  const negate = (value: bigint) => toZn(-value, modulus);
+  const powSecondGenerator = (exponent: bigint) => group.pow(secondGenerator, toZn(exponent, modulus));
+
+  // Sum together all the shares and blindings
+  const sum = (getValue: (params: CollectorQuestionParameters) => string | undefined) =>
+    collectorParams.reduce((acc, params) => acc + BigInt(getValue(params) ?? 0), BigInt(0));
+
+  const forwardShares = sum((params) => params.forwardBallotShares) - sum((params) => params.forwardVerificationShares);
+  const reverseShares = sum((params) => params.reverseBallotShares) - sum((params) => params.reverseVerificationShares);
+  const forwardBlinding = toZn(sum((params) => params.forwardBlinding), modulus);
+  const reverseBlinding = toZn(sum((params) => params.reverseBlinding), modulus);
+
+  // D_i = g^(S~i - S_i) * h^(rho_i)
+  const forwardShareCommitment = group.mul(
+    group.powGenerator(toZn(forwardShares, modulus)),
+    powSecondGenerator(forwardBlinding),
+  );
+  const reverseShareCommitment = group.mul(
+    group.powGenerator(toZn(reverseShares, modulus)),
+    powSecondGenerator(reverseBlinding),
+  );
+
+  // C_i = g^(p_i) / D_i
+  const inverse = modulus - BigInt(1);
+  const forwardBase = group.mul(group.powGenerator(forwardBallot), group.pow(forwardShareCommitment, inverse));
+  const reverseBase = group.mul(group.powGenerator(reverseBallot), group.pow(reverseShareCommitment, inverse));
+
+  // Find the branch for the voting vector, or use the first branch if it is not valid
+  const votingVectors = getValidVotingVectors(electionParams, questionIndex, weight);
+  const realIndex = votingVectors.findIndex(
+    (vector) => vector.forwardVector === forwardVector && vector.reverseVector === reverseVector,
+  );
+  const realBranch = Math.max(realIndex, 0);
+
+  // Commit to every branch
+  const [a, aPrime] = [randomScalar(modulus), randomScalar(modulus)];
+  const challenges: bigint[] = [];
+  const forwardResponses: bigint[] = [];
+  const reverseResponses: bigint[] = [];
+  const commitments: bigint[] = [];
+
+  votingVectors.forEach(({ forwardVector: v, reverseVector: vPrime }, k) => {
+    if (k === realBranch) {
+      challenges.push(BigInt(0));
+      forwardResponses.push(BigInt(0));
+      reverseResponses.push(BigInt(0));
+      commitments.push(powSecondGenerator(a), powSecondGenerator(aPrime));
+      return;
+    }
+
+    const [c, z, zPrime] = [randomScalar(modulus), randomScalar(modulus), randomScalar(modulus)];
+
+    // Y_k = C_i / g^(v_k), then A_k = h^(z_k) / Y_k^(c_k)
+    const y = group.mul(forwardBase, group.powGenerator(negate(v)));
+    const yPrime = group.mul(reverseBase, group.powGenerator(negate(vPrime)));
+    commitments.push(
+      group.mul(powSecondGenerator(z), group.pow(y, negate(c))),
+      group.mul(powSecondGenerator(zPrime), group.pow(yPrime, negate(c))),
+    );
+
+    challenges.push(c);
+    forwardResponses.push(z);
+    reverseResponses.push(zPrime);
+  });
+
+  // Split the Fiat-Shamir challenge to get the challenge for the real branch
+  const challenge = await fiatShamirChallenge(
+    [electionId, questionId, userId],
+    [forwardBallot, reverseBallot, forwardShareCommitment, reverseShareCommitment, ...commitments],
+    modulus,
+  );
+  const realChallenge = toZn(challenges.reduce((acc, c) => acc - c, challenge), modulus);
+
+  challenges[realBranch] = realChallenge;
+  forwardResponses[realBranch] = toZn(a - realChallenge * forwardBlinding, modulus);
+  reverseResponses[realBranch] = toZn(aPrime - realChallenge * reverseBlinding, modulus);
+
+  return { challenges, forwardResponses, reverseResponses };
+};
+
+/**
+ * Compute the Fiat-Shamir challenge c (mod q)
+ *   Every integer is hashed as an 8-byte big-endian length followed by its big-endian bytes
+ *
+ * @param ids UUIDs to hash, as their base64 strings
+ * @param values Integers to hash
+ * @param modulus Order of the group (q)
+ * @returns Challenge
+ */
+async function fiatShamirChallenge(ids: string[], values: bigint[], modulus: bigint): Promise<bigint> {
+  const bytes: number[] = [];
+  for (const text of [CHALLENGE_DOMAIN, ...ids]) {
+    bytes.push(...Array.from(new TextEncoder().encode(text)));
+  }
+
+  for (const value of values) {
+    const valueBytes = toBigEndian(value);
+    const lengthBytes = toBigEndian(BigInt(valueBytes.length));
+    bytes.push(...Array(8 - lengthBytes.length).fill(0), ...lengthBytes, ...valueBytes);
+  }
+
+  const digest = new Uint8Array(await crypto.subtle.digest('SHA-256', new Uint8Array(bytes)));
+  return toZn(fromBigEndian(Array.from(digest)), modulus);
+}
+
+/**
+ * Get the minimal big-endian bytes of a non-negative integer (Zero has no bytes)
+ */
+function toBigEndian(value: bigint): number[] {
+  if (value === BigInt(0)) {
+    return [];
+  }
+
+  const hex = value.toString(16);
+  const padded = hex.length % 2 === 0 ? hex : `0${hex}`;
+  const bytes: number[] = [];
+  for (let i = 0; i < padded.length; i += 2) {
+    bytes.push(parseInt(padded.slice(i, i + 2), 16));
+  }
+
+  return bytes;
+}
+
+/**
+ * Read big-endian bytes as a non-negative integer
+ */
+function fromBigEndian(bytes: number[]): bigint {
+  return bytes.reduce((acc, byte) => (acc << BigInt(8)) | BigInt(byte), BigInt(0));
+}
+
+/**
+ * Sample a random scalar modulo q
+ *   Takes 8 more bytes than the modulus, so the bias from the modulus is negligible
+ */
+function randomScalar(modulus: bigint): bigint {
+  const numBytes = toBigEndian(modulus).length + 8;
+  return toZn(fromBigEndian(Array.from(crypto.getRandomValues(new Uint8Array(numBytes)))), modulus);
+}
@@ -0,0 +1,375 @@
+import { modPow, toZn } from 'bigint-mod-arith';
+import { CommitmentGroup, QuestionParameters } from 'models/election';
+
+/**
+ * Commitment group for a question, which matches the group used by the server, mediator, and collectors
+ *
+ * Group elements are always passed around as integers. Curve points are stored as their compressed encoding,
+ *   read as a big-endian integer, so the commitments and ballots look the same for every group.
+ *
+ * The multiplicative notation is kept for every group:
+ *   For elliptic curves, g^x is the scalar multiplication x*G and a * b is the point addition A + B
+ */
+export interface Group {
+  /**
+   * Compute g^exponent
+   */
+  powGenerator: (exponent: bigint) => bigint;
+
+  /**
+   * Compute base^exponent
+   *   Throws an error if the base is not a valid group element
+   */
+  pow: (base: bigint, exponent: bigint) => bigint;
+
+  /**
+   * Compute a * b
+   *   Throws an error if either value is not a valid group element
+   */
+  mul: (a: bigint, b: bigint) => bigint;
+}
+
+/**
+ * Get the commitment group for a question
+ *   Elliptic curves always use the base point of the curve as the generator, and ignore the prime
+ *
+ * @param commitmentGroup Commitment group of the election
+ * @param questionParams Parameters of the question
+ * @returns Group to compute the commitments
+ */
+export const getGroup = (commitmentGroup: CommitmentGroup, questionParams: QuestionParameters): Group => {
+  switch (commitmentGroup) {
+    case CommitmentGroup.Secp256k1:
+      return curveGroup(secp256k1);
+    case CommitmentGroup.Ristretto:
+      return curveGroup(ristretto);
+    default:
+      return modularGroup(BigInt(questionParams.generator), BigInt(questionParams.prime));
+  }
+};
+
+/**
+ * Multiplicative group of integers modulo a safe prime (Full or prime-order subgroup)
+ */
+function modularGroup(generator: bigint, prime: bigint): Group {
+  return {
+    powGenerator: (exponent) => modPow(generator, exponent, prime),
+    pow: (base, exponent) => modPow(base, exponent, prime),
+    mul: (a, b) => (a * b) % prime,
+  };
+}
+
+/**
+ * Operations needed from an elliptic curve, where P is the type of a point
+ */
+interface Curve<P> {
+  order: bigint;
+  generator: P;
+  identity: P;
+
+  add: (a: P, b: P) => P;
+  encode: (point: P) => bigint;
+  decode: (value: bigint) => P | undefined;
+}
+
+/**
+ * Elliptic curve group, where every element is the encoded point
+ */
+function curveGroup<P>(curve: Curve<P>): Group {
+  // Double-and-add, from the most significant bit of the scalar
+  const multiply = (point: P, exponent: bigint): P => {
+    const scalar = toZn(exponent, curve.order);
+
+    let result = curve.identity;
+    for (let i = scalar.toString(2).length - 1; i >= 0; i -= 1) {
+      result = curve.add(result, result);
+      if ((scalar >> BigInt(i)) & BigInt(1)) {
+        result = curve.add(result, point);
+      }
+    }
+
+    return result;
+  };
+
+  const decode = (value: bigint): P => {
+    const point = curve.decode(value);
+    if (point === undefined) {
+      throw new Error(`Value is not a valid curve point: ${value.toString(10)}`);
+    }
+    return point;
+  };
+
+  return {
+    powGenerator: (exponent) => curve.encode(multiply(curve.generator, exponent)),
+    pow: (base, exponent) => curve.encode(multiply(decode(base), exponent)),
+    mul: (a, b) => curve.encode(curve.add(decode(a), decode(b))),
+  };
+}
+
+/**
+ * Convert an integer into big-endian bytes, padded with leading zeros
+ *   Returns undefined if the integer does not fit
+ */
+function toBytes(value: bigint, numBytes: number): number[] | undefined {
+  const hex = value.toString(16);
+  if (value < BigInt(0) || hex.length > 2 * numBytes) {
+    return undefined;
+  }
+
+  const padded = hex.padStart(2 * numBytes, '0');
+  return Array.from({ length: numBytes }, (_, i) => parseInt(padded.slice(2 * i, 2 * i + 2), 16));
+}
+
+/**
+ * Convert big-endian bytes into an integer
+ */
+function fromBytes(bytes: number[]): bigint {
+  return bytes.reduce((acc, byte) => (acc << BigInt(8)) | BigInt(byte), BigInt(0));
+}
+
+// ---------------------------------------------------------------------------------------------------------------------
+// secp256k1: y^2 = x^3 + 7, using Jacobian coordinates (X, Y, Z) for the point (X / Z^2, Y / Z^3)
+// ---------------------------------------------------------------------------------------------------------------------
+
+const SECP256K1_P = BigInt('0xfffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f');
+const SECP256K1_N = BigInt('0xfffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141');
+
+/** Number of bytes in a compressed secp256k1 point */
+const SECP256K1_POINT_BYTES = 33;
+
+interface JacobianPoint {
+  x: bigint;
+  y: bigint;
+  z: bigint; // Zero for the point at infinity
+}
+
+const secp256k1Mod = (value: bigint): bigint => toZn(value, SECP256K1_P);
+
+const secp256k1: Curve<JacobianPoint> = {
+  order: SECP256K1_N,
+  generator: {
+    x: BigInt('0x79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798'),
+    y: BigInt('0x483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8'),
+    z: BigInt(1),
+  },
+  identity: { x: BigInt(0), y: BigInt(1), z: BigInt(0) },
+
+  add: (a, b) => {
+    if (a.z === BigInt(0)) {
+      return b;
+    }
+    if (b.z === BigInt(0)) {
+      return a;
+    }
+
+    const z1z1 = secp256k1Mod(a.z * a.z);
+    const z2z2 = secp256k1Mod(b.z * b.z);
+    const u1 = secp256k1Mod(a.x * z2z2);
+    const u2 = secp256k1Mod(b.x * z1z1);
+    const s1 = secp256k1Mod(a.y * b.z * z2z2);
+    const s2 = secp256k1Mod(b.y * a.z * z1z1);
+
+    if (u1 === u2) {
+      return s1 === s2 ? secp256k1Double(a) : secp256k1.identity;
+    }
+
+    const h = secp256k1Mod(u2 - u1);
+    const i = secp256k1Mod(BigInt(4) * h * h);
+    const j = secp256k1Mod(h * i);
+    const r = secp256k1Mod(BigInt(2) * (s2 - s1));
+    const v = secp256k1Mod(u1 * i);
+
+    const x = secp256k1Mod(r * r - j - BigInt(2) * v);
+    const y = secp256k1Mod(r * (v - x) - BigInt(2) * s1 * j);
+    const z = secp256k1Mod(BigInt(2) * a.z * b.z * h);
+    return { x, y, z };
+  },
+
+  // Compressed encoding: 0x02 or 0x03 (Parity of y), followed by the 32 bytes of x
+  //   The point at infinity is encoded as zero
+  encode: (point) => {
+    if (point.z === BigInt(0)) {
+      return BigInt(0);
+    }
+
+    const zInv = modPow(point.z, SECP256K1_P - BigInt(2), SECP256K1_P);
+    const zInv2 = secp256k1Mod(zInv * zInv);
+    const x = secp256k1Mod(point.x * zInv2);
+    const y = secp256k1Mod(point.y * zInv2 * zInv);
+
+    return ((BigInt(2) + (y & BigInt(1))) << BigInt(256)) | x;
+  },
+
+  decode: (value) => {
+    const bytes = toBytes(value, SECP256K1_POINT_BYTES);
+    if (bytes === undefined) {
+      return undefined;
+    }
+    if (value === BigInt(0)) {
+      return secp256k1.identity;
+    }
+
+    const prefix = bytes[0];
+    const x = fromBytes(bytes.slice(1));
+    if ((prefix !== 2 && prefix !== 3) || x >= SECP256K1_P) {
+      return undefined;
+    }
+
+    // p = 3 (mod 4), so the square root is (y^2)^((p+1)/4)
+    const ySquared = secp256k1Mod(x * x * x + BigInt(7));
+    let y = modPow(ySquared, (SECP256K1_P + BigInt(1)) >> BigInt(2), SECP256K1_P);
+    if (secp256k1Mod(y * y) !== ySquared) {
+      return undefined;
+    }
+    if ((y & BigInt(1)) !== BigInt(prefix & 1)) {
+      y = SECP256K1_P - y;
+    }
+
+    return { x, y, z: BigInt(1) };
+  },
+};
+
+function secp256k1Double(point: JacobianPoint): JacobianPoint {
+  if (point.z === BigInt(0) || point.y === BigInt(0)) {
+    return secp256k1.identity;
+  }
+
+  const a = secp256k1Mod(point.x * point.x);
+  const b = secp256k1Mod(point.y * point.y);
+  const c = secp256k1Mod(b * b);
+  const d = secp256k1Mod(BigInt(2) * ((point.x + b) * (point.x + b) - a - c));
+  const e = secp256k1Mod(BigInt(3) * a);
+  const f = secp256k1Mod(e * e);
+
+  const x = secp256k1Mod(f - BigInt(2) * d);
+  const y = secp256k1Mod(e * (d - x) - BigInt(8) * c);
+  const z = secp256k1Mod(BigInt(2) * point.y * point.z);
+  return { x, y, z };
+}
+
+// ---------------------------------------------------------------------------------------------------------------------
+// Ristretto: Prime-order group built on Curve25519 (RFC 9496)
+//   Points are stored on the twisted Edwards curve -x^2 + y^2 = 1 + d*x^2*y^2,
+//   using extended coordinates (X, Y, Z, T) for the point (X / Z, Y / Z) with T = X * Y / Z
+// ---------------------------------------------------------------------------------------------------------------------
+
+const ED25519_P = BigInt('0x7fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffed');
+const ED25519_L = BigInt('0x1000000000000000000000000000000014def9dea2f79cd65812631a5cf5d3ed');
+const ED25519_D = BigInt('37095705934669439343138083508754565189542113879843219016388785533085940283555');
+const ED25519_BASE_X = BigInt('15112221349535400772501151409588531511454012693041857206046113283949847762202');
+const ED25519_BASE_Y = BigInt('46316835694926478169428394003475163141307993866256225615783033603165251855960');
+const SQRT_M1 = BigInt('19681161376707505956807079304988542015446066515923890162744021073123829784752');
+const INVSQRT_A_MINUS_D = BigInt('54469307008909316920995813868745141605393597292927456921205312896311721017578');
+
+/** Number of bytes in an encoded Ristretto point */
+const RISTRETTO_POINT_BYTES = 32;
+
+interface ExtendedPoint {
+  x: bigint;
+  y: bigint;
+  z: bigint;
+  t: bigint;
+}
+
+const ed25519Mod = (value: bigint): bigint => toZn(value, ED25519_P);
+const isNegative = (value: bigint): boolean => (ed25519Mod(value) & BigInt(1)) === BigInt(1);
+const abs = (value: bigint): bigint => (isNegative(value) ? ed25519Mod(-value) : ed25519Mod(value));
+
+/**
+ * Compute sqrt(u / v), returning [was_square, root] with a non-negative root
+ */
+function sqrtRatioM1(u: bigint, v: bigint): [boolean, bigint] {
+  const v3 = ed25519Mod(v * v * v);
+  const v7 = ed25519Mod(v3 * v3 * v);
+  let r = ed25519Mod(u * v3 * modPow(ed25519Mod(u * v7), (ED25519_P - BigInt(5)) >> BigInt(3), ED25519_P));
+
+  const check = ed25519Mod(v * r * r);
+  const correctSignSqrt = check === ed25519Mod(u);
+  const flippedSignSqrt = check === ed25519Mod(-u);
+  const flippedSignSqrtI = check === ed25519Mod(-u * SQRT_M1);
+
+  if (flippedSignSqrt || flippedSignSqrtI) {
+    r = ed25519Mod(r * SQRT_M1);
+  }
+
+  return [correctSignSqrt || flippedSignSqrt, abs(r)];
+}
+
+const ristretto: Curve<ExtendedPoint> = {
+  order: ED25519_L,
+  generator: {
+    x: ED25519_BASE_X,
+    y: ED25519_BASE_Y,
+    z: BigInt(1),
+    t: (ED25519_BASE_X * ED25519_BASE_Y) % ED25519_P,
+  },
+  identity: { x: BigInt(0), y: BigInt(1), z: BigInt(1), t: BigInt(0) },
+
+  // Complete addition formula for a = -1, which also works for doubling
+  add: (p, q) => {
+    const a = ed25519Mod((p.y - p.x) * (q.y - q.x));
+    const b = ed25519Mod((p.y + p.x) * (q.y + q.x));
+    const c = ed25519Mod(BigInt(2) * ED25519_D * p.t * q.t);
+    const d = ed25519Mod(BigInt(2) * p.z * q.z);
+    const e = b - a;
+    const f = d - c;
+    const g = d + c;
+    const h = b + a;
+
+    return { x: ed25519Mod(e * f), y: ed25519Mod(g * h), z: ed25519Mod(f * g), t: ed25519Mod(e * h) };
+  },
+
+  // The 32 bytes of the little-endian field element s, read as a big-endian integer
+  encode: (point) => {
+    const u1 = ed25519Mod((point.z + point.y) * (point.z - point.y));
+    const u2 = ed25519Mod(point.x * point.y);
+    const [, invsqrt] = sqrtRatioM1(BigInt(1), ed25519Mod(u1 * u2 * u2));
+    const den1 = ed25519Mod(invsqrt * u1);
+    const den2 = ed25519Mod(invsqrt * u2);
+    const zInv = ed25519Mod(den1 * den2 * point.t);
+
+    const rotate = isNegative(point.t * zInv);
+    const x = rotate ? ed25519Mod(point.y * SQRT_M1) : point.x;
+    let y = rotate ? ed25519Mod(point.x * SQRT_M1) : point.y;
+    const denInv = rotate ? ed25519Mod(den1 * INVSQRT_A_MINUS_D) : den2;
+
+    if (isNegative(x * zInv)) {
+      y = ed25519Mod(-y);
+    }
+
+    const s = abs(denInv * (point.z - y));
+    return fromBytes((toBytes(s, RISTRETTO_POINT_BYTES) as number[]).reverse());
+  },
+
+  decode: (value) => {
+    const bytes = toBytes(value, RISTRETTO_POINT_BYTES);
+    if (bytes === undefined) {
+      return undefined;
+    }
+
+    // Only the canonical, non-negative encoding of s is accepted
+    const s = fromBytes(bytes.reverse());
+    if (s >= ED25519_P || isNegative(s)) {
+      return undefined;
+    }
+
+    const ss = ed25519Mod(s * s);
+    const u1 = ed25519Mod(BigInt(1) - ss);
+    const u2 = ed25519Mod(BigInt(1) + ss);
+    const u2Squared = ed25519Mod(u2 * u2);
+    const v = ed25519Mod(-(ED25519_D * u1 * u1) - u2Squared);
+
+    const [wasSquare, invsqrt] = sqrtRatioM1(BigInt(1), ed25519Mod(v * u2Squared));
+    const denX = ed25519Mod(invsqrt * u2);
+    const denY = ed25519Mod(invsqrt * denX * v);
+
+    const x = abs(BigInt(2) * s * denX);
+    const y = ed25519Mod(u1 * denY);
+    const t = ed25519Mod(x * y);
+    if (!wasSquare || isNegative(t) || y === BigInt(0)) {
+      return undefined;
+    }
+
+    return { x, y, z: BigInt(1), t };
+  },
+};
@@ -1,2 +1,4 @@
 export * from './ballot';
+export * from './ballotProof';
 export * from './votingVector';
+export * from './group';
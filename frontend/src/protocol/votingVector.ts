@@ -1,7 +1,16 @@
-import { ElectionParameters } from 'models/election';
+import { BallotEncoding, ElectionParameters, QuestionType } from 'models/election';
 
 export interface GetVotingVectorInput {
   candidates: number[]; // 0...(n-1)
+  weight: number; // Registration weight of the voter
+
+  encryptedLocation: bigint;
+  electionParams: ElectionParameters;
+  questionIndex: number; // 0...(m-1)
+}
+
+export interface GetRankedVotingVectorInput {
+  ranking: number[]; // Candidates from first to last preference
 
   encryptedLocation: bigint;
   electionParams: ElectionParameters;
@@ -14,84 +23,360 @@ export interface GetVotingVectorOutput {
 }
 
 /**
- * Compute the binary voting vector for the given question
+ * Compute the voting vector for a choice question
+ *
+ * One-hot questions give every voter their own chunk of n bits, found at the encrypted location.
+ *   Counter questions share a single chunk of n base-counterRadix digits between all voters,
+ *   so the voter adds their weight to the digit of every selected candidate.
  *
  * @param input Input parameters to the function
  * @returns Forward and reverse voting vector
  */
 export const getVotingVector = ({
   candidates,
+  weight,
   encryptedLocation,
   electionParams,
   questionIndex,
 }: GetVotingVectorInput): GetVotingVectorOutput => {
-  const numCandidates = BigInt(electionParams.questions[questionIndex].numCandidates);
-  const numRegistered = BigInt(electionParams.numRegistered);
+  const { numCandidates, ballotEncoding } = electionParams.questions[questionIndex];
+
+  if (ballotEncoding === BallotEncoding.Counter) {
+    return encodeChunk(candidates, numCandidates, BigInt(0), 1, BigInt(electionParams.counterRadix), BigInt(weight));
+  }
 
   // Set the corresponding bit for each candidate in the list
-  const forwardBitsSet = candidates.reduce((prev, current) => prev | (BigInt(1) << BigInt(current)), BigInt(0));
-  const reverseBitsSet = candidates.reduce(
-    (prev, current) => prev | (BigInt(1) << (numCandidates - (BigInt(current) + BigInt(1)))),
-    BigInt(0),
+  //   One-hot elections only allow a weight of 1, since the summed ballots show the chunk of every voter
+  return encodeChunk(candidates, numCandidates, encryptedLocation, electionParams.numRegistered, BigInt(2), BigInt(1));
+};
+
+/**
+ * Compute the binary voting vector for a ranked question
+ *
+ * Every voter has a chunk of n^2 bits, where candidate c with rank r sets bit (c * n + r).
+ *   The ranking must include every candidate, or the collectors reject the ballot.
+ *
+ * @param input Input parameters to the function
+ * @returns Forward and reverse voting vector
+ */
+export const getRankedVotingVector = ({
+  ranking,
+  encryptedLocation,
+  electionParams,
+  questionIndex,
+}: GetRankedVotingVectorInput): GetVotingVectorOutput => {
+  const { numCandidates } = electionParams.questions[questionIndex];
+
+  const positions = ranking.map((candidate, rank) => candidate * numCandidates + rank);
+  return encodeChunk(
+    positions,
+    numCandidates * numCandidates,
+    encryptedLocation,
+    electionParams.numRegistered,
+    BigInt(2),
+    BigInt(1),
   );
+};
+
+/**
+ * Compute every pair of voting vectors that a ballot proof chooses between, in the same order as the server
+ *
+ * The voter location is secret, so one-hot questions have a pair for every valid set of positions in every chunk.
+ *   Counter questions only have a single chunk, shared between all voters.
+ *
+ * @param electionParams Parameters of the election
+ * @param questionIndex Question to compute the vectors for
+ * @param weight Registration weight of the voter
+ * @returns Every valid forward and reverse voting vector
+ */
+export const getValidVotingVectors = (
+  electionParams: ElectionParameters,
+  questionIndex: number,
+  weight: number,
+): GetVotingVectorOutput[] => {
+  const { numCandidates, questionType, ballotEncoding, minSelections, maxSelections } =
+    electionParams.questions[questionIndex];
 
-  // Compute the bit vectors
-  const forwardVector = forwardBitsSet << (encryptedLocation * numCandidates);
-  const reverseVector = reverseBitsSet << ((numRegistered - (encryptedLocation + BigInt(1))) * numCandidates);
+  const isRanked = questionType === QuestionType.Ranked;
+  const isCounter = !isRanked && ballotEncoding === BallotEncoding.Counter;
+  const chunkSize = isRanked ? numCandidates * numCandidates : numCandidates;
+  const numChunks = isCounter ? 1 : electionParams.numRegistered;
+  const radix = BigInt(isCounter ? electionParams.counterRadix : 2);
+  const chunkWeight = BigInt(isCounter ? weight : 1);
+  const allPositions = isRanked
+    ? rankedPositions(numCandidates)
+    : choicePositions(numCandidates, minSelections, maxSelections);
 
-  return { forwardVector, reverseVector };
+  const vectors: GetVotingVectorOutput[] = [];
+  for (let chunk = 0; chunk < numChunks; chunk += 1) {
+    for (const positions of allPositions) {
+      vectors.push(encodeChunk(positions, chunkSize, BigInt(chunk), numChunks, radix, chunkWeight));
+    }
+  }
+
+  return vectors;
 };
 
+/**
+ * Set the given digits within the chunk of the voter to the voter weight
+ *
+ * The voting vector is read as L digits in base R, where L = numChunks * chunkSize.
+ *   The reverse vector is the forward vector with the order of all L digits reversed.
+ *
+ * @param positions Digits to set within the chunk
+ * @param chunkSize Number of digits in every chunk
+ * @param chunk Index of the chunk to set (Location of the voter)
+ * @param numChunks Number of chunks in the voting vector
+ * @param radix Base of every digit (R)
+ * @param weight Value of every set digit (w)
+ * @returns Forward and reverse voting vector
+ */
+function encodeChunk(
+  positions: number[],
+  chunkSize: number,
+  chunk: bigint,
+  numChunks: number,
+  radix: bigint,
+  weight: bigint,
+): GetVotingVectorOutput {
+  const offset = chunk * BigInt(chunkSize);
+  const lastDigit = BigInt(numChunks) * BigInt(chunkSize) - BigInt(1);
+
+  // Compute the digit vectors
+  const forwardVector = positions.reduce((prev, current) => prev + power(radix, offset + BigInt(current)), BigInt(0));
+  const reverseVector = positions.reduce(
+    (prev, current) => prev + power(radix, lastDigit - offset - BigInt(current)),
+    BigInt(0),
+  );
+
+  return { forwardVector: weight * forwardVector, reverseVector: weight * reverseVector };
+}
+
+/**
+ * Compute base^exponent without any modulus (Square-and-multiply)
+ */
+function power(base: bigint, exponent: bigint): bigint {
+  let result = BigInt(1);
+  let square = base;
+  for (let e = exponent; e > BigInt(0); e >>= BigInt(1)) {
+    if (e & BigInt(1)) {
+      result *= square;
+    }
+    square *= square;
+  }
+
+  return result;
+}
+
+/**
+ * Every valid set of positions for a choice question:
+ *   Select between minSelections and maxSelections of the candidates, in lexicographic order for every size
+ */
+function choicePositions(numCandidates: number, minSelections: number, maxSelections: number): number[][] {
+  const combinations = (start: number, size: number): number[][] => {
+    if (size === 0) {
+      return [[]];
+    }
+
+    const result: number[][] = [];
+    for (let first = start; first <= numCandidates - size; first += 1) {
+      for (const rest of combinations(first + 1, size - 1)) {
+        result.push([first, ...rest]);
+      }
+    }
+
+    return result;
+  };
+
+  const positions: number[][] = [];
+  for (let size = minSelections; size <= maxSelections; size += 1) {
+    positions.push(...combinations(0, size));
+  }
+
+  return positions;
+}
+
+/**
+ * Every valid set of positions for a ranked question:
+ *   Candidate c with rank r sets bit (c * numCandidates + r), where the ranks are a permutation in lexicographic order
+ */
+function rankedPositions(numCandidates: number): number[][] {
+  const permutations = (remaining: number[]): number[][] => {
+    if (remaining.length === 0) {
+      return [[]];
+    }
+
+    const result: number[][] = [];
+    for (const rank of remaining) {
+      for (const rest of permutations(remaining.filter((r) => r !== rank))) {
+        result.push([rank, ...rest]);
+      }
+    }
+
+    return result;
+  };
+
+  const allRanks = [...Array(numCandidates).keys()];
+  return permutations(allRanks).map((ranks) => ranks.map((rank, candidate) => candidate * numCandidates + rank));
+}
+
 export interface ParseVectorEntry {
-  candidatePicked: number | null | undefined; // "null" = No vote, "undefined" = Invalid
+  candidatesPicked: number[] | null | undefined; // "null" = No vote, "undefined" = Invalid
   bits: string;
 }
 
 /**
- * Parse the voting vector
+ * Parse the voting vector of a one-hot choice question
+ *
+ * Every chunk must either be empty, or select between minSelections and maxSelections candidates
  *
  * @param vector Voting vector to parse
  * @param numCandidates Number of candidates
  * @param numRegistered Number of registered users
+ * @param minSelections Fewest candidates a voter can select
+ * @param maxSelections Most candidates a voter can select
  * @param reverse If true, reverses the order of the candidates
  *
  * @returns Vector of parsed entries
  */
 export const parseVotingVector = (
+  vector: bigint,
+  numCandidates: number,
+  numRegistered: number,
+  minSelections: number,
+  maxSelections: number,
+  reverse = false,
+): ParseVectorEntry[] =>
+  splitChunks(vector, numCandidates, numRegistered).map((bits) => {
+    const candidatesPicked = getChunkPositions(bits, reverse);
+    if (candidatesPicked.length === 0) {
+      // No bits set, so return NULL for "no vote"
+      return { candidatesPicked: null, bits };
+    }
+
+    // Picked too few or too many candidates, so invalid vector!
+    if (candidatesPicked.length < minSelections || candidatesPicked.length > maxSelections) {
+      return { candidatesPicked: undefined, bits };
+    }
+
+    return { candidatesPicked, bits };
+  });
+
+/**
+ * Parse the voting vector of a ranked question
+ *
+ * Every chunk has numCandidates^2 bits, where bit (candidate * numCandidates + rank) marks the rank of the candidate
+ *   The chunk must either be empty, or give every candidate a different rank
+ *
+ * @param vector Voting vector to parse
+ * @param numCandidates Number of candidates
+ * @param numRegistered Number of registered users
+ * @param reverse If true, parses the reverse voting vector
+ *
+ * @returns Vector of parsed entries, with the candidates picked ordered from first to last preference
+ */
+export const parseRankedVotingVector = (
   vector: bigint,
   numCandidates: number,
   numRegistered: number,
   reverse = false,
-): ParseVectorEntry[] => {
-  // Convert the voting vector into a bit string with L = n*m bits
-  const totalBits = numCandidates * numRegistered;
+): ParseVectorEntry[] =>
+  splitChunks(vector, numCandidates * numCandidates, numRegistered).map((bits) => {
+    const positions = getChunkPositions(bits, reverse);
+    if (positions.length === 0) {
+      return { candidatesPicked: null, bits };
+    }
+
+    // Every candidate needs exactly one rank, and every rank needs exactly one candidate
+    const ranking: number[] = [];
+    for (const position of positions) {
+      const candidate = Math.floor(position / numCandidates);
+      const rank = position % numCandidates;
+      if (ranking[rank] !== undefined || ranking.includes(candidate)) {
+        return { candidatesPicked: undefined, bits };
+      }
+
+      ranking[rank] = candidate;
+    }
+
+    if (positions.length !== numCandidates) {
+      return { candidatesPicked: undefined, bits };
+    }
+
+    return { candidatesPicked: ranking, bits };
+  });
+
+/**
+ * Split the voting vector into the chunk of bits for every voter
+ *
+ * @param vector Voting vector to split
+ * @param chunkSize Number of bits in every chunk
+ * @param numRegistered Number of registered users
+ * @returns Bit string of every chunk, from the most significant chunk to the least significant chunk
+ */
+function splitChunks(vector: bigint, chunkSize: number, numRegistered: number): string[] {
+  // Convert the voting vector into a bit string with L = chunkSize * numRegistered bits
+  const totalBits = chunkSize * numRegistered;
   const bitString = vector.toString(2).padStart(totalBits, '0');
 
-  // Split string into chunks of bit strings
   const chunks = [];
   for (let i = 0; i < numRegistered; i += 1) {
-    chunks.push(bitString.slice(i * numCandidates, (i + 1) * numCandidates));
+    chunks.push(bitString.slice(i * chunkSize, (i + 1) * chunkSize));
   }
 
-  // Now parse each chunk to find the candidate index
-  return chunks.map((bits) => {
-    // Make sure at least 1 bit is set
-    const index = bits.indexOf('1');
-    if (index < 0) {
-      // No bits set, so return NULL for "no vote"
-      return { candidatePicked: null, bits };
-    }
+  return chunks;
+}
 
-    // Test to see if two bits are set
-    const nextIndex = bits.indexOf('1', index + 1);
-    if (nextIndex >= 0) {
-      // Picked at least two candidates, so invalid vector!
-      return { candidatePicked: undefined, bits };
+/**
+ * Find the position of every set bit in the chunk, counting from the least-significant bit of the forward vector
+ *   The reverse vector has every bit in the opposite order, so the positions count from the left instead
+ *
+ * @param bits Bit string of the chunk
+ * @param reverse If true, the chunk comes from the reverse vector
+ * @returns Sorted list of positions
+ */
+function getChunkPositions(bits: string, reverse: boolean): number[] {
+  const positions: number[] = [];
+  for (let i = 0; i < bits.length; i += 1) {
+    if (bits[i] === '1') {
+      positions.push(reverse ? i : bits.length - (i + 1));
     }
+  }
 
-    // We only picked one candidate
-    // If the bits are in the reverse order, adjust the calculations
-    const candidatePicked = reverse ? index : numCandidates - (index + 1);
-    return { candidatePicked, bits };
-  });
+  return positions.sort((x, y) => x - y);
+}
+
+/**
+ * Parse a counter-encoded voting vector into the number of votes for every candidate
+ *
+ * Every candidate is a single base-counterRadix digit shared by all voters. Reading from the least-significant digit,
+ *   candidate c is digit c in the forward vector and digit (n - 1 - c) in the reverse vector.
+ *
+ * @param vector Voting vector to parse
+ * @param numCandidates Number of candidates
+ * @param counterRadix Base of every digit
+ * @param reverse If true, reverses the order of the candidates
+ *
+ * @returns Number of votes for each candidate, or undefined if the vector has more digits than candidates
+ */
+export const parseCounterVector = (
+  vector: bigint,
+  numCandidates: number,
+  counterRadix: number,
+  reverse = false,
+): number[] | undefined => {
+  const radix = BigInt(counterRadix);
+
+  let remaining = vector;
+  const digits: number[] = [];
+  for (let i = 0; i < numCandidates; i += 1) {
+    digits.push(Number(remaining % radix));
+    remaining /= radix;
+  }
+
+  if (remaining !== BigInt(0)) {
+    return undefined;
+  }
+
+  return reverse ? digits.reverse() : digits;
 };
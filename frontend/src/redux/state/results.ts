@@ -3,11 +3,13 @@
  */
 import { APIResult, apiLoading, APIOption, apiSome } from 'api';
 import {
+  BallotEncoding,
   CollectorElectionParameters,
   ElectionParameters,
   ElectionResult,
   PublicElectionDetails,
   QuestionResult,
+  QuestionType,
   UserBallotResult,
 } from 'models/election';
 import { PublicCollectorList } from 'models/mediator';
@@ -22,8 +24,6 @@ export interface ResultsState {
 
   questions: ExtendedQuestionResult[];
   currentQuestionIndex: number;
-  generator: bigint;
-  prime: bigint;
   verifySum: APIOption<[bigint, bigint] | null>;
 
   // Only if registered in the election
@@ -34,6 +34,16 @@ export interface ExtendedQuestionResult extends QuestionResult {
   id: string;
   name: string;
   candidates: ExtendedCandidatesResult[];
+  questionType: QuestionType;
+  ballotEncoding: BallotEncoding;
+  minSelections: number;
+  maxSelections: number;
+
+  // Group used by the question commitments
+  generator: bigint;
+  prime: bigint;
+  groupOrder: bigint;
+
   ballots: ExtendedBallotsResult[];
 
   currentTab: number;
@@ -63,8 +73,6 @@ export const initialResultsState: ResultsState = {
 
   questions: [],
   currentQuestionIndex: 0,
-  generator: BigInt(0),
-  prime: BigInt(0),
   verifySum: apiSome(null),
 
   encryptedLocation: null,
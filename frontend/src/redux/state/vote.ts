@@ -26,6 +26,7 @@ export interface QuestionDetails {
   hasVoted: boolean;
 
   choices: Set<number>;
+  ranking: number[]; // Candidates from first to last preference (Only for ranked questions)
   voting: APIResult<boolean>;
 }
 
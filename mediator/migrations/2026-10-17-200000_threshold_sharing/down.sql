@@ -0,0 +1,5 @@
+ALTER TABLE election_collectors
+  DROP COLUMN collector_index;
+
+ALTER TABLE elections
+  DROP COLUMN threshold;
@@ -0,0 +1,9 @@
+-- Number of collectors needed to compute the cancelation shares
+--   NULL = Every collector is needed ((n,n)-secret sharing)
+ALTER TABLE elections
+  ADD COLUMN threshold BIGINT;
+
+-- Position of the collector when the election was initialized
+--   Collector j holds the threshold shares evaluated at x = j + 1
+ALTER TABLE election_collectors
+  ADD COLUMN collector_index BIGINT NOT NULL DEFAULT 0;
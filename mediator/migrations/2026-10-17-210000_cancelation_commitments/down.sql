@@ -0,0 +1 @@
+DROP TABLE cancelation_commitments;
@@ -0,0 +1,13 @@
+-- Commitments to the cancelation shares of every voter, from every collector
+--   Used to check the cancelation shares returned by each collector when voting is closed
+CREATE TABLE cancelation_commitments (
+  user_id UUID NOT NULL,
+  election_id UUID NOT NULL REFERENCES elections(id) ON DELETE CASCADE,
+  question_id UUID NOT NULL REFERENCES questions(id) ON DELETE CASCADE,
+  collector_id UUID NOT NULL REFERENCES collectors(id),
+  PRIMARY KEY (user_id, election_id, question_id, collector_id),
+  FOREIGN KEY (user_id, election_id) REFERENCES registrations(user_id, election_id) ON DELETE CASCADE,
+
+  forward_commitment NUMERIC NOT NULL,  -- X_c,i = g^(S~c,i - S_c,i)
+  reverse_commitment NUMERIC NOT NULL   -- X_c,i' = g^(S~c,i' - S_c,i')
+);
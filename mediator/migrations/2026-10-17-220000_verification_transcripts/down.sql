@@ -0,0 +1,2 @@
+DROP TABLE verification_transcript_messages;
+DROP TABLE verification_transcripts;
@@ -0,0 +1,23 @@
+-- Every ballot verification run, so disputes can be checked after the websockets are closed
+CREATE TABLE verification_transcripts (
+  id UUID PRIMARY KEY,
+  election_id UUID NOT NULL REFERENCES elections(id) ON DELETE CASCADE,
+  question_id UUID NOT NULL REFERENCES questions(id) ON DELETE CASCADE,
+  user_id UUID NOT NULL,
+  FOREIGN KEY (user_id, election_id) REFERENCES registrations(user_id, election_id) ON DELETE CASCADE,
+
+  result TEXT  -- JSON verification result (NULL if verification was canceled)
+);
+
+CREATE INDEX verification_transcripts_user_idx ON verification_transcripts(election_id, question_id, user_id);
+
+-- Messages exchanged with the collectors, in the order the mediator received or sent them
+CREATE TABLE verification_transcript_messages (
+  transcript_id UUID NOT NULL REFERENCES verification_transcripts(id) ON DELETE CASCADE,
+  sequence INT8 NOT NULL,
+  PRIMARY KEY (transcript_id, sequence),
+
+  collector_index INT8 NOT NULL,  -- Websocket the message was received from or sent to
+  sent BOOLEAN NOT NULL,          -- Sent by the mediator, otherwise received from the collector
+  message TEXT NOT NULL           -- Raw JSON text, exactly as it was sent on the websocket
+);
@@ -0,0 +1,2 @@
+ALTER TABLE collectors
+  DROP COLUMN identity_key;
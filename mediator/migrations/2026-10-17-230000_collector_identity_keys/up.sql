@@ -0,0 +1,5 @@
+-- Long-term Ed25519 identity key for each collector (Base64 DER)
+--   The key is pinned the first time it is registered, and every websocket message must be signed with it
+--   NULL = Collector registered before identity keys were required
+ALTER TABLE collectors
+  ADD COLUMN identity_key TEXT;
@@ -0,0 +1,5 @@
+-- Hash of the election manifest signed by the API server (Base64 SHA-256)
+--   Every collector checks the manifest and returns the same hash, which is sent again when verifying a ballot
+--   NULL = Election initialized before manifests were signed
+ALTER TABLE elections
+  ADD COLUMN manifest_hash TEXT;
@@ -0,0 +1 @@
+DROP TABLE initialization_checkpoints;
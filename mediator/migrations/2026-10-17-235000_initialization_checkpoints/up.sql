@@ -0,0 +1,19 @@
+-- Progress of the collector chain while an election is initialized, so a failed run can resume
+--   There is no foreign key, since the election is only created once every collector succeeded
+--   The Paillier private key can decrypt every voter's location, so it is encrypted with the checkpoint secret
+--   and cleared as soon as the final collector received the decrypted locations
+CREATE TABLE initialization_checkpoints (
+  election_id UUID PRIMARY KEY,
+  manifest TEXT NOT NULL,              -- JSON signed manifest, the checkpoint is only resumed with the same one
+  collectors UUID[] NOT NULL,
+  next_collector_index INT8 NOT NULL,  -- Every collector before this index already accepted the election
+
+  distributed_locations BOOLEAN NOT NULL,
+  location_modulus NUMERIC NOT NULL,
+  location_key BYTEA,                  -- Encrypted Paillier private key (NULL with distributed locations)
+  encrypted_locations NUMERIC[] NOT NULL,
+  location_shuffle TEXT,               -- JSON proof for the last step of location anonymization
+
+  collector_results TEXT NOT NULL,     -- JSON commitments and public keys returned by the collectors so far
+  manifest_hash TEXT
+);
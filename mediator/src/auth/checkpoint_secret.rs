@@ -0,0 +1,63 @@
+use openssl::error::ErrorStack;
+use openssl::rand::rand_bytes;
+use openssl::symm::{decrypt_aead, encrypt_aead, Cipher};
+use sha2::{Digest, Sha256};
+
+const NONCE_LENGTH: usize = 12;
+const TAG_LENGTH: usize = 16;
+
+///
+/// Secret used to encrypt the Paillier location key in the initialization checkpoints
+///
+/// Values are encrypted with AES-256-GCM, using the SHA-256 hash of the secret as the key.
+///   The associated data binds the ciphertext to a single election, so it cannot be moved to another row.
+///
+#[derive(Clone)]
+pub struct CheckpointSecret {
+  key: [u8; 32],
+}
+
+impl CheckpointSecret {
+  pub fn new(secret: impl AsRef<[u8]>) -> Self {
+    Self {
+      key: Sha256::digest(secret.as_ref()).into(),
+    }
+  }
+
+  /// Encrypt the value, returning the nonce, the ciphertext, and the tag
+  pub fn encrypt(&self, associated_data: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, ErrorStack> {
+    let mut nonce = [0u8; NONCE_LENGTH];
+    rand_bytes(&mut nonce)?;
+
+    let mut tag = [0u8; TAG_LENGTH];
+    let ciphertext = encrypt_aead(
+      Cipher::aes_256_gcm(),
+      &self.key,
+      Some(&nonce),
+      associated_data,
+      plaintext,
+      &mut tag,
+    )?;
+
+    Ok([&nonce[..], &ciphertext, &tag].concat())
+  }
+
+  /// Decrypt the value, returning None if it was changed or encrypted with a different secret
+  pub fn decrypt(&self, associated_data: &[u8], data: &[u8]) -> Option<Vec<u8>> {
+    if data.len() < NONCE_LENGTH + TAG_LENGTH {
+      return None;
+    }
+
+    let (nonce, rest) = data.split_at(NONCE_LENGTH);
+    let (ciphertext, tag) = rest.split_at(rest.len() - TAG_LENGTH);
+    decrypt_aead(
+      Cipher::aes_256_gcm(),
+      &self.key,
+      Some(nonce),
+      associated_data,
+      ciphertext,
+      tag,
+    )
+    .ok()
+  }
+}
@@ -0,0 +1,39 @@
+use openssl::pkey::{Id, PKey, Public};
+use openssl::sign::Verifier;
+
+///
+/// Long-term Ed25519 identity keys that the collectors register with the mediator
+///
+/// Keys are encoded as base64 DER, and signatures as base64
+///
+pub struct IdentityKey;
+
+impl IdentityKey {
+  /// Make sure the base64 DER key is an Ed25519 public key
+  pub fn is_valid(public_key: &str) -> bool {
+    parse_public_key(public_key).is_some()
+  }
+
+  /// Verify a collector signature, returning false if either the key or the signature is not valid
+  pub fn verify(public_key: &str, message: &[u8], signature: &str) -> bool {
+    let public_key = match parse_public_key(public_key) {
+      Some(public_key) => public_key,
+      None => return false,
+    };
+    let signature = match base64::decode(signature) {
+      Ok(signature) => signature,
+      Err(_) => return false,
+    };
+
+    Verifier::new_without_digest(&public_key)
+      .and_then(|mut verifier| verifier.verify_oneshot(&signature, message))
+      .unwrap_or(false)
+  }
+}
+
+fn parse_public_key(public_key: &str) -> Option<PKey<Public>> {
+  let der = base64::decode(public_key).ok()?;
+  PKey::public_key_from_der(&der)
+    .ok()
+    .filter(|public_key| public_key.id() == Id::ED25519)
+}
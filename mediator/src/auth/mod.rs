@@ -2,13 +2,17 @@
 // Structures and functions related to user authentication and authorization
 //
 pub mod audience;
+mod checkpoint_secret;
 mod constants;
+mod identity_key;
 mod jwt_secret;
 mod jwt_token;
 mod permission;
 
 pub use audience::Audience;
+pub use checkpoint_secret::CheckpointSecret;
 pub use constants::*;
+pub use identity_key::IdentityKey;
 pub use jwt_secret::JWTSecret;
 pub use jwt_token::*;
 pub use permission::{Permission, DEFAULT_PERMISSIONS};
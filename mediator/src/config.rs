@@ -41,6 +41,10 @@ pub struct Opt {
   #[structopt(short = "s", long, env, hide_env_values = true, default_value = DEFAULT_JWT_SECRET, hide_default_value(true))]
   jwt_secret: String,
 
+  /// Secret for encrypting the location keys in the initialization checkpoints
+  #[structopt(long, env, hide_env_values = true)]
+  checkpoint_secret: String,
+
   /// Base URL to access the notifications server
   #[structopt(long, env)]
   notifications_url: String,
@@ -64,6 +68,7 @@ impl Opt {
 
     env::set_var("DATABASE_URL", &self.database_url);
     env::set_var("JWT_SECRET", &self.jwt_secret);
+    env::set_var("CHECKPOINT_SECRET", &self.checkpoint_secret);
     env::set_var("NOTIFICATIONS_URL", &self.notifications_url);
   }
 }
@@ -124,6 +129,10 @@ pub fn get_jwt_secret() -> String {
   env::var("JWT_SECRET").unwrap_or_else(|_| DEFAULT_JWT_SECRET.to_string())
 }
 
+pub fn get_checkpoint_secret() -> Option<String> {
+  env::var("CHECKPOINT_SECRET").ok()
+}
+
 //
 // Notifications
 //
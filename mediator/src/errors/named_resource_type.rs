@@ -11,6 +11,8 @@ pub enum NamedResourceType {
   Election { id: Uuid },
   Question { id: Uuid, election_id: Uuid },
   Registration { user_id: Uuid, election_id: Uuid },
+  Transcript { id: Uuid },
+  Checkpoint { election_id: Uuid },
 }
 
 impl NamedResourceType {
@@ -21,6 +23,8 @@ impl NamedResourceType {
       NamedResourceType::Election { .. } => ResourceType::Election,
       NamedResourceType::Question { .. } => ResourceType::Question,
       NamedResourceType::Registration { .. } => ResourceType::Registration,
+      NamedResourceType::Transcript { .. } => ResourceType::Transcript,
+      NamedResourceType::Checkpoint { .. } => ResourceType::Checkpoint,
     }
   }
 
@@ -51,6 +55,14 @@ impl NamedResourceType {
   pub fn registration(user_id: Uuid, election_id: Uuid) -> Self {
     NamedResourceType::Registration { user_id, election_id }
   }
+
+  pub fn transcript(id: Uuid) -> Self {
+    NamedResourceType::Transcript { id }
+  }
+
+  pub fn checkpoint(election_id: Uuid) -> Self {
+    NamedResourceType::Checkpoint { election_id }
+  }
 }
 
 impl fmt::Display for NamedResourceType {
@@ -71,6 +83,10 @@ impl fmt::Display for NamedResourceType {
           election_id
         )
       }
+      NamedResourceType::Transcript { id } => write!(f, "{} (ID: {})", self.get_name(), id),
+      NamedResourceType::Checkpoint { election_id } => {
+        write!(f, "{} (Election ID: {})", self.get_name(), election_id)
+      }
     }
   }
 }
@@ -8,6 +8,8 @@ pub enum ResourceType {
   Election,
   Question,
   Registration,
+  Transcript,
+  Checkpoint,
 }
 
 impl ResourceType {
@@ -18,6 +20,8 @@ impl ResourceType {
       ResourceType::Election => "Election",
       ResourceType::Question => "Question",
       ResourceType::Registration => "Registration",
+      ResourceType::Transcript => "Transcript",
+      ResourceType::Checkpoint => "Initialization Checkpoint",
     }
   }
 }
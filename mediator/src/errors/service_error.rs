@@ -44,7 +44,31 @@ pub enum ServiceError {
   },
   CancelationSharesError(Uuid, ClientRequestError),
   VerificationError(WebsocketError),
-  VerificationCanceled,
+  VerificationCanceled(Uuid),
+  NotEnoughCollectors {
+    election_id: Uuid,
+    available: usize,
+    threshold: usize,
+  },
+  CancelationSharesInvalid {
+    election_id: Uuid,
+    question_id: Uuid,
+    collector_id: Option<Uuid>,
+  },
+  IdentityKeyInvalid(Uuid),
+  IdentityKeyMismatch(Uuid),
+  ManifestInvalid {
+    election_id: Uuid,
+    collector_id: Uuid,
+  },
+  SharesAuditError(Uuid, ClientRequestError),
+  SharesAuditFailed {
+    election_id: Uuid,
+    question_id: Uuid,
+    collector_id: Option<Uuid>,
+  },
+  PurgeElectionError(Uuid, ClientRequestError),
+  SaveCheckpointError(Uuid, String),
 }
 
 impl ServiceError {
@@ -197,11 +221,104 @@ impl ServiceError {
         format!("{:?}", error),
       ),
 
-      ServiceError::VerificationCanceled => ErrorResponse::new(
+      ServiceError::VerificationCanceled(transcript_id) => ErrorResponse::new(
         StatusCode::INTERNAL_SERVER_ERROR,
         "Error verifying ballot".into(),
         GlobalErrorCode::VerificationError,
-        "Verification canceled by actor logic".into(),
+        format!("Verification canceled by actor logic, Transcript ID: {}", transcript_id),
+      ),
+
+      ServiceError::NotEnoughCollectors {
+        election_id,
+        available,
+        threshold,
+      } => ErrorResponse::new(
+        StatusCode::INTERNAL_SERVER_ERROR,
+        format!(
+          "Need at least {} collectors to compute the cancelation shares",
+          threshold
+        ),
+        GlobalErrorCode::NotEnoughCollectors,
+        format!("Election ID: {}, Available: {}", election_id, available),
+      ),
+
+      ServiceError::CancelationSharesInvalid {
+        election_id,
+        question_id,
+        collector_id,
+      } => ErrorResponse::new(
+        StatusCode::INTERNAL_SERVER_ERROR,
+        "Cancelation shares do not match the collector commitments".into(),
+        GlobalErrorCode::CancelationSharesInvalid,
+        match collector_id {
+          Some(collector_id) => format!(
+            "Election ID: {}, Question ID: {}, Collector ID: {}",
+            election_id, question_id, collector_id
+          ),
+          None => format!("Election ID: {}, Question ID: {}", election_id, question_id),
+        },
+      ),
+
+      ServiceError::IdentityKeyInvalid(collector_id) => ErrorResponse::new(
+        StatusCode::BAD_REQUEST,
+        "Collector identity key must be a base64 encoded Ed25519 public key".into(),
+        GlobalErrorCode::IdentityKeyInvalid,
+        format!("Collector ID: {}", collector_id),
+      ),
+
+      ServiceError::IdentityKeyMismatch(collector_id) => ErrorResponse::new(
+        StatusCode::CONFLICT,
+        "Collector identity key does not match the pinned key".into(),
+        GlobalErrorCode::IdentityKeyMismatch,
+        format!("Collector ID: {}", collector_id),
+      ),
+
+      ServiceError::ManifestInvalid {
+        election_id,
+        collector_id,
+      } => ErrorResponse::new(
+        StatusCode::INTERNAL_SERVER_ERROR,
+        "Collectors do not agree on the election manifest".into(),
+        GlobalErrorCode::ManifestInvalid,
+        format!("Election ID: {}, Collector ID: {}", election_id, collector_id),
+      ),
+
+      ServiceError::SharesAuditError(collector_id, error) => ErrorResponse::new(
+        StatusCode::INTERNAL_SERVER_ERROR,
+        "Failed to get the share totals for the audit".into(),
+        GlobalErrorCode::SharesAuditError,
+        format!("Collector ID: {}, Error: {:?}", collector_id, error),
+      ),
+
+      ServiceError::SharesAuditFailed {
+        election_id,
+        question_id,
+        collector_id,
+      } => ErrorResponse::new(
+        StatusCode::INTERNAL_SERVER_ERROR,
+        "Collector shares are not consistent with the other collectors".into(),
+        GlobalErrorCode::SharesAuditFailed,
+        match collector_id {
+          Some(collector_id) => format!(
+            "Election ID: {}, Question ID: {}, Collector ID: {}",
+            election_id, question_id, collector_id
+          ),
+          None => format!("Election ID: {}, Question ID: {}", election_id, question_id),
+        },
+      ),
+
+      ServiceError::PurgeElectionError(collector_id, error) => ErrorResponse::new(
+        StatusCode::INTERNAL_SERVER_ERROR,
+        "Failed to purge the election from a collector".into(),
+        GlobalErrorCode::PurgeElectionError,
+        format!("Collector ID: {}, Error: {:?}", collector_id, error),
+      ),
+
+      ServiceError::SaveCheckpointError(election_id, error) => ErrorResponse::new(
+        StatusCode::INTERNAL_SERVER_ERROR,
+        "Failed to save the initialization checkpoint".into(),
+        GlobalErrorCode::SaveCheckpointError,
+        format!("Election ID: {}, Error: {}", election_id, error),
       ),
     }
   }
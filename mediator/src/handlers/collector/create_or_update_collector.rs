@@ -4,7 +4,7 @@ use serde::Deserialize;
 use uuid_b64::UuidB64 as Uuid;
 use validator::Validate;
 
-use crate::auth::{CollectorToken, JWTSecret};
+use crate::auth::{CollectorToken, IdentityKey, JWTSecret};
 use crate::db::DbConnection;
 use crate::errors::ServiceError;
 use crate::models::Collector;
@@ -22,6 +22,9 @@ pub struct CreateUpdateCollectorData {
   private_base_uri: Uri,
 
   is_secure: bool,
+
+  // Ed25519 public key (Base64 DER)
+  identity_key: String,
 }
 
 pub async fn create_or_update_collector(
@@ -38,14 +41,28 @@ pub async fn create_or_update_collector(
     name,
     private_base_uri,
     is_secure,
+    identity_key,
   } = data.into_inner();
 
+  if !IdentityKey::is_valid(&identity_key) {
+    return Err(ServiceError::IdentityKeyInvalid(id));
+  }
+
+  // The identity key is pinned the first time it is registered, so it can never be replaced
+  //   Otherwise, anybody with a collector token could impersonate the collector during verification
+  if let Some(existing) = Collector::find_optional(&id, &conn)? {
+    if existing.identity_key.is_some_and(|pinned| pinned != identity_key) {
+      return Err(ServiceError::IdentityKeyMismatch(id));
+    }
+  }
+
   // Insert into the database, updating values if it already exists
   let collector = Collector {
     id,
     name,
     private_base_uri: private_base_uri.to_string(),
     is_secure,
+    identity_key: Some(identity_key),
   }
   .insert_or_update(&conn)?;
 
@@ -0,0 +1,211 @@
+use actix_web::client::Client;
+use actix_web::{web, HttpResponse};
+use curv_kzen::BigInt;
+use futures::future::join_all;
+use jsonwebtoken::EncodingKey;
+use uuid_b64::UuidB64 as Uuid;
+
+use crate::auth::{JWTSecret, MediatorToken, ServerToken, DEFAULT_PERMISSIONS};
+use crate::db::DbConnection;
+use crate::errors::{ClientRequestError, ServiceError};
+use crate::models::{CancelationCommitment, Collector, Election, Question};
+use crate::protocol::Group;
+use crate::utils::ConvertBigInt;
+use crate::views::election::QuestionShareTotals;
+
+pub async fn audit_shares(
+  token: ServerToken,
+  path: web::Path<Uuid>,
+  conn: DbConnection,
+  jwt_secret: web::Data<JWTSecret>,
+) -> Result<HttpResponse, ServiceError> {
+  token.test_can_view_elections()?;
+
+  let election = Election::find_resource(&path, &conn)?;
+  audit_election_shares(&election, &conn, &jwt_secret.get_encoding_key()).await?;
+
+  Ok(HttpResponse::Ok().finish())
+}
+
+///
+/// Make sure the shares matrices of the collectors are still consistent with each other
+///
+/// Each collector commits to the row and column totals of its own table: g^(Σ_i S_c,i) and g^(Σ_i S~c,i)
+///   1. The totals of every collector must match the cancelation commitments from initialization:
+///      g^(Σ_i S~c,i) = g^(Σ_i S_c,i) * Π_i X_c,i
+///      A collector that fails this check (Or does not respond) is named in the logs and skipped
+///   2. Every cell of the full matrix is filled by exactly one collector, so the verification shares
+///      and the ballot shares of every collector must add up to the same value:
+///      Π_c g^(Σ_i S_c,i) = Π_c g^(Σ_i S~c,i)
+///      The missing tables are part of both totals, so this needs every collector to pass the first check
+///
+/// With (t,n)-threshold shares, any t collectors can still compute the cancelation shares,
+///   so the audit only fails if fewer than t collectors pass. Otherwise every collector must pass.
+///
+pub async fn audit_election_shares(
+  election: &Election,
+  conn: &DbConnection,
+  jwt_encoding_key: &EncodingKey,
+) -> Result<(), ServiceError> {
+  let collectors = election.get_collectors_by_index(conn)?;
+  let results = join_all(
+    collectors
+      .iter()
+      .map(|(_, collector)| get_share_totals_collector(election.id, collector, jwt_encoding_key)),
+  )
+  .await;
+
+  let user_ids: Vec<Uuid> = election
+    .get_registrations(conn)?
+    .iter()
+    .map(|registration| registration.user_id)
+    .collect();
+
+  // Skip any collector that did not respond, as long as enough collectors are left
+  let required = election.threshold.map(|t| t as usize).unwrap_or(collectors.len());
+  let mut responses = Vec::with_capacity(collectors.len());
+  let mut last_error = None;
+  for ((_, collector), result) in collectors.iter().zip(results) {
+    match result {
+      Ok(totals) => responses.push((collector, totals)),
+      Err(e) => {
+        log::warn!("Skip collector '{}' in the shares audit: {}", collector.name, e);
+        last_error = Some(e);
+      }
+    }
+  }
+
+  if responses.len() < required {
+    return Err(last_error.expect("Error: Every collector responded"));
+  }
+
+  for question in election.get_questions(conn)? {
+    let group = Group::new(
+      election.commitment_group,
+      question.generator.to_bigint(),
+      question.prime.to_bigint(),
+    );
+
+    let mut passed = Vec::with_capacity(responses.len());
+    let mut failed_error = None;
+    for (collector, totals) in responses.iter() {
+      match check_collector_totals(&group, &question, collector, totals, &user_ids, conn)? {
+        Some(totals) => passed.push(totals),
+        None => {
+          log::warn!(
+            "Shares of collector '{}' no longer match the cancelation commitments for question {}",
+            collector.name,
+            question.id
+          );
+          failed_error = Some(ServiceError::SharesAuditFailed {
+            election_id: election.id,
+            question_id: question.id,
+            collector_id: Some(collector.id),
+          });
+        }
+      }
+    }
+
+    if passed.len() < required {
+      return Err(failed_error.expect("Error: Every collector passed"));
+    }
+
+    // Cross-collector check, once every table is known to be good
+    if passed.len() == collectors.len() {
+      let row_total = group.product(passed.iter().map(|t| &t.forward_row_total));
+      let column_total = group.product(passed.iter().map(|t| &t.forward_column_total));
+      let reverse_row_total = group.product(passed.iter().map(|t| &t.reverse_row_total));
+      let reverse_column_total = group.product(passed.iter().map(|t| &t.reverse_column_total));
+      if row_total.is_none()
+        || reverse_row_total.is_none()
+        || row_total != column_total
+        || reverse_row_total != reverse_column_total
+      {
+        log::warn!(
+          "Shares of the collectors do not add up to the same total for question {}",
+          question.id
+        );
+        return Err(ServiceError::SharesAuditFailed {
+          election_id: election.id,
+          question_id: question.id,
+          collector_id: None,
+        });
+      }
+    }
+  }
+
+  log::debug!(
+    "Shares of {} of {} collectors are consistent for election {}",
+    responses.len(),
+    collectors.len(),
+    election.id
+  );
+  Ok(())
+}
+
+///
+/// Check the totals from one collector against its cancelation commitments
+///
+/// Returns the totals for the question if they are valid, or None if the collector failed the check
+///
+fn check_collector_totals<'a>(
+  group: &Group,
+  question: &Question,
+  collector: &Collector,
+  totals: &'a [QuestionShareTotals],
+  user_ids: &[Uuid],
+  conn: &DbConnection,
+) -> Result<Option<&'a QuestionShareTotals>, ServiceError> {
+  let totals = match totals
+    .iter()
+    .find(|t| t.question_id == question.id && t.num_registrations == user_ids.len())
+  {
+    Some(totals) => totals,
+    None => return Ok(None),
+  };
+
+  let commitments = CancelationCommitment::get_for_users(question, &collector.id, user_ids, conn)?;
+  if commitments.len() != user_ids.len() {
+    return Ok(None);
+  }
+
+  let forward: Vec<BigInt> = commitments.iter().map(|c| c.forward_commitment.to_bigint()).collect();
+  let reverse: Vec<BigInt> = commitments.iter().map(|c| c.reverse_commitment.to_bigint()).collect();
+  let expected_forward = group
+    .product(forward.iter())
+    .and_then(|product| group.mul(&totals.forward_row_total, &product));
+  let expected_reverse = group
+    .product(reverse.iter())
+    .and_then(|product| group.mul(&totals.reverse_row_total, &product));
+
+  if expected_forward.as_ref() == Some(&totals.forward_column_total)
+    && expected_reverse.as_ref() == Some(&totals.reverse_column_total)
+  {
+    Ok(Some(totals))
+  } else {
+    Ok(None)
+  }
+}
+
+///
+/// Send request to an individual collector to get the committed totals of its shares
+///
+async fn get_share_totals_collector(
+  election_id: Uuid,
+  collector: &Collector,
+  jwt_encoding_key: &EncodingKey,
+) -> Result<Vec<QuestionShareTotals>, ServiceError> {
+  let url = collector.private_api_url(&format!("/elections/{}/shares/totals", election_id));
+
+  log::debug!("Request share totals from collector '{}'", collector.name);
+  let totals_request = Client::builder()
+    .disable_timeout()
+    .bearer_auth(MediatorToken::new(DEFAULT_PERMISSIONS).encode(jwt_encoding_key)?)
+    .finish()
+    .get(&url)
+    .send();
+
+  ClientRequestError::handle(totals_request)
+    .await
+    .map_err(|e| ServiceError::SharesAuditError(collector.id, e))
+}
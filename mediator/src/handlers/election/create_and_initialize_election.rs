@@ -1,51 +1,181 @@
 use actix_web::client::Client;
 use actix_web::{web, HttpResponse};
+use curv_kzen::arithmetic::Converter;
 use curv_kzen::BigInt;
 use diesel::prelude::*;
+use futures::future::try_join_all;
+use jsonwebtoken::EncodingKey;
 use kzen_paillier::*;
-use rand::{seq::SliceRandom, thread_rng};
 use serde::{Deserialize, Serialize};
 use uuid_b64::UuidB64 as Uuid;
-use validator::Validate;
+use validator::{Validate, ValidationError};
 
-use crate::auth::{JWTSecret, MediatorToken, ServerToken, DEFAULT_PERMISSIONS};
+use super::audit_shares::audit_election_shares;
+use crate::auth::{CheckpointSecret, JWTSecret, MediatorToken, ServerToken, DEFAULT_PERMISSIONS};
 use crate::db::DbConnection;
 use crate::errors::{ClientRequestError, ServiceError};
-use crate::models::{Collector, Election, ElectionCollector, Question, Registration};
+use crate::models::{
+  count_choice_selection_sets, BallotEncoding, CancelationCommitment, Collector, CommitmentGroup, Election,
+  ElectionCollector, InitializationCheckpoint, Question, QuestionType, Registration, VerificationMode,
+  MAX_CHOICE_SELECTION_SETS, MAX_RANKED_CANDIDATES,
+};
 use crate::protocol::location_anonymization as loc_anon;
-use crate::views::election::InitializeElectionResult;
+use crate::protocol::shuffle_proof::{self, ShuffleProof};
+use crate::utils::ConvertBigInt;
+use crate::views::election::{
+  EncryptedLocationShares, InitializeElectionResult, LocationLink, QuestionCancelationCommitments,
+  QuestionShareCommitments, ThresholdDealing,
+};
+
+/// Rounds of distributed location anonymization, with a different key holder each time
+const LOCATION_ROUNDS: usize = 2;
 
 #[derive(Debug, Serialize, Deserialize, Validate)]
+#[validate(schema(function = "validate_struct", skip_on_field_errors = false))]
 #[serde(rename_all = "camelCase")]
 pub struct CreateElectionData {
   id: Uuid,
   is_public: bool,
   creator_id: Uuid,
 
-  #[serde(with = "kzen_paillier::serialize::bigint")]
-  generator: BigInt,
-
-  #[serde(with = "kzen_paillier::serialize::bigint")]
-  prime: BigInt,
-
   #[validate(length(min = 1))]
   #[validate]
   questions: Vec<CreateElectionQuestion>,
 
   #[validate(length(min = 2))]
   registered_users: Vec<Uuid>,
+  registered_weights: Vec<i64>,
+
+  ballot_encoding: BallotEncoding,
+  #[validate(range(min = 2))]
+  counter_radix: i64,
+  commitment_group: CommitmentGroup,
+  verification_mode: VerificationMode,
 
   #[validate(length(min = 2))]
   collectors: Vec<Uuid>,
+
+  /// Use (t,n)-threshold secret sharing for the cancelation shares
+  #[serde(default)]
+  threshold: Option<usize>,
+
+  /// Shuffle the location shares between the collectors, so the mediator never holds a location key
+  #[serde(default)]
+  distributed_locations: bool,
+
+  /// Election manifest signed by the API server, which is forwarded to every collector without changes
+  manifest: serde_json::Value,
 }
 
 #[derive(Debug, Serialize, Deserialize, Validate)]
+#[validate(schema(function = "validate_question", skip_on_field_errors = false))]
 #[serde(rename_all = "camelCase")]
 pub struct CreateElectionQuestion {
   id: Uuid,
 
   #[validate(range(min = 2))]
   num_candidates: i64,
+  question_type: QuestionType,
+
+  #[validate(range(min = 1))]
+  min_selections: i64,
+  max_selections: i64,
+
+  #[serde(with = "kzen_paillier::serialize::bigint")]
+  generator: BigInt,
+
+  #[serde(with = "kzen_paillier::serialize::bigint")]
+  prime: BigInt,
+}
+
+///
+/// Make sure there is a weight for every registered user, and only counter questions have weights
+///
+/// A threshold must be between 2 and the number of collectors, and needs a prime-order group
+///
+fn validate_struct(input: &CreateElectionData) -> Result<(), ValidationError> {
+  if input.registered_users.len() != input.registered_weights.len() {
+    return Err(ValidationError::new(
+      "length(registered_users) not equal to length(registered_weights)",
+    ));
+  }
+
+  if input.registered_weights.iter().any(|w| *w < 1) {
+    return Err(ValidationError::new("registered_weights must be at least 1"));
+  }
+
+  // One-hot chunks are decoded one voter at a time, so a weight would identify the voter
+  let only_counter = input
+    .questions
+    .iter()
+    .all(|q| input.ballot_encoding.for_question(q.question_type) == BallotEncoding::Counter);
+  if !only_counter && input.registered_weights.iter().any(|w| *w != 1) {
+    return Err(ValidationError::new(
+      "registered_weights must all be 1 unless every question uses the counter encoding",
+    ));
+  }
+
+  // Counter digits store the total weight of every voter, so they must never overflow
+  if input.registered_weights.iter().sum::<i64>() >= input.counter_radix {
+    return Err(ValidationError::new(
+      "counter_radix must be larger than the sum of registered_weights",
+    ));
+  }
+
+  if let Some(threshold) = input.threshold {
+    if threshold < 2 || threshold > input.collectors.len() {
+      return Err(ValidationError::new(
+        "threshold must be between 2 and length(collectors)",
+      ));
+    }
+
+    // Lagrange interpolation needs every nonzero value to have an inverse mod the group order
+    if input.commitment_group == CommitmentGroup::Full {
+      return Err(ValidationError::new(
+        "threshold secret sharing needs a prime-order commitment group",
+      ));
+    }
+  }
+
+  Ok(())
+}
+
+///
+/// Make sure the question type is valid for the number of candidates:
+///   Choice questions must have 1 <= min_selections <= max_selections <= num_candidates,
+///     and at most MAX_CHOICE_SELECTION_SETS sets of selections
+///   Ranked questions must have num_candidates <= MAX_RANKED_CANDIDATES
+///
+/// Ranked questions always rank every candidate, so the selections are ignored
+///
+fn validate_question(input: &CreateElectionQuestion) -> Result<(), ValidationError> {
+  match input.question_type {
+    QuestionType::Choice => {
+      if input.max_selections < input.min_selections || input.max_selections > input.num_candidates {
+        return Err(ValidationError::new(
+          "max_selections must be between min_selections and num_candidates",
+        ));
+      }
+
+      if count_choice_selection_sets(input.num_candidates, input.min_selections, input.max_selections)
+        > MAX_CHOICE_SELECTION_SETS
+      {
+        return Err(ValidationError::new(
+          "choice questions cannot have more than 5040 sets of selections",
+        ));
+      }
+    }
+
+    QuestionType::Ranked => {
+      if input.num_candidates > MAX_RANKED_CANDIDATES {
+        return Err(ValidationError::new(
+          "ranked questions cannot have more than 7 candidates",
+        ));
+      }
+    }
+  }
+
+  Ok(())
 }
 
 pub async fn create_and_initialize_election(
@@ -53,6 +183,7 @@ pub async fn create_and_initialize_election(
   data: web::Json<CreateElectionData>,
   conn: DbConnection,
   jwt_key: web::Data<JWTSecret>,
+  checkpoint_secret: web::Data<CheckpointSecret>,
 ) -> Result<HttpResponse, ServiceError> {
   token.test_can_create_election()?;
   data.validate()?;
@@ -70,6 +201,7 @@ pub async fn create_and_initialize_election(
   // If the election already exists in the database, delete it!
   //   We want a fresh slate every time this method is called
   //   This will also automatically cascade delete entries foreign keys
+  //   A partially initialized election only has a checkpoint, which is resumed below
   if let Some(election) = Election::find_optional(&data.id, &conn)? {
     election.delete(&conn)?;
   }
@@ -81,25 +213,31 @@ pub async fn create_and_initialize_election(
     .map(|collector_id| Collector::find_resource(collector_id, &conn))
     .collect::<Result<_, _>>()?;
 
-  // Generate the STPM Paillier cryptosystem key pair
-  // Should have enough bits to store our locations without any modulus
-  let num_bits = 512;
-  log::debug!("Generate Paillier keypair with {} bits", num_bits);
-  let (encryption_key, decryption_key) = Paillier::keypair_safe_primes_with_modulus_size(num_bits).keys();
-
-  // Encrypt the locations 0 to N-1 using the location anonymization protocol
-  log::debug!(
-    "Encrypting locations 0 .. {} for location anonymization",
-    data.registered_users.len() - 1
-  );
-  let mut encrypted_locations: Vec<BigInt> = (0u64..(data.registered_users.len() as u64))
-    .into_iter()
-    .map(|i| loc_anon::step_1(&BigInt::from(i), &encryption_key.n))
-    .collect();
-
-  // Then shuffle the list
-  log::debug!("Shuffle encrypted locations");
-  encrypted_locations.shuffle(&mut thread_rng());
+  // Resume the collector chain from the last checkpoint if the election parameters did not change,
+  //   otherwise start again with a new location key pair
+  let mut resumed_chain = None;
+  if let Some(checkpoint) = InitializationCheckpoint::find_optional(&data.id, &conn)? {
+    resumed_chain = ChainState::resume(&checkpoint, &data, &checkpoint_secret);
+    if resumed_chain.is_none() {
+      log::debug!("Discard the initialization checkpoint, since the election parameters changed");
+      checkpoint.delete(&conn)?;
+    }
+  }
+
+  let mut chain = match resumed_chain {
+    Some(chain) => {
+      log::debug!(
+        "Resume initialization from collector {} of {}",
+        chain.next_collector_index + 1,
+        collectors.len()
+      );
+      chain
+    }
+    None => ChainState::start(&data),
+  };
+
+  // Taken before the collector data moves fields out of the request
+  let threshold = data.threshold;
 
   // Build data needed to register the election with both collectors
   //   Most of the data is the same, but some of values need to be updated per collector
@@ -108,32 +246,62 @@ pub async fn create_and_initialize_election(
     id: data.id,
     is_public: data.is_public,
     creator_id: data.creator_id,
-    generator: data.generator,
-    prime: data.prime,
     questions: data.questions,
     registered_users: data.registered_users,
+    registered_weights: data.registered_weights,
+    ballot_encoding: data.ballot_encoding,
+    counter_radix: data.counter_radix,
+    commitment_group: data.commitment_group,
+    verification_mode: data.verification_mode,
+    threshold,
     num_collectors: collectors.len(),
     collector_index: 0,
-    encrypted_locations,
-    n: Some(encryption_key.n.clone()),
+    encrypted_locations: std::mem::take(&mut chain.encrypted_locations),
+    n: chain.decryption_key.as_ref().map(|_| chain.location_modulus.clone()),
+    location_shuffle: chain.location_shuffle.take(),
+    distributed_locations: data.distributed_locations,
+    manifest: data.manifest,
   };
 
   // =========================================
   //   Talk with each collector in order
   // =========================================
-  for (index, collector) in collectors.iter().enumerate() {
+  for (index, collector) in collectors.iter().enumerate().skip(chain.next_collector_index) {
     // Set the index for the current collector
     create_elections_data.collector_index = index;
 
-    // If we are on the last collector, decrypt the locations for the protocol
-    if index == collectors.len() - 1 {
-      create_elections_data.n = None;
+    match chain.decryption_key {
+      // With distributed locations, every collector starts with empty location shares,
+      //   which are replaced by the rounds of location anonymization once every collector is initialized
+      None => {
+        create_elections_data.encrypted_locations = create_elections_data
+          .registered_users
+          .iter()
+          .map(|_| BigInt::from(0))
+          .collect();
+      }
 
-      log::debug!("Decrypt locations for the final collector");
-      create_elections_data
-        .encrypted_locations
-        .iter_mut()
-        .for_each(|l| *l = loc_anon::step_last(l, &decryption_key.p, &decryption_key.q));
+      // If we are on the last collector, decrypt the locations for the protocol
+      Some(ref decryption_key) if index == collectors.len() - 1 => {
+        create_elections_data.n = None;
+
+        // The final collector re-encrypts every location to check the decryption
+        if let Some(ref mut location_shuffle) = create_elections_data.location_shuffle {
+          location_shuffle.decryption_randomness = create_elections_data
+            .encrypted_locations
+            .iter()
+            .map(|l| loc_anon::recover_randomness(l, &decryption_key.p, &decryption_key.q))
+            .collect();
+        }
+
+        log::debug!("Decrypt locations for the final collector");
+        create_elections_data
+          .encrypted_locations
+          .iter_mut()
+          .for_each(|l| *l = loc_anon::step_last(l, &decryption_key.p, &decryption_key.q));
+      }
+
+      Some(_) => {}
     }
 
     // Build the request object for the collector
@@ -161,45 +329,569 @@ pub async fn create_and_initialize_election(
 
     log::debug!("Got success response from collector {}", index + 1);
 
+    // Every collector checked the same manifest, so they must all agree on the hash
+    match chain.manifest_hash {
+      Some(ref hash) if *hash != collector_response.manifest_hash => {
+        return Err(ServiceError::ManifestInvalid {
+          election_id: create_elections_data.id,
+          collector_id: collector.id,
+        });
+      }
+      _ => chain.manifest_hash = Some(collector_response.manifest_hash),
+    }
+
     // Update the list of encrypted locations
-    create_elections_data.encrypted_locations = collector_response.encryption_result;
+    let input = std::mem::replace(
+      &mut create_elections_data.encrypted_locations,
+      collector_response.encryption_result,
+    );
+
+    // The next collector checks the proof for the locations we just received
+    if let (Some(location_shuffle), Some(proof)) = (
+      create_elections_data.location_shuffle.as_mut(),
+      collector_response.shuffle_proof,
+    ) {
+      location_shuffle.input = input;
+      location_shuffle.output = create_elections_data.encrypted_locations.clone();
+      location_shuffle.proof = proof;
+    }
+
+    // The server combines the share commitments from every collector
+    chain.results.push(CollectorResult {
+      share_commitments: collector_response.share_commitments,
+      cancelation_commitments: collector_response.cancelation_commitments,
+      public_key: collector_response.public_key,
+    });
+    chain.next_collector_index = index + 1;
+
+    // A stale checkpoint would resume from an earlier collector with the state of a later one, so stop here
+    chain.save(&create_elections_data, &collectors, &checkpoint_secret, &conn)?;
+  }
+
+  let ChainState {
+    location_modulus,
+    results,
+    manifest_hash,
+    ..
+  } = chain;
+
+  let mut share_commitments = Vec::with_capacity(results.len());
+  let mut cancelation_commitments = Vec::with_capacity(results.len());
+  let mut public_keys = Vec::with_capacity(results.len());
+  for result in results {
+    share_commitments.push(result.share_commitments);
+    cancelation_commitments.push(result.cancelation_commitments);
+    public_keys.push(result.public_key);
+  }
+
+  // ==========================================
+  //  Distributed location anonymization
+  // ==========================================
+  if data.distributed_locations {
+    // The collectors overwrite their location shares on every shuffle, so a failed shuffle cannot be resumed
+    let shuffle_result = shuffle_location_shares(
+      create_elections_data.id,
+      &location_modulus,
+      &create_elections_data.registered_users,
+      &collectors,
+      &public_keys,
+      &jwt_encoding_key,
+    )
+    .await;
+
+    if shuffle_result.is_err() {
+      InitializationCheckpoint::delete_from_id_optional(&create_elections_data.id, &conn)?;
+    }
+    shuffle_result?;
+  }
+
+  // ==========================================
+  //  Threshold shares between the collectors
+  // ==========================================
+  if let Some(threshold) = threshold {
+    log::debug!(
+      "Distribute threshold shares between {} collectors (threshold {})",
+      collectors.len(),
+      threshold
+    );
+    distribute_threshold_shares(
+      create_elections_data.id,
+      threshold,
+      &create_elections_data.registered_users,
+      &collectors,
+      public_keys,
+      &jwt_encoding_key,
+    )
+    .await?;
   }
 
   // ==========================================
   //  Database transaction to mark as finished
   // ==========================================
-  conn.get().transaction::<_, ServiceError, _>(|| {
+  let election = conn.get().transaction::<_, ServiceError, _>(|| {
     // Create the election itself
     let election = Election::new(
       create_elections_data.id,
       create_elections_data.is_public,
       create_elections_data.creator_id,
-      &create_elections_data.generator,
-      &create_elections_data.prime,
+      create_elections_data.commitment_group,
+      threshold.map(|threshold| threshold as i64),
+      manifest_hash.clone().unwrap_or_default(),
     )
     .insert(&conn)?;
 
     // Add the list of questions
-    for question in create_elections_data.questions {
-      Question::new(question.id, election.id).insert(&conn)?;
+    for question in create_elections_data.questions.iter() {
+      Question::new(question.id, election.id, &question.generator, &question.prime).insert(&conn)?;
     }
 
     // Add the list of registered users
-    for user_id in create_elections_data.registered_users {
-      Registration::new(user_id, election.id).insert(&conn)?;
+    for user_id in create_elections_data.registered_users.iter() {
+      Registration::new(*user_id, election.id).insert(&conn)?;
     }
 
-    // Finally, the list of collectors
-    for collector in collectors {
-      ElectionCollector::new(election.id, collector.id).insert(&conn)?;
+    // The list of collectors (Keep the order for threshold secret sharing)
+    for (index, collector) in collectors.iter().enumerate() {
+      ElectionCollector::new(election.id, collector.id, index as i64).insert(&conn)?;
     }
 
-    Ok(())
+    // The cancelation commitments from every collector, so we can check the cancelation shares later
+    let num_users = create_elections_data.registered_users.len();
+    for (collector, commitments) in collectors.iter().zip(cancelation_commitments.iter()) {
+      for question in create_elections_data.questions.iter() {
+        let question_commitments = commitments
+          .iter()
+          .find(|c| c.question_id == question.id)
+          .filter(|c| c.forward_commitments.len() == num_users && c.reverse_commitments.len() == num_users)
+          .ok_or(ServiceError::CancelationSharesInvalid {
+            election_id: election.id,
+            question_id: question.id,
+            collector_id: Some(collector.id),
+          })?;
+
+        for (user_id, (forward, reverse)) in create_elections_data.registered_users.iter().zip(
+          question_commitments
+            .forward_commitments
+            .iter()
+            .zip(question_commitments.reverse_commitments.iter()),
+        ) {
+          CancelationCommitment::new(*user_id, election.id, question.id, collector.id, forward, reverse)
+            .insert(&conn)?;
+        }
+      }
+    }
+
+    // Finally, the collector chain does not need to be resumed anymore
+    InitializationCheckpoint::delete_from_id_optional(&election.id, &conn)?;
+
+    Ok(election)
   })?;
 
+  // Catch a broken shares table now, instead of when voting is closed
+  //   The election is kept, so the audit can be run again. Retrying initialization replaces the election anyways.
+  log::debug!("Audit the shares of every collector");
+  audit_election_shares(&election, &conn, &jwt_encoding_key).await?;
+
   // Woohoo! Election is now fully initialized!
-  //  Return the modulus n back to the server
-  Ok(HttpResponse::Ok().json(InitializeElectionResult { n: encryption_key.n }))
+  //  Return the modulus n and the commitments back to the server
+  Ok(HttpResponse::Ok().json(InitializeElectionResult {
+    n: location_modulus,
+    share_commitments,
+    cancelation_commitments,
+  }))
+}
+
+///
+/// Distributed location anonymization: the collectors run the location anonymization chain between themselves,
+///   so the mediator never holds a key that can decrypt the locations
+///
+/// Every round has a key holder Ck, which uses its own Paillier public key nk:
+///   1. The first round starts from the trivial encryptions E(i; 1) of the locations 0 to N-1.
+///      Every later round starts with E(s_1,j) * ... * E(s_c,j), the location shares of every collector encrypted with nk
+///   2. Every other collector (in order) verifies all shuffles in the round so far, then masks the ciphertexts
+///      with a proof of shuffle. It keeps the random values as its new location shares.
+///      The first collector in the round also shuffles the ciphertexts, just like collector 0 in the normal chain.
+///   3. The key holder verifies every shuffle and decrypts the result as its new location share
+///
+/// The first two collectors take turns as the key holder, so each of them shuffles one round.
+///   Nobody can link the users to their locations without the permutations of both collectors.
+///
+async fn shuffle_location_shares(
+  election_id: Uuid,
+  modulus: &BigInt,
+  registered_users: &[Uuid],
+  collectors: &[Collector],
+  public_keys: &[BigInt],
+  jwt_encoding_key: &EncodingKey,
+) -> Result<(), ServiceError> {
+  for (key_holder_index, key_holder) in collectors.iter().enumerate().take(LOCATION_ROUNDS) {
+    log::debug!(
+      "Location anonymization round {} of {}, with collector {} as the key holder",
+      key_holder_index + 1,
+      LOCATION_ROUNDS,
+      key_holder_index + 1
+    );
+    let public_key = &public_keys[key_holder_index];
+
+    // Step 1: Every collector encrypts its location shares from the previous round with the key of the key holder
+    let encrypted_shares: Vec<EncryptedLocationShares> = if key_holder_index == 0 {
+      Vec::new()
+    } else {
+      let encrypt_data = EncryptLocationSharesData {
+        registered_users,
+        public_key,
+      };
+      try_join_all(collectors.iter().enumerate().map(|(index, collector)| {
+        let encrypt_data = &encrypt_data;
+        async move {
+          let request = Client::builder()
+            .disable_timeout()
+            .bearer_auth(MediatorToken::new(DEFAULT_PERMISSIONS).encode(jwt_encoding_key)?)
+            .finish()
+            .post(collector.private_api_url(&format!("/elections/{}/locations/encrypt", election_id)))
+            .send_json(encrypt_data);
+
+          ClientRequestError::handle(request)
+            .await
+            .map_err(|error| ServiceError::RegisterElectionError {
+              collector_id: collector.id,
+              collector_number: index + 1,
+              error,
+            })
+        }
+      }))
+      .await?
+    };
+
+    // Step i: Every other collector shuffles in order, checking every shuffle before it
+    let mut links: Vec<LocationLink> = Vec::new();
+    for (index, collector) in collectors.iter().enumerate() {
+      if index == key_holder_index {
+        continue;
+      }
+
+      let shuffle_data = LocationRoundData {
+        registered_users,
+        modulus,
+        round: LocationRound {
+          public_key,
+          encrypted_shares: &encrypted_shares,
+          links: &links,
+        },
+      };
+      let shuffle_request = Client::builder()
+        .disable_timeout()
+        .bearer_auth(MediatorToken::new(DEFAULT_PERMISSIONS).encode(jwt_encoding_key)?)
+        .finish()
+        .post(collector.private_api_url(&format!("/elections/{}/locations/shuffle", election_id)))
+        .send_json(&shuffle_data);
+
+      let link: LocationLink =
+        ClientRequestError::handle(shuffle_request)
+          .await
+          .map_err(|error| ServiceError::RegisterElectionError {
+            collector_id: collector.id,
+            collector_number: index + 1,
+            error,
+          })?;
+      links.push(link);
+    }
+
+    // Last step: The key holder checks the whole round, then decrypts its new location shares
+    let decrypt_data = LocationRoundData {
+      registered_users,
+      modulus,
+      round: LocationRound {
+        public_key,
+        encrypted_shares: &encrypted_shares,
+        links: &links,
+      },
+    };
+    let decrypt_request = Client::builder()
+      .disable_timeout()
+      .bearer_auth(MediatorToken::new(DEFAULT_PERMISSIONS).encode(jwt_encoding_key)?)
+      .finish()
+      .post(key_holder.private_api_url(&format!("/elections/{}/locations/decrypt", election_id)))
+      .send_json(&decrypt_data);
+
+    ClientRequestError::handle_empty(decrypt_request)
+      .await
+      .map_err(|error| ServiceError::RegisterElectionError {
+        collector_id: key_holder.id,
+        collector_number: key_holder_index + 1,
+        error,
+      })?;
+  }
+
+  Ok(())
+}
+
+///
+/// Have every collector split its cancelation shares into (t,n)-threshold shares,
+///   then forward the dealings to the collector that should receive them
+///
+/// Each share is encrypted with the public key of the recipient, so the mediator never learns any of them
+///
+async fn distribute_threshold_shares(
+  election_id: Uuid,
+  threshold: usize,
+  registered_users: &[Uuid],
+  collectors: &[Collector],
+  public_keys: Vec<BigInt>,
+  jwt_encoding_key: &EncodingKey,
+) -> Result<(), ServiceError> {
+  let deal_data = DealThresholdSharesData {
+    threshold,
+    registered_users,
+    public_keys,
+  };
+
+  // dealings[c][j] = Shares from collector c for collector j
+  let dealings: Vec<Vec<ThresholdDealing>> = try_join_all(collectors.iter().enumerate().map(|(index, collector)| {
+    let deal_data = &deal_data;
+    async move {
+      let request = Client::builder()
+        .disable_timeout()
+        .bearer_auth(MediatorToken::new(DEFAULT_PERMISSIONS).encode(jwt_encoding_key)?)
+        .finish()
+        .post(collector.private_api_url(&format!("/elections/{}/threshold/deal", election_id)))
+        .send_json(deal_data);
+
+      ClientRequestError::handle(request)
+        .await
+        .map_err(|error| ServiceError::RegisterElectionError {
+          collector_id: collector.id,
+          collector_number: index + 1,
+          error,
+        })
+    }
+  }))
+  .await?;
+
+  // Regroup the dealings by the recipient
+  let mut received: Vec<Vec<ThresholdDealing>> = collectors.iter().map(|_| Vec::new()).collect();
+  for dealer in dealings {
+    for (recipient, dealing) in received.iter_mut().zip(dealer) {
+      recipient.push(dealing);
+    }
+  }
+
+  try_join_all(
+    collectors
+      .iter()
+      .zip(received)
+      .enumerate()
+      .map(|(index, (collector, dealings))| async move {
+        let receive_data = ReceiveThresholdSharesData {
+          registered_users,
+          dealings,
+        };
+
+        let request = Client::builder()
+          .disable_timeout()
+          .bearer_auth(MediatorToken::new(DEFAULT_PERMISSIONS).encode(jwt_encoding_key)?)
+          .finish()
+          .post(collector.private_api_url(&format!("/elections/{}/threshold/receive", election_id)))
+          .send_json(&receive_data);
+
+        ClientRequestError::handle_empty(request)
+          .await
+          .map_err(|error| ServiceError::RegisterElectionError {
+            collector_id: collector.id,
+            collector_number: index + 1,
+            error,
+          })
+      }),
+  )
+  .await?;
+
+  Ok(())
+}
+
+///
+/// State of the collector chain, which is saved after every collector so initialization can resume
+///
+struct ChainState {
+  next_collector_index: usize,
+
+  // Location anonymization key pair held by the mediator (None with distributed locations)
+  location_modulus: BigInt,
+  decryption_key: Option<DecryptionKey>,
+  encrypted_locations: Vec<BigInt>,
+  location_shuffle: Option<LocationShuffle>,
+
+  // Responses from every collector before next_collector_index
+  results: Vec<CollectorResult>,
+  manifest_hash: Option<String>,
+}
+
+/// Values returned by a collector that are sent back to the server once every collector is done
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CollectorResult {
+  share_commitments: Vec<QuestionShareCommitments>,
+  cancelation_commitments: Vec<QuestionCancelationCommitments>,
+
+  #[serde(with = "kzen_paillier::serialize::bigint")]
+  public_key: BigInt,
+}
+
+impl ChainState {
+  ///
+  /// Location anonymization either uses a Paillier key pair held by the mediator,
+  ///   or the collectors shuffle the location shares between themselves without any shared key
+  ///
+  fn start(data: &CreateElectionData) -> Self {
+    let num_users = data.registered_users.len() as u64;
+
+    let (location_modulus, decryption_key, encrypted_locations, location_shuffle) = if data.distributed_locations {
+      // The locations are 0 to N-1, so the shares only need to be mod N
+      (BigInt::from(num_users), None, Vec::new(), None)
+    } else {
+      // Generate the STPM Paillier cryptosystem key pair
+      // Should have enough bits to store our locations without any modulus
+      let num_bits = 512;
+      log::debug!("Generate Paillier keypair with {} bits", num_bits);
+      let (encryption_key, decryption_key) = Paillier::keypair_safe_primes_with_modulus_size(num_bits).keys();
+      let n = encryption_key.n;
+
+      // Encrypt the locations 0 to N-1 using the location anonymization protocol
+      //   Re-encrypting the trivial encryptions E(i; 1) gives the same result as step 1,
+      //   but the first collector can check that every location appears exactly once
+      log::debug!("Encrypting locations 0 .. {} for location anonymization", num_users - 1);
+      let trivial_locations: Vec<BigInt> = (0u64..num_users)
+        .map(|i| shuffle_proof::encrypt_with_randomness(&BigInt::from(i), &BigInt::from(1), &n))
+        .collect();
+
+      // Then shuffle the list
+      log::debug!("Shuffle encrypted locations");
+      let (encrypted_locations, witness) = shuffle_proof::shuffle_and_mask(&trivial_locations, &n, true, None);
+      let proof = ShuffleProof::prove(&trivial_locations, &encrypted_locations, &n, &witness, false);
+
+      let location_shuffle = LocationShuffle {
+        public_key: n.clone(),
+        input: Vec::new(),
+        output: encrypted_locations.clone(),
+        proof,
+        decryption_randomness: Vec::new(),
+      };
+
+      (n, Some(decryption_key), encrypted_locations, Some(location_shuffle))
+    };
+
+    Self {
+      next_collector_index: 0,
+      location_modulus,
+      decryption_key,
+      encrypted_locations,
+      location_shuffle,
+      results: Vec::new(),
+      manifest_hash: None,
+    }
+  }
+
+  ///
+  /// Load the chain from a checkpoint
+  ///
+  /// Only resumes with the exact same signed manifest, which also fixes the collectors and their order
+  ///   The location key is only missing once the final collector accepted the decrypted locations
+  ///
+  fn resume(
+    checkpoint: &InitializationCheckpoint,
+    data: &CreateElectionData,
+    checkpoint_secret: &CheckpointSecret,
+  ) -> Option<Self> {
+    let manifest: serde_json::Value = serde_json::from_str(&checkpoint.manifest).ok()?;
+    if manifest != data.manifest
+      || checkpoint.collectors != data.collectors
+      || checkpoint.distributed_locations != data.distributed_locations
+    {
+      return None;
+    }
+
+    let decryption_key = match checkpoint.location_key {
+      Some(ref location_key) => {
+        let location_key = checkpoint_secret.decrypt(checkpoint.election_id.uuid().as_bytes(), location_key)?;
+        let [p, q]: [String; 2] = serde_json::from_slice(&location_key).ok()?;
+        let keypair = Keypair {
+          p: BigInt::from_hex(&p).ok()?,
+          q: BigInt::from_hex(&q).ok()?,
+        };
+        Some(keypair.keys().1)
+      }
+      None if !data.distributed_locations && (checkpoint.next_collector_index as usize) < data.collectors.len() => {
+        return None
+      }
+      None => None,
+    };
+
+    let location_shuffle = match checkpoint.location_shuffle {
+      Some(ref location_shuffle) => Some(serde_json::from_str(location_shuffle).ok()?),
+      None => None,
+    };
+
+    Some(Self {
+      next_collector_index: checkpoint.next_collector_index as usize,
+      location_modulus: checkpoint.location_modulus.to_bigint(),
+      decryption_key,
+      encrypted_locations: checkpoint.encrypted_locations.iter().map(|l| l.to_bigint()).collect(),
+      location_shuffle,
+      results: serde_json::from_str(&checkpoint.collector_results).ok()?,
+      manifest_hash: checkpoint.manifest_hash.clone(),
+    })
+  }
+
+  ///
+  /// Save the chain after the last collector, along with the values that will be sent to the next one
+  ///
+  fn save(
+    &self,
+    create_elections_data: &CollectorCreateElectionData,
+    collectors: &[Collector],
+    checkpoint_secret: &CheckpointSecret,
+    conn: &DbConnection,
+  ) -> Result<(), ServiceError> {
+    let save_error = |error: String| ServiceError::SaveCheckpointError(create_elections_data.id, error);
+    let json_error = |error: serde_json::Error| save_error(error.to_string());
+    let location_shuffle = match create_elections_data.location_shuffle {
+      Some(ref location_shuffle) => Some(serde_json::to_string(location_shuffle).map_err(json_error)?),
+      None => None,
+    };
+
+    // The final collector already has the decrypted locations, so the private key is not needed anymore
+    let location_key = match self.decryption_key {
+      Some(ref decryption_key) if self.next_collector_index < collectors.len() => {
+        let plaintext =
+          serde_json::to_vec(&[decryption_key.p.to_hex(), decryption_key.q.to_hex()]).map_err(json_error)?;
+        let location_key = checkpoint_secret
+          .encrypt(create_elections_data.id.uuid().as_bytes(), &plaintext)
+          .map_err(|error| save_error(error.to_string()))?;
+        Some(location_key)
+      }
+      _ => None,
+    };
+
+    InitializationCheckpoint {
+      election_id: create_elections_data.id,
+      manifest: serde_json::to_string(&create_elections_data.manifest).map_err(json_error)?,
+      collectors: collectors.iter().map(|c| c.id).collect(),
+      next_collector_index: self.next_collector_index as i64,
+      distributed_locations: create_elections_data.distributed_locations,
+      location_modulus: self.location_modulus.to_bigdecimal(),
+      location_key,
+      encrypted_locations: create_elections_data
+        .encrypted_locations
+        .iter()
+        .map(|l| l.to_bigdecimal())
+        .collect(),
+      location_shuffle,
+      collector_results: serde_json::to_string(&self.results).map_err(json_error)?,
+      manifest_hash: self.manifest_hash.clone(),
+    }
+    .insert_or_update(conn)?;
+
+    Ok(())
+  }
 }
 
 ///
@@ -212,14 +904,14 @@ struct CollectorCreateElectionData {
   is_public: bool,
   creator_id: Uuid,
 
-  #[serde(with = "kzen_paillier::serialize::bigint")]
-  generator: BigInt,
-
-  #[serde(with = "kzen_paillier::serialize::bigint")]
-  prime: BigInt,
-
   questions: Vec<CreateElectionQuestion>,
   registered_users: Vec<Uuid>,
+  registered_weights: Vec<i64>,
+  ballot_encoding: BallotEncoding,
+  counter_radix: i64,
+  commitment_group: CommitmentGroup,
+  verification_mode: VerificationMode,
+  threshold: Option<usize>,
 
   num_collectors: usize,
   collector_index: usize,
@@ -230,6 +922,86 @@ struct CollectorCreateElectionData {
   encrypted_locations: Vec<BigInt>,
   #[serde(with = "crate::utils::serialize_option_bigint")]
   n: Option<BigInt>,
+
+  /// Proof for the previous step of location anonymization (None with distributed locations)
+  #[serde(skip_serializing_if = "Option::is_none")]
+  location_shuffle: Option<LocationShuffle>,
+  distributed_locations: bool,
+
+  manifest: serde_json::Value,
+}
+
+///
+/// Previous step of location anonymization, which the next collector verifies before continuing the chain
+///
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LocationShuffle {
+  #[serde(with = "kzen_paillier::serialize::bigint")]
+  public_key: BigInt,
+
+  /// Encrypted locations before and after the previous step (Input is empty for the first collector)
+  #[serde(with = "kzen_paillier::serialize::vecbigint")]
+  input: Vec<BigInt>,
+  #[serde(with = "kzen_paillier::serialize::vecbigint")]
+  output: Vec<BigInt>,
+  proof: ShuffleProof,
+
+  /// Randomness of every output ciphertext, only sent to the last collector to check the decryption
+  #[serde(with = "kzen_paillier::serialize::vecbigint")]
+  decryption_randomness: Vec<BigInt>,
+}
+
+/// Data sent to each collector to encrypt its location shares for the next round
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct EncryptLocationSharesData<'a> {
+  registered_users: &'a [Uuid],
+
+  #[serde(with = "kzen_paillier::serialize::bigint")]
+  public_key: &'a BigInt,
+}
+
+/// Data sent to the collectors that shuffle a round of location anonymization, and then to the key holder
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct LocationRoundData<'a> {
+  registered_users: &'a [Uuid],
+
+  #[serde(with = "kzen_paillier::serialize::bigint")]
+  modulus: &'a BigInt,
+
+  round: LocationRound<'a>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct LocationRound<'a> {
+  #[serde(with = "kzen_paillier::serialize::bigint")]
+  public_key: &'a BigInt,
+
+  encrypted_shares: &'a [EncryptedLocationShares],
+  links: &'a [LocationLink],
+}
+
+/// Data sent to each collector to split the cancelation shares
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct DealThresholdSharesData<'a> {
+  threshold: usize,
+  registered_users: &'a [Uuid],
+
+  /// Paillier public key of every collector, in the same order as the collectors
+  #[serde(with = "kzen_paillier::serialize::vecbigint")]
+  public_keys: Vec<BigInt>,
+}
+
+/// Data sent to each collector to store the threshold shares dealt to it
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ReceiveThresholdSharesData<'a> {
+  registered_users: &'a [Uuid],
+  dealings: Vec<ThresholdDealing>,
 }
 
 /// Response from each individual collector
@@ -239,4 +1011,23 @@ struct CreateElectionResponse {
   // Vector might be empty when returning from the last collector
   #[serde(with = "kzen_paillier::serialize::vecbigint")]
   encryption_result: Vec<BigInt>,
+
+  // Only provided for elections that use non-interactive ballot proofs
+  #[serde(default)]
+  share_commitments: Vec<QuestionShareCommitments>,
+
+  // Commitments to the cancelation shares of every user, used to check the cancelation shares
+  #[serde(default)]
+  cancelation_commitments: Vec<QuestionCancelationCommitments>,
+
+  // Paillier public key n of the collector, used to encrypt its threshold shares
+  #[serde(with = "kzen_paillier::serialize::bigint")]
+  public_key: BigInt,
+
+  // Proof of shuffle for the encryption result (Not provided by the last collector)
+  #[serde(default)]
+  shuffle_proof: Option<ShuffleProof>,
+
+  // Hash of the election manifest, after the collector checked the signature and the parameters
+  manifest_hash: String,
 }
@@ -0,0 +1,80 @@
+use actix_web::client::Client;
+use actix_web::{web, HttpResponse};
+use futures::future::try_join_all;
+use jsonwebtoken::EncodingKey;
+use uuid_b64::UuidB64 as Uuid;
+
+use crate::auth::{JWTSecret, MediatorToken, ServerToken, DEFAULT_PERMISSIONS};
+use crate::db::DbConnection;
+use crate::errors::{ClientRequestError, ServiceError};
+use crate::models::{Collector, Election, InitializationCheckpoint};
+
+///
+/// Purge a canceled election from every collector, then from the mediator
+///
+/// A failed initialization might have sent the election to collectors that we never saved,
+///   so the checkpoint is used when the election does not exist, and every collector as a last resort
+///
+pub async fn delete_election(
+  token: ServerToken,
+  path: web::Path<Uuid>,
+  conn: DbConnection,
+  jwt_secret: web::Data<JWTSecret>,
+) -> Result<HttpResponse, ServiceError> {
+  token.test_can_create_election()?;
+
+  let election = Election::find_optional(&path, &conn)?;
+  let checkpoint = InitializationCheckpoint::find_optional(&path, &conn)?;
+
+  let collectors: Vec<Collector> = match (&election, &checkpoint) {
+    (Some(election), _) => election.get_collectors(&conn)?,
+    (None, Some(checkpoint)) => checkpoint
+      .collectors
+      .iter()
+      .map(|collector_id| Collector::find_resource(collector_id, &conn))
+      .collect::<Result<_, _>>()?,
+    (None, None) => Collector::all(&conn)?,
+  };
+
+  // Only forget the election once every collector has purged its secrets, so a failed purge can be retried
+  let jwt_encoding_key = jwt_secret.get_encoding_key();
+  try_join_all(
+    collectors
+      .iter()
+      .map(|collector| delete_election_collector(*path, collector, &jwt_encoding_key)),
+  )
+  .await?;
+
+  if let Some(checkpoint) = checkpoint {
+    checkpoint.delete(&conn)?;
+  }
+  if let Some(election) = election {
+    election.delete(&conn)?;
+  }
+
+  log::info!("Purged election <{}> from {} collectors", path, collectors.len());
+  Ok(HttpResponse::Ok().finish())
+}
+
+///
+/// Send request to an individual collector to purge the election
+///
+async fn delete_election_collector(
+  election_id: Uuid,
+  collector: &Collector,
+  jwt_encoding_key: &EncodingKey,
+) -> Result<(), ServiceError> {
+  let url = collector.private_api_url(&format!("/elections/{}", election_id));
+
+  log::debug!("Request election purge from collector '{}'", collector.name);
+  let delete_request = Client::builder()
+    .disable_timeout()
+    .bearer_auth(MediatorToken::new(DEFAULT_PERMISSIONS).encode(jwt_encoding_key)?)
+    .finish()
+    .delete(&url)
+    .send();
+
+  ClientRequestError::handle_empty(delete_request)
+    .await
+    .map_err(|e| ServiceError::PurgeElectionError(collector.id, e))
+}
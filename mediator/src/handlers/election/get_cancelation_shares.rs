@@ -1,7 +1,8 @@
 use actix_web::client::Client;
 use actix_web::{web, HttpResponse};
 use curv_kzen::{arithmetic::Modulo, BigInt};
-use futures::future::try_join_all;
+use futures::future::{join_all, try_join_all};
+use itertools::Itertools;
 use jsonwebtoken::EncodingKey;
 use serde::{Deserialize, Serialize};
 use uuid_b64::UuidB64 as Uuid;
@@ -10,9 +11,10 @@ use validator::Validate;
 use crate::auth::{JWTSecret, MediatorToken, ServerToken, DEFAULT_PERMISSIONS};
 use crate::db::DbConnection;
 use crate::errors::{ClientRequestError, ServiceError};
-use crate::models::{Collector, Election};
+use crate::models::{CancelationCommitment, Collector, Election, Question};
+use crate::protocol::{shamir, Group};
 use crate::utils::ConvertBigInt;
-use crate::views::election::CancelationShares;
+use crate::views::election::{CancelationShares, CollectorCancelationShares};
 
 #[derive(Serialize, Deserialize, Validate)]
 #[serde(rename_all = "camelCase")]
@@ -36,7 +38,7 @@ pub async fn get_cancelation_shares(
 
   // Make sure the election, question, and all registrations exist
   let election = Election::find_resource(&election_id, &conn)?;
-  election.get_question(&question_id, &conn)?;
+  let question = election.get_question(&question_id, &conn)?;
   for user_id in data.user_ids.iter() {
     election
       .get_registration_optional(user_id, &conn)?
@@ -48,31 +50,229 @@ pub async fn get_cancelation_shares(
   }
 
   // Data needed for sending API requests
-  let collectors = election.get_collectors(&conn)?;
-  let modulus = election.prime.to_bigint() - 1;
+  let modulus = election.commitment_group.order(&question.prime.to_bigint());
   let jwt_encoding_key = jwt_secret.get_encoding_key();
 
-  // Compute the sum of the shares for all users from every collector in the election
-  //   Run all requests in parallel to optimize the code
-  let (forward_cancelation_shares, reverse_cancelation_shares) =
-    try_join_all(collectors.iter().map(|collector| {
-      get_cancelation_shares_collector(election_id, question_id, &data, collector, &jwt_encoding_key)
-    }))
-    .await?
-    .into_iter()
-    .fold((BigInt::from(0), BigInt::from(0)), |(forward, reverse), result| {
-      (
-        BigInt::mod_add(&forward, &result.forward_cancelation_shares, &modulus),
-        BigInt::mod_add(&reverse, &result.reverse_cancelation_shares, &modulus),
-      )
-    });
+  // Every collector committed to the cancelation shares of each user when the election was initialized
+  let group = Group::new(
+    election.commitment_group,
+    question.generator.to_bigint(),
+    question.prime.to_bigint(),
+  );
+  let invalid_error = |collector_id| ServiceError::CancelationSharesInvalid {
+    election_id,
+    question_id,
+    collector_id,
+  };
+
+  let (forward_cancelation_shares, reverse_cancelation_shares, collector_shares) =
+    if let Some(threshold) = election.threshold {
+      // Any t collectors can compute the cancelation shares, so failures are okay as long as enough collectors respond
+      //   Collector j holds the threshold shares at x = j + 1, so interpolate the points back to x = 0
+      let threshold = threshold as usize;
+      let collectors = election.get_collectors_by_index(&conn)?;
+      let commitments = collectors
+        .iter()
+        .map(|(_, collector)| CollectorCommitments::find(&group, &question, collector, &data.user_ids, &conn))
+        .collect::<Result<Vec<_>, _>>()?;
+      let results = join_all(collectors.iter().map(|(_, collector)| {
+        get_cancelation_shares_collector(election_id, question_id, &data, collector, &jwt_encoding_key)
+      }))
+      .await;
+
+      // Every point is (collector, (x, forward share), (x, reverse share))
+      let points: Vec<_> = collectors
+        .iter()
+        .zip(commitments.iter())
+        .zip(results)
+        .filter_map(
+          |(((election_collector, collector), commitments), result)| match result {
+            // A collector that returns the wrong cancelation shares is treated the same as a collector that is down
+            Ok(ref shares)
+              if !commitments.matches(
+                &group,
+                &shares.forward_cancelation_shares,
+                &shares.reverse_cancelation_shares,
+              ) =>
+            {
+              log::warn!(
+                "Cancelation shares from collector '{}' do not match the commitments",
+                collector.name
+              );
+              None
+            }
+            Ok(CancelationShares {
+              forward_threshold_shares: Some(forward),
+              reverse_threshold_shares: Some(reverse),
+              ..
+            }) => {
+              let x = BigInt::from((election_collector.collector_index + 1) as u64);
+              Some((collector, (x.clone(), forward), (x, reverse)))
+            }
+            Ok(_) => {
+              log::warn!("Collector '{}' did not return any threshold shares", collector.name);
+              None
+            }
+            Err(e) => {
+              log::warn!(
+                "Failed to get threshold shares from collector '{}': {}",
+                collector.name,
+                e
+              );
+              None
+            }
+          },
+        )
+        .collect();
+
+      if points.len() < threshold {
+        return Err(ServiceError::NotEnoughCollectors {
+          election_id,
+          available: points.len(),
+          threshold,
+        });
+      }
+
+      // The threshold shares are not committed individually, so we can only check the combined result
+      //   against the commitments from every collector: g^(Σ S~c,i - S_c,i) = Π X_c,i
+      let combined = CollectorCommitments::combine(&group, &commitments);
+      let interpolate = |subset: &[&(&Collector, (BigInt, BigInt), (BigInt, BigInt))]| {
+        let forward_points: Vec<_> = subset.iter().map(|(_, forward, _)| forward.clone()).collect();
+        let reverse_points: Vec<_> = subset.iter().map(|(_, _, reverse)| reverse.clone()).collect();
+        let forward = shamir::interpolate_at_zero(&forward_points, &modulus)?;
+        let reverse = shamir::interpolate_at_zero(&reverse_points, &modulus)?;
+
+        Some((forward, reverse)).filter(|(forward, reverse)| combined.matches(&group, forward, reverse))
+      };
+
+      // A collector can return valid sums with bad threshold shares, so try every set of t collectors
+      //   until one of them recovers shares that match the commitments
+      let (subset, (forward, reverse)) = points
+        .iter()
+        .combinations(threshold)
+        .find_map(|subset| interpolate(&subset).map(|shares| (subset, shares)))
+        .ok_or_else(|| invalid_error(None))?;
+
+      // Name every other collector whose threshold shares do not agree with the set that matched
+      for point in points.iter() {
+        if subset.iter().any(|member| std::ptr::eq(*member, point)) {
+          continue;
+        }
+
+        let mut swapped = subset.clone();
+        swapped[0] = point;
+        if interpolate(&swapped).is_none() {
+          log::warn!(
+            "Threshold shares from collector '{}' do not match the other collectors",
+            point.0.name
+          );
+        }
+      }
+
+      (forward, reverse, Vec::new())
+    } else {
+      // Compute the sum of the shares for all users from every collector in the election
+      //   Run all requests in parallel to optimize the code
+      let collectors = election.get_collectors(&conn)?;
+      let results = try_join_all(collectors.iter().map(|collector| {
+        get_cancelation_shares_collector(election_id, question_id, &data, collector, &jwt_encoding_key)
+      }))
+      .await?;
+
+      // Make sure no collector skewed the tally before adding the shares together
+      let mut collector_shares = Vec::with_capacity(collectors.len());
+      for (collector, result) in collectors.iter().zip(results) {
+        let commitments = CollectorCommitments::find(&group, &question, collector, &data.user_ids, &conn)?;
+        if !commitments.matches(
+          &group,
+          &result.forward_cancelation_shares,
+          &result.reverse_cancelation_shares,
+        ) {
+          return Err(invalid_error(Some(collector.id)));
+        }
+
+        collector_shares.push(CollectorCancelationShares {
+          collector_id: collector.id,
+          forward_cancelation_shares: result.forward_cancelation_shares,
+          reverse_cancelation_shares: result.reverse_cancelation_shares,
+        });
+      }
+
+      let (forward, reverse) =
+        collector_shares
+          .iter()
+          .fold((BigInt::from(0), BigInt::from(0)), |(forward, reverse), shares| {
+            (
+              BigInt::mod_add(&forward, &shares.forward_cancelation_shares, &modulus),
+              BigInt::mod_add(&reverse, &shares.reverse_cancelation_shares, &modulus),
+            )
+          });
+
+      (forward, reverse, collector_shares)
+    };
 
   Ok(HttpResponse::Ok().json(CancelationShares {
     forward_cancelation_shares,
     reverse_cancelation_shares,
+    forward_threshold_shares: None,
+    reverse_threshold_shares: None,
+    collector_shares,
   }))
 }
 
+///
+/// Product of the cancelation commitments from a collector for the requested users:
+///   Π X_c,i and Π X_c,i'
+///
+/// The products are None if a commitment is missing for any user, so the shares never match
+///
+struct CollectorCommitments {
+  forward: Option<BigInt>,
+  reverse: Option<BigInt>,
+}
+
+impl CollectorCommitments {
+  fn find(
+    group: &Group,
+    question: &Question,
+    collector: &Collector,
+    user_ids: &[Uuid],
+    conn: &DbConnection,
+  ) -> Result<Self, ServiceError> {
+    let commitments = CancelationCommitment::get_for_users(question, &collector.id, user_ids, conn)?;
+    if commitments.len() != user_ids.len() {
+      return Ok(Self {
+        forward: None,
+        reverse: None,
+      });
+    }
+
+    let forward: Vec<BigInt> = commitments.iter().map(|c| c.forward_commitment.to_bigint()).collect();
+    let reverse: Vec<BigInt> = commitments.iter().map(|c| c.reverse_commitment.to_bigint()).collect();
+
+    Ok(Self {
+      forward: group.product(forward.iter()),
+      reverse: group.product(reverse.iter()),
+    })
+  }
+
+  /// Multiply the commitments from every collector together
+  fn combine(group: &Group, commitments: &[Self]) -> Self {
+    let product = |values: Option<Vec<&BigInt>>| values.and_then(|values| group.product(values.into_iter()));
+
+    Self {
+      forward: product(commitments.iter().map(|c| c.forward.as_ref()).collect()),
+      reverse: product(commitments.iter().map(|c| c.reverse.as_ref()).collect()),
+    }
+  }
+
+  /// Check the sums of the cancelation shares: g^(Σ S~c,i - S_c,i) = Π X_c,i
+  fn matches(&self, group: &Group, forward: &BigInt, reverse: &BigInt) -> bool {
+    self.forward.as_ref() == Some(&group.pow_generator(forward))
+      && self.reverse.as_ref() == Some(&group.pow_generator(reverse))
+  }
+}
+
 ///
 /// Send request to an individual collector to get the cancelation shares
 ///
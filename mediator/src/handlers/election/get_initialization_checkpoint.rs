@@ -0,0 +1,31 @@
+use actix_web::{web, HttpResponse};
+use uuid_b64::UuidB64 as Uuid;
+
+use crate::auth::ServerToken;
+use crate::db::DbConnection;
+use crate::errors::ServiceError;
+use crate::models::InitializationCheckpoint;
+use crate::views::election::InitializationCheckpointDetails;
+
+pub async fn get_initialization_checkpoint(
+  token: ServerToken,
+  path: web::Path<Uuid>,
+  conn: DbConnection,
+) -> Result<HttpResponse, ServiceError> {
+  token.test_can_view_elections()?;
+
+  let checkpoint = InitializationCheckpoint::find_resource(&path, &conn)?;
+  let next_collector_id = checkpoint
+    .collectors
+    .get(checkpoint.next_collector_index as usize)
+    .copied();
+
+  Ok(HttpResponse::Ok().json(InitializationCheckpointDetails {
+    election_id: checkpoint.election_id,
+    next_collector_index: checkpoint.next_collector_index,
+    next_collector_id,
+    distributed_locations: checkpoint.distributed_locations,
+    manifest_hash: checkpoint.manifest_hash,
+    collectors: checkpoint.collectors,
+  }))
+}
@@ -1,10 +1,16 @@
 //
 // All API handlers for managing elections
 //
+mod audit_shares;
 mod create_and_initialize_election;
+mod delete_election;
 mod get_cancelation_shares;
 mod get_election_collectors;
+mod get_initialization_checkpoint;
 
+pub use audit_shares::audit_shares;
 pub use create_and_initialize_election::create_and_initialize_election;
+pub use delete_election::delete_election;
 pub use get_cancelation_shares::get_cancelation_shares;
 pub use get_election_collectors::get_election_collectors;
+pub use get_initialization_checkpoint::get_initialization_checkpoint;
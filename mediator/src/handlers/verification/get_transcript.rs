@@ -0,0 +1,47 @@
+use actix_web::{web, HttpResponse};
+use uuid::Uuid as UUID;
+use uuid_b64::UuidB64 as Uuid;
+
+use crate::auth::AnyToken;
+use crate::db::DbConnection;
+use crate::errors::{NamedResourceType, ServiceError};
+use crate::models::{Election, VerificationTranscript};
+use crate::views::verification::VerificationTranscriptDetails;
+
+pub async fn get_transcript(
+  token: AnyToken,
+  path: web::Path<(Uuid, Uuid, Uuid)>,
+  conn: DbConnection,
+) -> Result<HttpResponse, ServiceError> {
+  token.test_can_view_elections()?;
+
+  let (election_id, question_id, transcript_id) = path.into_inner();
+  let election = Election::find_resource(&election_id, &conn)?;
+
+  // For a private election, only registered users can see the transcript (Same as verifying the ballot)
+  let user_id = token.get_user_id();
+  if !election.is_public && user_id != UUID::nil().into() {
+    let registration = election.get_registration_optional(&user_id, &conn)?;
+    if registration.is_none() {
+      return Err(NamedResourceType::election(election.id).into_error());
+    }
+  }
+
+  let transcript = VerificationTranscript::find_resource(&transcript_id, &election_id, &question_id, &conn)?;
+  let messages = transcript.get_messages_ordered(&conn)?;
+
+  // The result was saved as JSON from the same structure
+  let result = transcript
+    .result
+    .as_ref()
+    .and_then(|result| serde_json::from_str(result).ok());
+
+  Ok(HttpResponse::Ok().json(VerificationTranscriptDetails {
+    id: transcript.id,
+    election_id: transcript.election_id,
+    question_id: transcript.question_id,
+    user_id: transcript.user_id,
+    result,
+    messages,
+  }))
+}
@@ -8,10 +8,12 @@ use futures::channel::oneshot;
 use futures::stream::{select_all, SplitSink, SplitStream, StreamExt};
 use serde::Serialize;
 use std::collections::BTreeMap;
+use uuid_b64::UuidB64 as Uuid;
 
 use super::types::*;
 use super::websocket_messages::*;
-use crate::views::verification::VerificationResult;
+use crate::models::VerificationTranscriptMessage;
+use crate::views::verification::{VerificationCheck, VerificationFailure, VerificationResult};
 
 pub type WsConnection = Framed<BoxedSocket, ws::Codec>;
 
@@ -25,8 +27,20 @@ type WsFramedSink = SplitSink<Framed<BoxedSocket, ws::Codec>, ws::Message>;
 pub struct MediatorActor {
   num_collectors: usize,
   websocket_sinks: Vec<SinkWrite<ws::Message, WsFramedSink>>,
+  identity_keys: Vec<Option<String>>, // Pinned when each collector registered
   sender: Option<oneshot::Sender<VerificationResult>>,
 
+  // Every message received from or sent to the collectors (Except forwarded copies)
+  transcript_id: Uuid,
+  transcript: Vec<VerificationTranscriptMessage>,
+  transcript_sender: Option<oneshot::Sender<Vec<VerificationTranscriptMessage>>>,
+
+  // Every signed collector message must be bound to this session
+  session: Session,
+
+  // Hash of the election manifest that every collector accepted (None for older elections)
+  manifest_hash: Option<String>,
+
   // Published ballots
   forward_ballot: BigInt, // Forward Ballot = p_i
   reverse_ballot: BigInt, // Reverse Ballot = p_i'
@@ -45,13 +59,23 @@ pub struct MediatorActor {
 impl MediatorActor {
   /// Create and start the mediator actor
   ///
-  /// This method returns a receiver which indicates the calculation is finished
+  /// This method returns a receiver which indicates the calculation is finished,
+  ///   and a receiver for the transcript once the actor has stopped (Even if the calculation was canceled)
   pub fn start(
+    transcript_id: Uuid,
+    session: Session,
+    manifest_hash: Option<String>,
     websocket_connections: Vec<WsConnection>,
+    identity_keys: Vec<Option<String>>,
     ballot: VerifyBallotData,
-  ) -> (Addr<Self>, oneshot::Receiver<VerificationResult>) {
+  ) -> (
+    Addr<Self>,
+    oneshot::Receiver<VerificationResult>,
+    oneshot::Receiver<Vec<VerificationTranscriptMessage>>,
+  ) {
     // Channel to return the result to the API handler when finished
     let (sender, receiver) = oneshot::channel();
+    let (transcript_sender, transcript_receiver) = oneshot::channel();
 
     // Build and start the actor
     let actor = Self::create(|ctx| {
@@ -75,8 +99,16 @@ impl MediatorActor {
       Self {
         num_collectors: sinks.len(),
         websocket_sinks: sinks,
+        identity_keys,
         sender: Some(sender),
 
+        transcript_id,
+        transcript: Vec::new(),
+        transcript_sender: Some(transcript_sender),
+
+        session,
+        manifest_hash,
+
         forward_ballot: ballot.forward_ballot,
         reverse_ballot: ballot.reverse_ballot,
         g_s: ballot.g_s,
@@ -89,7 +121,37 @@ impl MediatorActor {
       }
     });
 
-    (actor, receiver)
+    (actor, receiver, transcript_receiver)
+  }
+
+  /// Make sure the collector uses the same protocol version as the mediator
+  ///
+  /// Sends a error close message to the mediator if the versions are different
+  ///  If this happens, the actor should stop any processing immediately
+  fn check_protocol_version(
+    &mut self,
+    collector_index: usize,
+    protocol_version: u32,
+    ctx: &mut <Self as Actor>::Context,
+  ) -> bool {
+    if protocol_version != PROTOCOL_VERSION {
+      self.error_close(
+        (
+          CloseCode::Unsupported,
+          format!(
+            "Collector {} uses protocol version {}, expected version {}",
+            collector_index + 1,
+            protocol_version,
+            PROTOCOL_VERSION
+          ),
+        ),
+        ctx,
+      );
+
+      return false;
+    }
+
+    true
   }
 
   /// Verify the signature on a message using the internal public key
@@ -116,12 +178,11 @@ impl MediatorActor {
     }
 
     // Test the message signature
+    //   The message came directly from the collector websocket, so the collector is to blame
     if !msg.verify_signature(public_key.unwrap()) {
-      self.error_close(
-        (
-          CloseCode::Invalid,
-          format!("Invalid signature from collector {}", msg.get_from() + 1),
-        ),
+      log::error!("Invalid signature from collector {}", msg.get_from() + 1);
+      self.send_failure(
+        VerificationFailure::collector(VerificationCheck::BadSignature, Some(msg.get_from()), None),
         ctx,
       );
 
@@ -131,11 +192,11 @@ impl MediatorActor {
     true
   }
 
-  /// Verifies the "from" field when receiving a message from the collector
+  /// Verifies the "from" field and the session when receiving a message from the collector
   ///
   /// Sends a error close message to the mediator if the origin validation fails
   ///  If this happens, the actor should stop any processing immediately
-  fn verify_origin<T: OriginMessage>(
+  fn verify_origin<T: OriginMessage + SessionMessage>(
     &mut self,
     data: &T,
     collector_index: usize,
@@ -150,6 +211,20 @@ impl MediatorActor {
         ctx,
       );
 
+      false
+    } else if data.get_session() != &self.session {
+      // Most likely a message recorded during another verification
+      self.error_close(
+        (
+          CloseCode::Invalid,
+          format!(
+            "Invalid message: collector {} sent a message for a different verification session",
+            collector_index + 1
+          ),
+        ),
+        ctx,
+      );
+
       false
     } else {
       true
@@ -172,6 +247,38 @@ impl MediatorActor {
     serialized.is_ok()
   }
 
+  /// Send a JSON message created by the mediator, also saving it into the transcript
+  ///
+  /// Returns "false" if this failed due to an error, meaning the actor should stop any processing immediately
+  fn send_recorded_json<T>(&mut self, data: &T, collector_index: usize, ctx: &mut <Self as Actor>::Context) -> bool
+  where
+    T: ?Sized + Serialize,
+  {
+    match serde_json::to_string(data) {
+      Ok(json) => {
+        self.record(collector_index, true, json.clone());
+        self.text(collector_index, json);
+        true
+      }
+      Err(e) => {
+        self.error_close((CloseCode::Error, format!("{}", e)), ctx);
+        false
+      }
+    }
+  }
+
+  /// Add a message to the end of the transcript
+  fn record(&mut self, collector_index: usize, sent: bool, message: String) {
+    let sequence = self.transcript.len();
+    self.transcript.push(VerificationTranscriptMessage::new(
+      self.transcript_id,
+      sequence,
+      collector_index,
+      sent,
+      message,
+    ));
+  }
+
   /// Close the mediator actor due to a fatal error
   fn error_close(&mut self, error: impl Into<ErrorClose>, ctx: &mut <Self as Actor>::Context) {
     let ErrorClose(code, description) = error.into();
@@ -260,11 +367,22 @@ impl Actor for MediatorActor {
   type Context = Context<Self>;
 
   fn started(&mut self, _ctx: &mut Self::Context) {
-    log::info!("Mediator actor started with {} collectors...", self.num_collectors);
+    log::info!(
+      "Mediator actor started with {} collectors (Transcript {})...",
+      self.num_collectors,
+      self.transcript_id
+    );
   }
 
   fn stopped(&mut self, _ctx: &mut Self::Context) {
     log::info!("Mediator actor stopped");
+
+    // Return the transcript so the API handler can save it
+    if let Some(transcript_sender) = self.transcript_sender.take() {
+      if transcript_sender.send(std::mem::take(&mut self.transcript)).is_err() {
+        log::error!("Failed to send transcript {}, receiver is closed", self.transcript_id);
+      }
+    }
   }
 }
 
@@ -311,10 +429,30 @@ impl StreamHandler<(usize, Result<ws::Frame, WsProtocolError>)> for MediatorActo
       }
 
       // Parse JSON message
-      ws::Frame::Text(text) => match serde_json::from_slice::<WebsocketMessage>(text.as_ref()) {
-        Err(e) => return self.error_close((CloseCode::Invalid, format!("Invalid JSON: {}", e)), ctx),
-        Ok(json) => json,
-      },
+      //   Record the raw text first, so the transcript also keeps any messages that fail to parse
+      ws::Frame::Text(text) => {
+        self.record(
+          collector_index,
+          false,
+          String::from_utf8_lossy(text.as_ref()).into_owned(),
+        );
+        match serde_json::from_slice::<WebsocketMessage>(text.as_ref()) {
+          Ok(json) => json,
+          Err(e) => {
+            // The first message from each collector is the public key, which has the protocol version
+            //   A different version probably has a different format, so report the version instead
+            if !self.public_keys.contains_key(&collector_index) {
+              if let Ok(header) = serde_json::from_slice::<ProtocolHeader>(text.as_ref()) {
+                if !self.check_protocol_version(collector_index, header.protocol_version, ctx) {
+                  return;
+                }
+              }
+            }
+
+            return self.error_close((CloseCode::Invalid, format!("Invalid JSON: {}", e)), ctx);
+          }
+        }
+      }
 
       // Unsupported messages
       ws::Frame::Binary(_) => return self.error_close((CloseCode::Unsupported, "Binary Data"), ctx),
@@ -341,6 +479,11 @@ impl StreamHandler<(usize, Result<ws::Frame, WsProtocolError>)> for MediatorActo
           self_addr.do_send(data)
         }
       }
+      WebsocketMessage::Misbehavior_Report(data) => {
+        if self.verify_origin(&data, collector_index, ctx) && self.verify_signature(&data, ctx) {
+          self_addr.do_send(data)
+        }
+      }
       WebsocketMessage::UnicastMessage(data) => {
         if self.verify_origin(&data, collector_index, ctx) {
           self_addr.do_send(data)
@@ -367,6 +510,24 @@ impl Handler<UnsignedMediatorMessage<PublicKey>> for MediatorActor {
   type Result = ();
 
   fn handle(&mut self, msg: UnsignedMediatorMessage<PublicKey>, ctx: &mut Self::Context) -> Self::Result {
+    if !self.check_protocol_version(msg.from, msg.data.protocol_version, ctx) {
+      return;
+    }
+
+    // The key must be signed by the identity key that was pinned when the collector registered
+    //   Otherwise, the mediator would be forwarding a key that nobody can attribute to the collector
+    let pinned_key = self.identity_keys.get(msg.from).cloned().flatten();
+    if pinned_key.as_ref() != Some(&msg.data.identity_key) || !msg.data.verify_signature() {
+      log::error!(
+        "Public key from collector {} does not match its identity key",
+        msg.from + 1
+      );
+      return self.send_failure(
+        VerificationFailure::collector(VerificationCheck::UnknownIdentityKey, Some(msg.from), None),
+        ctx,
+      );
+    }
+
     // Save the value into the map
     self.public_keys.insert(msg.from, msg.data);
     self.initialize_if_all_public_keys_received(ctx);
@@ -382,6 +543,9 @@ impl MediatorActor {
 
     // Build initialization data to send to all websockets
     let mut data = Initialize {
+      protocol_version: PROTOCOL_VERSION,
+      session: self.session.clone(),
+      manifest_hash: self.manifest_hash.clone(),
       collector_index: 0,
       num_collectors: self.num_collectors,
 
@@ -397,7 +561,7 @@ impl MediatorActor {
     // Initialize all of the websockets
     for collector_index in 0..self.num_collectors {
       data.collector_index = collector_index;
-      if !self.send_json(&data, collector_index, ctx) {
+      if !self.send_recorded_json(&data, collector_index, ctx) {
         return; // Error occured when sending JSON
       }
     }
@@ -430,6 +594,29 @@ impl Handler<SignedMediatorMessage<SP2_Result_Response>> for MediatorActor {
   }
 }
 
+///
+/// Handle a collector reporting another collector
+///
+impl Handler<SignedMediatorMessage<Misbehavior_Report>> for MediatorActor {
+  type Result = ();
+
+  fn handle(&mut self, msg: SignedMediatorMessage<Misbehavior_Report>, ctx: &mut Self::Context) -> Self::Result {
+    log::error!(
+      "Collector {} reported collector {}: {:?}",
+      msg.from + 1,
+      msg.data.collector_index + 1,
+      msg.data.check
+    );
+
+    // Ignore reports about collectors that don't exist
+    let collector_index = Some(msg.data.collector_index).filter(|index| *index < self.num_collectors);
+    self.send_failure(
+      VerificationFailure::collector(msg.data.check, collector_index, Some(msg.from)),
+      ctx,
+    );
+  }
+}
+
 impl MediatorActor {
   /// Send the final result if verification has finished
   fn test_if_calculations_finished(&mut self, ctx: &mut <Self as Actor>::Context) {
@@ -451,35 +638,103 @@ impl MediatorActor {
       if sub_protocol_2 { "valid" } else { "invalid" }
     );
 
-    // Send the verification result back through the channel to the API handler
+    let failure = Self::find_failure(&self.sp1_result, VerificationCheck::ProductMismatch)
+      .or_else(|| Self::find_failure(&self.sp2_result, VerificationCheck::CommitmentMismatch));
+    if let Some(ref failure) = failure {
+      log::debug!("Verification failure: {:?}", failure);
+    }
+
+    let result = VerificationResult {
+      sub_protocol_1,
+      sub_protocol_2,
+      transcript_id: self.transcript_id,
+      failure,
+    };
+    if !self.send_result(result, ctx) {
+      return; // Error occured when sending the result
+    }
+
+    // Gracefully close all of the websockets, then stop the actor
+    self.close_all_websockets(&Some(CloseReason::from(CloseCode::Normal)));
+    ctx.stop();
+  }
+
+  /// Find out why a sub-protocol failed from the result of every collector
+  ///
+  /// Honest collectors always agree, so the collectors that disagree with the majority misbehaved.
+  ///   On a tie, we know a collector misbehaved, but we cannot tell which one.
+  fn find_failure(results: &BTreeMap<usize, bool>, check: VerificationCheck) -> Option<VerificationFailure> {
+    let num_valid = results.values().filter(|valid| **valid).count();
+    let num_invalid = results.len() - num_valid;
+
+    if num_invalid == 0 {
+      return None;
+    }
+    if num_valid == 0 {
+      return Some(VerificationFailure::ballot(check));
+    }
+    if num_valid == num_invalid {
+      return Some(VerificationFailure::collector(check, None, None));
+    }
+
+    let majority = num_valid > num_invalid;
+    let collector_index = results
+      .iter()
+      .find(|(_, valid)| **valid != majority)
+      .map(|(index, _)| *index);
+    Some(VerificationFailure::collector(check, collector_index, None))
+  }
+
+  /// Stop verification early because a collector misbehaved
+  ///
+  /// Neither sub-protocol finished, so the ballot cannot be accepted
+  fn send_failure(&mut self, failure: VerificationFailure, ctx: &mut <Self as Actor>::Context) {
+    let result = VerificationResult {
+      sub_protocol_1: false,
+      sub_protocol_2: false,
+      transcript_id: self.transcript_id,
+      failure: Some(failure),
+    };
+    if !self.send_result(result, ctx) {
+      return; // Error occured when sending the result
+    }
+
+    self.close_all_websockets(&Some(CloseReason {
+      code: CloseCode::Invalid,
+      description: Some("Collector misbehaved during verification".into()),
+    }));
+    ctx.stop();
+  }
+
+  /// Send the verification result back through the channel to the API handler
+  ///
+  /// Returns "false" if this failed due to an error, meaning the actor should stop any processing immediately
+  fn send_result(&mut self, result: VerificationResult, ctx: &mut <Self as Actor>::Context) -> bool {
     if let Some(sender) = self.sender.take() {
-      if let Err(_) = sender.send(VerificationResult {
-        sub_protocol_1,
-        sub_protocol_2,
-      }) {
+      if sender.send(result).is_err() {
         // Handle any errors
-        return self.error_close(
+        self.error_close(
           (
             CloseCode::Abnormal,
             "Failed to send verification result, receiver is closed",
           ),
           ctx,
         );
+        return false;
       }
     } else {
       log::error!("Already sent the verification result");
-      return self.error_close(
+      self.error_close(
         (
           CloseCode::Abnormal,
           "Failed to send verification result, sender was previously consumed",
         ),
         ctx,
       );
+      return false;
     }
 
-    // Gracefully close all of the websockets, then stop the actor
-    self.close_all_websockets(&Some(CloseReason::from(CloseCode::Normal)));
-    ctx.stop();
+    true
   }
 }
 
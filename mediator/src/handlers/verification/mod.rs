@@ -1,10 +1,14 @@
 //
 // All API handlers and data types for ballot verification
 //
+mod get_transcript;
 pub(self) mod mediator_actor;
+mod recheck_transcript;
 pub(self) mod sha_hasher;
 pub(self) mod types;
 mod verify_ballot;
 pub(self) mod websocket_messages;
 
+pub use get_transcript::get_transcript;
+pub use recheck_transcript::{recheck_signatures, recheck_transcript};
 pub use verify_ballot::verify_ballot;
@@ -0,0 +1,177 @@
+use actix_web::{web, HttpResponse};
+use std::collections::BTreeMap;
+use uuid::Uuid as UUID;
+use uuid_b64::UuidB64 as Uuid;
+
+use super::websocket_messages::{
+  OriginMessage, PublicKey, Session, SessionMessage, SignedBroadcastMessage, SignedMessage, SignedUnicastMessage,
+  WebsocketMessage,
+};
+use crate::auth::AnyToken;
+use crate::db::DbConnection;
+use crate::errors::{NamedResourceType, ServiceError};
+use crate::models::{Election, VerificationTranscript, VerificationTranscriptMessage};
+use crate::views::verification::{InvalidTranscriptMessage, TranscriptRecheck};
+
+pub async fn recheck_transcript(
+  token: AnyToken,
+  path: web::Path<(Uuid, Uuid, Uuid)>,
+  conn: DbConnection,
+) -> Result<HttpResponse, ServiceError> {
+  token.test_can_view_elections()?;
+
+  let (election_id, question_id, transcript_id) = path.into_inner();
+  let election = Election::find_resource(&election_id, &conn)?;
+
+  // For a private election, only registered users can see the transcript (Same as verifying the ballot)
+  let user_id = token.get_user_id();
+  if !election.is_public && user_id != UUID::nil().into() {
+    let registration = election.get_registration_optional(&user_id, &conn)?;
+    if registration.is_none() {
+      return Err(NamedResourceType::election(election.id).into_error());
+    }
+  }
+
+  let transcript = VerificationTranscript::find_resource(&transcript_id, &election_id, &question_id, &conn)?;
+  let messages = transcript.get_messages_ordered(&conn)?;
+  // The messages name the collectors by index, so the keys must be in the same order
+  let identity_keys: Vec<_> = election
+    .get_collectors_by_index(&conn)?
+    .into_iter()
+    .map(|(_, collector)| collector.identity_key)
+    .collect();
+
+  // Same session that was sent to the collectors (The transcript ID is the session nonce)
+  let session = Session {
+    nonce: transcript.id,
+    election_id: transcript.election_id,
+    question_id: transcript.question_id,
+    user_id: transcript.user_id,
+  };
+
+  Ok(HttpResponse::Ok().json(recheck_signatures(transcript.id, &session, &identity_keys, &messages)))
+}
+
+///
+/// Check every signature in the transcript again, without contacting the collectors
+///
+/// Only the messages received from the collectors are signed. Every message must also come
+///   from the same collector as the websocket, and must be signed by the public key it published on that websocket.
+///   Each public key must be signed by the identity key pinned for that collector,
+///   and every other message must be bound to the session of this transcript.
+///
+pub fn recheck_signatures(
+  transcript_id: Uuid,
+  session: &Session,
+  identity_keys: &[Option<String>],
+  messages: &[VerificationTranscriptMessage],
+) -> TranscriptRecheck {
+  let mut public_keys: BTreeMap<usize, PublicKey> = BTreeMap::new();
+  let mut num_signed_messages = 0;
+  let mut invalid_messages = Vec::new();
+
+  for message in messages.iter().filter(|message| !message.sent) {
+    let collector_index = message.collector_index as usize;
+    let checked = match serde_json::from_str::<WebsocketMessage>(&message.message) {
+      Err(e) => Err(format!("Invalid JSON: {}", e)),
+
+      // Save the public keys for the other messages, even if they are invalid
+      //   Otherwise, every later message from the collector would also be reported
+      Ok(WebsocketMessage::PublicKey(public_key)) => {
+        let checked = check_identity_key(&public_key, identity_keys.get(collector_index).cloned().flatten());
+        public_keys.insert(collector_index, public_key);
+        checked
+      }
+
+      Ok(WebsocketMessage::SP1_Result_Response(data)) => check_signature(
+        &data,
+        collector_index,
+        session,
+        &public_keys,
+        SignedMessage::verify_signature,
+      ),
+      Ok(WebsocketMessage::SP2_Result_Response(data)) => check_signature(
+        &data,
+        collector_index,
+        session,
+        &public_keys,
+        SignedMessage::verify_signature,
+      ),
+      Ok(WebsocketMessage::Misbehavior_Report(data)) => check_signature(
+        &data,
+        collector_index,
+        session,
+        &public_keys,
+        SignedMessage::verify_signature,
+      ),
+      Ok(WebsocketMessage::UnicastMessage(data)) => check_signature(
+        &data,
+        collector_index,
+        session,
+        &public_keys,
+        SignedUnicastMessage::verify_signature,
+      ),
+      Ok(WebsocketMessage::BroadcastMessage(data)) => check_signature(
+        &data,
+        collector_index,
+        session,
+        &public_keys,
+        SignedBroadcastMessage::verify_signature,
+      ),
+    };
+
+    num_signed_messages += 1;
+    if let Err(reason) = checked {
+      invalid_messages.push(InvalidTranscriptMessage {
+        sequence: message.sequence,
+        collector_index,
+        reason,
+      });
+    }
+  }
+
+  TranscriptRecheck {
+    transcript_id,
+    valid: invalid_messages.is_empty(),
+    num_signed_messages,
+    invalid_messages,
+  }
+}
+
+/// Make sure the public key is signed by the pinned identity key
+fn check_identity_key(public_key: &PublicKey, pinned_key: Option<String>) -> Result<(), String> {
+  match pinned_key {
+    None => Err("Collector does not have a pinned identity key".into()),
+    Some(pinned_key) if pinned_key != public_key.identity_key => {
+      Err("Identity key does not match the pinned identity key".into())
+    }
+    Some(_) if !public_key.verify_signature() => Err("Invalid signature".into()),
+    Some(_) => Ok(()),
+  }
+}
+
+/// Check the origin and session of a single message, then verify the signature with the collector public key
+fn check_signature<M: OriginMessage + SessionMessage>(
+  message: &M,
+  collector_index: usize,
+  session: &Session,
+  public_keys: &BTreeMap<usize, PublicKey>,
+  verify: impl Fn(&M, &PublicKey) -> bool,
+) -> Result<(), String> {
+  let from = message.get_from();
+  if from != collector_index {
+    return Err(format!("Message claims to be from collector {}", from + 1));
+  }
+  if message.get_session() != session {
+    return Err("Message belongs to a different verification session".into());
+  }
+
+  let public_key = public_keys
+    .get(&collector_index)
+    .ok_or_else(|| "Collector did not publish a public key before this message".to_string())?;
+  if !verify(message, public_key) {
+    return Err("Invalid signature".into());
+  }
+
+  Ok(())
+}
@@ -1,5 +1,6 @@
 use actix_web::client::Client;
 use actix_web::{web, HttpResponse};
+use diesel::prelude::*;
 use futures::future::try_join_all;
 use uuid::Uuid as UUID;
 use uuid_b64::UuidB64 as Uuid;
@@ -7,10 +8,11 @@ use validator::Validate;
 
 use super::mediator_actor::MediatorActor;
 use super::types::VerifyBallotData;
+use super::websocket_messages::Session;
 use crate::auth::{AnyToken, JWTSecret, MediatorToken, DEFAULT_PERMISSIONS};
 use crate::db::DbConnection;
 use crate::errors::{NamedResourceType, ServiceError, WebsocketError};
-use crate::models::{Election, Question};
+use crate::models::{Election, Question, VerificationTranscript, VerificationTranscriptMessage};
 use crate::views::verification::VerificationResult;
 
 pub async fn verify_ballot(
@@ -50,8 +52,13 @@ pub async fn verify_ballot(
     })?;
 
   // Create all of the collector websocket connections in parallel
-  let collectors = election.get_collectors(&conn)?;
-  let websocket_connections = try_join_all(collectors.into_iter().map(|collector| {
+  //   The actor names every collector by its index, so the connections and keys must be in the same order
+  let collectors = election.get_collectors_by_index(&conn)?;
+  let identity_keys = collectors
+    .iter()
+    .map(|(_, collector)| collector.identity_key.clone())
+    .collect();
+  let websocket_connections = try_join_all(collectors.into_iter().map(|(_, collector)| {
     let jwt_encoding_key = jwt_secret.get_encoding_key();
     let url = collector.private_websocket_url(&format!(
       "/elections/{}/questions/{}/verification/ws/{}",
@@ -78,14 +85,57 @@ pub async fn verify_ballot(
 
   // Start actor to handle the websocket communication protocol
   log::info!("Starting mediator actor to handle ballot verification...");
-  let (mediator_addr, receiver) = MediatorActor::start(websocket_connections, data);
+  let user_id = data.user_id;
+  let transcript_id = Uuid::new();
+
+  // Bind every signed message to this verification
+  //   The transcript ID is new for every verification, so it doubles as the session nonce
+  let session = Session {
+    nonce: transcript_id,
+    election_id,
+    question_id,
+    user_id,
+  };
+
+  let (mediator_addr, receiver, transcript_receiver) = MediatorActor::start(
+    transcript_id,
+    session,
+    election.manifest_hash.clone(),
+    websocket_connections,
+    identity_keys,
+    data,
+  );
 
   // Wait for the calculations to finish
   //   The actor will automatically stop itself
   log::debug!("Beginning protocols and waiting for result...");
-  let result: VerificationResult = receiver.await.map_err(|_| ServiceError::VerificationCanceled)?;
+  let result = receiver.await;
   drop(mediator_addr); // Force Rust to not stop the actor prematurely
 
+  // Save the transcript, even if verification was canceled
+  let messages = transcript_receiver.await.unwrap_or_else(|_| {
+    log::error!("Transcript {} was not returned by the mediator actor", transcript_id);
+    Vec::new()
+  });
+  let transcript = VerificationTranscript::new(
+    transcript_id,
+    election_id,
+    question_id,
+    user_id,
+    result
+      .as_ref()
+      .ok()
+      .and_then(|result| serde_json::to_string(result).ok()),
+  );
+
+  conn.get().transaction::<_, ServiceError, _>(|| {
+    transcript.insert(&conn)?;
+    VerificationTranscriptMessage::insert_list(&messages, &conn)?;
+    Ok(())
+  })?;
+
+  let result: VerificationResult = result.map_err(|_| ServiceError::VerificationCanceled(transcript_id))?;
+
   // Return final verification results
   log::debug!("Calculations finished, returning final result");
   Ok(HttpResponse::Ok().json(result))
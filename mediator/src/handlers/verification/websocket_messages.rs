@@ -1,12 +1,15 @@
 #![allow(non_camel_case_types)]
 use actix::prelude::*;
 use actix_http::ws::CloseCode;
-use curv_kzen::arithmetic::Modulo;
+use curv_kzen::arithmetic::Converter;
 use curv_kzen::BigInt;
 use serde::{Deserialize, Serialize};
-use std::hash::Hash;
+use std::hash::{Hash, Hasher};
+use uuid_b64::UuidB64 as Uuid;
 
 use super::sha_hasher::SHAHasher;
+use crate::auth::IdentityKey;
+use crate::views::verification::VerificationCheck;
 
 /// Close the mediator actor due to an error
 pub struct ErrorClose(pub CloseCode, pub Option<String>);
@@ -26,6 +29,29 @@ where
   }
 }
 
+///
+/// Version of the verification protocol, which must be the same for every collector and the mediator
+///
+/// Increment this whenever a message format or signature scheme changes
+///   Version 1 = Textbook RSA signatures using the Paillier modulus (No version field)
+///   Version 2 = Ed25519 signatures using the collector identity keys
+///   Version 3 = Signed messages are bound to the verification session
+///
+pub const PROTOCOL_VERSION: u32 = 3;
+
+/// Messages without a version field are from the first version of the protocol
+fn legacy_protocol_version() -> u32 {
+  1
+}
+
+/// Only reads the protocol version, so the mediator can explain why a message from another version did not parse
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProtocolHeader {
+  #[serde(default = "legacy_protocol_version")]
+  pub protocol_version: u32,
+}
+
 /// Top level structure for any type of JSON value that can be received by the mediator
 #[derive(Debug, Clone, Deserialize)]
 #[serde(untagged)]
@@ -33,6 +59,7 @@ pub enum WebsocketMessage {
   PublicKey(PublicKey),
   SP1_Result_Response(SignedMediatorMessage<SP1_Result_Response>),
   SP2_Result_Response(SignedMediatorMessage<SP2_Result_Response>),
+  Misbehavior_Report(SignedMediatorMessage<Misbehavior_Report>),
 
   // Forward these messages without any signature verification
   // (Does check to make sure it didn't lie about "from" field)
@@ -40,6 +67,36 @@ pub enum WebsocketMessage {
   BroadcastMessage(SignedBroadcastMessage),
 }
 
+///
+/// Identifies a single verification run
+///
+/// The mediator picks a new nonce for every verification, and every signed collector message includes the session.
+///   This way, a recorded message cannot be replayed into the verification of another ballot.
+///
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Session {
+  pub nonce: Uuid,
+  pub election_id: Uuid,
+  pub question_id: Uuid,
+  pub user_id: Uuid,
+}
+
+impl Hash for Session {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    self.nonce.to_string().hash(state);
+    self.election_id.to_string().hash(state);
+    self.question_id.to_string().hash(state);
+    self.user_id.to_string().hash(state);
+  }
+}
+
+/// Messages that are bound to a verification session
+pub trait SessionMessage {
+  /// Extract the session from the message
+  fn get_session(&self) -> &Session;
+}
+
 /// Messages that have a "from" field
 pub trait OriginMessage {
   /// Extract the source of the message
@@ -49,14 +106,18 @@ pub trait OriginMessage {
 /// Represents a received message that is signed
 pub trait SignedMessage: OriginMessage {
   /// Extract the signature stored in the struct
-  fn get_signature(&self) -> &BigInt;
+  fn get_signature(&self) -> &str;
 
   /// Hash the message to get the signature
   fn compute_hash(&self) -> BigInt;
 
-  /// Verify the message signature using RSA from the public key
+  /// Verify the message signature using the identity key from the public key
   fn verify_signature(&self, public_key: &PublicKey) -> bool {
-    &self.compute_hash() == &BigInt::mod_pow(self.get_signature(), &public_key.b, &public_key.n)
+    IdentityKey::verify(
+      &public_key.identity_key,
+      &self.compute_hash().to_bytes(),
+      self.get_signature(),
+    )
   }
 }
 
@@ -85,11 +146,12 @@ impl<T> OriginMessage for UnsignedMediatorMessage<T> {
 #[serde(rename_all = "camelCase")]
 #[rtype(result = "()")]
 pub struct SignedMediatorMessage<T> {
+  pub session: Session,
   pub from: usize,
   pub data: T,
 
-  #[serde(with = "kzen_paillier::serialize::bigint")]
-  pub collector_signature: BigInt,
+  /// Ed25519 signature (base64)
+  pub collector_signature: String,
 }
 
 impl<T> OriginMessage for SignedMediatorMessage<T> {
@@ -99,14 +161,23 @@ impl<T> OriginMessage for SignedMediatorMessage<T> {
   }
 }
 
+impl<T> SessionMessage for SignedMediatorMessage<T> {
+  #[inline]
+  fn get_session(&self) -> &Session {
+    &self.session
+  }
+}
+
 impl<T: Hash> SignedMessage for SignedMediatorMessage<T> {
   #[inline]
-  fn get_signature(&self) -> &BigInt {
+  fn get_signature(&self) -> &str {
     &self.collector_signature
   }
 
   fn compute_hash(&self) -> BigInt {
     let mut hasher = SHAHasher::new();
+    PROTOCOL_VERSION.hash(&mut hasher);
+    self.session.hash(&mut hasher);
     self.from.hash(&mut hasher);
     self.data.hash(&mut hasher);
     hasher.get_sha_hash()
@@ -122,13 +193,13 @@ impl<T: Hash> SignedMessage for SignedMediatorMessage<T> {
 #[serde(rename_all = "camelCase")]
 #[rtype(result = "()")]
 pub struct SignedUnicastMessage {
+  pub session: Session,
   pub from: usize,
   pub to: usize,
   pub data: serde_json::Value,
 
-  /// RSA Signature
-  #[serde(with = "kzen_paillier::serialize::bigint")]
-  pub signature: BigInt,
+  /// Ed25519 signature (base64)
+  pub signature: String,
 }
 
 impl OriginMessage for SignedUnicastMessage {
@@ -138,6 +209,36 @@ impl OriginMessage for SignedUnicastMessage {
   }
 }
 
+impl SessionMessage for SignedUnicastMessage {
+  #[inline]
+  fn get_session(&self) -> &Session {
+    &self.session
+  }
+}
+
+impl SignedUnicastMessage {
+  /// Verify the signature, which needs the typed data to compute the same hash as the collector
+  ///   Returns false if the data is not a known message type
+  pub fn verify_signature(&self, public_key: &PublicKey) -> bool {
+    let data = match serde_json::from_value::<UnicastData>(self.data.clone()) {
+      Ok(data) => data,
+      Err(_) => return false,
+    };
+
+    let mut hasher = SHAHasher::new();
+    PROTOCOL_VERSION.hash(&mut hasher);
+    self.session.hash(&mut hasher);
+    self.from.hash(&mut hasher);
+    self.to.hash(&mut hasher);
+    data.hash(&mut hasher);
+    IdentityKey::verify(
+      &public_key.identity_key,
+      &hasher.get_sha_hash().to_bytes(),
+      &self.signature,
+    )
+  }
+}
+
 ///
 /// Message to send to or receive from ALL websockets
 ///  This is simply forwarded verbatum, uses serde_json::Value
@@ -147,12 +248,12 @@ impl OriginMessage for SignedUnicastMessage {
 #[serde(rename_all = "camelCase")]
 #[rtype(result = "()")]
 pub struct SignedBroadcastMessage {
+  pub session: Session,
   pub from: usize,
   pub data: serde_json::Value,
 
-  /// RSA Signature
-  #[serde(with = "kzen_paillier::serialize::bigint")]
-  pub signature: BigInt,
+  /// Ed25519 signature (base64)
+  pub signature: String,
 }
 
 impl OriginMessage for SignedBroadcastMessage {
@@ -162,31 +263,88 @@ impl OriginMessage for SignedBroadcastMessage {
   }
 }
 
+impl SessionMessage for SignedBroadcastMessage {
+  #[inline]
+  fn get_session(&self) -> &Session {
+    &self.session
+  }
+}
+
+impl SignedBroadcastMessage {
+  /// Verify the signature, which needs the typed data to compute the same hash as the collector
+  ///   Returns false if the data is not a known message type
+  pub fn verify_signature(&self, public_key: &PublicKey) -> bool {
+    let data = match serde_json::from_value::<BroadcastData>(self.data.clone()) {
+      Ok(data) => data,
+      Err(_) => return false,
+    };
+
+    let mut hasher = SHAHasher::new();
+    PROTOCOL_VERSION.hash(&mut hasher);
+    self.session.hash(&mut hasher);
+    self.from.hash(&mut hasher);
+    data.hash(&mut hasher);
+    IdentityKey::verify(
+      &public_key.identity_key,
+      &hasher.get_sha_hash().to_bytes(),
+      &self.signature,
+    )
+  }
+}
+
 // =============================================
 // Define all data structures from the mediator
 // =============================================
 
+///
 /// Publish the public key for a collector
+///
+/// The secret hash can only be checked by the collectors,
+///   but the mediator can check the signature against the pinned identity key
+///
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct PublicKey {
-  /// Modulus for both RSA and Paillier cryptosystem
+  #[serde(default = "legacy_protocol_version")]
+  pub protocol_version: u32,
+
+  /// Modulus for the Paillier cryptosystem
   #[serde(with = "kzen_paillier::serialize::bigint")]
   pub n: BigInt,
 
-  /// Public exponent b for RSA
-  #[serde(with = "kzen_paillier::serialize::bigint")]
-  pub b: BigInt,
+  /// Ed25519 identity key of the collector (base64 DER)
+  pub identity_key: String,
 
-  /// Signature to ensure public key has been faithfully published
+  /// Hash to ensure public key has been faithfully published
   #[serde(with = "kzen_paillier::serialize::bigint")]
-  pub signature: BigInt,
+  pub secret_hash: BigInt,
+
+  /// Identity key signature over the modulus and identity key
+  pub signature: String,
+}
+
+impl PublicKey {
+  /// Verify the public key was signed by its own identity key
+  pub fn verify_signature(&self) -> bool {
+    let mut hasher = SHAHasher::new();
+    self.protocol_version.hash(&mut hasher);
+    self.n.to_bytes().hash(&mut hasher);
+    self.identity_key.hash(&mut hasher);
+    IdentityKey::verify(&self.identity_key, &hasher.get_sha_hash().to_bytes(), &self.signature)
+  }
 }
 
 /// Initialization parameters to send to the websocket
 #[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Initialize {
+  pub protocol_version: u32,
+  pub session: Session,
+
+  // Election manifest that every collector must have accepted
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub manifest_hash: Option<String>,
+
   // Collector details
   pub collector_index: usize,
   pub num_collectors: usize,
@@ -205,7 +363,7 @@ pub struct Initialize {
   #[serde(with = "kzen_paillier::serialize::bigint")]
   pub g_s_s_prime: BigInt, // g^(s_i * s_i')
 
-  // STPM Encryption Key and RSA signatures
+  // STPM Encryption Key and identity keys for signatures
   pub public_keys: Vec<PublicKey>,
 }
 
@@ -213,6 +371,110 @@ pub struct Initialize {
 // Define all data structures for Sub-Protocols
 // =============================================
 
+///
+/// Messages between collectors are forwarded without looking at the data,
+///   but the data types are needed to check the signatures in a transcript
+///
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum UnicastData {
+  SP1_STMP_Request(SP1_STMP_Request),
+  SP1_STMP_Response(SP1_STMP_Response),
+}
+
+impl Hash for UnicastData {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    match self {
+      UnicastData::SP1_STMP_Request(data) => data.hash(state),
+      UnicastData::SP1_STMP_Response(data) => data.hash(state),
+    }
+  }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum BroadcastData {
+  SP1_Product_Response(SP1_Product_Response),
+  SP2_Shares_Response(SP2_Shares_Response),
+}
+
+impl Hash for BroadcastData {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    match self {
+      BroadcastData::SP1_Product_Response(data) => data.hash(state),
+      BroadcastData::SP2_Shares_Response(data) => data.hash(state),
+    }
+  }
+}
+
+/// Sub-Protocol 1 - Secure Two-Party Multiplication Request
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SP1_STMP_Request {
+  #[serde(with = "kzen_paillier::serialize::bigint")]
+  pub e_s_cj: BigInt, // E(S_i,Cj, e)
+
+  #[serde(with = "kzen_paillier::serialize::bigint")]
+  pub e_s_cj_prime: BigInt, // E(S_i,Cj', e)
+}
+
+impl Hash for SP1_STMP_Request {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    self.e_s_cj.to_bytes().hash(state);
+    self.e_s_cj_prime.to_bytes().hash(state);
+  }
+}
+
+/// Sub-Protocol 1 - Secure Two-Party Multiplication Response
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SP1_STMP_Response {
+  #[serde(with = "kzen_paillier::serialize::bigint")]
+  pub e_s_cj_e_rk_prime: BigInt, // (E(S_i,Cj, e)^(S_i,Ck')) * (E(rk', e)^(-1)) (mod n^2)
+
+  #[serde(with = "kzen_paillier::serialize::bigint")]
+  pub e_s_cj_prime_e_rk: BigInt, // (E(S_i,Cj', e)^(S_i,Ck)) * (E(rk, e)^(-1)) (mod n^2)
+}
+
+impl Hash for SP1_STMP_Response {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    self.e_s_cj_e_rk_prime.to_bytes().hash(state);
+    self.e_s_cj_prime_e_rk.to_bytes().hash(state);
+  }
+}
+
+/// Sub-Protocol 1 - Computed product for collector j
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SP1_Product_Response {
+  #[serde(with = "kzen_paillier::serialize::bigint")]
+  pub product_j: BigInt,
+}
+
+impl Hash for SP1_Product_Response {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    self.product_j.to_bytes().hash(state);
+  }
+}
+
+/// Sub-Protocol 2 - Computed values g^(S~i,Cj) and g^(S~i,Cj') for collector j
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SP2_Shares_Response {
+  #[serde(with = "kzen_paillier::serialize::bigint")]
+  pub g_stild: BigInt,
+
+  #[serde(with = "kzen_paillier::serialize::bigint")]
+  pub g_stild_prime: BigInt,
+}
+
+impl Hash for SP2_Shares_Response {
+  fn hash<H: Hasher>(&self, state: &mut H) {
+    self.g_stild.to_bytes().hash(state);
+    self.g_stild_prime.to_bytes().hash(state);
+  }
+}
+
 /// Sub-Protocol 1 - Final result from the websocket
 #[derive(Debug, Clone, Hash, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -226,3 +488,11 @@ pub struct SP1_Result_Response {
 pub struct SP2_Result_Response {
   pub sp2_ballot_valid: bool,
 }
+
+/// Sent by a collector that received an invalid message from another collector
+#[derive(Debug, Clone, Hash, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Misbehavior_Report {
+  pub collector_index: usize,
+  pub check: VerificationCheck,
+}
@@ -28,12 +28,16 @@ async fn main() -> anyhow::Result<()> {
 
   // Database connection pool and web server
   let connection_pool = db::establish_new_connection_pool()?;
+  let checkpoint_secret =
+    config::get_checkpoint_secret().ok_or_else(|| anyhow::anyhow!("CHECKPOINT_SECRET environment variable not set"))?;
   let mut server = HttpServer::new(move || {
     App::new()
       // Connect to database
       .data(connection_pool.clone())
       // Encryption secret for JSON Web Token
       .data(auth::JWTSecret::new(config::get_jwt_secret()))
+      // Encryption secret for the location keys in the initialization checkpoints
+      .data(auth::CheckpointSecret::new(&checkpoint_secret))
       // Enable logger
       .wrap(middleware::Logger::default())
       // Configure error handlers
@@ -61,14 +65,28 @@ async fn main() -> anyhow::Result<()> {
               .route("", web::post().to(handlers::election::create_and_initialize_election))
               .service(
                 web::scope("{election_id}")
+                  .route("", web::delete().to(handlers::election::delete_election))
                   .route(
                     "/collectors",
                     web::get().to(handlers::election::get_election_collectors),
                   )
+                  .route("/shares/audit", web::post().to(handlers::election::audit_shares))
+                  .route(
+                    "/checkpoint",
+                    web::get().to(handlers::election::get_initialization_checkpoint),
+                  )
                   .service(
                     web::scope("/questions").service(
                       web::scope("{question_id}")
                         .route("/verification", web::post().to(handlers::verification::verify_ballot))
+                        .route(
+                          "/verification/transcripts/{transcript_id}",
+                          web::get().to(handlers::verification::get_transcript),
+                        )
+                        .route(
+                          "/verification/transcripts/{transcript_id}/recheck",
+                          web::get().to(handlers::verification::recheck_transcript),
+                        )
                         .route(
                           "/cancelation",
                           web::get().to(handlers::election::get_cancelation_shares),
@@ -0,0 +1,33 @@
+use crate::models::QuestionType;
+
+// Every election uses one of the following encodings for the voting vector:
+//
+//   [OneHot]  - Every voter has their own chunk of the voting vector (Voters cannot have a weight)
+//
+//   [Counter] - Every candidate of a choice question is a single base-(N+1) digit shared by all voters,
+//               where N is the total weight of all registered voters
+//
+// The mediator only forwards this to the collectors
+sql_enum!(
+  pub BallotEncoding {
+    OneHot = 0,
+    Counter
+  }
+);
+
+impl BallotEncoding {
+  pub fn get_name(&self) -> &'static str {
+    match self {
+      BallotEncoding::OneHot => "One-Hot",
+      BallotEncoding::Counter => "Counter",
+    }
+  }
+
+  /// Encoding actually used for a given question in the election
+  pub fn for_question(&self, question_type: QuestionType) -> Self {
+    match question_type {
+      QuestionType::Choice => *self,
+      QuestionType::Ranked => BallotEncoding::OneHot,
+    }
+  }
+}
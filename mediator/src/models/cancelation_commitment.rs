@@ -0,0 +1,73 @@
+use bigdecimal::BigDecimal;
+use curv_kzen::BigInt;
+use diesel::prelude::*;
+use serde::Serialize;
+use uuid_b64::UuidB64 as Uuid;
+
+use crate::db::DbConnection;
+use crate::errors::ServiceError;
+use crate::models::{Collector, Election, Question};
+use crate::schema::cancelation_commitments;
+use crate::utils::ConvertBigInt;
+
+///
+/// Commitments from a single collector to the cancelation shares of a voter:
+///   X_c,i = g^(S~c,i - S_c,i) and X_c,i' = g^(S~c,i' - S_c,i')
+///
+#[derive(Debug, Clone, Serialize, Queryable, Insertable, Identifiable, Associations)]
+#[primary_key(user_id, election_id, question_id, collector_id)]
+#[belongs_to(Election)]
+#[belongs_to(Question)]
+#[belongs_to(Collector)]
+#[serde(rename_all = "camelCase")]
+pub struct CancelationCommitment {
+  pub user_id: Uuid,
+  pub election_id: Uuid,
+  pub question_id: Uuid,
+  pub collector_id: Uuid,
+
+  pub forward_commitment: BigDecimal,
+  pub reverse_commitment: BigDecimal,
+}
+
+impl CancelationCommitment {
+  model_base!(no update);
+
+  belongs_to!(Election);
+  belongs_to!(Question);
+  belongs_to!(Collector);
+
+  pub fn new(
+    user_id: Uuid,
+    election_id: Uuid,
+    question_id: Uuid,
+    collector_id: Uuid,
+    forward_commitment: &BigInt,
+    reverse_commitment: &BigInt,
+  ) -> Self {
+    Self {
+      user_id,
+      election_id,
+      question_id,
+      collector_id,
+      forward_commitment: forward_commitment.to_bigdecimal(),
+      reverse_commitment: reverse_commitment.to_bigdecimal(),
+    }
+  }
+
+  /// Get the commitments from a single collector for a list of users in a question
+  pub fn get_for_users(
+    question: &Question,
+    collector_id: &Uuid,
+    user_ids: &[Uuid],
+    conn: &DbConnection,
+  ) -> Result<Vec<Self>, ServiceError> {
+    Ok(
+      cancelation_commitments::table
+        .filter(cancelation_commitments::question_id.eq(question.id))
+        .filter(cancelation_commitments::collector_id.eq(collector_id))
+        .filter(cancelation_commitments::user_id.eq_any(user_ids))
+        .get_results::<Self>(conn.get())?,
+    )
+  }
+}
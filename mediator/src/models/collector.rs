@@ -15,6 +15,7 @@ pub struct Collector {
   pub name: String,
   pub private_base_uri: String,
   pub is_secure: bool,
+  pub identity_key: Option<String>,
 }
 
 impl Collector {
@@ -0,0 +1,43 @@
+use curv_kzen::elliptic::curves::{Ristretto, Scalar, Secp256k1};
+use curv_kzen::BigInt;
+
+// Every election computes the commitments g^s in one of the following groups:
+//
+//   [Full]       - Z*p, which has order p-1
+//
+//   [PrimeOrder] - Subgroup of quadratic residues, which has prime order q = (p-1)/2
+//
+//   [Secp256k1]  - Points on the secp256k1 curve
+//   [Ristretto]  - Points in the Ristretto group
+//
+// The mediator needs the order of the group to sum the cancelation shares
+sql_enum!(
+  pub CommitmentGroup {
+    Full = 0,
+    PrimeOrder,
+    Secp256k1,
+    Ristretto
+  }
+);
+
+impl CommitmentGroup {
+  pub fn get_name(&self) -> &'static str {
+    match self {
+      CommitmentGroup::Full => "Full",
+      CommitmentGroup::PrimeOrder => "Prime Order",
+      CommitmentGroup::Secp256k1 => "secp256k1",
+      CommitmentGroup::Ristretto => "Ristretto",
+    }
+  }
+
+  /// Order of the group, which is the modulus for all share arithmetic
+  ///   The prime is ignored for elliptic curves
+  pub fn order(&self, prime: &BigInt) -> BigInt {
+    match self {
+      CommitmentGroup::Full => prime - 1,
+      CommitmentGroup::PrimeOrder => (prime - 1) / 2,
+      CommitmentGroup::Secp256k1 => Scalar::<Secp256k1>::group_order().clone(),
+      CommitmentGroup::Ristretto => Scalar::<Ristretto>::group_order().clone(),
+    }
+  }
+}
@@ -1,13 +1,11 @@
-use bigdecimal::BigDecimal;
-use curv_kzen::BigInt;
+use diesel::prelude::*;
 use serde::Serialize;
 use uuid_b64::UuidB64 as Uuid;
 
 use crate::db::DbConnection;
 use crate::errors::{NamedResourceType, ServiceError};
-use crate::models::{Question, Registration};
-use crate::schema::elections;
-use crate::utils::ConvertBigInt;
+use crate::models::{Collector, CommitmentGroup, ElectionCollector, Question, Registration};
+use crate::schema::{collectors, election_collectors, elections};
 
 #[derive(Debug, Clone, Serialize, Queryable, Insertable, Identifiable, AsChangeset, Associations)]
 #[changeset_options(treat_none_as_null = "true")]
@@ -16,8 +14,13 @@ pub struct Election {
   pub id: Uuid,
   pub is_public: bool,
   pub creator_id: Uuid,
-  pub generator: BigDecimal,
-  pub prime: BigDecimal,
+  pub commitment_group: CommitmentGroup,
+
+  // Any threshold collectors can compute the cancelation shares (None means every collector is needed)
+  pub threshold: Option<i64>,
+
+  // Hash of the signed election manifest that every collector accepted (None for older elections)
+  pub manifest_hash: Option<String>,
 }
 
 impl Election {
@@ -27,13 +30,21 @@ impl Election {
   has_many!(Registration);
   has_many!(Collector through ElectionCollector, order by collectors::name.asc());
 
-  pub fn new(id: Uuid, is_public: bool, creator_id: Uuid, generator: &BigInt, prime: &BigInt) -> Self {
+  pub fn new(
+    id: Uuid,
+    is_public: bool,
+    creator_id: Uuid,
+    commitment_group: CommitmentGroup,
+    threshold: Option<i64>,
+    manifest_hash: String,
+  ) -> Self {
     Self {
       id,
       is_public,
       creator_id,
-      generator: generator.to_bigdecimal(),
-      prime: prime.to_bigdecimal(),
+      commitment_group,
+      threshold,
+      manifest_hash: Some(manifest_hash),
     }
   }
 
@@ -54,4 +65,18 @@ impl Election {
   pub fn get_question(&self, question_id: &Uuid, conn: &DbConnection) -> Result<Question, ServiceError> {
     Question::find_resource(question_id, &self.id, conn)
   }
+
+  /// Get every collector in the same order that the election was initialized
+  pub fn get_collectors_by_index(
+    &self,
+    conn: &DbConnection,
+  ) -> Result<Vec<(ElectionCollector, Collector)>, ServiceError> {
+    Ok(
+      election_collectors::table
+        .inner_join(collectors::table)
+        .filter(election_collectors::election_id.eq(self.id))
+        .order_by(election_collectors::collector_index.asc())
+        .get_results::<(ElectionCollector, Collector)>(conn.get())?,
+    )
+  }
 }
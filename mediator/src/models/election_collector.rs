@@ -13,6 +13,9 @@ use crate::schema::election_collectors;
 pub struct ElectionCollector {
   pub election_id: Uuid,
   pub collector_id: Uuid,
+
+  // Order of the collector during initialization (Used as the x-coordinate for threshold shares)
+  pub collector_index: i64,
 }
 
 impl ElectionCollector {
@@ -21,22 +24,24 @@ impl ElectionCollector {
   belongs_to!(Election);
   belongs_to!(Collector);
 
-  pub fn new(election_id: Uuid, collector_id: Uuid) -> Self {
+  pub fn new(election_id: Uuid, collector_id: Uuid, collector_index: i64) -> Self {
     Self {
       election_id,
       collector_id,
+      collector_index,
     }
   }
 }
 
+// The association helpers don't know the initialization order, so they always use index 0
 impl ManyToManyConstructor<Election, Collector> for ElectionCollector {
   fn new(election_id: &Uuid, collector_id: &Uuid) -> Self {
-    Self::new(*election_id, *collector_id)
+    Self::new(*election_id, *collector_id, 0)
   }
 }
 
 impl ManyToManyConstructor<Collector, Election> for ElectionCollector {
   fn new(collector_id: &Uuid, election_id: &Uuid) -> Self {
-    Self::new(*election_id, *collector_id)
+    Self::new(*election_id, *collector_id, 0)
   }
 }
@@ -0,0 +1,46 @@
+use bigdecimal::BigDecimal;
+use uuid_b64::UuidB64 as Uuid;
+
+use crate::db::DbConnection;
+use crate::errors::{NamedResourceType, ServiceError};
+use crate::schema::initialization_checkpoints;
+
+///
+/// State of the collector chain after the last collector that accepted the election
+///
+/// Saved after every collector, so initializing the election again resumes from the collector that failed
+///   instead of deleting everything and generating a new Paillier key pair.
+///   The checkpoint is deleted once the election is created.
+///
+#[derive(Debug, Clone, Queryable, Insertable, Identifiable, AsChangeset)]
+#[primary_key(election_id)]
+#[changeset_options(treat_none_as_null = "true")]
+pub struct InitializationCheckpoint {
+  pub election_id: Uuid,
+
+  // JSON signed manifest from the API server, which must not change between attempts
+  pub manifest: String,
+  pub collectors: Vec<Uuid>,
+  pub next_collector_index: i64,
+
+  // Location anonymization (The private key is None with distributed locations)
+  //   A resumed chain must decrypt the same locations, so the Paillier private key is saved encrypted with
+  //   the checkpoint secret. It is cleared once the final collector accepted the decrypted locations.
+  pub distributed_locations: bool,
+  pub location_modulus: BigDecimal,
+  pub location_key: Option<Vec<u8>>,
+  pub encrypted_locations: Vec<BigDecimal>,
+  pub location_shuffle: Option<String>,
+
+  // JSON list of the commitments and public keys returned by every collector before next_collector_index
+  pub collector_results: String,
+  pub manifest_hash: Option<String>,
+}
+
+impl InitializationCheckpoint {
+  model_base!();
+
+  pub fn find_resource(election_id: &Uuid, conn: &DbConnection) -> Result<Self, ServiceError> {
+    Self::find_optional(election_id, conn)?.ok_or_else(|| NamedResourceType::checkpoint(*election_id).into_error())
+  }
+}
@@ -1,14 +1,30 @@
 //
 // Database tables (ORM models)
 //
+mod ballot_encoding;
+mod cancelation_commitment;
 mod collector;
+mod commitment_group;
 mod election;
 mod election_collector;
+mod initialization_checkpoint;
 mod question;
+mod question_type;
 mod registration;
+mod verification_mode;
+mod verification_transcript;
+mod verification_transcript_message;
 
+pub use ballot_encoding::BallotEncoding;
+pub use cancelation_commitment::CancelationCommitment;
 pub use collector::Collector;
+pub use commitment_group::CommitmentGroup;
 pub use election::Election;
 pub use election_collector::ElectionCollector;
+pub use initialization_checkpoint::InitializationCheckpoint;
 pub use question::Question;
+pub use question_type::{count_choice_selection_sets, QuestionType, MAX_CHOICE_SELECTION_SETS, MAX_RANKED_CANDIDATES};
 pub use registration::Registration;
+pub use verification_mode::VerificationMode;
+pub use verification_transcript::VerificationTranscript;
+pub use verification_transcript_message::VerificationTranscriptMessage;
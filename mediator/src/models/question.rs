@@ -1,3 +1,5 @@
+use bigdecimal::BigDecimal;
+use curv_kzen::BigInt;
 use serde::Serialize;
 use uuid_b64::UuidB64 as Uuid;
 
@@ -5,6 +7,7 @@ use crate::db::DbConnection;
 use crate::errors::{NamedResourceType, ServiceError};
 use crate::models::Election;
 use crate::schema::questions;
+use crate::utils::ConvertBigInt;
 
 #[derive(Debug, Clone, Serialize, Queryable, Insertable, Identifiable, AsChangeset, Associations)]
 #[belongs_to(Election)]
@@ -13,6 +16,10 @@ use crate::schema::questions;
 pub struct Question {
   pub id: Uuid,
   pub election_id: Uuid,
+
+  // Cached copy of the group g^x (mod p) for this question from the API server
+  pub generator: BigDecimal,
+  pub prime: BigDecimal,
 }
 
 impl Question {
@@ -20,8 +27,13 @@ impl Question {
 
   belongs_to!(Election);
 
-  pub fn new(id: Uuid, election_id: Uuid) -> Self {
-    Self { id, election_id }
+  pub fn new(id: Uuid, election_id: Uuid, generator: &BigInt, prime: &BigInt) -> Self {
+    Self {
+      id,
+      election_id,
+      generator: generator.to_bigdecimal(),
+      prime: prime.to_bigdecimal(),
+    }
   }
 
   pub fn find_resource(id: &Uuid, election_id: &Uuid, conn: &DbConnection) -> Result<Self, ServiceError> {
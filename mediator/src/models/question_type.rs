@@ -0,0 +1,63 @@
+// Every question uses one of the following ballot formats:
+//
+//   [Choice] - Voter selects between min_selections and max_selections candidates
+//              Each candidate is a single bit in the voter's chunk of the voting vector
+//
+//   [Ranked] - Voter ranks every candidate from first to last preference
+//              Each candidate has a one-hot group of num_candidates bits for the rank,
+//              so the voter's chunk has num_candidates^2 bits
+sql_enum!(
+  pub QuestionType {
+    Choice = 0,
+    Ranked
+  }
+);
+
+/// Largest number of candidates allowed for a ranked question (Same limit as the server and collectors)
+///   The mediator rejects larger questions before any collector starts initializing the election
+pub const MAX_RANKED_CANDIDATES: i64 = 7;
+
+/// Largest number of valid selection sets allowed for a choice question (7! = 5040)
+///   The collectors compute an expected product for every set in sub-protocol 1, so the mediator
+///   checks the limit up front instead of failing part way through the collector chain
+pub const MAX_CHOICE_SELECTION_SETS: u64 = 5040;
+
+///
+/// Number of ways to select between min_selections and max_selections of the candidates
+///
+/// Expects 0 <= min_selections <= max_selections <= num_candidates
+///   Stops counting at MAX_CHOICE_SELECTION_SETS + 1, since the exact number can overflow for large questions
+///
+pub fn count_choice_selection_sets(num_candidates: i64, min_selections: i64, max_selections: i64) -> u64 {
+  let limit = MAX_CHOICE_SELECTION_SETS as u128 + 1;
+  let n = num_candidates as u128;
+
+  let mut total: u128 = 0;
+  for k in min_selections as u128..=max_selections as u128 {
+    // C(n, k) = C(n, n - k), and C(n, i) only grows until i = n / 2, so stop as soon as it passes the limit
+    let k = k.min(n - k);
+    let mut subsets: u128 = 1;
+    for i in 1..=k {
+      subsets = subsets * (n - i + 1) / i;
+      if subsets >= limit {
+        return limit as u64;
+      }
+    }
+
+    total += subsets;
+    if total >= limit {
+      return limit as u64;
+    }
+  }
+
+  total as u64
+}
+
+impl QuestionType {
+  pub fn get_name(&self) -> &'static str {
+    match self {
+      QuestionType::Choice => "Choice",
+      QuestionType::Ranked => "Ranked",
+    }
+  }
+}
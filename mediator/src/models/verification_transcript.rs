@@ -0,0 +1,59 @@
+use serde::Serialize;
+use uuid_b64::UuidB64 as Uuid;
+
+use crate::db::DbConnection;
+use crate::errors::{NamedResourceType, ServiceError};
+use crate::models::{Election, Question};
+use crate::schema::verification_transcripts;
+
+///
+/// A single ballot verification run between the mediator and the collectors
+///
+/// The messages are stored separately in VerificationTranscriptMessage
+///
+#[derive(Debug, Clone, Serialize, Queryable, Insertable, Identifiable, Associations)]
+#[belongs_to(Election)]
+#[belongs_to(Question)]
+#[serde(rename_all = "camelCase")]
+pub struct VerificationTranscript {
+  pub id: Uuid,
+  pub election_id: Uuid,
+  pub question_id: Uuid,
+  pub user_id: Uuid,
+
+  // JSON verification result returned by the mediator (None if verification was canceled)
+  pub result: Option<String>,
+}
+
+impl VerificationTranscript {
+  model_base!(no update);
+
+  belongs_to!(Election);
+  belongs_to!(Question);
+  has_many!(
+    VerificationTranscriptMessage,
+    order by verification_transcript_messages::sequence.asc(),
+    messages
+  );
+
+  pub fn new(id: Uuid, election_id: Uuid, question_id: Uuid, user_id: Uuid, result: Option<String>) -> Self {
+    Self {
+      id,
+      election_id,
+      question_id,
+      user_id,
+      result,
+    }
+  }
+
+  pub fn find_resource(
+    id: &Uuid,
+    election_id: &Uuid,
+    question_id: &Uuid,
+    conn: &DbConnection,
+  ) -> Result<Self, ServiceError> {
+    VerificationTranscript::find_optional(id, conn)?
+      .filter(|t| t.election_id == *election_id && t.question_id == *question_id)
+      .ok_or_else(|| NamedResourceType::transcript(*id).into_error())
+  }
+}
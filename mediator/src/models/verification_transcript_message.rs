@@ -0,0 +1,40 @@
+use serde::Serialize;
+use uuid_b64::UuidB64 as Uuid;
+
+use crate::models::VerificationTranscript;
+use crate::schema::verification_transcript_messages;
+
+///
+/// Raw websocket message exchanged during a ballot verification run
+///
+/// Messages received from a collector are stored exactly as they were sent, so the signatures can be checked later
+///
+#[derive(Debug, Clone, Serialize, Queryable, Insertable, Identifiable, Associations)]
+#[primary_key(transcript_id, sequence)]
+#[belongs_to(VerificationTranscript, foreign_key = "transcript_id")]
+#[serde(rename_all = "camelCase")]
+pub struct VerificationTranscriptMessage {
+  #[serde(skip)]
+  pub transcript_id: Uuid,
+  pub sequence: i64,
+
+  pub collector_index: i64,
+  pub sent: bool,
+  pub message: String,
+}
+
+impl VerificationTranscriptMessage {
+  model_base!(no update);
+
+  belongs_to!(VerificationTranscript);
+
+  pub fn new(transcript_id: Uuid, sequence: usize, collector_index: usize, sent: bool, message: String) -> Self {
+    Self {
+      transcript_id,
+      sequence: sequence as i64,
+      collector_index: collector_index as i64,
+      sent,
+      message,
+    }
+  }
+}
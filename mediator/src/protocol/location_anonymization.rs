@@ -63,3 +63,18 @@ pub fn step_last(e_xn_1: &BigInt, p: &BigInt, q: &BigInt) -> BigInt {
 
   r1.0.into_owned()
 }
+
+///
+/// Recover the randomness ρ from a ciphertext c = E(m; ρ), which needs the private key:
+///   c = ρ^n (mod n), so ρ = c^(n^-1 mod φ(n)) (mod n)
+///
+/// This lets anybody check a decryption, since E(m; ρ) must give back the same ciphertext
+///
+pub fn recover_randomness(c: &BigInt, p: &BigInt, q: &BigInt) -> BigInt {
+  let n = p * q;
+  let phi = (p - 1) * (q - 1);
+
+  // n and φ(n) never share a factor when p and q are safe primes of the same size
+  let exponent = BigInt::mod_inv(&n, &phi).expect("Error: No Inverse");
+  BigInt::mod_pow(&BigInt::modulus(c, &n), &exponent, &n)
+}
@@ -1,5 +1,142 @@
 //
 // Functions and structures related to the math behind the e-voting protocol
 //
+mod group;
 pub mod location_anonymization;
+pub mod shamir;
+pub mod shuffle_proof;
 pub mod stpm;
+
+pub use group::Group;
+
+#[cfg(test)]
+mod copies {
+  #[test]
+  fn group_matches_other_crates() {
+    let group = include_str!("group.rs");
+    assert_eq!(group, include_str!("../../../server/src/protocol/group.rs"));
+    assert_eq!(group, include_str!("../../../collector/src/protocol/group.rs"));
+  }
+
+  #[test]
+  fn shuffle_proof_matches_collector_copy() {
+    assert_eq!(
+      include_str!("shuffle_proof.rs"),
+      include_str!("../../../collector/src/protocol/shuffle_proof.rs")
+    );
+  }
+
+  #[test]
+  fn question_limits_match_other_crates() {
+    // Each crate documents the limits for its own use, but the definitions must be identical
+    let limits = |source: &'static str| -> Vec<&'static str> {
+      source
+        .lines()
+        .filter(|line| line.starts_with("pub const MAX_"))
+        .collect()
+    };
+
+    let question_type = limits(include_str!("../models/question_type.rs"));
+    assert_eq!(question_type.len(), 2);
+    assert_eq!(
+      question_type,
+      limits(include_str!("../../../server/src/models/question_type.rs"))
+    );
+    assert_eq!(
+      question_type,
+      limits(include_str!("../../../collector/src/models/question_type.rs"))
+    );
+  }
+}
+
+#[cfg(test)]
+mod shuffle_proof_tests {
+  use curv_kzen::BigInt;
+  use kzen_paillier::*;
+
+  use super::shuffle_proof::{self, ShuffleProof};
+
+  /// Trivial encryptions E(i; 1) of the locations 0 to num_values - 1
+  fn setup(num_values: u64) -> (DecryptionKey, BigInt, Vec<BigInt>) {
+    let (encryption_key, decryption_key) = Paillier::keypair_with_modulus_size(512).keys();
+    let n = encryption_key.n;
+    let input = (0..num_values)
+      .map(|i| shuffle_proof::encrypt_with_randomness(&BigInt::from(i), &BigInt::from(1), &n))
+      .collect();
+
+    (decryption_key, n, input)
+  }
+
+  fn decrypt_sorted(decryption_key: &DecryptionKey, values: &[BigInt]) -> Vec<BigInt> {
+    let mut plaintexts: Vec<BigInt> = values
+      .iter()
+      .map(|c| {
+        let plaintext: RawPlaintext = Paillier::decrypt(decryption_key, RawCiphertext::from(c));
+        plaintext.into()
+      })
+      .collect();
+
+    plaintexts.sort();
+    plaintexts
+  }
+
+  #[test]
+  fn accepts_valid_shuffle() {
+    let (decryption_key, n, input) = setup(6);
+    let (output, witness) = shuffle_proof::shuffle_and_mask(&input, &n, true, None);
+    let proof = ShuffleProof::prove(&input, &output, &n, &witness, false);
+
+    assert!(proof.verify(&input, &output, &n, false));
+    assert_eq!(
+      decrypt_sorted(&decryption_key, &output),
+      decrypt_sorted(&decryption_key, &input)
+    );
+  }
+
+  #[test]
+  fn accepts_valid_masked_shuffle() {
+    let (_, n, input) = setup(6);
+    let (output, witness) = shuffle_proof::shuffle_and_mask(&input, &n, true, Some(&n));
+    let proof = ShuffleProof::prove(&input, &output, &n, &witness, true);
+
+    assert!(proof.verify(&input, &output, &n, true));
+
+    // The offsets are not 0, so the proof does not pass as a plain shuffle
+    assert!(!proof.verify(&input, &output, &n, false));
+  }
+
+  #[test]
+  fn rejects_tampered_shuffle() {
+    let (_, n, input) = setup(6);
+    let (output, witness) = shuffle_proof::shuffle_and_mask(&input, &n, true, None);
+    let proof = ShuffleProof::prove(&input, &output, &n, &witness, false);
+
+    // Swapped outputs
+    let mut swapped = output.clone();
+    swapped.swap(0, 1);
+    assert!(!proof.verify(&input, &swapped, &n, false));
+
+    // Duplicated location, which drops another location
+    let mut duplicated = output.clone();
+    duplicated[1] = duplicated[0].clone();
+    assert!(!proof.verify(&input, &duplicated, &n, false));
+
+    // Different input
+    let mut reordered = input.clone();
+    reordered.reverse();
+    assert!(!proof.verify(&reordered, &output, &n, false));
+
+    // Changed or missing rounds
+    let mut tampered = proof.clone();
+    tampered.rounds[0].permutation.swap(0, 1);
+    assert!(!tampered.verify(&input, &output, &n, false));
+
+    let mut tampered = proof.clone();
+    tampered.rounds[1].randomness[2] = &tampered.rounds[1].randomness[2] + BigInt::from(1);
+    assert!(!tampered.verify(&input, &output, &n, false));
+
+    let mut tampered = proof;
+    tampered.rounds.pop();
+    assert!(!tampered.verify(&input, &output, &n, false));
+  }
+}
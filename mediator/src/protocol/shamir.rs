@@ -0,0 +1,85 @@
+use curv_kzen::arithmetic::Modulo;
+use curv_kzen::BigInt;
+
+///
+/// Recover f(0) from the points (x_j, f(x_j)) using Lagrange interpolation (mod q)
+///
+///   f(0) = Σ f(x_j) * λ_j, where λ_j = Π (x_k / (x_k - x_j)) over every k != j
+///
+/// The modulus q must be prime. Returns None if two points have the same x-coordinate.
+///
+pub fn interpolate_at_zero(points: &[(BigInt, BigInt)], modulus: &BigInt) -> Option<BigInt> {
+  points
+    .iter()
+    .enumerate()
+    .try_fold(BigInt::from(0), |sum, (j, (x_j, y_j))| {
+      let mut numerator = BigInt::from(1);
+      let mut denominator = BigInt::from(1);
+      for (k, (x_k, _)) in points.iter().enumerate() {
+        if k != j {
+          numerator = BigInt::mod_mul(&numerator, x_k, modulus);
+          denominator = BigInt::mod_mul(&denominator, &BigInt::mod_sub(x_k, x_j, modulus), modulus);
+        }
+      }
+
+      let lambda = BigInt::mod_mul(&numerator, &BigInt::mod_inv(&denominator, modulus)?, modulus);
+      Some(BigInt::mod_add(&sum, &BigInt::mod_mul(y_j, &lambda, modulus), modulus))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+  use itertools::Itertools;
+
+  use super::*;
+
+  /// Mersenne prime 2^61 - 1
+  const MODULUS: u64 = 2_305_843_009_213_693_951;
+
+  /// Points (x, f(x)) for x = 1 to num_points on f(x) = 1234 + 567*x + 89*x^2 + 10*x^3 (mod q)
+  fn points(num_points: u64) -> Vec<(BigInt, BigInt)> {
+    let modulus = BigInt::from(MODULUS);
+    (1..=num_points)
+      .map(|x| {
+        let x = BigInt::from(x);
+        let y = [10u64, 89, 567, 1234].iter().fold(BigInt::from(0), |acc, coefficient| {
+          BigInt::mod_add(
+            &BigInt::mod_mul(&acc, &x, &modulus),
+            &BigInt::from(*coefficient),
+            &modulus,
+          )
+        });
+        (x, y)
+      })
+      .collect()
+  }
+
+  #[test]
+  fn reconstructs_from_any_threshold_points() {
+    let modulus = BigInt::from(MODULUS);
+    let points = points(6);
+
+    // Degree 3, so every set of 4 or more points recovers f(0)
+    for threshold in 4..=points.len() {
+      for subset in points.iter().cloned().combinations(threshold) {
+        assert_eq!(interpolate_at_zero(&subset, &modulus), Some(BigInt::from(1234)));
+      }
+    }
+  }
+
+  #[test]
+  fn fewer_points_do_not_reconstruct() {
+    let modulus = BigInt::from(MODULUS);
+    for subset in points(6).into_iter().combinations(3) {
+      assert_ne!(interpolate_at_zero(&subset, &modulus), Some(BigInt::from(1234)));
+    }
+  }
+
+  #[test]
+  fn rejects_duplicate_points() {
+    let modulus = BigInt::from(MODULUS);
+    let mut points = points(4);
+    points[3] = points[0].clone();
+    assert_eq!(interpolate_at_zero(&points, &modulus), None);
+  }
+}
@@ -1,9 +1,21 @@
+table! {
+    cancelation_commitments (user_id, election_id, question_id, collector_id) {
+        user_id -> Uuid,
+        election_id -> Uuid,
+        question_id -> Uuid,
+        collector_id -> Uuid,
+        forward_commitment -> Numeric,
+        reverse_commitment -> Numeric,
+    }
+}
+
 table! {
     collectors (id) {
         id -> Uuid,
         name -> Varchar,
         private_base_uri -> Text,
         is_secure -> Bool,
+        identity_key -> Nullable<Text>,
     }
 }
 
@@ -11,6 +23,7 @@ table! {
     election_collectors (election_id, collector_id) {
         election_id -> Uuid,
         collector_id -> Uuid,
+        collector_index -> Int8,
     }
 }
 
@@ -19,8 +32,25 @@ table! {
         id -> Uuid,
         is_public -> Bool,
         creator_id -> Uuid,
-        generator -> Numeric,
-        prime -> Numeric,
+        commitment_group -> Int4,
+        threshold -> Nullable<Int8>,
+        manifest_hash -> Nullable<Text>,
+    }
+}
+
+table! {
+    initialization_checkpoints (election_id) {
+        election_id -> Uuid,
+        manifest -> Text,
+        collectors -> Array<Uuid>,
+        next_collector_index -> Int8,
+        distributed_locations -> Bool,
+        location_modulus -> Numeric,
+        location_key -> Nullable<Bytea>,
+        encrypted_locations -> Array<Numeric>,
+        location_shuffle -> Nullable<Text>,
+        collector_results -> Text,
+        manifest_hash -> Nullable<Text>,
     }
 }
 
@@ -28,6 +58,8 @@ table! {
     questions (id) {
         id -> Uuid,
         election_id -> Uuid,
+        generator -> Numeric,
+        prime -> Numeric,
     }
 }
 
@@ -38,15 +70,45 @@ table! {
     }
 }
 
+table! {
+    verification_transcript_messages (transcript_id, sequence) {
+        transcript_id -> Uuid,
+        sequence -> Int8,
+        collector_index -> Int8,
+        sent -> Bool,
+        message -> Text,
+    }
+}
+
+table! {
+    verification_transcripts (id) {
+        id -> Uuid,
+        election_id -> Uuid,
+        question_id -> Uuid,
+        user_id -> Uuid,
+        result -> Nullable<Text>,
+    }
+}
+
+joinable!(cancelation_commitments -> collectors (collector_id));
+joinable!(cancelation_commitments -> elections (election_id));
+joinable!(cancelation_commitments -> questions (question_id));
 joinable!(election_collectors -> collectors (collector_id));
 joinable!(election_collectors -> elections (election_id));
 joinable!(questions -> elections (election_id));
 joinable!(registrations -> elections (election_id));
+joinable!(verification_transcript_messages -> verification_transcripts (transcript_id));
+joinable!(verification_transcripts -> elections (election_id));
+joinable!(verification_transcripts -> questions (question_id));
 
 allow_tables_to_appear_in_same_query!(
+    cancelation_commitments,
     collectors,
     election_collectors,
     elections,
+    initialization_checkpoints,
     questions,
     registrations,
+    verification_transcript_messages,
+    verification_transcripts,
 );
@@ -1,11 +1,120 @@
 use curv_kzen::BigInt;
 use serde::{Deserialize, Serialize};
+use uuid_b64::UuidB64 as Uuid;
+
+use crate::protocol::shuffle_proof::ShuffleProof;
 
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct InitializeElectionResult {
   #[serde(with = "kzen_paillier::serialize::bigint")]
   pub n: BigInt,
+
+  /// Share commitments from every collector, in the same order as the collectors
+  pub share_commitments: Vec<Vec<QuestionShareCommitments>>,
+
+  /// Cancelation commitments from every collector, in the same order as the collectors
+  pub cancelation_commitments: Vec<Vec<QuestionCancelationCommitments>>,
+}
+
+///
+/// Blinded commitments to the ballot shares of every registered user (Same order as the registered users)
+///   Only provided for elections that use non-interactive ballot proofs
+///
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuestionShareCommitments {
+  pub question_id: Uuid,
+
+  #[serde(with = "kzen_paillier::serialize::vecbigint")]
+  pub forward_commitments: Vec<BigInt>,
+
+  #[serde(with = "kzen_paillier::serialize::vecbigint")]
+  pub reverse_commitments: Vec<BigInt>,
+}
+
+///
+/// Commitments to the row and column totals of the shares matrix from a single collector
+///
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuestionShareTotals {
+  pub question_id: Uuid,
+  pub num_registrations: usize,
+
+  #[serde(with = "kzen_paillier::serialize::bigint")]
+  pub forward_row_total: BigInt, // g^(Σ S_c,i)
+  #[serde(with = "kzen_paillier::serialize::bigint")]
+  pub forward_column_total: BigInt, // g^(Σ S~c,i)
+
+  #[serde(with = "kzen_paillier::serialize::bigint")]
+  pub reverse_row_total: BigInt, // g^(Σ S_c,i')
+  #[serde(with = "kzen_paillier::serialize::bigint")]
+  pub reverse_column_total: BigInt, // g^(Σ S~c,i')
+}
+
+///
+/// Commitments to the cancelation shares of every registered user (Same order as the registered users)
+///   X_c,i = g^(S~c,i - S_c,i) and X_c,i' = g^(S~c,i' - S_c,i')
+///
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuestionCancelationCommitments {
+  pub question_id: Uuid,
+
+  #[serde(with = "kzen_paillier::serialize::vecbigint")]
+  pub forward_commitments: Vec<BigInt>,
+
+  #[serde(with = "kzen_paillier::serialize::vecbigint")]
+  pub reverse_commitments: Vec<BigInt>,
+}
+
+///
+/// Location shares of every registered user, encrypted with the Paillier public key of the key holder for the round
+///   The mediator only forwards these between the collectors
+///
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EncryptedLocationShares {
+  #[serde(with = "kzen_paillier::serialize::bigint")]
+  pub public_key: BigInt,
+
+  #[serde(with = "kzen_paillier::serialize::vecbigint")]
+  pub encrypted_locations: Vec<BigInt>,
+}
+
+///
+/// One shuffle in a round of distributed location anonymization, with the proof for the next collector
+///   The mediator only forwards these, since it cannot decrypt them
+///
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocationLink {
+  #[serde(with = "kzen_paillier::serialize::vecbigint")]
+  pub output: Vec<BigInt>,
+  pub proof: ShuffleProof,
+}
+
+///
+/// Encrypted threshold shares sent from one collector to another
+///   The mediator only forwards these, since only the recipient can decrypt them
+///
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ThresholdDealing {
+  pub questions: Vec<QuestionThresholdShares>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuestionThresholdShares {
+  pub question_id: Uuid,
+
+  #[serde(with = "kzen_paillier::serialize::vecbigint")]
+  pub forward_shares: Vec<BigInt>,
+
+  #[serde(with = "kzen_paillier::serialize::vecbigint")]
+  pub reverse_shares: Vec<BigInt>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -16,4 +125,56 @@ pub struct CancelationShares {
 
   #[serde(with = "kzen_paillier::serialize::bigint")]
   pub reverse_cancelation_shares: BigInt,
+
+  /// Sum of the threshold shares held by a single collector (Only used with threshold secret sharing)
+  ///   These are never sent back to the server
+  #[serde(
+    default,
+    skip_serializing_if = "Option::is_none",
+    with = "crate::utils::serialize_option_bigint"
+  )]
+  pub forward_threshold_shares: Option<BigInt>,
+  #[serde(
+    default,
+    skip_serializing_if = "Option::is_none",
+    with = "crate::utils::serialize_option_bigint"
+  )]
+  pub reverse_threshold_shares: Option<BigInt>,
+
+  /// Cancelation shares returned by every collector, so the server can check them against the commitments
+  ///   Only sent back to the server with (n,n)-secret sharing
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub collector_shares: Vec<CollectorCancelationShares>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CollectorCancelationShares {
+  pub collector_id: Uuid,
+
+  #[serde(with = "kzen_paillier::serialize::bigint")]
+  pub forward_cancelation_shares: BigInt,
+
+  #[serde(with = "kzen_paillier::serialize::bigint")]
+  pub reverse_cancelation_shares: BigInt,
+}
+
+///
+/// Progress of an election that failed part way through initialization
+///   The location key and the intermediate values stay in the mediator
+///
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InitializationCheckpointDetails {
+  pub election_id: Uuid,
+  pub collectors: Vec<Uuid>,
+
+  /// Every collector before this index already accepted the election
+  pub next_collector_index: i64,
+
+  /// Collector that initialization resumes from (None if the chain itself is done)
+  pub next_collector_id: Option<Uuid>,
+
+  pub distributed_locations: bool,
+  pub manifest_hash: Option<String>,
 }
@@ -1,8 +1,109 @@
 use serde::{Deserialize, Serialize};
+use uuid_b64::UuidB64 as Uuid;
+
+use crate::models::VerificationTranscriptMessage;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct VerificationResult {
   pub sub_protocol_1: bool,
   pub sub_protocol_2: bool,
+
+  // Saved transcript for this verification run
+  pub transcript_id: Uuid,
+
+  // Only set if the ballot was not accepted
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub failure: Option<VerificationFailure>,
+}
+
+///
+/// Explains why a ballot was not accepted
+///
+/// Every collector checks the same broadcast values, so honest collectors always agree on the result.
+///   If every collector rejects the ballot, the ballot itself is invalid.
+///   Otherwise, a collector misbehaved (collector_index is None if we cannot tell which one).
+///
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerificationFailure {
+  pub cause: FailureCause,
+  pub check: VerificationCheck,
+  pub collector_index: Option<usize>,
+
+  // Set if another collector reported the failure (The mediator could not check it)
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub reported_by: Option<usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FailureCause {
+  Ballot,
+  Collector,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum VerificationCheck {
+  ProductMismatch,    // Sub-protocol 1: g^(s_i * s_i') * P1 * ... * Pn is not an expected product
+  CommitmentMismatch, // Sub-protocol 2: g^(p_i) != g^(s_i) * g^(S~i,C1) * ... * g^(S~i,Cn)
+  BadSignature,       // Message signature does not match the collector public key
+  UnknownIdentityKey, // Public key is not signed by the identity key pinned for the collector
+}
+
+impl VerificationFailure {
+  pub fn ballot(check: VerificationCheck) -> Self {
+    Self {
+      cause: FailureCause::Ballot,
+      check,
+      collector_index: None,
+      reported_by: None,
+    }
+  }
+
+  pub fn collector(check: VerificationCheck, collector_index: Option<usize>, reported_by: Option<usize>) -> Self {
+    Self {
+      cause: FailureCause::Collector,
+      check,
+      collector_index,
+      reported_by,
+    }
+  }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerificationTranscriptDetails {
+  pub id: Uuid,
+  pub election_id: Uuid,
+  pub question_id: Uuid,
+  pub user_id: Uuid,
+
+  // None if verification was canceled before a result was found
+  pub result: Option<VerificationResult>,
+  pub messages: Vec<VerificationTranscriptMessage>,
+}
+
+///
+/// Result of checking every signature in a transcript again
+///
+/// The public keys are taken from the transcript itself,
+///   but they must be signed by the identity keys pinned when the collectors registered
+///
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptRecheck {
+  pub transcript_id: Uuid,
+  pub valid: bool,
+  pub num_signed_messages: usize,
+  pub invalid_messages: Vec<InvalidTranscriptMessage>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InvalidTranscriptMessage {
+  pub sequence: i64,
+  pub collector_index: usize,
+  pub reason: String,
 }
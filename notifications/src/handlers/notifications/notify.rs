@@ -31,6 +31,8 @@ pub async fn notify(
     AllServerMessages::VoteReceived(data) => addr.do_send(data.wrap()),
     AllServerMessages::VotingClosed(data) => addr.do_send(data.wrap()),
     AllServerMessages::ResultsPublished(data) => addr.do_send(data.wrap()),
+    AllServerMessages::CollectionProgress(data) => addr.do_send(data.wrap()),
+    AllServerMessages::ElectionCanceled(data) => addr.do_send(data.wrap()),
   }
 
   Ok(HttpResponse::Ok().finish())
@@ -104,6 +104,8 @@ pub enum AllClientResponses {
   VoteReceived(VoteReceivedDetails),
   VotingClosed(ElectionDetails),
   ResultsPublished(ElectionDetails),
+  CollectionProgress(CollectionProgressDetails),
+  ElectionCanceled(ElectionCanceledDetails),
 }
 
 #[derive(Serialize)]
@@ -166,10 +168,18 @@ pub struct RegistrationClosedDetails {
 pub struct VotingOpenedDetails {
   pub election_id: Uuid,
   pub collectors: Vec<Uuid>,
+  pub questions: Vec<VotingOpenedQuestion>,
+
+  pub location_modulus: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VotingOpenedQuestion {
+  pub question_id: Uuid,
 
   pub prime: String,
   pub generator: String,
-  pub location_modulus: String,
 }
 
 #[derive(Serialize)]
@@ -190,3 +200,24 @@ pub struct VoteReceivedDetails {
 
   pub num_votes: i64,
 }
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CollectionProgressDetails {
+  pub election_id: Uuid,
+  pub questions: Vec<QuestionCollectionProgress>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ElectionCanceledDetails {
+  pub election_id: Uuid,
+  pub reason: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuestionCollectionProgress {
+  pub question_id: Uuid,
+  pub collection_status: u32,
+}
@@ -29,6 +29,8 @@ pub enum ElectionEvents {
   VoteReceived,
   VotingClosed,
   ResultsPublished,
+  CollectionProgress,
+  ElectionCanceled,
 }
 
 /// Wraps a global event
@@ -28,6 +28,8 @@ pub enum AllServerMessages {
   VoteReceived(VoteReceived),
   VotingClosed(VotingClosed),
   ResultsPublished(ResultsPublished),
+  CollectionProgress(CollectionProgress),
+  ElectionCanceled(ElectionCanceled),
 }
 
 ///
@@ -285,9 +287,8 @@ impl ElectionEvent for RegistrationClosed {
 pub struct VotingOpened {
   pub election_id: Uuid,
   pub collectors: Vec<Uuid>,
+  pub questions: Vec<client_types::VotingOpenedQuestion>,
 
-  pub prime: String,
-  pub generator: String,
   pub location_modulus: String,
 }
 
@@ -303,9 +304,8 @@ impl ElectionEvent for VotingOpened {
     AllClientResponses::VotingOpened(client_types::VotingOpenedDetails {
       election_id: self.election_id,
       collectors: self.collectors,
+      questions: self.questions,
 
-      prime: self.prime,
-      generator: self.generator,
       location_modulus: self.location_modulus,
     })
   }
@@ -407,3 +407,57 @@ impl ElectionEvent for ResultsPublished {
     AllClientResponses::ResultsPublished(self.election_id.into())
   }
 }
+
+///
+/// Collection Progress
+///
+#[derive(Debug, Serialize, Deserialize, Message)]
+#[serde(rename_all = "camelCase")]
+#[rtype(result = "()")]
+pub struct CollectionProgress {
+  pub election_id: Uuid,
+  pub questions: Vec<client_types::QuestionCollectionProgress>,
+}
+
+impl ElectionEvent for CollectionProgress {
+  const EVENT_TYPE: ElectionEvents = ElectionEvents::CollectionProgress;
+
+  fn get_election_id(&self) -> Uuid {
+    self.election_id
+  }
+
+  type Output = AllClientResponses;
+  fn into_output(self) -> Self::Output {
+    AllClientResponses::CollectionProgress(client_types::CollectionProgressDetails {
+      election_id: self.election_id,
+      questions: self.questions,
+    })
+  }
+}
+
+///
+/// Election Canceled
+///
+#[derive(Debug, Serialize, Deserialize, Message)]
+#[serde(rename_all = "camelCase")]
+#[rtype(result = "()")]
+pub struct ElectionCanceled {
+  pub election_id: Uuid,
+  pub reason: String,
+}
+
+impl ElectionEvent for ElectionCanceled {
+  const EVENT_TYPE: ElectionEvents = ElectionEvents::ElectionCanceled;
+
+  fn get_election_id(&self) -> Uuid {
+    self.election_id
+  }
+
+  type Output = AllClientResponses;
+  fn into_output(self) -> Self::Output {
+    AllClientResponses::ElectionCanceled(client_types::ElectionCanceledDetails {
+      election_id: self.election_id,
+      reason: self.reason,
+    })
+  }
+}
@@ -0,0 +1,6 @@
+-- Approval and "choose up to k" questions
+--   A voter must select between min_selections and max_selections candidates
+--   Defaults to the original single-choice behavior (exactly one candidate)
+ALTER TABLE questions
+  ADD COLUMN min_selections BIGINT NOT NULL DEFAULT 1 CHECK (min_selections >= 1),
+  ADD COLUMN max_selections BIGINT NOT NULL DEFAULT 1 CHECK (max_selections >= min_selections);
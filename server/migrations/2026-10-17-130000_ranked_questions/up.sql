@@ -0,0 +1,4 @@
+-- Type of question (See QuestionType enum)
+--   Choice = 0, Ranked = 1
+ALTER TABLE questions
+  ADD COLUMN question_type INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,2 @@
+ALTER TABLE registrations
+  DROP COLUMN weight;
@@ -0,0 +1,4 @@
+-- Weighted voters (Such as shareholder elections)
+--   Every ballot counts as "weight" votes in the final tally
+ALTER TABLE registrations
+  ADD COLUMN weight BIGINT NOT NULL DEFAULT 1 CHECK (weight >= 1);
@@ -0,0 +1,4 @@
+-- Encoding used for the voting vector of choice questions
+--   0 = One-hot chunk for every voter, 1 = Shared base-(N+1) counter for every candidate
+ALTER TABLE elections
+  ADD COLUMN ballot_encoding INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,17 @@
+ALTER TABLE elections
+  ADD COLUMN generator NUMERIC NOT NULL DEFAULT 0,
+  ADD COLUMN prime NUMERIC NOT NULL DEFAULT 0;
+
+-- Elections only have a single group, so use the group from the largest question
+UPDATE elections
+  SET generator = questions.generator, prime = questions.prime
+  FROM (
+    SELECT DISTINCT ON (election_id) election_id, generator, prime
+    FROM questions
+    ORDER BY election_id, prime DESC
+  ) AS questions
+  WHERE elections.id = questions.election_id;
+
+ALTER TABLE questions
+  DROP COLUMN generator,
+  DROP COLUMN prime;
@@ -0,0 +1 @@
+DROP TABLE pooled_primes;
@@ -0,0 +1,12 @@
+-- Safe primes and generators that are generated in the background, bucketed by bit size
+--   Initializing an election takes the smallest prime that is large enough from the pool
+CREATE TABLE pooled_primes (
+  id UUID NOT NULL PRIMARY KEY,
+  num_bits BIGINT NOT NULL CHECK (num_bits > 0),
+
+  -- g^x (mod p) is a cyclic group of order p-1
+  generator NUMERIC NOT NULL,
+  prime NUMERIC NOT NULL
+);
+
+CREATE INDEX pooled_primes_num_bits_idx ON pooled_primes (num_bits);
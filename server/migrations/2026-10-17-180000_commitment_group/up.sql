@@ -0,0 +1,4 @@
+-- Group used for the commitments g^s
+--   0 = Full group Z*p of order p-1, 1 = Prime-order subgroup of order q = (p-1)/2
+ALTER TABLE elections
+  ADD COLUMN commitment_group INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,11 @@
+ALTER TABLE commitments
+  DROP COLUMN forward_share_commitment,
+  DROP COLUMN reverse_share_commitment,
+  DROP COLUMN proof_challenges,
+  DROP COLUMN proof_forward_responses,
+  DROP COLUMN proof_reverse_responses;
+
+DROP TABLE share_commitments;
+
+ALTER TABLE elections
+  DROP COLUMN verification_mode;
@@ -0,0 +1,26 @@
+-- How every ballot is verified when it is cast
+--   0 = Interactive sub-protocols with the collectors, 1 = Non-interactive proof attached to the ballot
+ALTER TABLE elections
+  ADD COLUMN verification_mode INTEGER NOT NULL DEFAULT 0;
+
+-- Blinded commitments to the ballot shares of every voter, multiplied across all collectors
+--   h is a second generator of the commitment group with an unknown discrete log
+CREATE TABLE share_commitments (
+  user_id UUID NOT NULL REFERENCES users (id),
+  election_id UUID NOT NULL REFERENCES elections (id),
+  question_id UUID NOT NULL REFERENCES questions (id),
+  PRIMARY KEY (user_id, election_id, question_id),
+  FOREIGN KEY (user_id, election_id) REFERENCES registrations (user_id, election_id),
+
+  forward_commitment NUMERIC NOT NULL,  -- D_i = g^(S~_i) * h^(rho_i)
+  reverse_commitment NUMERIC NOT NULL   -- D_i' = g^(S~_i') * h^(rho_i')
+);
+
+-- Non-interactive ballot proof, so anyone can re-verify the ballot later
+--   Stores a copy of the share commitments, since the proof is checked against them
+ALTER TABLE commitments
+  ADD COLUMN forward_share_commitment NUMERIC,
+  ADD COLUMN reverse_share_commitment NUMERIC,
+  ADD COLUMN proof_challenges NUMERIC[],
+  ADD COLUMN proof_forward_responses NUMERIC[],
+  ADD COLUMN proof_reverse_responses NUMERIC[];
@@ -0,0 +1,13 @@
+-- Commitments to the cancelation shares of every voter from each collector
+--   The collectors are only known by their ID, since the mediator keeps the list of collectors
+CREATE TABLE cancelation_commitments (
+  user_id UUID NOT NULL REFERENCES users (id),
+  election_id UUID NOT NULL REFERENCES elections (id),
+  question_id UUID NOT NULL REFERENCES questions (id),
+  collector_id UUID NOT NULL,
+  PRIMARY KEY (user_id, election_id, question_id, collector_id),
+  FOREIGN KEY (user_id, election_id) REFERENCES registrations (user_id, election_id),
+
+  forward_commitment NUMERIC NOT NULL,  -- X_c,i = g^(S~c,i - S_c,i)
+  reverse_commitment NUMERIC NOT NULL   -- X_c,i' = g^(S~c,i' - S_c,i')
+);
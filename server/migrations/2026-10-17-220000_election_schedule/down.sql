@@ -0,0 +1,10 @@
+ALTER TABLE elections
+  DROP COLUMN registration_opens_at,
+  DROP COLUMN registration_closes_at,
+  DROP COLUMN voting_opens_at,
+  DROP COLUMN voting_closes_at,
+  DROP COLUMN scheduled_collectors,
+  DROP COLUMN scheduled_threshold,
+  DROP COLUMN scheduled_distributed_locations,
+  DROP COLUMN retry_attempts,
+  DROP COLUMN next_retry_at;
@@ -0,0 +1,18 @@
+-- Times when the scheduler moves the election to the next status (NULL = Only the owner can change the status)
+--   Registrations are refused after registration_closes_at, even before voting is initialized
+ALTER TABLE elections
+  ADD COLUMN registration_opens_at TIMESTAMPTZ,
+  ADD COLUMN registration_closes_at TIMESTAMPTZ,
+  ADD COLUMN voting_opens_at TIMESTAMPTZ,
+  ADD COLUMN voting_closes_at TIMESTAMPTZ;
+
+-- Parameters for initializing voting at voting_opens_at, since the owner is not there to provide them
+ALTER TABLE elections
+  ADD COLUMN scheduled_collectors UUID[] NOT NULL DEFAULT '{}',
+  ADD COLUMN scheduled_threshold BIGINT,
+  ADD COLUMN scheduled_distributed_locations BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- Failed transitions are retried with exponential backoff
+ALTER TABLE elections
+  ADD COLUMN retry_attempts INTEGER NOT NULL DEFAULT 0,
+  ADD COLUMN next_retry_at TIMESTAMPTZ;
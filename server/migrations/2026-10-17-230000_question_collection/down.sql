@@ -0,0 +1,3 @@
+ALTER TABLE questions
+  DROP COLUMN collection_status,
+  DROP COLUMN collection_error;
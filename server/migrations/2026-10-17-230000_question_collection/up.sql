@@ -0,0 +1,5 @@
+-- Progress of collecting the cancelation shares for every question after voting closes
+--   0 = Pending, 1 = Collected, 2 = Failed (With the error in collection_error)
+ALTER TABLE questions
+  ADD COLUMN collection_status INTEGER NOT NULL DEFAULT 0,
+  ADD COLUMN collection_error TEXT;
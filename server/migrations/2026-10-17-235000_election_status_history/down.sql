@@ -0,0 +1 @@
+DROP TABLE election_status_history;
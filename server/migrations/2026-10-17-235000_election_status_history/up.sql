@@ -0,0 +1,13 @@
+-- Every status change of an election, in the order it happened
+CREATE TABLE election_status_history (
+  id UUID NOT NULL PRIMARY KEY,
+  election_id UUID NOT NULL REFERENCES elections (id) ON DELETE CASCADE,
+  from_status INTEGER NOT NULL,
+  to_status INTEGER NOT NULL,
+
+  actor_id UUID NULL REFERENCES users (id),  -- NULL when the scheduler changed the status
+  reason TEXT NOT NULL,
+  changed_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX election_status_history_election_idx ON election_status_history (election_id, changed_at);
@@ -141,6 +141,17 @@ where
       ))
     }
   }
+
+  pub fn test_can_view_prime_pool(&self) -> Result<(), ServiceError> {
+    if self.has_permission(Permission::CanLogin) && self.has_permission(Permission::ManagePrimePool) {
+      Ok(())
+    } else {
+      Err(ServiceError::ForbiddenResourceAction(
+        ResourceType::PrimePool,
+        ResourceAction::ReadPrivate,
+      ))
+    }
+  }
 }
 
 impl<A> JWTToken<A, JWTClientData>
@@ -0,0 +1,66 @@
+use openssl::pkey::{Id, PKey, Private};
+use openssl::sign::Signer;
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+
+use crate::protocol::{ElectionManifest, SignedManifest};
+
+///
+/// Ed25519 key used to sign the election manifests
+///
+/// The collectors are configured with the public key, so the mediator cannot change
+///   the election parameters without the collectors noticing
+///
+#[derive(Clone)]
+pub struct ManifestKey {
+  private_key: PKey<Private>,
+}
+
+impl ManifestKey {
+  /// Load the private key from a PEM file, or generate a new key if the file does not exist yet
+  pub fn load_or_generate(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+    let path = path.as_ref();
+    if path.exists() {
+      let pem = fs::read(path)?;
+      let private_key = PKey::private_key_from_pem(&pem)?;
+      if private_key.id() != Id::ED25519 {
+        return Err(anyhow::anyhow!("{} is not an Ed25519 private key", path.display()));
+      }
+
+      return Ok(Self { private_key });
+    }
+
+    log::info!("Generating a new manifest signing key in {}", path.display());
+    let private_key = PKey::generate_ed25519()?;
+    fs::write(path, private_key.private_key_to_pem_pkcs8()?)?;
+    fs::set_permissions(path, fs::Permissions::from_mode(0o600))?;
+
+    Ok(Self { private_key })
+  }
+
+  /// Public key for the collectors, encoded as base64 DER
+  pub fn public_key(&self) -> String {
+    // Converting a valid key to DER should never fail
+    let der = self
+      .private_key
+      .public_key_to_der()
+      .expect("Error: Invalid manifest key");
+    base64::encode(der)
+  }
+
+  /// Sign the manifest, returning the base64 signature
+  pub fn sign(&self, message: &[u8]) -> String {
+    let mut signer = Signer::new_without_digest(&self.private_key).expect("Error: Invalid manifest key");
+    let signature = signer
+      .sign_oneshot_to_vec(message)
+      .expect("Error: Failed to sign manifest");
+    base64::encode(signature)
+  }
+
+  /// Sign the hash of the election manifest
+  pub fn sign_manifest(&self, manifest: ElectionManifest) -> SignedManifest {
+    let signature = self.sign(&manifest.compute_hash());
+    SignedManifest { manifest, signature }
+  }
+}
@@ -6,6 +6,7 @@ mod captcha;
 mod constants;
 mod jwt_secret;
 mod jwt_token;
+mod manifest_key;
 mod password_complexity;
 mod permission;
 mod refresh_token;
@@ -15,6 +16,7 @@ pub use captcha::verify_recaptcha;
 pub use constants::*;
 pub use jwt_secret::JWTSecret;
 pub use jwt_token::*;
+pub use manifest_key::ManifestKey;
 pub use password_complexity::validate_password_complexity;
 pub use permission::{Permission, DEFAULT_PERMISSIONS};
 pub use refresh_token::RefreshToken;
@@ -17,6 +17,7 @@ pub enum Permission {
   Register,
   Vote,
   ManageCollector,
+  ManagePrimePool,
 }
 
 impl fmt::Display for Permission {
@@ -9,6 +9,9 @@ use structopt::StructOpt;
 const DEFAULT_HOST: &str = "127.0.0.1";
 const DEFAULT_PORT: u16 = 3000;
 const DEFAULT_JWT_SECRET: &str = "JWT_SECRET_VALUE_LOL";
+const DEFAULT_MANIFEST_KEY_FILE: &str = "manifest_key.pem";
+const DEFAULT_PRIME_POOL_SIZE: i64 = 4;
+const DEFAULT_PRIME_POOL_MAX_BITS: usize = 4096;
 
 /// Electronic voting Rest API server
 #[derive(StructOpt)]
@@ -41,6 +44,10 @@ pub struct Opt {
   #[structopt(short = "s", long, env, hide_env_values = true, default_value = DEFAULT_JWT_SECRET, hide_default_value(true))]
   jwt_secret: String,
 
+  /// Path for the Ed25519 key that signs the election manifests, which is created if it does not exist
+  #[structopt(long, env, parse(from_os_str), default_value = DEFAULT_MANIFEST_KEY_FILE)]
+  manifest_key_file: PathBuf,
+
   /// Secret key to verify Google reCAPTCHA
   #[structopt(short = "r", long, env, hide_env_values = true)]
   recaptcha_secret_key: String,
@@ -52,6 +59,14 @@ pub struct Opt {
   /// Base URL to access the notifications server
   #[structopt(long, env)]
   notifications_url: String,
+
+  /// Number of safe primes to keep in each bucket of the prime pool (0 disables the pool)
+  #[structopt(long, env, default_value = "4")]
+  prime_pool_size: i64,
+
+  /// Number of bits for the largest bucket of the prime pool
+  #[structopt(long, env, default_value = "4096")]
+  prime_pool_max_bits: usize,
 }
 
 impl Opt {
@@ -72,9 +87,12 @@ impl Opt {
 
     env::set_var("DATABASE_URL", &self.database_url);
     env::set_var("JWT_SECRET", &self.jwt_secret);
+    env::set_var("MANIFEST_KEY_FILE", &self.manifest_key_file);
     env::set_var("RECAPTCHA_SECRET_KEY", &self.recaptcha_secret_key);
     env::set_var("MEDIATOR_URL", &self.mediator_url);
     env::set_var("NOTIFICATIONS_URL", &self.notifications_url);
+    env::set_var("PRIME_POOL_SIZE", self.prime_pool_size.to_string());
+    env::set_var("PRIME_POOL_MAX_BITS", self.prime_pool_max_bits.to_string());
   }
 }
 
@@ -134,6 +152,10 @@ pub fn get_jwt_secret() -> String {
   env::var("JWT_SECRET").unwrap_or_else(|_| DEFAULT_JWT_SECRET.to_string())
 }
 
+pub fn get_manifest_key_file() -> String {
+  env::var("MANIFEST_KEY_FILE").unwrap_or_else(|_| DEFAULT_MANIFEST_KEY_FILE.to_string())
+}
+
 pub fn get_recaptcha_secret_key() -> Option<String> {
   return env::var("RECAPTCHA_SECRET_KEY").ok();
 }
@@ -151,3 +173,18 @@ pub fn get_mediator_url() -> Option<String> {
 pub fn get_notifications_url() -> Option<String> {
   return env::var("NOTIFICATIONS_URL").ok();
 }
+
+//
+// Prime Pool
+//
+pub fn get_prime_pool_size() -> i64 {
+  env::var("PRIME_POOL_SIZE")
+    .map(|size| size.parse().unwrap_or(DEFAULT_PRIME_POOL_SIZE))
+    .unwrap_or(DEFAULT_PRIME_POOL_SIZE)
+}
+
+pub fn get_prime_pool_max_bits() -> usize {
+  env::var("PRIME_POOL_MAX_BITS")
+    .map(|bits| bits.parse().unwrap_or(DEFAULT_PRIME_POOL_MAX_BITS))
+    .unwrap_or(DEFAULT_PRIME_POOL_MAX_BITS)
+}
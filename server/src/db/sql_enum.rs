@@ -1,6 +1,7 @@
 #[macro_export]
 macro_rules! sql_enum {
-  ($v:vis $name:ident $type:tt) => {
+  ($(#[$meta:meta])* $v:vis $name:ident $type:tt) => {
+    $(#[$meta])*
     #[repr(i32)]
     #[derive(
       Debug, Copy, Clone, Eq, PartialEq, Hash,
@@ -70,4 +70,14 @@ impl ClientRequestError {
       }
     }
   }
+
+  /// Connection problems and server errors might go away when the request is sent again
+  pub fn is_transient(&self) -> bool {
+    match self {
+      Self::SendError(_) => true,
+      Self::ResponseError(error) => error.get_status_code().is_server_error(),
+      Self::JSONError(_) => false,
+      Self::UnknownError(status) => status.is_server_error(),
+    }
+  }
 }
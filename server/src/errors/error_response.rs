@@ -52,6 +52,10 @@ impl ErrorResponse {
       developer_notes: Some(developer_notes),
     }
   }
+
+  pub fn get_status_code(&self) -> StatusCode {
+    self.status_code
+  }
 }
 
 impl fmt::Display for ErrorResponse {
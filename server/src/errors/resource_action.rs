@@ -9,9 +9,11 @@ pub enum ResourceAction {
   Delete,
   OpenRegistration,
   Register,
+  UpdateRegistration,
   InitVoting,
   Vote,
   CloseVoting,
+  Cancel,
 }
 
 impl ResourceAction {
@@ -23,9 +25,11 @@ impl ResourceAction {
       ResourceAction::Delete => "Delete",
       ResourceAction::OpenRegistration => "Open Registration for",
       ResourceAction::Register => "Register for",
+      ResourceAction::UpdateRegistration => "Update registration for",
       ResourceAction::InitVoting => "Initialize voting for",
       ResourceAction::Vote => "Vote in",
       ResourceAction::CloseVoting => "Close voting for",
+      ResourceAction::Cancel => "Cancel",
     }
   }
 }
@@ -6,6 +6,7 @@ pub enum ResourceType {
   User,
   Election,
   Question,
+  PrimePool,
 }
 
 impl ResourceType {
@@ -14,6 +15,7 @@ impl ResourceType {
       ResourceType::User => "User",
       ResourceType::Election => "Election",
       ResourceType::Question => "Question",
+      ResourceType::PrimePool => "Prime Pool",
     }
   }
 }
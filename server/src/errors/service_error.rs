@@ -12,7 +12,8 @@ use validator::ValidationErrors;
 use crate::errors::{
   ClientRequestError, ErrorResponse, GlobalErrorCode, NamedResourceType, ResourceAction, ResourceType,
 };
-use crate::models::ElectionStatus;
+use crate::models::{CommitmentGroup, ElectionStatus, VerificationMode};
+use crate::views::verification::{FailureCause, VerificationFailure};
 
 /// Enumeration of all possible errors that can occur
 #[derive(Debug)]
@@ -21,6 +22,7 @@ pub enum ServiceError {
   DatabasePoolError(PoolError),
   DatabaseError(diesel::result::Error),
   MissingAppData(String),
+  BlockingTaskCanceled,
   JSONPayloadError(JsonPayloadError),
   FormPayloadError(UrlencodedError),
   URLPathError(PathError),
@@ -87,6 +89,8 @@ pub enum ServiceError {
   VoteInvalid {
     sub_protocol_1: bool,
     sub_protocol_2: bool,
+    transcript_id: Uuid,
+    failure: Option<VerificationFailure>,
   },
   NotOpenForVoting {
     election_id: Uuid,
@@ -99,6 +103,57 @@ pub enum ServiceError {
   ElectionNotStarted {
     election_id: Uuid,
   },
+  VotingVectorTooLarge {
+    election_id: Uuid,
+    question_id: Uuid,
+    num_bits: usize,
+    max_bits: usize,
+  },
+  VerificationModeNotSupported {
+    election_id: Uuid,
+    verification_mode: VerificationMode,
+    commitment_group: CommitmentGroup,
+  },
+  BallotProofNotSupported {
+    election_id: Uuid,
+    commitment_group: CommitmentGroup,
+  },
+  BallotProofTooLarge {
+    election_id: Uuid,
+    question_id: Uuid,
+    num_branches: u64,
+    max_branches: u64,
+  },
+  ShareCommitmentsInvalid {
+    election_id: Uuid,
+    question_id: Uuid,
+  },
+  BallotProofMissing {
+    election_id: Uuid,
+    question_id: Uuid,
+  },
+  BallotProofInvalid {
+    election_id: Uuid,
+    question_id: Uuid,
+  },
+  ThresholdSharingNotSupported {
+    election_id: Uuid,
+    commitment_group: CommitmentGroup,
+  },
+  CancelationSharesInvalid {
+    election_id: Uuid,
+    question_id: Uuid,
+    collector_id: Option<Uuid>,
+  },
+  SharesAuditError(ClientRequestError),
+  PurgeElectionError(ClientRequestError),
+  WeightsRequireCounterEncoding {
+    election_id: Uuid,
+  },
+  InvalidTransition {
+    election_id: Uuid,
+    action: ResourceAction,
+  },
 }
 
 impl ServiceError {
@@ -132,6 +187,13 @@ impl ServiceError {
         format!("'{}' not configured using App::data()", data),
       ),
 
+      ServiceError::BlockingTaskCanceled => ErrorResponse::new(
+        StatusCode::INTERNAL_SERVER_ERROR,
+        "Blocking task was canceled before it finished".into(),
+        GlobalErrorCode::BlockingTaskCanceled,
+        "The blocking thread pool is shutting down".into(),
+      ),
+
       ServiceError::JSONPayloadError(error) => ErrorResponse::new(
         StatusCode::BAD_REQUEST,
         "Invalid JSON Object".into(),
@@ -382,6 +444,39 @@ impl ServiceError {
       ServiceError::VoteInvalid {
         sub_protocol_1,
         sub_protocol_2,
+        transcript_id,
+        failure: Some(failure),
+      } if failure.cause == FailureCause::Collector => ErrorResponse::new(
+        StatusCode::BAD_GATEWAY,
+        format!(
+          "Vote could not be verified: {} misbehaved ({})",
+          match failure.collector_index {
+            Some(index) => format!("collector {}", index + 1),
+            None => "a collector".to_string(),
+          },
+          failure.check.description()
+        ),
+        GlobalErrorCode::CollectorMisbehaved,
+        match failure.reported_by {
+          Some(index) => format!(
+            "Sub-protocol 1: {}, Sub-protocol 2: {}, Reported by collector {}, Transcript ID: {}",
+            sub_protocol_1,
+            sub_protocol_2,
+            index + 1,
+            transcript_id
+          ),
+          None => format!(
+            "Sub-protocol 1: {}, Sub-protocol 2: {}, Transcript ID: {}",
+            sub_protocol_1, sub_protocol_2, transcript_id
+          ),
+        },
+      ),
+
+      ServiceError::VoteInvalid {
+        sub_protocol_1,
+        sub_protocol_2,
+        transcript_id,
+        ..
       } => ErrorResponse::new(
         StatusCode::CONFLICT,
         format!(
@@ -393,7 +488,10 @@ impl ServiceError {
           }
         ),
         GlobalErrorCode::VoteInvalid,
-        format!("Sub-protocol 1: {}, Sub-protocol 2: {}", sub_protocol_1, sub_protocol_2),
+        format!(
+          "Sub-protocol 1: {}, Sub-protocol 2: {}, Transcript ID: {}",
+          sub_protocol_1, sub_protocol_2, transcript_id
+        ),
       ),
 
       ServiceError::NotOpenForVoting { election_id } => ErrorResponse::new(
@@ -426,6 +524,171 @@ impl ServiceError {
         GlobalErrorCode::ElectionNotStarted,
         format!("Election ID: {}", election_id),
       ),
+
+      ServiceError::VotingVectorTooLarge {
+        election_id,
+        question_id,
+        num_bits,
+        max_bits,
+      } => ErrorResponse::new(
+        StatusCode::CONFLICT,
+        "Too many registered users or candidates for the elliptic curve commitment group".into(),
+        GlobalErrorCode::VotingVectorTooLarge,
+        format!(
+          "Election ID: {}, Question ID: {}, Bits Needed: {}, Max Bits: {}",
+          election_id, question_id, num_bits, max_bits
+        ),
+      ),
+
+      ServiceError::VerificationModeNotSupported {
+        election_id,
+        verification_mode,
+        commitment_group,
+      } => ErrorResponse::new(
+        StatusCode::CONFLICT,
+        format!(
+          "{} verification is not supported by the {} commitment group",
+          verification_mode.get_name(),
+          commitment_group.get_name()
+        ),
+        GlobalErrorCode::VerificationModeNotSupported,
+        format!("Election ID: {}", election_id),
+      ),
+
+      ServiceError::BallotProofNotSupported {
+        election_id,
+        commitment_group,
+      } => ErrorResponse::new(
+        StatusCode::CONFLICT,
+        format!(
+          "Counter, multi-select, and ranked ballots need ballot proofs, which the {} commitment group does not support",
+          commitment_group.get_name()
+        ),
+        GlobalErrorCode::BallotProofNotSupported,
+        format!("Election ID: {}", election_id),
+      ),
+
+      ServiceError::BallotProofTooLarge {
+        election_id,
+        question_id,
+        num_branches,
+        max_branches,
+      } => ErrorResponse::new(
+        StatusCode::CONFLICT,
+        "Too many registered users or selections to verify the ballot proofs".into(),
+        GlobalErrorCode::BallotProofTooLarge,
+        format!(
+          "Election ID: {}, Question ID: {}, Branches Needed: {}, Max Branches: {}",
+          election_id, question_id, num_branches, max_branches
+        ),
+      ),
+
+      ServiceError::ShareCommitmentsInvalid {
+        election_id,
+        question_id,
+      } => ErrorResponse::new(
+        StatusCode::INTERNAL_SERVER_ERROR,
+        "Collectors returned invalid share commitments".into(),
+        GlobalErrorCode::ShareCommitmentsInvalid,
+        format!("Election ID: {}, Question ID: {}", election_id, question_id),
+      ),
+
+      ServiceError::BallotProofMissing {
+        election_id,
+        question_id,
+      } => ErrorResponse::new(
+        StatusCode::BAD_REQUEST,
+        "Election requires a ballot proof with every vote".into(),
+        GlobalErrorCode::BallotProofMissing,
+        format!("Election ID: {}, Question ID: {}", election_id, question_id),
+      ),
+
+      ServiceError::BallotProofInvalid {
+        election_id,
+        question_id,
+      } => ErrorResponse::new(
+        StatusCode::CONFLICT,
+        "Vote is not valid: failed to verify the ballot proof".into(),
+        GlobalErrorCode::BallotProofInvalid,
+        format!("Election ID: {}, Question ID: {}", election_id, question_id),
+      ),
+
+      ServiceError::ThresholdSharingNotSupported {
+        election_id,
+        commitment_group,
+      } => ErrorResponse::new(
+        StatusCode::CONFLICT,
+        format!(
+          "Threshold secret sharing is not supported by the {} commitment group",
+          commitment_group.get_name()
+        ),
+        GlobalErrorCode::ThresholdSharingNotSupported,
+        format!("Election ID: {}", election_id),
+      ),
+
+      ServiceError::CancelationSharesInvalid {
+        election_id,
+        question_id,
+        collector_id,
+      } => ErrorResponse::new(
+        StatusCode::INTERNAL_SERVER_ERROR,
+        "Cancelation shares do not match the collector commitments".into(),
+        GlobalErrorCode::CancelationSharesInvalid,
+        match collector_id {
+          Some(collector_id) => format!(
+            "Election ID: {}, Question ID: {}, Collector ID: {}",
+            election_id, question_id, collector_id
+          ),
+          None => format!("Election ID: {}, Question ID: {}", election_id, question_id),
+        },
+      ),
+
+      ServiceError::SharesAuditError(error) => ErrorResponse::new(
+        StatusCode::INTERNAL_SERVER_ERROR,
+        "Collector shares failed the consistency audit".into(),
+        GlobalErrorCode::SharesAuditError,
+        format!("{:?}", error),
+      ),
+
+      ServiceError::PurgeElectionError(error) => ErrorResponse::new(
+        StatusCode::INTERNAL_SERVER_ERROR,
+        "Failed to purge the election secrets from the collectors".into(),
+        GlobalErrorCode::PurgeElectionError,
+        format!("{:?}", error),
+      ),
+
+      ServiceError::WeightsRequireCounterEncoding { election_id } => ErrorResponse::new(
+        StatusCode::CONFLICT,
+        "Voter weights require the counter ballot encoding without any ranked questions".into(),
+        GlobalErrorCode::WeightsRequireCounterEncoding,
+        format!("Election ID: {}", election_id),
+      ),
+
+      ServiceError::InvalidTransition { election_id, action } => ErrorResponse::new(
+        StatusCode::CONFLICT,
+        format!(
+          "Cannot {} election: the status was changed by another request",
+          action.get_name().to_lowercase()
+        ),
+        GlobalErrorCode::InvalidTransition,
+        format!("Election ID: {}", election_id),
+      ),
+    }
+  }
+
+  /// Errors that might go away on their own, so a scheduled transition is worth retrying
+  pub fn is_transient(&self) -> bool {
+    match self {
+      ServiceError::DatabaseConnectionError(_)
+      | ServiceError::DatabasePoolError(_)
+      | ServiceError::DatabaseError(_) => true,
+
+      ServiceError::RegisterElectionError(error)
+      | ServiceError::CancelationSharesError(error)
+      | ServiceError::SharesAuditError(error)
+      | ServiceError::PurgeElectionError(error) => error.is_transient(),
+
+      _ => false,
     }
   }
 }
@@ -0,0 +1,28 @@
+use actix_web::HttpResponse;
+
+use crate::auth::ServerToken;
+use crate::config;
+use crate::db::DbConnection;
+use crate::errors::ServiceError;
+use crate::models::PooledPrime;
+use crate::protocol::prime_pool_buckets;
+use crate::views::admin::{PrimePoolBucket, PrimePoolStatus};
+
+pub async fn get_prime_pool(token: ServerToken, conn: DbConnection) -> Result<HttpResponse, ServiceError> {
+  token.test_can_view_prime_pool()?;
+
+  let buckets = prime_pool_buckets()
+    .into_iter()
+    .map(|num_bits| {
+      Ok(PrimePoolBucket {
+        num_bits: num_bits as i64,
+        num_primes: PooledPrime::count_bucket(num_bits as i64, &conn)?,
+      })
+    })
+    .collect::<Result<Vec<_>, ServiceError>>()?;
+
+  Ok(HttpResponse::Ok().json(PrimePoolStatus {
+    target_size: config::get_prime_pool_size(),
+    buckets,
+  }))
+}
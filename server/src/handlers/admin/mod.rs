@@ -0,0 +1,6 @@
+//
+// All API handlers for server administration
+//
+mod get_prime_pool;
+
+pub use get_prime_pool::get_prime_pool;
@@ -0,0 +1,110 @@
+use actix_web::client::Client;
+use actix_web::{web, HttpResponse};
+use serde::{Deserialize, Serialize};
+use uuid_b64::UuidB64 as Uuid;
+use validator::Validate;
+
+use crate::auth::{ClientToken, JWTSecret, ServerToken, DEFAULT_PERMISSIONS};
+use crate::config;
+use crate::db::DbConnection;
+use crate::errors::{ClientRequestError, ResourceAction, ServiceError};
+use crate::models::{Election, ElectionStatus, ElectionTransition};
+use crate::notifications::notify_election_canceled;
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct CancelElectionData {
+  /// Shown to the registered users, and saved in the status history
+  #[serde(default)]
+  #[validate(length(min = 1, max = 255))]
+  pub reason: Option<String>,
+}
+
+pub async fn cancel_election(
+  token: ClientToken,
+  path: web::Path<Uuid>,
+  data: web::Json<CancelElectionData>,
+  conn: DbConnection,
+  jwt_key: web::Data<JWTSecret>,
+) -> Result<HttpResponse, ServiceError> {
+  token.test_can_create_election()?;
+  token.validate_user_id(&conn)?;
+  data.validate()?;
+
+  // Make sure the election exists
+  let mut election = Election::find_resource(&path, &conn)?;
+
+  // Only the election creator can cancel the election
+  let current_user_id = token.get_user_id();
+  if election.created_by != current_user_id {
+    return Err(ServiceError::ElectionNotOwnedByUser {
+      current_user_id,
+      owner_id: election.created_by,
+      action: ResourceAction::Cancel,
+    });
+  }
+
+  // Canceling again only retries the purge, in case it failed the first time
+  if election.status == ElectionStatus::Canceled {
+    purge_election_secrets(&election.id, &jwt_key).await?;
+    return Ok(HttpResponse::Ok().finish());
+  }
+
+  // Make sure the election is in the correct status
+  election.test_transition(ElectionTransition::Cancel)?;
+
+  // Nothing is scheduled for a canceled election
+  election.registration_opens_at = None;
+  election.registration_closes_at = None;
+  election.voting_opens_at = None;
+  election.voting_closes_at = None;
+  election.clear_retries();
+
+  // Cancel before purging, so an initialization that is still running cannot open voting with the purged collectors
+  //   The initialization checks for the canceled status once the collectors finish, and purges again
+  let reason = data
+    .into_inner()
+    .reason
+    .unwrap_or_else(|| "Canceled by the election owner".into());
+  let election = election.transition(
+    ElectionTransition::Cancel,
+    Some(current_user_id),
+    reason.as_str(),
+    &conn,
+  )?;
+
+  notify_election_canceled(&election, &reason, &jwt_key).await;
+  log::info!("Canceled election \"{}\" <{}>", election.name, election.id);
+
+  // Always purge, since the delete is idempotent and a failed initialization can leave secrets on some collectors
+  purge_election_secrets(&election.id, &jwt_key).await?;
+
+  Ok(HttpResponse::Ok().finish())
+}
+
+///
+/// Have the mediator delete the election from itself and every collector
+///
+/// A failed purge can be retried by canceling the election again
+///
+/// Also called when an initialization finds out the election was canceled while the collectors were initializing it
+///
+pub async fn purge_election_secrets(election_id: &Uuid, jwt_key: &JWTSecret) -> Result<(), ServiceError> {
+  let mediator_url = config::get_mediator_url().ok_or_else(|| ServiceError::MediatorURLNotSet)?;
+  let url = format!("{}/api/v1/mediator/elections/{}", mediator_url, election_id);
+
+  log::debug!("Request election purge from collector mediator...");
+  let purge_request = Client::builder()
+    .disable_timeout()
+    .bearer_auth(ServerToken::new(DEFAULT_PERMISSIONS).encode(&jwt_key.get_encoding_key())?)
+    .finish()
+    .delete(&url)
+    .send();
+
+  ClientRequestError::handle_empty(purge_request)
+    .await
+    .map_err(ServiceError::PurgeElectionError)?;
+  log::debug!("Success! Election secrets were purged from every collector");
+
+  Ok(())
+}
@@ -1,13 +1,13 @@
 use actix_web::{web, HttpResponse};
 use diesel::prelude::*;
 use serde::{Deserialize, Serialize};
-use validator::Validate;
+use validator::{Validate, ValidationError};
 
-use super::helpers::validate_candidates;
+use super::helpers::{default_selections, validate_candidates, validate_ranked_candidates, validate_selections};
 use crate::auth::{ClientToken, JWTSecret};
 use crate::db::DbConnection;
 use crate::errors::ServiceError;
-use crate::models::{Candidate, Election, Question};
+use crate::models::{BallotEncoding, Candidate, CommitmentGroup, Election, Question, QuestionType, VerificationMode};
 use crate::notifications::notify_election_created;
 use crate::views::election::NewElectionResult;
 
@@ -18,12 +18,25 @@ pub struct CreateElectionData {
   pub name: String,
   pub is_public: bool,
 
+  /// Counter encoding shrinks the election prime, but only applies to choice questions
+  #[serde(default)]
+  pub ballot_encoding: BallotEncoding,
+
+  /// Prime-order subgroup hides the quadratic residuosity of the commitment exponents
+  #[serde(default)]
+  pub commitment_group: CommitmentGroup,
+
+  /// Non-interactive proofs let the server verify ballots without contacting the collectors
+  #[serde(default)]
+  pub verification_mode: VerificationMode,
+
   #[validate(length(min = 1))]
   #[validate]
   pub questions: Vec<ElectionQuestion>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Validate)]
+#[validate(schema(function = "validate_question", skip_on_field_errors = false))]
 #[serde(rename_all = "camelCase")]
 pub struct ElectionQuestion {
   #[validate(length(min = 1, max = 255))]
@@ -31,6 +44,23 @@ pub struct ElectionQuestion {
 
   #[validate(length(min = 2), custom = "validate_candidates")]
   pub candidates: Vec<String>,
+
+  #[serde(default)]
+  pub question_type: QuestionType,
+
+  /// Approval and "choose up to k" questions allow selecting more than one candidate
+  #[serde(default = "default_selections")]
+  pub min_selections: i64,
+  #[serde(default = "default_selections")]
+  pub max_selections: i64,
+}
+
+/// Make sure the question type is valid for the number of candidates
+fn validate_question(input: &ElectionQuestion) -> Result<(), ValidationError> {
+  match input.question_type {
+    QuestionType::Choice => validate_selections(input.min_selections, input.max_selections, input.candidates.len()),
+    QuestionType::Ranked => validate_ranked_candidates(input.candidates.len()),
+  }
 }
 
 pub async fn create_election(
@@ -46,15 +76,42 @@ pub async fn create_election(
   let CreateElectionData {
     name,
     is_public,
+    ballot_encoding,
+    commitment_group,
+    verification_mode,
     questions,
   } = data.into_inner();
 
   // Create the election, questions, and candidates
   let new_election = conn.get().transaction::<_, ServiceError, _>(|| {
-    let election = Election::new(name, token.get_user_id(), is_public).insert(&conn)?;
+    let election = Election::new(
+      name,
+      token.get_user_id(),
+      is_public,
+      ballot_encoding,
+      commitment_group,
+      verification_mode,
+    )
+    .insert(&conn)?;
+
+    for (question_number, question) in questions.into_iter().enumerate() {
+      let ElectionQuestion {
+        name,
+        candidates,
+        question_type,
+        min_selections,
+        max_selections,
+      } = question;
 
-    for (question_number, ElectionQuestion { name, candidates }) in questions.into_iter().enumerate() {
-      let question = Question::new(election.id, name, question_number as i64).insert(&conn)?;
+      let question = Question::new(
+        election.id,
+        name,
+        question_number as i64,
+        question_type,
+        min_selections,
+        max_selections,
+      )
+      .insert(&conn)?;
 
       for (candidate_number, candidate) in candidates.into_iter().enumerate() {
         Candidate::new(question.id, candidate, candidate_number as i64).insert(&conn)?;
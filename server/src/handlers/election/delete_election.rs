@@ -4,7 +4,7 @@ use uuid_b64::UuidB64 as Uuid;
 use crate::auth::{ClientToken, JWTSecret};
 use crate::db::DbConnection;
 use crate::errors::{ResourceAction, ServiceError};
-use crate::models::{Election, ElectionStatus};
+use crate::models::Election;
 use crate::notifications::notify_election_deleted;
 
 pub async fn delete_election(
@@ -30,7 +30,7 @@ pub async fn delete_election(
   }
 
   // Make sure the election is still a draft
-  if election.status != ElectionStatus::Draft {
+  if !election.status.is_editable() {
     return Err(ServiceError::ElectionNotDraft {
       election_id: election.id,
       action: ResourceAction::Delete,
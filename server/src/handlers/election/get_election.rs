@@ -53,7 +53,12 @@ pub async fn get_election(
     .into_iter()
     .map(|user| {
       let has_voted_status = election.has_user_voted_status(&user.id, &conn)?;
-      Ok(RegisteredUserDetails::new(user, has_voted_status))
+      let weight = election
+        .get_user_registration(&user.id, &conn)?
+        .map(|r| r.weight)
+        .unwrap_or(1);
+
+      Ok(RegisteredUserDetails::new(user, has_voted_status, weight))
     })
     .collect::<Result<_, ServiceError>>()?;
 
@@ -68,9 +73,13 @@ pub async fn get_election(
       has_voted,
       num_votes_received,
       candidates,
+      election.created_by == current_user_id,
     ));
   }
 
+  // Every status change, so users can see when and why the election moved on
+  let status_history = election.get_status_history_ordered(&conn)?;
+
   // Build the final result
   let result = PublicElectionDetails::new(
     election,
@@ -79,6 +88,7 @@ pub async fn get_election(
     has_voted,
     registrations,
     questions,
+    status_history,
   );
   Ok(HttpResponse::Ok().json(result))
 }
@@ -1,10 +1,12 @@
 use actix_web::{web, HttpResponse};
+use curv_kzen::BigInt;
 use uuid_b64::UuidB64 as Uuid;
 
 use crate::auth::ClientToken;
 use crate::db::DbConnection;
 use crate::errors::{NamedResourceType, ServiceError};
 use crate::models::Election;
+use crate::protocol::Group;
 use crate::utils::ConvertBigInt;
 use crate::views::election::{ElectionParameters, QuestionParameters};
 
@@ -40,9 +42,30 @@ pub async fn get_election_parameters(
   let questions = election
     .get_questions_candidates_ordered(&conn)?
     .into_iter()
-    .map(|(_question, candidates)| {
+    .map(|(question, candidates)| {
+      let generator = question.generator.to_bigint();
+      let prime = question.prime.to_bigint();
+
+      // Voters need h to build the ballot proofs
+      let second_generator = if election.requires_ballot_proof(&question) {
+        Some(
+          Group::new(election.commitment_group, generator.clone(), prime.clone())
+            .pow_second_generator(&BigInt::from(1)),
+        )
+      } else {
+        None
+      };
+
       Ok(QuestionParameters {
         num_candidates: candidates.len() as i64,
+        question_type: question.question_type,
+        ballot_encoding: election.ballot_encoding.for_question(question.question_type),
+        min_selections: question.min_selections,
+        max_selections: question.max_selections,
+        generator,
+        group_order: election.commitment_group.order(&prime),
+        prime,
+        second_generator,
       })
     })
     .collect::<Result<Vec<_>, ServiceError>>()?;
@@ -50,9 +73,10 @@ pub async fn get_election_parameters(
   let result = ElectionParameters {
     num_registered: election.count_registrations(&conn)?,
     questions,
+    counter_radix: election.get_counter_radix(&conn)?,
+    commitment_group: election.commitment_group,
+    verification_mode: election.verification_mode,
 
-    generator: election.generator.to_bigint(),
-    prime: election.prime.to_bigint(),
     location_modulus: election.location_modulus.to_bigint(),
   };
 
@@ -5,9 +5,10 @@ use uuid_b64::UuidB64 as Uuid;
 use crate::auth::ClientToken;
 use crate::db::DbConnection;
 use crate::errors::{NamedResourceType, ServiceError};
-use crate::models::{Election, ElectionStatus};
-use crate::protocol::count_ballot_votes;
-use crate::utils::ConvertBigInt;
+use crate::models::{BallotEncoding, Election, ElectionStatus, QuestionType};
+use crate::protocol::{
+  borda_count, count_ballot_votes, count_counter_ballot_votes, count_ranked_ballot_votes, instant_runoff,
+};
 use crate::views::election::{ElectionResult, QuestionResult, UserBallotResult, UserDetails};
 
 pub async fn get_election_results(
@@ -41,8 +42,8 @@ pub async fn get_election_results(
   // ===============================================
   //  Gather all details and build the final result
   // ===============================================
-  let modulo = election.prime.to_bigint() - 1;
   let num_voters = election.count_registrations(&conn)?;
+  let counter_radix = election.get_counter_radix(&conn)?;
 
   let mut question_results: HashMap<Uuid, QuestionResult> = HashMap::new();
   for question in election.get_questions_ordered(&conn)? {
@@ -65,27 +66,71 @@ pub async fn get_election_results(
     // If the election is finished, then we can safely sum all the ballots to get the final result
     let question_result = if election.status == ElectionStatus::Finished {
       // Compute the sum of all ballots and the cancelation shares
-      let (forward_ballots, reverse_ballots) = question.get_ballots_sum(&modulo, &conn)?;
+      let (forward_ballots, reverse_ballots) = question.get_ballots_sum(election.commitment_group, &conn)?;
 
       // Parse the ballots to count the number of votes for each candidates
       //   (This process tests to make sure the voting vector is valid)
-      let candidate_votes = count_ballot_votes(
-        &forward_ballots,
-        &reverse_ballots,
-        question.count_candidates(&conn)?,
-        num_voters,
-        no_votes.len(),
-      );
-
-      // Return the full results for the question
-      QuestionResult::new(
-        question,
-        forward_ballots,
-        reverse_ballots,
-        candidate_votes,
-        user_ballots,
-        no_votes,
-      )
+      let num_candidates = question.count_candidates(&conn)?;
+      let voted_weights = question.get_voted_weights(&conn)?;
+      match question.question_type {
+        QuestionType::Choice => {
+          let selections = question.min_selections..=question.max_selections;
+          let candidate_votes = match election.ballot_encoding {
+            BallotEncoding::OneHot => count_ballot_votes(
+              &forward_ballots,
+              &reverse_ballots,
+              num_candidates,
+              selections,
+              num_voters,
+              voted_weights.len(),
+            ),
+            BallotEncoding::Counter => count_counter_ballot_votes(
+              &forward_ballots,
+              &reverse_ballots,
+              num_candidates,
+              selections,
+              counter_radix,
+              &voted_weights,
+            ),
+          };
+
+          // Return the full results for the question
+          QuestionResult::new(
+            question,
+            forward_ballots,
+            reverse_ballots,
+            candidate_votes,
+            user_ballots,
+            no_votes,
+          )
+        }
+
+        QuestionType::Ranked => {
+          // Decode the rankings, then run both tally strategies
+          let tally = count_ranked_ballot_votes(
+            &forward_ballots,
+            &reverse_ballots,
+            num_candidates,
+            num_voters,
+            voted_weights.len(),
+          )
+          .map(|rankings| {
+            (
+              instant_runoff(&rankings, num_candidates as usize),
+              borda_count(&rankings, num_candidates as usize),
+            )
+          });
+
+          QuestionResult::new_ranked(
+            question,
+            forward_ballots,
+            reverse_ballots,
+            tally,
+            user_ballots,
+            no_votes,
+          )
+        }
+      }
     } else {
       // Otherwise, we don't have all information yet, so return a partial result
       QuestionResult::new_partial(question, user_ballots, no_votes)
@@ -1,5 +1,7 @@
 use validator::ValidationError;
 
+use crate::models::{count_choice_selection_sets, MAX_CHOICE_SELECTION_SETS, MAX_RANKED_CANDIDATES};
+
 ///
 /// Custom validator function for the individual candidates
 ///   Checks to make sure the string has between 1 and 255 characters
@@ -16,3 +18,57 @@ pub fn validate_candidates(candidates: &Vec<String>) -> Result<(), ValidationErr
 
   Ok(())
 }
+
+/// Default value for min_selections and max_selections (single-choice question)
+pub fn default_selections() -> i64 {
+  1
+}
+
+///
+/// Custom validator function for the number of selections on a question
+///   Checks that 1 <= min_selections <= max_selections <= num_candidates
+///   Collectors test every set of selections, so there can be at most MAX_CHOICE_SELECTION_SETS sets
+///
+pub fn validate_selections(
+  min_selections: i64,
+  max_selections: i64,
+  num_candidates: usize,
+) -> Result<(), ValidationError> {
+  if min_selections < 1 {
+    return Err(ValidationError::new("min_selections must be at least 1"));
+  }
+
+  if max_selections < min_selections {
+    return Err(ValidationError::new(
+      "max_selections must be greater than or equal to min_selections",
+    ));
+  }
+
+  if max_selections > num_candidates as i64 {
+    return Err(ValidationError::new(
+      "max_selections cannot be greater than the number of candidates",
+    ));
+  }
+
+  if count_choice_selection_sets(num_candidates as i64, min_selections, max_selections) > MAX_CHOICE_SELECTION_SETS {
+    return Err(ValidationError::new(
+      "choice questions cannot have more than 5040 sets of selections",
+    ));
+  }
+
+  Ok(())
+}
+
+///
+/// Custom validator function for ranked questions
+///   Checks that the number of candidates is not too large to verify
+///
+pub fn validate_ranked_candidates(num_candidates: usize) -> Result<(), ValidationError> {
+  if num_candidates as i64 > MAX_RANKED_CANDIDATES {
+    return Err(ValidationError::new(
+      "ranked questions cannot have more than 7 candidates",
+    ));
+  }
+
+  Ok(())
+}
@@ -2,6 +2,7 @@
 // All API handlers for managing elections
 //
 mod all_elections;
+mod cancel_election;
 mod create_election;
 mod delete_election;
 mod get_election;
@@ -10,8 +11,10 @@ mod get_election_parameters;
 mod get_election_results;
 pub(self) mod helpers;
 mod update_election;
+mod update_schedule;
 
 pub use all_elections::all_elections;
+pub use cancel_election::{cancel_election, purge_election_secrets};
 pub use create_election::create_election;
 pub use delete_election::delete_election;
 pub use get_election::get_election;
@@ -19,3 +22,4 @@ pub use get_election_by_access_code::get_election_by_access_code;
 pub use get_election_parameters::get_election_parameters;
 pub use get_election_results::get_election_results;
 pub use update_election::update_election;
+pub use update_schedule::update_schedule;
@@ -2,13 +2,13 @@ use actix_web::{web, HttpResponse};
 use diesel::prelude::*;
 use serde::{Deserialize, Serialize};
 use uuid_b64::UuidB64 as Uuid;
-use validator::Validate;
+use validator::{Validate, ValidationError};
 
-use super::helpers::validate_candidates;
+use super::helpers::{default_selections, validate_candidates, validate_ranked_candidates, validate_selections};
 use crate::auth::{ClientToken, JWTSecret};
 use crate::db::DbConnection;
 use crate::errors::{ResourceAction, ServiceError};
-use crate::models::{Candidate, Election, ElectionStatus, Question};
+use crate::models::{BallotEncoding, Candidate, CommitmentGroup, Election, Question, QuestionType, VerificationMode};
 use crate::notifications::notify_election_updated;
 
 #[derive(Debug, Serialize, Deserialize, Validate)]
@@ -17,6 +17,9 @@ pub struct UpdateElectionData {
   #[validate(length(min = 1, max = 255))]
   pub name: Option<String>,
   pub is_public: Option<bool>,
+  pub ballot_encoding: Option<BallotEncoding>,
+  pub commitment_group: Option<CommitmentGroup>,
+  pub verification_mode: Option<VerificationMode>,
 
   #[validate(length(min = 1))]
   #[validate]
@@ -24,6 +27,7 @@ pub struct UpdateElectionData {
 }
 
 #[derive(Debug, Serialize, Deserialize, Validate)]
+#[validate(schema(function = "validate_question", skip_on_field_errors = false))]
 #[serde(rename_all = "camelCase")]
 pub struct ElectionQuestion {
   #[validate(length(min = 1, max = 255))]
@@ -31,6 +35,23 @@ pub struct ElectionQuestion {
 
   #[validate(length(min = 2), custom = "validate_candidates")]
   pub candidates: Vec<String>,
+
+  #[serde(default)]
+  pub question_type: QuestionType,
+
+  /// Approval and "choose up to k" questions allow selecting more than one candidate
+  #[serde(default = "default_selections")]
+  pub min_selections: i64,
+  #[serde(default = "default_selections")]
+  pub max_selections: i64,
+}
+
+/// Make sure the question type is valid for the number of candidates
+fn validate_question(input: &ElectionQuestion) -> Result<(), ValidationError> {
+  match input.question_type {
+    QuestionType::Choice => validate_selections(input.min_selections, input.max_selections, input.candidates.len()),
+    QuestionType::Ranked => validate_ranked_candidates(input.candidates.len()),
+  }
 }
 
 pub async fn update_election(
@@ -47,6 +68,9 @@ pub async fn update_election(
   let UpdateElectionData {
     name,
     is_public,
+    ballot_encoding,
+    commitment_group,
+    verification_mode,
     questions,
   } = data.into_inner();
 
@@ -64,7 +88,7 @@ pub async fn update_election(
   }
 
   // Make sure the election is still a draft
-  if election.status != ElectionStatus::Draft {
+  if !election.status.is_editable() {
     return Err(ServiceError::ElectionNotDraft {
       election_id: election.id,
       action: ResourceAction::Update,
@@ -80,6 +104,15 @@ pub async fn update_election(
     if let Some(is_public) = is_public {
       election.is_public = is_public;
     }
+    if let Some(ballot_encoding) = ballot_encoding {
+      election.ballot_encoding = ballot_encoding;
+    }
+    if let Some(commitment_group) = commitment_group {
+      election.commitment_group = commitment_group;
+    }
+    if let Some(verification_mode) = verification_mode {
+      election.verification_mode = verification_mode;
+    }
 
     election = election.update(&conn)?;
 
@@ -87,8 +120,24 @@ pub async fn update_election(
     if let Some(questions) = questions {
       // Delete and re-create the questions
       election.delete_all_questions(&conn)?;
-      for (question_number, ElectionQuestion { name, candidates }) in questions.into_iter().enumerate() {
-        let question = Question::new(election.id, name, question_number as i64).insert(&conn)?;
+      for (question_number, question) in questions.into_iter().enumerate() {
+        let ElectionQuestion {
+          name,
+          candidates,
+          question_type,
+          min_selections,
+          max_selections,
+        } = question;
+
+        let question = Question::new(
+          election.id,
+          name,
+          question_number as i64,
+          question_type,
+          min_selections,
+          max_selections,
+        )
+        .insert(&conn)?;
 
         for (candidate_number, candidate) in candidates.into_iter().enumerate() {
           Candidate::new(question.id, candidate, candidate_number as i64).insert(&conn)?;
@@ -0,0 +1,119 @@
+use actix_web::{web, HttpResponse};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid_b64::UuidB64 as Uuid;
+use validator::{Validate, ValidationError};
+
+use crate::auth::{ClientToken, JWTSecret};
+use crate::db::DbConnection;
+use crate::errors::{ResourceAction, ServiceError};
+use crate::handlers::voting::InitializeVotingData;
+use crate::models::Election;
+use crate::notifications::notify_election_updated;
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+#[validate(schema(function = "validate_schedule", skip_on_field_errors = false))]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateScheduleData {
+  #[serde(default)]
+  pub registration_opens_at: Option<DateTime<Utc>>,
+  #[serde(default)]
+  pub registration_closes_at: Option<DateTime<Utc>>,
+  #[serde(default)]
+  pub voting_opens_at: Option<DateTime<Utc>>,
+  #[serde(default)]
+  pub voting_closes_at: Option<DateTime<Utc>>,
+
+  /// Parameters used to initialize voting at voting_opens_at
+  #[serde(default)]
+  #[validate]
+  pub voting: Option<InitializeVotingData>,
+}
+
+///
+/// Make sure every scheduled time comes after the previous one,
+///   and that voting can actually be initialized when it is scheduled
+///
+fn validate_schedule(input: &UpdateScheduleData) -> Result<(), ValidationError> {
+  let times: Vec<DateTime<Utc>> = [
+    input.registration_opens_at,
+    input.registration_closes_at,
+    input.voting_opens_at,
+    input.voting_closes_at,
+  ]
+  .iter()
+  .filter_map(|time| *time)
+  .collect();
+
+  if times.windows(2).any(|pair| pair[0] >= pair[1]) {
+    return Err(ValidationError::new("scheduled times are not in order"));
+  }
+
+  if input.voting_opens_at.is_some() && input.voting.is_none() {
+    return Err(ValidationError::new("voting_opens_at requires voting parameters"));
+  }
+
+  Ok(())
+}
+
+pub async fn update_schedule(
+  token: ClientToken,
+  path: web::Path<Uuid>,
+  data: web::Json<UpdateScheduleData>,
+  conn: DbConnection,
+  jwt_key: web::Data<JWTSecret>,
+) -> Result<HttpResponse, ServiceError> {
+  token.test_can_create_election()?;
+  token.validate_user_id(&conn)?;
+  data.validate()?;
+
+  // Make sure the election exists
+  let mut election = Election::find_resource(&path, &conn)?;
+
+  // Only the election creator can schedule the election
+  let current_user_id = token.get_user_id();
+  if election.created_by != current_user_id {
+    return Err(ServiceError::ElectionNotOwnedByUser {
+      current_user_id,
+      owner_id: election.created_by,
+      action: ResourceAction::Update,
+    });
+  }
+
+  // Nothing left to schedule once the results are published or the election is canceled
+  if election.status.is_final() {
+    return Err(ServiceError::WrongStatusFor {
+      election_id: election.id,
+      action: ResourceAction::Update,
+      status: election.status,
+    });
+  }
+
+  // Replace the whole schedule, which also restarts any retries
+  let UpdateScheduleData {
+    registration_opens_at,
+    registration_closes_at,
+    voting_opens_at,
+    voting_closes_at,
+    voting,
+  } = data.into_inner();
+
+  election.registration_opens_at = registration_opens_at;
+  election.registration_closes_at = registration_closes_at;
+  election.voting_opens_at = voting_opens_at;
+  election.voting_closes_at = voting_closes_at;
+
+  let voting = voting.unwrap_or_default();
+  election.scheduled_collectors = voting.collectors;
+  election.scheduled_threshold = voting.threshold.map(|threshold| threshold as i64);
+  election.scheduled_distributed_locations = voting.distributed_locations;
+
+  // Only save the schedule, so a status change by the scheduler or a cancel in the meantime is never reverted
+  election.clear_retries();
+  let election = election.save_schedule(&conn)?;
+
+  notify_election_updated(&election, &jwt_key).await;
+  log::info!("Updated schedule for election \"{}\" <{}>", election.name, election.id);
+
+  Ok(HttpResponse::Ok().finish())
+}
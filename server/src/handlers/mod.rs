@@ -2,6 +2,7 @@
 // All API handlers for the server
 //
 pub mod account;
+pub mod admin;
 pub mod auth;
 pub mod election;
 pub mod registration;
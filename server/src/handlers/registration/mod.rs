@@ -4,7 +4,9 @@
 mod open_registration;
 mod register_for_election;
 mod unregister_from_election;
+mod update_registration;
 
-pub use open_registration::open_registration;
+pub use open_registration::{open_election_registration, open_registration};
 pub use register_for_election::register_for_election;
 pub use unregister_from_election::unregister_from_election;
+pub use update_registration::update_registration;
@@ -4,7 +4,7 @@ use uuid_b64::UuidB64 as Uuid;
 use crate::auth::{ClientToken, JWTSecret};
 use crate::db::DbConnection;
 use crate::errors::{ResourceAction, ServiceError};
-use crate::models::{Election, ElectionStatus};
+use crate::models::{Election, ElectionTransition};
 use crate::notifications::{notify_election_published, notify_registration_opened};
 use crate::views::election::PublishElectionResult;
 
@@ -18,7 +18,7 @@ pub async fn open_registration(
   token.validate_user_id(&conn)?;
 
   // Find election to make sure it exists in the database
-  let mut election = Election::find_resource(&*path, &conn)?;
+  let election = Election::find_resource(&path, &conn)?;
 
   // Only the election creator can update the election
   let current_user_id = token.get_user_id();
@@ -30,26 +30,45 @@ pub async fn open_registration(
     });
   }
 
+  let election = open_election_registration(election, Some(current_user_id), &conn, &jwt_key).await?;
+
+  Ok(HttpResponse::Ok().json(PublishElectionResult {
+    access_code: election.access_code,
+  }))
+}
+
+///
+/// Move a draft election to the registration status
+///
+/// Also called by the scheduler at registration_opens_at, so the owner is not checked here
+///   (The actor is None for the scheduler)
+///
+pub async fn open_election_registration(
+  mut election: Election,
+  actor_id: Option<Uuid>,
+  conn: &DbConnection,
+  jwt_key: &JWTSecret,
+) -> Result<Election, ServiceError> {
   // Make sure the election is still a draft
-  if election.status != ElectionStatus::Draft {
-    return Err(ServiceError::ElectionNotDraft {
-      election_id: election.id,
-      action: ResourceAction::Update,
-    });
-  }
+  election.test_transition(ElectionTransition::OpenRegistration)?;
 
   // Update the status and generate the access code (If applicable)
-  election.status = ElectionStatus::Registration;
   if !election.is_public {
-    election.generate_unique_access_code(&conn)?;
+    election.generate_unique_access_code(conn)?;
   }
-  election.update(&conn)?;
+  election.clear_retries();
+  election = election.transition(
+    ElectionTransition::OpenRegistration,
+    actor_id,
+    "Registration opened",
+    conn,
+  )?;
 
   // Send notifications
   if election.is_public {
-    notify_election_published(&election, &jwt_key).await;
+    notify_election_published(&election, jwt_key).await;
   }
-  notify_registration_opened(&election, &jwt_key).await;
+  notify_registration_opened(&election, jwt_key).await;
 
   log::info!(
     "Opened registration for election \"{}\" <{}>",
@@ -57,7 +76,5 @@ pub async fn open_registration(
     election.id
   );
 
-  Ok(HttpResponse::Ok().json(PublishElectionResult {
-    access_code: election.access_code,
-  }))
+  Ok(election)
 }
@@ -28,8 +28,8 @@ pub async fn register_for_election(
     });
   }
 
-  // Make sure the election is actually open for registration
-  if election.status != ElectionStatus::Registration {
+  // Make sure the election is actually open for registration (And the scheduled deadline has not passed)
+  if election.status != ElectionStatus::Registration || election.is_registration_deadline_passed() {
     return Err(ServiceError::RegistrationClosed {
       election_id: election.id,
     });
@@ -19,8 +19,8 @@ pub async fn unregister_from_election(
   // Find election to make sure it exists in the database
   let election = Election::find_resource(&*path, &conn)?;
 
-  // Make sure the election is actually open for registration
-  if election.status != ElectionStatus::Registration {
+  // Make sure the election is actually open for registration (And the scheduled deadline has not passed)
+  if election.status != ElectionStatus::Registration || election.is_registration_deadline_passed() {
     return Err(ServiceError::RegistrationClosed {
       election_id: election.id,
     });
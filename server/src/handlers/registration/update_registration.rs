@@ -0,0 +1,89 @@
+use actix_web::{web, HttpResponse};
+use serde::{Deserialize, Serialize};
+use uuid_b64::UuidB64 as Uuid;
+use validator::Validate;
+
+use crate::auth::{ClientToken, JWTSecret};
+use crate::db::DbConnection;
+use crate::errors::{ResourceAction, ServiceError};
+use crate::models::{Election, ElectionStatus};
+use crate::notifications::notify_election_updated;
+
+#[derive(Debug, Serialize, Deserialize, Validate)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateRegistrationData {
+  /// Number of votes the user's ballot counts for
+  #[validate(range(min = 1, max = 1000000000))]
+  pub weight: i64,
+}
+
+pub async fn update_registration(
+  token: ClientToken,
+  path: web::Path<(Uuid, Uuid)>,
+  data: web::Json<UpdateRegistrationData>,
+  conn: DbConnection,
+  jwt_key: web::Data<JWTSecret>,
+) -> Result<HttpResponse, ServiceError> {
+  token.test_can_create_election()?;
+  token.validate_user_id(&conn)?;
+  data.validate()?;
+
+  let (election_id, user_id) = path.into_inner();
+
+  // Make sure the election exists
+  let election = Election::find_resource(&election_id, &conn)?;
+
+  // Only the election creator can change the registration weights
+  let current_user_id = token.get_user_id();
+  if election.created_by != current_user_id {
+    return Err(ServiceError::ElectionNotOwnedByUser {
+      current_user_id,
+      owner_id: election.created_by,
+      action: ResourceAction::UpdateRegistration,
+    });
+  }
+
+  // Weights are fixed once registration closes
+  if election.status != ElectionStatus::Registration {
+    return Err(ServiceError::WrongStatusFor {
+      election_id: election.id,
+      action: ResourceAction::UpdateRegistration,
+      status: election.status,
+    });
+  }
+  if election.is_registration_deadline_passed() {
+    return Err(ServiceError::RegistrationClosed {
+      election_id: election.id,
+    });
+  }
+
+  // The summed one-hot ballots show the chunk of every voter, so a weight in the chunk would identify the voter
+  //   Only the counter encoding mixes the weights together, and ranked questions always use one-hot
+  if data.weight != 1 && !election.supports_weights(&conn)? {
+    return Err(ServiceError::WeightsRequireCounterEncoding {
+      election_id: election.id,
+    });
+  }
+
+  // Make sure the user is actually registered
+  let mut registration = election
+    .get_user_registration(&user_id, &conn)?
+    .ok_or_else(|| ServiceError::NotRegistered {
+      user_id,
+      election_id: election.id,
+    })?;
+
+  registration.weight = data.weight;
+  registration.update(&conn)?;
+
+  notify_election_updated(&election, &jwt_key).await;
+  log::info!(
+    "Set weight {} for user {} in election \"{}\" <{}>",
+    registration.weight,
+    user_id,
+    election.name,
+    election.id
+  );
+
+  Ok(HttpResponse::Ok().finish())
+}
@@ -1,18 +1,22 @@
 use actix_web::client::Client;
 use actix_web::{web, HttpResponse};
-use curv_kzen::BigInt;
-use diesel::prelude::*;
-use futures::future::try_join_all;
+use curv_kzen::{arithmetic::Modulo, BigInt};
+use futures::future::join_all;
 use jsonwebtoken::EncodingKey;
 use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use uuid_b64::UuidB64 as Uuid;
 
 use crate::auth::{ClientToken, JWTSecret, ServerToken, DEFAULT_PERMISSIONS};
 use crate::config;
 use crate::db::DbConnection;
 use crate::errors::{ClientRequestError, ResourceAction, ServiceError};
-use crate::models::{Election, ElectionStatus, Question};
-use crate::notifications::{notify_results_published, notify_voting_closed};
+use crate::handlers::voting::fail_in_progress;
+use crate::models::{
+  CancelationCommitment, CollectionStatus, CommitmentGroup, Election, ElectionStatus, ElectionTransition, Question,
+};
+use crate::notifications::{notify_collection_progress, notify_results_published, notify_voting_closed};
+use crate::protocol::Group;
 use crate::utils::ConvertBigInt;
 
 pub async fn close_voting(
@@ -25,7 +29,7 @@ pub async fn close_voting(
   token.validate_user_id(&conn)?;
 
   // Make sure the election exists
-  let mut election = Election::find_resource(&*path, &conn)?;
+  let election = Election::find_resource(&path, &conn)?;
 
   // Only the election creator can close voting
   let current_user_id = token.get_user_id();
@@ -37,19 +41,30 @@ pub async fn close_voting(
     });
   }
 
+  close_election_voting(election, Some(current_user_id), &conn, &jwt_key).await?;
+
+  Ok(HttpResponse::Ok().finish())
+}
+
+///
+/// Collect the cancelation shares from the collectors and publish the results
+///
+/// Also called by the scheduler at voting_closes_at, so the owner is not checked here
+///   (The actor is None for the scheduler)
+///
+pub async fn close_election_voting(
+  mut election: Election,
+  actor_id: Option<Uuid>,
+  conn: &DbConnection,
+  jwt_key: &JWTSecret,
+) -> Result<(), ServiceError> {
   // Make sure the election is in the correct status
-  if !(election.status == ElectionStatus::Voting || election.status == ElectionStatus::CollectionFailed) {
-    return Err(ServiceError::WrongStatusFor {
-      election_id: election.id,
-      action: ResourceAction::CloseVoting,
-      status: election.status,
-    });
-  }
+  election.test_transition(ElectionTransition::StartCollection)?;
 
   // Each question in the election MUST have at least 3 votes
-  let questions: Vec<Question> = election.get_questions(&conn)?;
+  let questions: Vec<Question> = election.get_questions(conn)?;
   for question in questions.iter() {
-    if question.count_commitments(&conn)? < 3 {
+    if question.count_commitments(conn)? < 3 {
       return Err(ServiceError::NotEnoughVotes {
         election_id: election.id,
         question_id: question.id,
@@ -57,47 +72,141 @@ pub async fn close_voting(
     }
   }
 
-  // Mark the election as being closed
-  election.status = ElectionStatus::CollectionFailed;
-  election = election.update(&conn)?;
-  notify_voting_closed(&election, &jwt_key).await;
-
   // Data needed for the requests
   let mediator_url = config::get_mediator_url().ok_or_else(|| ServiceError::MediatorURLNotSet)?;
   let jwt_encoding_key = jwt_key.get_encoding_key();
+  let commitment_group = election.commitment_group;
+
+  // Mark the election as being closed
+  //   Only notify the first time, since retries start from CollectionFailed
+  let was_voting = election.status == ElectionStatus::Voting;
+  let reason = if was_voting {
+    "Voting closed"
+  } else {
+    "Retrying cancelation share collection"
+  };
+  election = election.transition(ElectionTransition::StartCollection, actor_id, reason, conn)?;
+  if was_voting {
+    notify_voting_closed(&election, jwt_key).await;
+  }
 
-  // Run all requests in parallel and cache all of the updates
-  let questions = try_join_all(questions.into_iter().map(|mut question| {
-    async {
-      // Get cancelation shares for users who didn't vote
-      let no_vote = question.get_user_ids_without_vote(&conn)?;
-      let (forward_cancelation_shares, reverse_cancelation_shares) =
-        get_cancelation_shares(&question, no_vote, &mediator_url, &jwt_encoding_key).await?;
+  // Collecting is always left again, so a failed collection can be retried from CollectionFailed
+  let election_id = election.id;
+  let collected = async {
+    // A collector with a broken shares table would skew the tally, so check the collectors before collecting
+    //   A failed audit moves the election to CollectionFailed with the reason, and is run again on every retry
+    audit_shares(&election, &mediator_url, &jwt_encoding_key).await?;
 
-      // Update the values in the database model
-      //  Don't save yet, we will perform a massive transaction at the end
-      question.forward_cancelation_shares = forward_cancelation_shares.to_bigdecimal();
-      question.reverse_cancelation_shares = reverse_cancelation_shares.to_bigdecimal();
+    // Run all requests in parallel, but save every question as soon as it finishes
+    //   A failed question does not throw away the shares that were already collected
+    let results = join_all(
+      questions
+        .into_iter()
+        .filter(|question| question.collection_status != CollectionStatus::Collected)
+        .map(|question| collect_question(question, commitment_group, &mediator_url, &jwt_encoding_key, conn)),
+    )
+    .await;
 
-      Result::<_, ServiceError>::Ok(question)
+    notify_collection_progress(&election, &election.get_questions_ordered(conn)?, jwt_key).await;
+
+    // Move back to CollectionFailed, so closing voting again only requests the missing questions
+    if let Some(error) = results.into_iter().find_map(Result::err) {
+      return Err(error);
     }
-  }))
-  .await?;
 
-  // Perform a massive database transaction to update all questions at once
-  election = conn.get().transaction::<_, ServiceError, _>(|| {
-    for question in questions {
-      question.update(&conn)?;
+    election.clear_retries();
+    let election = election.transition(
+      ElectionTransition::PublishResults,
+      actor_id,
+      "Cancelation shares collected for every question",
+      conn,
+    )?;
+
+    notify_results_published(&election, jwt_key).await;
+    log::info!("Results published for election \"{}\" <{}>", election.name, election.id);
+
+    Ok::<(), ServiceError>(())
+  }
+  .await;
+
+  if let Err(e) = &collected {
+    fail_in_progress(&election_id, &e.to_string(), actor_id, conn, jwt_key).await;
+  }
+
+  collected
+}
+
+///
+/// Get the verified cancelation shares for every user who did not vote on the question,
+///   then save the shares or the error with the question
+///
+async fn collect_question(
+  mut question: Question,
+  commitment_group: CommitmentGroup,
+  mediator_url: &str,
+  jwt_encoding_key: &EncodingKey,
+  conn: &DbConnection,
+) -> Result<(), ServiceError> {
+  let no_vote = question.get_user_ids_without_vote(conn)?;
+  let result = get_cancelation_shares(
+    &question,
+    commitment_group,
+    &no_vote,
+    mediator_url,
+    jwt_encoding_key,
+    conn,
+  )
+  .await;
+
+  match &result {
+    Ok((forward_cancelation_shares, reverse_cancelation_shares)) => {
+      question.forward_cancelation_shares = forward_cancelation_shares.to_bigdecimal();
+      question.reverse_cancelation_shares = reverse_cancelation_shares.to_bigdecimal();
+      question.collection_status = CollectionStatus::Collected;
+      question.collection_error = None;
     }
+    Err(e) => {
+      log::warn!(
+        "Failed to collect cancelation shares for question {}: {}",
+        question.question_number + 1,
+        e
+      );
+      question.collection_status = CollectionStatus::Failed;
+      question.collection_error = Some(e.to_string());
+    }
+  }
 
-    election.status = ElectionStatus::Finished;
-    Ok(election.update(&conn)?)
-  })?;
+  question.update(conn)?;
+  result.map(|_| ())
+}
 
-  notify_results_published(&election, &jwt_key).await;
-  log::info!("Results published for election \"{}\" <{}>", election.name, election.id);
+///
+/// Have the mediator audit the shares matrices of every collector
+///
+async fn audit_shares(
+  election: &Election,
+  mediator_url: &str,
+  jwt_encoding_key: &EncodingKey,
+) -> Result<(), ServiceError> {
+  let url = format!(
+    "{}/api/v1/mediator/elections/{}/shares/audit",
+    mediator_url, election.id
+  );
 
-  Ok(HttpResponse::Ok().finish())
+  log::debug!("Request shares audit from collector mediator...");
+  let audit_request = Client::builder()
+    .disable_timeout()
+    .bearer_auth(ServerToken::new(DEFAULT_PERMISSIONS).encode(jwt_encoding_key)?)
+    .finish()
+    .post(&url)
+    .send();
+
+  ClientRequestError::handle_empty(audit_request)
+    .await
+    .map_err(ServiceError::SharesAuditError)?;
+  log::debug!("Success! Shares of every collector are consistent");
+
+  Ok(())
 }
 
 ///
@@ -105,9 +214,11 @@ pub async fn close_voting(
 ///
 async fn get_cancelation_shares(
   question: &Question,
-  user_ids: Vec<Uuid>,
+  commitment_group: CommitmentGroup,
+  user_ids: &[Uuid],
   mediator_url: &str,
   jwt_encoding_key: &EncodingKey,
+  conn: &DbConnection,
 ) -> Result<(BigInt, BigInt), ServiceError> {
   // Make sure we actually need to get cancelation shares
   log::debug!("Get cancelation shares for question {}", question.question_number + 1);
@@ -136,6 +247,9 @@ async fn get_cancelation_shares(
     .map_err(|e| ServiceError::CancelationSharesError(e))?;
   log::debug!("Success! Got cancelation shares from collector mediator");
 
+  // Check the shares against the collector commitments before they are used in the tally
+  verify_cancelation_shares(question, commitment_group, user_ids, &shares_result, conn)?;
+
   // Return the final result
   Ok((
     shares_result.forward_cancelation_shares,
@@ -143,13 +257,123 @@ async fn get_cancelation_shares(
   ))
 }
 
+///
+/// Check the cancelation shares against the commitments from every collector:
+///   g^(Σ S~c,i - S_c,i) = Π X_c,i for every user who did not vote
+///
+/// With (n,n)-secret sharing, the mediator returns the shares from each collector, so a faulty collector is named.
+///   Threshold shares are not committed individually, so only the combined shares are checked.
+///
+fn verify_cancelation_shares(
+  question: &Question,
+  commitment_group: CommitmentGroup,
+  user_ids: &[Uuid],
+  shares: &CancelationShares,
+  conn: &DbConnection,
+) -> Result<(), ServiceError> {
+  let invalid_error = |collector_id| ServiceError::CancelationSharesInvalid {
+    election_id: question.election_id,
+    question_id: question.id,
+    collector_id,
+  };
+
+  let prime = question.prime.to_bigint();
+  let modulus = commitment_group.order(&prime);
+  let group = Group::new(commitment_group, question.generator.to_bigint(), prime);
+
+  // Group the commitments by collector
+  let mut collector_commitments: HashMap<Uuid, Vec<CancelationCommitment>> = HashMap::new();
+  for commitment in CancelationCommitment::get_for_users(question, user_ids, conn)? {
+    collector_commitments
+      .entry(commitment.collector_id)
+      .or_default()
+      .push(commitment);
+  }
+
+  // Π X_c,i and Π X_c,i' for every collector, which must have a commitment for every user
+  let products = collector_commitments
+    .into_iter()
+    .map(|(collector_id, commitments)| {
+      let forward: Vec<BigInt> = commitments.iter().map(|c| c.forward_commitment.to_bigint()).collect();
+      let reverse: Vec<BigInt> = commitments.iter().map(|c| c.reverse_commitment.to_bigint()).collect();
+
+      match (group.product(forward.iter()), group.product(reverse.iter())) {
+        (Some(forward), Some(reverse)) if commitments.len() == user_ids.len() => Ok((collector_id, (forward, reverse))),
+        _ => Err(invalid_error(Some(collector_id))),
+      }
+    })
+    .collect::<Result<HashMap<_, _>, ServiceError>>()?;
+
+  if products.is_empty() {
+    return Err(invalid_error(None));
+  }
+
+  let matches = |forward: &BigInt, reverse: &BigInt, commitments: &(BigInt, BigInt)| {
+    group.pow_generator(forward) == commitments.0 && group.pow_generator(reverse) == commitments.1
+  };
+
+  if shares.collector_shares.is_empty() {
+    // Threshold secret sharing: Check the combined shares against the commitments from every collector
+    let combined = group
+      .product(products.values().map(|(forward, _)| forward))
+      .zip(group.product(products.values().map(|(_, reverse)| reverse)))
+      .ok_or_else(|| invalid_error(None))?;
+
+    if !matches(
+      &shares.forward_cancelation_shares,
+      &shares.reverse_cancelation_shares,
+      &combined,
+    ) {
+      return Err(invalid_error(None));
+    }
+  } else {
+    // Every collector must be included exactly once
+    let collector_ids: HashSet<Uuid> = shares.collector_shares.iter().map(|c| c.collector_id).collect();
+    if collector_ids.len() != shares.collector_shares.len() || collector_ids.len() != products.len() {
+      return Err(invalid_error(None));
+    }
+
+    for collector_shares in shares.collector_shares.iter() {
+      let commitments = products
+        .get(&collector_shares.collector_id)
+        .ok_or_else(|| invalid_error(Some(collector_shares.collector_id)))?;
+
+      if !matches(
+        &collector_shares.forward_cancelation_shares,
+        &collector_shares.reverse_cancelation_shares,
+        commitments,
+      ) {
+        return Err(invalid_error(Some(collector_shares.collector_id)));
+      }
+    }
+
+    // The mediator must have added the shares from every collector together
+    let (forward, reverse) =
+      shares
+        .collector_shares
+        .iter()
+        .fold((BigInt::from(0), BigInt::from(0)), |(forward, reverse), c| {
+          (
+            BigInt::mod_add(&forward, &c.forward_cancelation_shares, &modulus),
+            BigInt::mod_add(&reverse, &c.reverse_cancelation_shares, &modulus),
+          )
+        });
+
+    if forward != shares.forward_cancelation_shares || reverse != shares.reverse_cancelation_shares {
+      return Err(invalid_error(None));
+    }
+  }
+
+  Ok(())
+}
+
 ///
 /// JSON structure to send to the collectors to get cancelation shares
 ///
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
-struct CancelationSharesData {
-  user_ids: Vec<Uuid>,
+struct CancelationSharesData<'a> {
+  user_ids: &'a [Uuid],
 }
 
 #[derive(Deserialize)]
@@ -160,4 +384,20 @@ struct CancelationShares {
 
   #[serde(with = "kzen_paillier::serialize::bigint")]
   reverse_cancelation_shares: BigInt,
+
+  /// Shares from every collector (Not provided with threshold secret sharing)
+  #[serde(default)]
+  collector_shares: Vec<CollectorCancelationShares>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CollectorCancelationShares {
+  collector_id: Uuid,
+
+  #[serde(with = "kzen_paillier::serialize::bigint")]
+  forward_cancelation_shares: BigInt,
+
+  #[serde(with = "kzen_paillier::serialize::bigint")]
+  reverse_cancelation_shares: BigInt,
 }
@@ -0,0 +1,48 @@
+use uuid_b64::UuidB64 as Uuid;
+
+use crate::auth::JWTSecret;
+use crate::db::DbConnection;
+use crate::handlers::election::purge_election_secrets;
+use crate::models::{Election, ElectionStatus, ElectionTransition};
+use crate::notifications::notify_election_updated;
+
+///
+/// Move the election out of Initializing or Collecting after the run stopped, so it can be retried from the failed status
+///
+/// The election may have been canceled while the collectors were still working on it,
+///   so whatever they saved after the cancel purge is purged again.
+///
+/// Errors are only logged, since the caller already has the error that stopped the run
+///
+pub async fn fail_in_progress(
+  election_id: &Uuid,
+  reason: &str,
+  actor_id: Option<Uuid>,
+  conn: &DbConnection,
+  jwt_key: &JWTSecret,
+) {
+  let election = match Election::find(election_id, conn) {
+    Ok(election) => election,
+    Err(e) => {
+      log::error!("Failed to load election <{}> after a failed run: {}", election_id, e);
+      return;
+    }
+  };
+
+  if election.status == ElectionStatus::Canceled {
+    if let Err(e) = purge_election_secrets(election_id, jwt_key).await {
+      log::error!("Failed to purge canceled election <{}>: {}", election_id, e);
+    }
+    return;
+  }
+
+  let transition = match ElectionTransition::fail_from(election.status) {
+    Some(transition) => transition,
+    None => return,
+  };
+
+  match election.transition(transition, actor_id, reason, conn) {
+    Ok(election) => notify_election_updated(&election, jwt_key).await,
+    Err(e) => log::error!("Failed to move election <{}> to the failed status: {}", election_id, e),
+  }
+}
@@ -1,26 +1,57 @@
 use actix_web::client::Client;
 use actix_web::{web, HttpResponse};
 use curv_kzen::BigInt;
+use diesel::prelude::*;
 use num::Zero;
 use serde::{Deserialize, Serialize};
 use std::cmp::max;
 use uuid_b64::UuidB64 as Uuid;
-use validator::Validate;
+use validator::{Validate, ValidationError};
 
-use crate::auth::{ClientToken, JWTSecret, ServerToken, DEFAULT_PERMISSIONS};
+use crate::auth::{ClientToken, JWTSecret, ManifestKey, ServerToken, DEFAULT_PERMISSIONS};
 use crate::config;
 use crate::db::DbConnection;
 use crate::errors::{ClientRequestError, ResourceAction, ServiceError};
-use crate::models::{Election, ElectionStatus, Registration};
+use crate::handlers::voting::fail_in_progress;
+use crate::models::{
+  BallotEncoding, CancelationCommitment, CommitmentGroup, Election, ElectionStatus, ElectionTransition, PooledPrime,
+  Question, QuestionType, Registration, ShareCommitment, VerificationMode,
+};
 use crate::notifications::{notify_registration_closed, notify_voting_opened};
-use crate::protocol::generator_prime_pair;
+use crate::protocol::{
+  generator_prime_pair, prime_order_generator, ElectionManifest, Group, ManifestQuestion, SignedManifest,
+  MAX_BALLOT_PROOF_BRANCHES,
+};
 use crate::utils::ConvertBigInt;
 
-#[derive(Debug, Serialize, Deserialize, Validate)]
+#[derive(Debug, Default, Serialize, Deserialize, Validate)]
+#[validate(schema(function = "validate_voting_data", skip_on_field_errors = false))]
 #[serde(rename_all = "camelCase")]
 pub struct InitializeVotingData {
   #[validate(length(min = 2))]
-  collectors: Vec<Uuid>,
+  pub collectors: Vec<Uuid>,
+
+  /// Any t of the n collectors can compute the cancelation shares (Every collector is needed if not set)
+  #[serde(default)]
+  #[validate(range(min = 2))]
+  pub threshold: Option<usize>,
+
+  /// Collectors shuffle the location shares between themselves, so the mediator never holds a location key
+  #[serde(default)]
+  pub distributed_locations: bool,
+}
+
+///
+/// Make sure the threshold is not larger than the number of collectors
+///
+fn validate_voting_data(input: &InitializeVotingData) -> Result<(), ValidationError> {
+  if let Some(threshold) = input.threshold {
+    if threshold > input.collectors.len() {
+      return Err(ValidationError::new("threshold is greater than length(collectors)"));
+    }
+  }
+
+  Ok(())
 }
 
 pub async fn initialize_voting(
@@ -29,13 +60,14 @@ pub async fn initialize_voting(
   data: web::Json<InitializeVotingData>,
   conn: DbConnection,
   jwt_key: web::Data<JWTSecret>,
+  manifest_key: web::Data<ManifestKey>,
 ) -> Result<HttpResponse, ServiceError> {
   token.test_can_create_election()?;
   token.validate_user_id(&conn)?;
   data.validate()?;
 
   // Make sure the election exists
-  let mut election = Election::find_resource(&*path, &conn)?;
+  let election = Election::find_resource(&path, &conn)?;
 
   // Only the election creator can open the election for voting
   let current_user_id = token.get_user_id();
@@ -47,17 +79,38 @@ pub async fn initialize_voting(
     });
   }
 
+  initialize_election_voting(
+    election,
+    data.into_inner(),
+    Some(current_user_id),
+    &conn,
+    &jwt_key,
+    &manifest_key,
+  )
+  .await?;
+
+  Ok(HttpResponse::Ok().finish())
+}
+
+///
+/// Close registration, send the election parameters to the collectors, and open voting
+///
+/// Also called by the scheduler at voting_opens_at with the parameters saved in the schedule,
+///   so the owner is not checked here (The actor is None for the scheduler)
+///
+pub async fn initialize_election_voting(
+  mut election: Election,
+  data: InitializeVotingData,
+  actor_id: Option<Uuid>,
+  conn: &DbConnection,
+  jwt_key: &JWTSecret,
+  manifest_key: &ManifestKey,
+) -> Result<(), ServiceError> {
   // Make sure the election is in the correct status
-  if !(election.status == ElectionStatus::Registration || election.status == ElectionStatus::InitFailed) {
-    return Err(ServiceError::WrongStatusFor {
-      election_id: election.id,
-      action: ResourceAction::InitVoting,
-      status: election.status,
-    });
-  }
+  election.test_transition(ElectionTransition::StartInitialization)?;
 
   // Election MUST have at least 2*(num collectors) users registered
-  let registrations: Vec<Registration> = election.get_registrations(&conn)?;
+  let registrations: Vec<Registration> = election.get_registrations(conn)?;
   if registrations.len() < 2 * data.collectors.len() {
     return Err(ServiceError::NotEnoughRegistered {
       election_id: election.id,
@@ -66,82 +119,290 @@ pub async fn initialize_voting(
     });
   }
 
+  // Every question gets its own group that can serve the voting vector for that question
+  //   One-hot questions need a chunk for every voter (Ranked questions use more bits per voter than choice questions)
+  //   Counter questions only need a single base-(N+1) digit for every candidate
+  let counter_radix = election.get_counter_radix(conn)?;
+  let digit_bits = 64 - counter_radix.leading_zeros() as usize;
+
+  let questions_candidates = election.get_questions_candidates_ordered(conn)?;
+  let voting_vector_bits: Vec<usize> = questions_candidates
+    .iter()
+    .map(
+      |(question, candidates)| match election.ballot_encoding.for_question(question.question_type) {
+        BallotEncoding::OneHot => {
+          registrations.len() * (question.question_type.chunk_size(candidates.len() as i64) as usize)
+        }
+        BallotEncoding::Counter => candidates.len() * digit_bits,
+      },
+    )
+    .collect();
+
+  // Elliptic curves have a fixed order, which must be large enough to store v * v' for every question
+  if let Some(max_bits) = election.commitment_group.max_product_bits() {
+    for ((question, _), bits) in questions_candidates.iter().zip(voting_vector_bits.iter()) {
+      if 2 * bits + 1 > max_bits {
+        return Err(ServiceError::VotingVectorTooLarge {
+          election_id: election.id,
+          question_id: question.id,
+          num_bits: 2 * bits + 1,
+          max_bits,
+        });
+      }
+    }
+  }
+
+  // Ballot proofs blind the share commitments with h^rho, which is always a quadratic residue mod p
+  //   In the full group Z*p, the commitment g^(S~) * h^(rho) would still leak the parity of the ballot share S~
+  if election.verification_mode == VerificationMode::NonInteractive
+    && election.commitment_group == CommitmentGroup::Full
+  {
+    return Err(ServiceError::VerificationModeNotSupported {
+      election_id: election.id,
+      verification_mode: election.verification_mode,
+      commitment_group: election.commitment_group,
+    });
+  }
+
+  // Counter, multi-select, and ranked ballots always carry a ballot proof, even when the collectors verify the ballots
+  let requires_ballot_proofs = questions_candidates
+    .iter()
+    .any(|(question, _)| election.requires_ballot_proof(question));
+  if requires_ballot_proofs && election.commitment_group == CommitmentGroup::Full {
+    return Err(ServiceError::BallotProofNotSupported {
+      election_id: election.id,
+      commitment_group: election.commitment_group,
+    });
+  }
+
+  // The server checks every branch of a ballot proof, and one-hot questions have a branch
+  //   for every valid selection in every voter's chunk, so the proof grows with the number of voters
+  for (question, candidates) in questions_candidates.iter() {
+    if !election.requires_ballot_proof(question) {
+      continue;
+    }
+
+    let num_chunks = match election.ballot_encoding.for_question(question.question_type) {
+      BallotEncoding::OneHot => registrations.len() as u64,
+      BallotEncoding::Counter => 1,
+    };
+    let num_branches = num_chunks.saturating_mul(question.count_selection_sets(candidates.len() as i64));
+    if num_branches > MAX_BALLOT_PROOF_BRANCHES {
+      return Err(ServiceError::BallotProofTooLarge {
+        election_id: election.id,
+        question_id: question.id,
+        num_branches,
+        max_branches: MAX_BALLOT_PROOF_BRANCHES,
+      });
+    }
+  }
+
+  // Threshold secret sharing recovers the cancelation shares with Lagrange interpolation,
+  //   which needs a prime group order to invert the differences between the collector indexes
+  if data.threshold.is_some() && election.commitment_group == CommitmentGroup::Full {
+    return Err(ServiceError::ThresholdSharingNotSupported {
+      election_id: election.id,
+      commitment_group: election.commitment_group,
+    });
+  }
+
   // Mark the election as being initialized
   //   Clear the access code, since it is no longer needed after registration closes
-  election.status = ElectionStatus::InitFailed;
+  let reason = match election.status {
+    ElectionStatus::Registration => "Registration closed",
+    _ => "Retrying initialization",
+  };
   election.access_code = None;
-  election = election.update(&conn)?;
-  notify_registration_closed(&election, &jwt_key).await;
+  election = election.transition(ElectionTransition::StartInitialization, actor_id, reason, conn)?;
+  notify_registration_closed(&election, jwt_key).await;
 
-  // We use a single prime that can serve the largest voting vector
-  let questions_candidates = election.get_questions_candidates_ordered(&conn)?;
-  let max_num_candidates = questions_candidates.iter().map(|(_, c)| c.len()).max().unwrap_or(2);
-  let voting_vector_max_bits = registrations.len() * max_num_candidates;
+  // Initializing is always left again, so a failed initialization can be retried from InitFailed
+  let election_id = election.id;
+  let initialized = async {
+    let mut questions = Vec::new();
+    let mut num_candidates = Vec::new();
+    for ((mut question, candidates), voting_vector_bits) in questions_candidates.into_iter().zip(voting_vector_bits) {
+      // Since we may call this method multiple times, only generate if we haven't done so before
+      //   Elliptic curves use the base point of the curve, so they don't need a prime
+      if !election.commitment_group.is_elliptic_curve() && (question.generator.is_zero() || question.prime.is_zero()) {
+        // The prime-order subgroup only has (p-1)/2 elements, so it needs one extra bit
+        let num_bits = match election.commitment_group {
+          CommitmentGroup::PrimeOrder => max(2 * voting_vector_bits + 2, 256),
+          _ => max(2 * voting_vector_bits + 1, 256),
+        };
 
-  // Since we may call this method multiple times, only generate if we haven't done so before
-  if election.generator.is_zero() || election.prime.is_zero() {
-    let num_bits = max(2 * voting_vector_max_bits + 1, 256);
-    log::debug!("Generating prime with {} bits", num_bits);
+        // Take the smallest adequate prime from the pool, or generate a new one if the pool is empty
+        let (generator, prime) = match PooledPrime::take_smallest(num_bits as i64, conn)? {
+          Some(pooled_prime) => {
+            log::debug!(
+              "Using {}-bit prime from the pool for question {}",
+              pooled_prime.num_bits,
+              question.question_number + 1
+            );
+            (pooled_prime.generator.to_bigint(), pooled_prime.prime.to_bigint())
+          }
+          None => {
+            log::debug!(
+              "Prime pool is empty, generating prime with {} bits for question {}",
+              num_bits,
+              question.question_number + 1
+            );
+            generator_prime_pair(num_bits)
+          }
+        };
 
-    let (generator, prime) = generator_prime_pair(num_bits);
-    election.generator = generator.to_bigdecimal();
-    election.prime = prime.to_bigdecimal();
-    election = election.update(&conn)?;
+        // Both the pool and the generator return a primitive root of Z*p
+        let generator = match election.commitment_group {
+          CommitmentGroup::PrimeOrder => prime_order_generator(&generator, &prime),
+          _ => generator,
+        };
 
-    log::debug!("Picked g = {} and p = {}", generator, prime);
-  }
+        question.generator = generator.to_bigdecimal();
+        question.prime = prime.to_bigdecimal();
+        question = question.update(conn)?;
 
-  // Build data needed to register the election with the mediator
-  let jwt_encoding_key = jwt_key.get_encoding_key();
-  let create_elections_data = CreateElectionData {
-    id: election.id,
-    is_public: election.is_public,
-    creator_id: election.created_by,
-    generator: election.generator.to_bigint(),
-    prime: election.prime.to_bigint(),
-    questions: questions_candidates
-      .iter()
-      .map(|(question, candidates)| CreateElectionQuestion {
-        id: question.id,
-        num_candidates: candidates.len() as i64,
-      })
-      .collect(),
-    registered_users: registrations.iter().map(|r| r.user_id).collect(),
-    collectors: data.into_inner().collectors,
-  };
+        log::debug!("Picked g = {} and p = {}", generator, prime);
+      }
 
-  // Build the URL to the mediator API
-  let mediator_url = config::get_mediator_url().ok_or_else(|| ServiceError::MediatorURLNotSet)?;
-  let url = format!("{}/api/v1/mediator/elections", mediator_url);
-
-  // Register the election with the collector mediator
-  log::debug!("Send election parameters to collector mediator");
-  let mediator_request = Client::builder()
-    .disable_timeout()
-    .bearer_auth(ServerToken::new(DEFAULT_PERMISSIONS).encode(&jwt_encoding_key)?)
-    .finish()
-    .post(&url)
-    .send_json(&create_elections_data);
-
-  let result: InitializeElectionResult = ClientRequestError::handle(mediator_request)
-    .await
-    .map_err(|e| ServiceError::RegisterElectionError(e))?;
-
-  // Election is now FULLY INITIALIZED!!!
-  log::debug!("Got success response from mediator");
-  log::debug!("Marking election as fully initialized...");
-  election.location_modulus = result.n.to_bigdecimal();
-  election.status = ElectionStatus::Voting;
-  election.update(&conn)?;
-
-  notify_voting_opened(&election, create_elections_data.collectors, &jwt_key).await;
-  log::info!(
-    "Voting initialized for election \"{}\" <{}>",
-    election.name,
-    election.id
-  );
+      questions.push(question);
+      num_candidates.push(candidates.len() as i64);
+    }
 
-  Ok(HttpResponse::Ok().finish())
+    // Sign every parameter the collectors depend on, so the mediator cannot change them
+    let manifest = ElectionManifest {
+      election_id: election.id,
+      collectors: data.collectors.clone(),
+      registered_users: registrations.iter().map(|r| r.user_id).collect(),
+      registered_weights: registrations.iter().map(|r| r.weight).collect(),
+      ballot_encoding: election.ballot_encoding,
+      counter_radix,
+      commitment_group: election.commitment_group,
+      verification_mode: election.verification_mode,
+      threshold: data.threshold,
+      distributed_locations: data.distributed_locations,
+      questions: questions
+        .iter()
+        .zip(num_candidates)
+        .map(|(question, num_candidates)| ManifestQuestion {
+          id: question.id,
+          num_candidates,
+          question_type: question.question_type,
+          min_selections: question.min_selections,
+          max_selections: question.max_selections,
+          generator: question.generator.to_bigint(),
+          prime: question.prime.to_bigint(),
+        })
+        .collect(),
+    };
+
+    // Build data needed to register the election with the mediator
+    let jwt_encoding_key = jwt_key.get_encoding_key();
+    let create_elections_data = CreateElectionData {
+      id: election.id,
+      is_public: election.is_public,
+      creator_id: election.created_by,
+      questions: manifest
+        .questions
+        .iter()
+        .map(|question| CreateElectionQuestion {
+          id: question.id,
+          num_candidates: question.num_candidates,
+          question_type: question.question_type,
+          min_selections: question.min_selections,
+          max_selections: question.max_selections,
+          generator: question.generator.clone(),
+          prime: question.prime.clone(),
+        })
+        .collect(),
+      registered_users: manifest.registered_users.clone(),
+      registered_weights: manifest.registered_weights.clone(),
+      ballot_encoding: election.ballot_encoding,
+      counter_radix,
+      commitment_group: election.commitment_group,
+      verification_mode: election.verification_mode,
+      threshold: data.threshold,
+      distributed_locations: data.distributed_locations,
+      collectors: data.collectors,
+      manifest: manifest_key.sign_manifest(manifest),
+    };
+
+    // Build the URL to the mediator API
+    let mediator_url = config::get_mediator_url().ok_or_else(|| ServiceError::MediatorURLNotSet)?;
+    let url = format!("{}/api/v1/mediator/elections", mediator_url);
+
+    // Register the election with the collector mediator
+    log::debug!("Send election parameters to collector mediator");
+    let mediator_request = Client::builder()
+      .disable_timeout()
+      .bearer_auth(ServerToken::new(DEFAULT_PERMISSIONS).encode(&jwt_encoding_key)?)
+      .finish()
+      .post(&url)
+      .send_json(&create_elections_data);
+
+    let result: InitializeElectionResult = ClientRequestError::handle(mediator_request)
+      .await
+      .map_err(ServiceError::RegisterElectionError)?;
+
+    log::debug!("Got success response from mediator");
+
+    // Ballot proofs are checked against the share commitments of every collector
+    if requires_ballot_proofs {
+      log::debug!(
+        "Combining share commitments from {} collectors",
+        result.share_commitments.len()
+      );
+      store_share_commitments(
+        &election,
+        &questions,
+        &registrations,
+        create_elections_data.collectors.len(),
+        &result.share_commitments,
+        conn,
+      )?;
+    }
+
+    // Cancelation shares are always checked against the commitments from every collector
+    log::debug!(
+      "Storing cancelation commitments from {} collectors",
+      result.cancelation_commitments.len()
+    );
+    store_cancelation_commitments(
+      &election,
+      &questions,
+      &registrations,
+      &create_elections_data.collectors,
+      &result.cancelation_commitments,
+      conn,
+    )?;
+
+    // Election is now FULLY INITIALIZED!!!
+    //   The transition fails if the election was canceled since it was loaded
+    log::debug!("Marking election as fully initialized...");
+    election.location_modulus = result.n.to_bigdecimal();
+    election.clear_retries();
+    let election = election.transition(
+      ElectionTransition::OpenVoting,
+      actor_id,
+      "Every collector initialized the election",
+      conn,
+    )?;
+
+    notify_voting_opened(&election, &questions, create_elections_data.collectors, jwt_key).await;
+    log::info!(
+      "Voting initialized for election \"{}\" <{}>",
+      election.name,
+      election.id
+    );
+
+    Ok::<(), ServiceError>(())
+  }
+  .await;
+
+  if let Err(e) = &initialized {
+    fail_in_progress(&election_id, &e.to_string(), actor_id, conn, jwt_key).await;
+  }
+
+  initialized
 }
 
 ///
@@ -153,16 +414,19 @@ struct CreateElectionData {
   id: Uuid,
   is_public: bool,
   creator_id: Uuid,
-
-  #[serde(with = "kzen_paillier::serialize::bigint")]
-  generator: BigInt,
-
-  #[serde(with = "kzen_paillier::serialize::bigint")]
-  prime: BigInt,
-
   questions: Vec<CreateElectionQuestion>,
   registered_users: Vec<Uuid>,
+  registered_weights: Vec<i64>,
+  ballot_encoding: BallotEncoding,
+  counter_radix: i64,
+  commitment_group: CommitmentGroup,
+  verification_mode: VerificationMode,
+  threshold: Option<usize>,
+  distributed_locations: bool,
   collectors: Vec<Uuid>,
+
+  /// Signed copy of the parameters, which the mediator forwards to every collector
+  manifest: SignedManifest,
 }
 
 #[derive(Debug, Serialize)]
@@ -170,6 +434,15 @@ struct CreateElectionData {
 struct CreateElectionQuestion {
   id: Uuid,
   num_candidates: i64,
+  question_type: QuestionType,
+  min_selections: i64,
+  max_selections: i64,
+
+  #[serde(with = "kzen_paillier::serialize::bigint")]
+  generator: BigInt,
+
+  #[serde(with = "kzen_paillier::serialize::bigint")]
+  prime: BigInt,
 }
 
 #[derive(Debug, Deserialize)]
@@ -177,4 +450,161 @@ struct CreateElectionQuestion {
 struct InitializeElectionResult {
   #[serde(with = "kzen_paillier::serialize::bigint")]
   n: BigInt,
+
+  /// Share commitments from every collector (Empty lists for interactive verification)
+  #[serde(default)]
+  share_commitments: Vec<Vec<QuestionShareCommitments>>,
+
+  /// Cancelation commitments from every collector, in the same order as the collectors
+  #[serde(default)]
+  cancelation_commitments: Vec<Vec<QuestionCancelationCommitments>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct QuestionShareCommitments {
+  question_id: Uuid,
+
+  #[serde(with = "kzen_paillier::serialize::vecbigint")]
+  forward_commitments: Vec<BigInt>,
+
+  #[serde(with = "kzen_paillier::serialize::vecbigint")]
+  reverse_commitments: Vec<BigInt>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct QuestionCancelationCommitments {
+  question_id: Uuid,
+
+  #[serde(with = "kzen_paillier::serialize::vecbigint")]
+  forward_commitments: Vec<BigInt>,
+
+  #[serde(with = "kzen_paillier::serialize::vecbigint")]
+  reverse_commitments: Vec<BigInt>,
+}
+
+///
+/// Multiply the share commitments from every collector for every registered user:
+///   D_i = D_1,i * ... * D_n,i and D_i' = D_1,i' * ... * D_n,i'
+///
+/// Every collector must return a commitment for every user on every question
+///
+fn store_share_commitments(
+  election: &Election,
+  questions: &[Question],
+  registrations: &[Registration],
+  num_collectors: usize,
+  share_commitments: &[Vec<QuestionShareCommitments>],
+  conn: &DbConnection,
+) -> Result<(), ServiceError> {
+  for question in questions {
+    if !election.requires_ballot_proof(question) {
+      continue;
+    }
+
+    let invalid_error = || ServiceError::ShareCommitmentsInvalid {
+      election_id: election.id,
+      question_id: question.id,
+    };
+
+    let collector_commitments: Vec<&QuestionShareCommitments> = share_commitments
+      .iter()
+      .filter_map(|commitments| commitments.iter().find(|c| c.question_id == question.id))
+      .collect();
+
+    if collector_commitments.len() != num_collectors
+      || collector_commitments.iter().any(|c| {
+        c.forward_commitments.len() != registrations.len() || c.reverse_commitments.len() != registrations.len()
+      })
+    {
+      return Err(invalid_error());
+    }
+
+    let group = Group::new(
+      election.commitment_group,
+      question.generator.to_bigint(),
+      question.prime.to_bigint(),
+    );
+
+    for (index, registration) in registrations.iter().enumerate() {
+      let forward_commitment = group
+        .product(collector_commitments.iter().map(|c| &c.forward_commitments[index]))
+        .ok_or_else(invalid_error)?;
+      let reverse_commitment = group
+        .product(collector_commitments.iter().map(|c| &c.reverse_commitments[index]))
+        .ok_or_else(invalid_error)?;
+
+      // Initialization might be retried, so replace any commitments from a previous attempt
+      ShareCommitment::new(
+        registration.user_id,
+        election.id,
+        question.id,
+        &forward_commitment,
+        &reverse_commitment,
+      )
+      .insert_or_update(conn)?;
+    }
+  }
+
+  Ok(())
+}
+
+///
+/// Store the cancelation commitments X_c,i and X_c,i' from every collector for every registered user
+///
+/// These are kept per collector, so a collector that returns the wrong cancelation shares can be named
+///
+fn store_cancelation_commitments(
+  election: &Election,
+  questions: &[Question],
+  registrations: &[Registration],
+  collectors: &[Uuid],
+  cancelation_commitments: &[Vec<QuestionCancelationCommitments>],
+  conn: &DbConnection,
+) -> Result<(), ServiceError> {
+  conn.get().transaction::<_, ServiceError, _>(|| {
+    // Initialization might be retried with a different list of collectors
+    CancelationCommitment::delete_all_from_election(&election.id, conn)?;
+
+    for question in questions {
+      let invalid_error = || ServiceError::ShareCommitmentsInvalid {
+        election_id: election.id,
+        question_id: question.id,
+      };
+
+      if cancelation_commitments.len() != collectors.len() {
+        return Err(invalid_error());
+      }
+
+      for (collector_id, commitments) in collectors.iter().zip(cancelation_commitments.iter()) {
+        let commitments = commitments
+          .iter()
+          .find(|c| c.question_id == question.id)
+          .filter(|c| {
+            c.forward_commitments.len() == registrations.len() && c.reverse_commitments.len() == registrations.len()
+          })
+          .ok_or_else(invalid_error)?;
+
+        for (registration, (forward, reverse)) in registrations.iter().zip(
+          commitments
+            .forward_commitments
+            .iter()
+            .zip(commitments.reverse_commitments.iter()),
+        ) {
+          CancelationCommitment::new(
+            registration.user_id,
+            election.id,
+            question.id,
+            *collector_id,
+            forward,
+            reverse,
+          )
+          .insert(conn)?;
+        }
+      }
+    }
+
+    Ok(())
+  })
 }
@@ -2,9 +2,11 @@
 // API handlers for initializing the voting and casting a ballot
 //
 mod close_voting;
+mod fail_in_progress;
 mod initialize_voting;
 mod vote;
 
-pub use close_voting::close_voting;
-pub use initialize_voting::initialize_voting;
+pub use close_voting::{close_election_voting, close_voting};
+pub use fail_in_progress::fail_in_progress;
+pub use initialize_voting::{initialize_election_voting, initialize_voting, InitializeVotingData};
 pub use vote::vote;
@@ -1,5 +1,7 @@
 use actix_web::client::Client;
+use actix_web::error::BlockingError;
 use actix_web::{web, HttpResponse};
+use bigdecimal::BigDecimal;
 use curv_kzen::BigInt;
 use serde::{Deserialize, Serialize};
 use uuid_b64::UuidB64 as Uuid;
@@ -9,9 +11,14 @@ use crate::auth::{ClientToken, JWTSecret, ServerToken, DEFAULT_PERMISSIONS};
 use crate::config;
 use crate::db::DbConnection;
 use crate::errors::{ClientRequestError, ServiceError};
-use crate::models::{Commitment, Election, ElectionStatus};
+use crate::models::{
+  BallotEncoding, Commitment, Election, ElectionStatus, Question, QuestionType, Registration, ShareCommitment,
+  VerificationMode,
+};
 use crate::notifications::notify_vote_received;
+use crate::protocol::{selections, BallotProof, BallotStatement, Group};
 use crate::utils::ConvertBigInt;
+use crate::views::verification::VerificationFailure;
 
 #[derive(Debug, Deserialize, Validate)]
 #[serde(rename_all = "camelCase")]
@@ -29,6 +36,20 @@ pub struct VotingData {
   pub g_s_prime: BigInt, // g^(s_i')
   #[serde(with = "kzen_paillier::serialize::bigint")]
   pub g_s_s_prime: BigInt, // g^(s_i * s_i')
+
+  // Only needed for questions that require a ballot proof (See Election::requires_ballot_proof)
+  pub proof: Option<BallotProofData>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BallotProofData {
+  #[serde(with = "kzen_paillier::serialize::vecbigint")]
+  pub challenges: Vec<BigInt>, // c_k
+  #[serde(with = "kzen_paillier::serialize::vecbigint")]
+  pub forward_responses: Vec<BigInt>, // z_k
+  #[serde(with = "kzen_paillier::serialize::vecbigint")]
+  pub reverse_responses: Vec<BigInt>, // z_k'
 }
 
 pub async fn vote(
@@ -56,12 +77,12 @@ pub async fn vote(
 
   // Make sure user is registered for the election
   let user_id = token.get_user_id();
-  if election.get_user_registration(&user_id, &conn)?.is_none() {
-    return Err(ServiceError::NotRegistered {
+  let registration = election
+    .get_user_registration(&user_id, &conn)?
+    .ok_or_else(|| ServiceError::NotRegistered {
       user_id,
       election_id: election.id,
-    });
-  };
+    })?;
 
   // Make sure the user has not already voted
   if let Some(_) = question.find_commitment_optional(&user_id, &conn)? {
@@ -72,12 +93,78 @@ pub async fn vote(
     });
   }
 
-  // =======================================================
-  // Verify the vote with the collectors using the mediator
-  //
-  // The mediator simplifies communication with any number
-  // of collectors in the system
-  // =======================================================
+  // Ballot proofs are verified locally, which binds the forward and reverse vectors to a single valid vote
+  let (share_commitment, proof) = if election.requires_ballot_proof(&question) {
+    let proof = data.proof.as_ref().ok_or_else(|| ServiceError::BallotProofMissing {
+      election_id,
+      question_id,
+    })?;
+
+    let share_commitment = verify_ballot_proof(&election, &question, &registration, &data, proof, &conn).await?;
+    (Some(share_commitment), Some(proof))
+  } else {
+    (None, None)
+  };
+
+  // Interactive elections still run both sub-protocols with the collectors
+  if election.verification_mode == VerificationMode::Interactive {
+    verify_with_collectors(user_id, election_id, question_id, &data, &jwt_key).await?;
+  }
+
+  // ================================================
+  // Load the data into the database
+  // ================================================
+  let commitment = Commitment {
+    user_id,
+    election_id,
+    question_id,
+
+    forward_ballot: data.forward_ballot.to_bigdecimal(),
+    reverse_ballot: data.reverse_ballot.to_bigdecimal(),
+
+    g_s: data.g_s.to_bigdecimal(),
+    g_s_prime: data.g_s_prime.to_bigdecimal(),
+    g_s_s_prime: data.g_s_s_prime.to_bigdecimal(),
+
+    // Both sub-protocols were valid, or the ballot proof covers both of them
+    //   (Ballots with a required proof in interactive elections have both)
+    single_vote_verified: true,
+    published_ballots_verified: true,
+
+    forward_share_commitment: share_commitment.as_ref().map(|c| c.forward_commitment.clone()),
+    reverse_share_commitment: share_commitment.as_ref().map(|c| c.reverse_commitment.clone()),
+    proof_challenges: proof.map(|p| to_bigdecimals(&p.challenges)),
+    proof_forward_responses: proof.map(|p| to_bigdecimals(&p.forward_responses)),
+    proof_reverse_responses: proof.map(|p| to_bigdecimals(&p.reverse_responses)),
+  }
+  .insert(&conn)?;
+
+  notify_vote_received(&election, &question, &commitment, &conn, &jwt_key).await;
+  log::info!(
+    "User {} <{}> cast vote for question {} of \"{}\" <{}>",
+    token.get_name(),
+    token.get_email(),
+    question.question_number + 1,
+    election.name,
+    election.id
+  );
+
+  Ok(HttpResponse::Ok().finish())
+}
+
+///
+/// Verify the vote with the collectors using the mediator
+///
+/// The mediator simplifies communication with any number
+/// of collectors in the system
+///
+async fn verify_with_collectors(
+  user_id: Uuid,
+  election_id: Uuid,
+  question_id: Uuid,
+  data: &VotingData,
+  jwt_key: &JWTSecret,
+) -> Result<(), ServiceError> {
   log::debug!("Verifying ballot with the collectors");
 
   let verify_ballot_data = VerifyBallotData {
@@ -108,6 +195,8 @@ pub async fn vote(
   let VerificationResult {
     sub_protocol_1,
     sub_protocol_2,
+    transcript_id,
+    failure,
   } = ClientRequestError::handle(verify_request)
     .await
     .map_err(|e| ServiceError::VerifyVoteError(e))?;
@@ -122,44 +211,110 @@ pub async fn vote(
     if sub_protocol_2 { "valid" } else { "invalid" }
   );
 
-  if !(sub_protocol_1 && sub_protocol_2) {
+  // The failure says if the ballot was invalid or a collector misbehaved
+  if !(sub_protocol_1 && sub_protocol_2) || failure.is_some() {
     return Err(ServiceError::VoteInvalid {
       sub_protocol_1,
       sub_protocol_2,
+      transcript_id,
+      failure,
     });
   }
 
-  // ================================================
-  // Load the data into the database
-  // ================================================
-  let commitment = Commitment {
-    user_id,
-    election_id,
-    question_id,
+  Ok(())
+}
 
-    forward_ballot: data.forward_ballot.to_bigdecimal(),
-    reverse_ballot: data.reverse_ballot.to_bigdecimal(),
+///
+/// Verify the non-interactive ballot proof against every valid voting vector for the voter
+///
+/// Returns the combined share commitments the proof was checked against, so they can be stored with the ballot
+///   A proof can have thousands of branches, so the proof is checked on the blocking thread pool
+///
+async fn verify_ballot_proof(
+  election: &Election,
+  question: &Question,
+  registration: &Registration,
+  data: &VotingData,
+  proof: &BallotProofData,
+  conn: &DbConnection,
+) -> Result<ShareCommitment, ServiceError> {
+  let share_commitment = ShareCommitment::find((&registration.user_id, &election.id, &question.id), conn)?;
 
-    g_s: data.g_s.to_bigdecimal(),
-    g_s_prime: data.g_s_prime.to_bigdecimal(),
-    g_s_s_prime: data.g_s_s_prime.to_bigdecimal(),
+  // One-hot questions give every voter a chunk of single-bit fields,
+  //   while counter questions share a single chunk of base-counter_radix digits between all voters
+  //   The voter location is secret, so the proof has a branch for every chunk
+  let (radix, num_chunks) = match election.ballot_encoding.for_question(question.question_type) {
+    BallotEncoding::OneHot => (BigInt::from(2), election.count_registrations(conn)?),
+    BallotEncoding::Counter => (BigInt::from(election.get_counter_radix(conn)? as u64), 1),
+  };
 
-    single_vote_verified: sub_protocol_1,
-    published_ballots_verified: sub_protocol_2,
-  }
-  .insert(&conn)?;
+  let num_candidates = question.count_candidates(conn)?;
+  let chunk_size = question.question_type.chunk_size(num_candidates);
+  let positions: Vec<Vec<usize>> = match question.question_type {
+    QuestionType::Choice => {
+      selections::choice_positions(num_candidates, question.min_selections, question.max_selections).collect()
+    }
+    QuestionType::Ranked => selections::ranked_positions(num_candidates).collect(),
+  };
 
-  notify_vote_received(&election, &question, &commitment, &conn, &jwt_key).await;
-  log::info!(
-    "User {} <{}> cast vote for question {} of \"{}\" <{}>",
-    token.get_name(),
-    token.get_email(),
-    question.question_number + 1,
-    election.name,
-    election.id
-  );
+  let prime = question.prime.to_bigint();
+  let group = Group::new(election.commitment_group, question.generator.to_bigint(), prime.clone());
+  let group_order = election.commitment_group.order(&prime);
 
-  Ok(HttpResponse::Ok().finish())
+  let election_id = election.id;
+  let question_id = question.id;
+  let user_id = registration.user_id;
+  let weight = registration.weight;
+  let forward_ballot = data.forward_ballot.clone();
+  let reverse_ballot = data.reverse_ballot.clone();
+  let forward_share_commitment = share_commitment.forward_commitment.to_bigint();
+  let reverse_share_commitment = share_commitment.reverse_commitment.to_bigint();
+  let proof = BallotProof {
+    challenges: proof.challenges.clone(),
+    forward_responses: proof.forward_responses.clone(),
+    reverse_responses: proof.reverse_responses.clone(),
+  };
+
+  web::block(move || {
+    let voting_vectors = selections::voting_vectors(&radix, num_chunks, chunk_size, weight, positions.into_iter());
+    let statement = BallotStatement {
+      election_id: &election_id,
+      question_id: &question_id,
+      user_id: &user_id,
+      forward_ballot: &forward_ballot,
+      reverse_ballot: &reverse_ballot,
+      forward_share_commitment: &forward_share_commitment,
+      reverse_share_commitment: &reverse_share_commitment,
+    };
+
+    let proof_valid = proof.verify(&statement, &group, &group_order, &voting_vectors);
+    log::debug!(
+      "Ballot proof with {} branches: {}",
+      voting_vectors.len(),
+      if proof_valid { "valid" } else { "invalid" }
+    );
+
+    // Only the result crosses back from the thread pool, since a ServiceError cannot be sent between threads
+    if proof_valid {
+      Ok(())
+    } else {
+      Err(())
+    }
+  })
+  .await
+  .map_err(|e| match e {
+    BlockingError::Error(()) => ServiceError::BallotProofInvalid {
+      election_id,
+      question_id,
+    },
+    BlockingError::Canceled => ServiceError::BlockingTaskCanceled,
+  })?;
+
+  Ok(share_commitment)
+}
+
+fn to_bigdecimals(values: &[BigInt]) -> Vec<BigDecimal> {
+  values.iter().map(|v| v.to_bigdecimal()).collect()
 }
 
 ///
@@ -190,4 +345,8 @@ struct VerifyBallotData {
 struct VerificationResult {
   sub_protocol_1: bool,
   sub_protocol_2: bool,
+  transcript_id: Uuid,
+
+  #[serde(default)]
+  failure: Option<VerificationFailure>,
 }
@@ -14,6 +14,7 @@ pub mod handlers;
 pub mod models;
 pub mod notifications;
 pub mod protocol;
+pub mod scheduler;
 pub mod schema;
 pub mod utils;
 pub mod views;
@@ -9,6 +9,8 @@ use evoting_server::config;
 use evoting_server::db;
 use evoting_server::errors::ServiceError;
 use evoting_server::handlers;
+use evoting_server::protocol;
+use evoting_server::scheduler;
 
 #[actix_web::main]
 async fn main() -> anyhow::Result<()> {
@@ -24,14 +26,27 @@ async fn main() -> anyhow::Result<()> {
     log::set_max_level(LevelFilter::Info);
   }
 
+  // Collectors must be configured with this public key to accept the election manifests
+  let manifest_key = auth::ManifestKey::load_or_generate(config::get_manifest_key_file())?;
+  log::info!("Election manifest public key: {}", manifest_key.public_key());
+
   // Database connection pool and web server
   let connection_pool = db::establish_new_connection_pool()?;
+  protocol::spawn_prime_pool_worker(connection_pool.clone());
+  scheduler::spawn_election_scheduler(
+    connection_pool.clone(),
+    auth::JWTSecret::new(config::get_jwt_secret()),
+    manifest_key.clone(),
+  );
+
   let mut server = HttpServer::new(move || {
     App::new()
       // Connect to database
       .data(connection_pool.clone())
       // Encryption secret for JSON Web Token
       .data(auth::JWTSecret::new(config::get_jwt_secret()))
+      // Signing key for the election manifests
+      .data(manifest_key.clone())
       // Enable logger
       .wrap(middleware::Logger::default())
       // Configure error handlers
@@ -48,6 +63,7 @@ async fn main() -> anyhow::Result<()> {
               .route("/login", web::post().to(handlers::auth::login))
               .route("/refresh", web::post().to(handlers::auth::refresh)),
           )
+          .service(web::scope("/admin").route("/prime-pool", web::get().to(handlers::admin::get_prime_pool)))
           .service(
             web::scope("/account")
               .route("", web::patch().to(handlers::account::update_account))
@@ -72,11 +88,17 @@ async fn main() -> anyhow::Result<()> {
                     web::get().to(handlers::election::get_election_parameters),
                   )
                   .route("/results", web::get().to(handlers::election::get_election_results))
+                  .route("/schedule", web::put().to(handlers::election::update_schedule))
+                  .route("/cancel", web::post().to(handlers::election::cancel_election))
                   .service(
                     web::scope("/registration")
                       .route("", web::post().to(handlers::registration::register_for_election))
                       .route("", web::delete().to(handlers::registration::unregister_from_election))
-                      .route("", web::put().to(handlers::registration::open_registration)),
+                      .route("", web::put().to(handlers::registration::open_registration))
+                      .route(
+                        "/{user_id}",
+                        web::patch().to(handlers::registration::update_registration),
+                      ),
                   )
                   .service(
                     web::scope("/voting")
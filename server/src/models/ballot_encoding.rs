@@ -0,0 +1,40 @@
+use crate::models::QuestionType;
+
+// Every election uses one of the following encodings for the voting vector:
+//
+//   [OneHot]  - Every voter has their own chunk of the voting vector (Chosen by their location)
+//               The voting vector needs (num_registered * num_candidates) bits
+//               Every chunk is decoded from the published tally, so one-hot voters cannot have a weight
+//
+//   [Counter] - Every candidate is a single base-(N+1) digit shared by all voters,
+//               where N is the total weight of all registered voters
+//               The voting vector only needs (num_candidates * log2(N+1)) bits
+//               Every counter ballot needs a ballot proof, so this only works in prime-order groups
+//
+// Note: Ranked questions always use the one-hot encoding,
+//   since instant-runoff voting needs the individual rankings of every voter
+sql_enum!(
+  #[derive(Default)]
+  pub BallotEncoding {
+    #[default]
+    OneHot = 0,
+    Counter
+  }
+);
+
+impl BallotEncoding {
+  pub fn get_name(&self) -> &'static str {
+    match self {
+      BallotEncoding::OneHot => "One-Hot",
+      BallotEncoding::Counter => "Counter",
+    }
+  }
+
+  /// Encoding actually used for a given question in the election
+  pub fn for_question(&self, question_type: QuestionType) -> Self {
+    match question_type {
+      QuestionType::Choice => *self,
+      QuestionType::Ranked => BallotEncoding::OneHot,
+    }
+  }
+}
@@ -0,0 +1,78 @@
+use bigdecimal::BigDecimal;
+use curv_kzen::BigInt;
+use diesel::prelude::*;
+use serde::Serialize;
+use uuid_b64::UuidB64 as Uuid;
+
+use crate::db::DbConnection;
+use crate::errors::ServiceError;
+use crate::models::{Election, Question, User};
+use crate::schema::cancelation_commitments;
+use crate::utils::ConvertBigInt;
+
+///
+/// Commitments from a single collector to the cancelation shares of a voter:
+///   X_c,i = g^(S~c,i - S_c,i) and X_c,i' = g^(S~c,i' - S_c,i')
+///
+/// Used to check the cancelation shares from every collector when voting is closed
+///
+#[derive(Debug, Clone, Serialize, Queryable, Insertable, Identifiable, AsChangeset, Associations)]
+#[primary_key(user_id, election_id, question_id, collector_id)]
+#[belongs_to(User)]
+#[belongs_to(Question)]
+#[belongs_to(Election)]
+#[serde(rename_all = "camelCase")]
+pub struct CancelationCommitment {
+  pub user_id: Uuid,
+  pub election_id: Uuid,
+  pub question_id: Uuid,
+  pub collector_id: Uuid,
+
+  pub forward_commitment: BigDecimal,
+  pub reverse_commitment: BigDecimal,
+}
+
+impl CancelationCommitment {
+  model_base!();
+
+  belongs_to!(User);
+  belongs_to!(Election);
+  belongs_to!(Question);
+
+  pub fn new(
+    user_id: Uuid,
+    election_id: Uuid,
+    question_id: Uuid,
+    collector_id: Uuid,
+    forward_commitment: &BigInt,
+    reverse_commitment: &BigInt,
+  ) -> Self {
+    Self {
+      user_id,
+      election_id,
+      question_id,
+      collector_id,
+      forward_commitment: forward_commitment.to_bigdecimal(),
+      reverse_commitment: reverse_commitment.to_bigdecimal(),
+    }
+  }
+
+  /// Get the commitments from every collector for a list of users in a question
+  pub fn get_for_users(question: &Question, user_ids: &[Uuid], conn: &DbConnection) -> Result<Vec<Self>, ServiceError> {
+    Ok(
+      cancelation_commitments::table
+        .filter(cancelation_commitments::election_id.eq(&question.election_id))
+        .filter(cancelation_commitments::question_id.eq(&question.id))
+        .filter(cancelation_commitments::user_id.eq_any(user_ids))
+        .get_results::<Self>(conn.get())?,
+    )
+  }
+
+  /// Delete the commitments from a previous attempt at initializing the election
+  pub fn delete_all_from_election(election_id: &Uuid, conn: &DbConnection) -> Result<usize, ServiceError> {
+    Ok(
+      diesel::delete(cancelation_commitments::table.filter(cancelation_commitments::election_id.eq(election_id)))
+        .execute(conn.get())?,
+    )
+  }
+}
@@ -0,0 +1,24 @@
+// Progress of collecting the cancelation shares for a single question:
+//
+//   [Pending]   - Voting is still open, or the shares have not been requested yet
+//   [Collected] - Cancelation shares were verified and saved, so they are never requested again
+//   [Failed]    - The last request failed, and will be retried when closing voting again
+sql_enum!(
+  #[derive(Default)]
+  pub CollectionStatus {
+    #[default]
+    Pending = 0,
+    Collected,
+    Failed
+  }
+);
+
+impl CollectionStatus {
+  pub fn get_name(&self) -> &'static str {
+    match self {
+      CollectionStatus::Pending => "Pending",
+      CollectionStatus::Collected => "Collected",
+      CollectionStatus::Failed => "Failed",
+    }
+  }
+}
@@ -26,6 +26,13 @@ pub struct Commitment {
 
   pub single_vote_verified: bool,
   pub published_ballots_verified: bool,
+
+  // Only provided for questions that require a ballot proof
+  pub forward_share_commitment: Option<BigDecimal>,
+  pub reverse_share_commitment: Option<BigDecimal>,
+  pub proof_challenges: Option<Vec<BigDecimal>>,
+  pub proof_forward_responses: Option<Vec<BigDecimal>>,
+  pub proof_reverse_responses: Option<Vec<BigDecimal>>,
 }
 
 impl Commitment {
@@ -0,0 +1,70 @@
+use curv_kzen::arithmetic::BitManipulation;
+use curv_kzen::elliptic::curves::{Ristretto, Scalar, Secp256k1};
+use curv_kzen::BigInt;
+
+// Every election computes the commitments g^s in one of the following groups:
+//
+//   [Full]       - g is a primitive root of Z*p, which has order p-1
+//                  Commitments leak the quadratic residuosity (parity) of the exponent s
+//
+//   [PrimeOrder] - g generates the subgroup of quadratic residues, which has prime order q = (p-1)/2
+//                  Since p is a safe prime, every element except 1 generates this subgroup
+//
+//   [Secp256k1]  - g is the base point of the secp256k1 curve, and g^s is the point s*G
+//   [Ristretto]  - g is the base point of the Ristretto group, and g^s is the point s*G
+//                  Curve points are stored as integers (Big-endian compressed encoding)
+//
+// All shares and ballots are computed modulo the order of the group
+//
+// Note: Elliptic curves have a fixed group order, so the voting vector of every question
+//   must fit inside that order (Only small elections or counter-encoded elections)
+sql_enum!(
+  #[derive(Default)]
+  pub CommitmentGroup {
+    #[default]
+    Full = 0,
+    PrimeOrder,
+    Secp256k1,
+    Ristretto
+  }
+);
+
+impl CommitmentGroup {
+  pub fn get_name(&self) -> &'static str {
+    match self {
+      CommitmentGroup::Full => "Full",
+      CommitmentGroup::PrimeOrder => "Prime Order",
+      CommitmentGroup::Secp256k1 => "secp256k1",
+      CommitmentGroup::Ristretto => "Ristretto",
+    }
+  }
+
+  /// Order of the group, which is the modulus for all share arithmetic
+  ///   The prime is ignored for elliptic curves
+  pub fn order(&self, prime: &BigInt) -> BigInt {
+    match self {
+      CommitmentGroup::Full => prime - 1,
+      CommitmentGroup::PrimeOrder => (prime - 1) / 2,
+      CommitmentGroup::Secp256k1 => Scalar::<Secp256k1>::group_order().clone(),
+      CommitmentGroup::Ristretto => Scalar::<Ristretto>::group_order().clone(),
+    }
+  }
+
+  /// Largest number of bits the product v * v' can use without wrapping around the group order
+  ///   Only elliptic curves have a fixed order, so this returns None for the other groups
+  pub fn max_product_bits(&self) -> Option<usize> {
+    if self.is_elliptic_curve() {
+      Some(self.order(&BigInt::from(0)).bit_length() - 1)
+    } else {
+      None
+    }
+  }
+
+  /// Elliptic curve groups don't need a generated prime for every question
+  pub fn is_elliptic_curve(&self) -> bool {
+    match self {
+      CommitmentGroup::Full | CommitmentGroup::PrimeOrder => false,
+      CommitmentGroup::Secp256k1 | CommitmentGroup::Ristretto => true,
+    }
+  }
+}
@@ -1,4 +1,5 @@
 use bigdecimal::BigDecimal;
+use chrono::{DateTime, Duration, Utc};
 use diesel::prelude::*;
 use rand::distributions::Alphanumeric;
 use rand::Rng;
@@ -9,12 +10,19 @@ use uuid_b64::UuidB64 as Uuid;
 
 use crate::db::DbConnection;
 use crate::errors::{NamedResourceType, ServiceError};
-use crate::models::{Candidate, ElectionStatus, Question, Registration, User};
+use crate::models::{
+  BallotEncoding, Candidate, CommitmentGroup, ElectionStatus, ElectionStatusChange, ElectionTransition, Question,
+  QuestionType, Registration, User, VerificationMode,
+};
 use crate::schema::elections;
 use crate::utils::new_safe_uuid_v4;
 
 pub const ACCESS_CODE_LENGTH: usize = 6;
 
+/// Delay before retrying a failed scheduled transition, which doubles with every attempt
+const RETRY_BASE_DELAY_SECONDS: i64 = 30;
+const RETRY_MAX_DELAY_SECONDS: i64 = 60 * 60;
+
 #[derive(Debug, Clone, Serialize, Queryable, Insertable, Identifiable, AsChangeset, Associations)]
 #[belongs_to(User, foreign_key = "created_by")]
 #[changeset_options(treat_none_as_null = "true")]
@@ -28,10 +36,23 @@ pub struct Election {
   pub is_public: bool,
   pub access_code: Option<String>,
 
-  pub generator: BigDecimal,
-  pub prime: BigDecimal,
-
   pub location_modulus: BigDecimal,
+
+  pub ballot_encoding: BallotEncoding,
+  pub commitment_group: CommitmentGroup,
+  pub verification_mode: VerificationMode,
+
+  pub registration_opens_at: Option<DateTime<Utc>>,
+  pub registration_closes_at: Option<DateTime<Utc>>,
+  pub voting_opens_at: Option<DateTime<Utc>>,
+  pub voting_closes_at: Option<DateTime<Utc>>,
+
+  pub scheduled_collectors: Vec<Uuid>,
+  pub scheduled_threshold: Option<i64>,
+  pub scheduled_distributed_locations: bool,
+
+  pub retry_attempts: i32,
+  pub next_retry_at: Option<DateTime<Utc>>,
 }
 
 /// Status for the current "voted" status for a user
@@ -51,8 +72,20 @@ impl Election {
   has_many!(Question, order by questions::question_number.asc());
   has_many!(Registration);
   has_many!(Commitment);
+  has_many!(
+    ElectionStatusChange,
+    order by election_status_history::changed_at.asc(),
+    status_history
+  );
 
-  pub fn new(name: impl Into<String>, created_by: Uuid, is_public: bool) -> Self {
+  pub fn new(
+    name: impl Into<String>,
+    created_by: Uuid,
+    is_public: bool,
+    ballot_encoding: BallotEncoding,
+    commitment_group: CommitmentGroup,
+    verification_mode: VerificationMode,
+  ) -> Self {
     Self {
       id: new_safe_uuid_v4(),
       name: name.into(),
@@ -60,9 +93,19 @@ impl Election {
       status: ElectionStatus::Draft,
       is_public,
       access_code: None,
-      generator: BigDecimal::default(),
-      prime: BigDecimal::default(),
       location_modulus: BigDecimal::default(),
+      ballot_encoding,
+      commitment_group,
+      verification_mode,
+      registration_opens_at: None,
+      registration_closes_at: None,
+      voting_opens_at: None,
+      voting_closes_at: None,
+      scheduled_collectors: Vec::new(),
+      scheduled_threshold: None,
+      scheduled_distributed_locations: false,
+      retry_attempts: 0,
+      next_retry_at: None,
     }
   }
 
@@ -83,6 +126,161 @@ impl Election {
     )
   }
 
+  ///
+  /// Find every election with a scheduled transition that is due
+  ///
+  /// Elections waiting for a retry are skipped until the backoff delay has passed.
+  ///   Elections in Initializing or Collecting are never due, since no transition starts from them.
+  ///
+  pub fn find_scheduled_due(now: DateTime<Utc>, conn: &DbConnection) -> Result<Vec<Self>, ServiceError> {
+    use crate::schema::elections::dsl::{
+      elections, next_retry_at, registration_opens_at, status, voting_closes_at, voting_opens_at,
+    };
+
+    Ok(
+      elections
+        .filter(
+          registration_opens_at
+            .le(now)
+            .and(status.eq_any(ElectionTransition::OpenRegistration.from_statuses().to_vec()))
+            .or(
+              voting_opens_at
+                .le(now)
+                .and(status.eq_any(ElectionTransition::StartInitialization.from_statuses().to_vec())),
+            )
+            .or(
+              voting_closes_at
+                .le(now)
+                .and(status.eq_any(ElectionTransition::StartCollection.from_statuses().to_vec())),
+            ),
+        )
+        .filter(next_retry_at.is_null().or(next_retry_at.le(now)))
+        .get_results::<Self>(conn.get())?,
+    )
+  }
+
+  /// Find every election held in Initializing or Collecting
+  pub fn find_in_progress(conn: &DbConnection) -> Result<Vec<Self>, ServiceError> {
+    use crate::schema::elections::dsl::{elections, status};
+
+    Ok(
+      elections
+        .filter(status.eq_any(vec![ElectionStatus::Initializing, ElectionStatus::Collecting]))
+        .get_results::<Self>(conn.get())?,
+    )
+  }
+
+  /// Test if the registration deadline has passed (Registration may still be open until voting is initialized)
+  pub fn is_registration_deadline_passed(&self) -> bool {
+    self
+      .registration_closes_at
+      .is_some_and(|closes_at| closes_at <= Utc::now())
+  }
+
+  /// Forget about any failed attempts, since the election moved on to the next status
+  pub fn clear_retries(&mut self) {
+    self.retry_attempts = 0;
+    self.next_retry_at = None;
+  }
+
+  ///
+  /// Save only the schedule and the retries of the election
+  ///
+  /// The scheduler saves these after a failed transition, and the owner when updating the schedule,
+  ///   neither of which may overwrite a status change by another request
+  ///
+  pub fn save_schedule(&self, conn: &DbConnection) -> Result<Self, ServiceError> {
+    Ok(
+      diesel::update(elections::table.filter(elections::id.eq(&self.id)))
+        .set((
+          elections::registration_opens_at.eq(&self.registration_opens_at),
+          elections::registration_closes_at.eq(&self.registration_closes_at),
+          elections::voting_opens_at.eq(&self.voting_opens_at),
+          elections::voting_closes_at.eq(&self.voting_closes_at),
+          elections::scheduled_collectors.eq(&self.scheduled_collectors),
+          elections::scheduled_threshold.eq(self.scheduled_threshold),
+          elections::scheduled_distributed_locations.eq(self.scheduled_distributed_locations),
+          elections::retry_attempts.eq(self.retry_attempts),
+          elections::next_retry_at.eq(&self.next_retry_at),
+        ))
+        .get_result::<Self>(conn.get())?,
+    )
+  }
+
+  /// Schedule the next attempt after a failed transition
+  pub fn schedule_retry(&mut self, now: DateTime<Utc>) {
+    let delay = RETRY_BASE_DELAY_SECONDS
+      .checked_shl(self.retry_attempts.max(0) as u32)
+      .filter(|delay| *delay > 0 && *delay < RETRY_MAX_DELAY_SECONDS)
+      .unwrap_or(RETRY_MAX_DELAY_SECONDS);
+
+    self.retry_attempts += 1;
+    self.next_retry_at = Some(now + Duration::seconds(delay));
+  }
+
+  /// Make sure the transition is allowed from the current status
+  pub fn test_transition(&self, transition: ElectionTransition) -> Result<(), ServiceError> {
+    if transition.can_start_from(self.status) {
+      return Ok(());
+    }
+
+    // Transitions out of a draft keep the same error as editing an election that is no longer a draft
+    if transition.from_statuses() == [ElectionStatus::Draft] {
+      Err(ServiceError::ElectionNotDraft {
+        election_id: self.id,
+        action: transition.get_action(),
+      })
+    } else {
+      Err(ServiceError::WrongStatusFor {
+        election_id: self.id,
+        action: transition.get_action(),
+        status: self.status,
+      })
+    }
+  }
+
+  ///
+  /// Change the status of the election, and save the change in the status history
+  ///
+  /// Any other field that was changed on the election is saved along with the new status
+  ///
+  /// Fails with `InvalidTransition` if the status in the database no longer matches the loaded election
+  ///
+  pub fn transition(
+    mut self,
+    transition: ElectionTransition,
+    actor_id: Option<Uuid>,
+    reason: impl Into<String>,
+    conn: &DbConnection,
+  ) -> Result<Self, ServiceError> {
+    self.test_transition(transition)?;
+
+    let change = ElectionStatusChange::new(self.id, self.status, transition.to_status(), actor_id, reason);
+    self.status = change.to_status;
+
+    conn.get().transaction::<_, ServiceError, _>(|| {
+      // Only change the status if no other request changed it since the election was loaded
+      //  The update also locks the row, so concurrent transitions wait for this transaction and then fail
+      let updated = diesel::update(
+        elections::table
+          .filter(elections::id.eq(&self.id))
+          .filter(elections::status.eq(change.from_status)),
+      )
+      .set(elections::status.eq(change.to_status))
+      .execute(conn.get())?;
+
+      if updated == 0 {
+        return Err(ServiceError::InvalidTransition {
+          election_id: self.id,
+          action: transition.get_action(),
+        });
+      }
+
+      change.insert(conn)?;
+      Ok(self.update(conn)?)
+    })
+  }
+
   /// Get the questions and the candidates, ordered by question number
   pub fn get_questions_candidates_ordered(
     &self,
@@ -96,6 +294,59 @@ impl Election {
     Ok(questions.into_iter().zip(candidates).collect())
   }
 
+  ///
+  /// Test if the registrations can have a weight other than 1
+  ///
+  /// Only counter-encoded questions mix the weights of every voter into the candidate totals.
+  ///   One-hot chunks are decoded one voter at a time, so a weight in the chunk would identify the voter.
+  ///   One-hot weighting is not supported: every one-hot field is a single bit and every voter has a weight of 1.
+  ///
+  pub fn supports_weights(&self, conn: &DbConnection) -> Result<bool, ServiceError> {
+    Ok(
+      self
+        .get_questions(conn)?
+        .iter()
+        .all(|question| self.ballot_encoding.for_question(question.question_type) == BallotEncoding::Counter),
+    )
+  }
+
+  ///
+  /// Test if every ballot for a question must include a ballot proof
+  ///
+  /// Sub-protocol 1 only checks the product v * v', so a counter digit of 2w with a reversed digit of w/2
+  ///   has the same product as a valid vote of weight w. The ballot proof binds the forward and reverse
+  ///   digits to one of the valid voting vectors, so counter questions need it in every verification mode.
+  ///
+  /// Choice questions with more than one selection and ranked questions have the same problem: sub-protocol 1
+  ///   accepts any product that matches a valid set of selections, and many pairs (v, v') that are not valid votes
+  ///   share it. Only a single selection fixes v and v' from the product alone.
+  ///
+  pub fn requires_ballot_proof(&self, question: &Question) -> bool {
+    let multiple_selections = match question.question_type {
+      QuestionType::Choice => question.max_selections > 1,
+      QuestionType::Ranked => true,
+    };
+
+    self.verification_mode == VerificationMode::NonInteractive
+      || self.ballot_encoding.for_question(question.question_type) == BallotEncoding::Counter
+      || multiple_selections
+  }
+
+  ///
+  /// Radix used by the counter ballot encoding (Total weight of all registrations + 1)
+  ///   Every candidate is a single digit in the voting vector, which can never overflow
+  ///
+  pub fn get_counter_radix(&self, conn: &DbConnection) -> Result<i64, ServiceError> {
+    use crate::schema::registrations::dsl::{election_id, registrations, weight};
+
+    let weights = registrations
+      .select(weight)
+      .filter(election_id.eq(&self.id))
+      .get_results::<i64>(conn.get())?;
+
+    Ok(weights.into_iter().sum::<i64>() + 1)
+  }
+
   /// Find a question from this election given the question ID
   pub fn find_question(&self, question_id: &Uuid, conn: &DbConnection) -> Result<Question, ServiceError> {
     Question::find_resource(&self.id, question_id, conn)
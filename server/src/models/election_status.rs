@@ -1,11 +1,22 @@
+use crate::errors::ResourceAction;
+
 // State Transition Diagram:
 //
-//   [Draft] -> [Registration] -> [Voting] ----------> [Finished]
-//      V        V                 ^  V                      ^
-//   <Delete>    \-> [InitFailed] -/  \->[CollectionFailed] -/
+//   [Draft] -> [Registration] -> [Initializing] -> [Voting] -> [Collecting] -> [Finished]
+//      V                           ^       V                      ^     V
+//   <Delete>                 [InitFailed] <-/         [CollectionFailed] <-/
+//
+//   [Registration], [Initializing], [InitFailed], [Voting], [Collecting], [CollectionFailed] -> [Canceled]
+//
+// Every status change is listed in TRANSITIONS below, and Election::transition is the only way to change the status.
+//
+// Note: [Initializing] and [Collecting] are only held while a request to the mediator is running.
+//   A second initialization or collection cannot start from them, so only one of each runs at a time.
+//   If the server stops in the middle, they are moved back to the failed status when it restarts.
 //
 // Note: Elections can ONLY be edited or deleted in the [Draft] state.
-//   Once registration has begun, the election must be carried to the end.
+//   Once registration has begun, the election must be carried to the end, unless the owner cancels it.
+//   Canceling purges the election secrets from the mediator and the collectors, so it can never be undone.
 //
 // Note: Public elections are not visible until the [Registration] state.
 sql_enum!(
@@ -15,7 +26,10 @@ sql_enum!(
     InitFailed,
     Voting,
     CollectionFailed,
-    Finished
+    Finished,
+    Canceled,
+    Initializing,
+    Collecting
   }
 );
 
@@ -28,9 +42,22 @@ impl ElectionStatus {
       ElectionStatus::Voting => "Voting",
       ElectionStatus::CollectionFailed => "Collection Failed",
       ElectionStatus::Finished => "Finished",
+      ElectionStatus::Canceled => "Canceled",
+      ElectionStatus::Initializing => "Initializing",
+      ElectionStatus::Collecting => "Collecting",
     }
   }
 
+  /// Test if the election can still be edited or deleted
+  pub fn is_editable(&self) -> bool {
+    *self == ElectionStatus::Draft
+  }
+
+  /// Test if the election will never change status again
+  pub fn is_final(&self) -> bool {
+    *self == ElectionStatus::Finished || *self == ElectionStatus::Canceled
+  }
+
   /// Test if the election parameters have been initialized
   pub fn is_initialized(&self) -> bool {
     match self {
@@ -40,6 +67,9 @@ impl ElectionStatus {
       ElectionStatus::Voting => true,
       ElectionStatus::CollectionFailed => true,
       ElectionStatus::Finished => true,
+      ElectionStatus::Canceled => false,
+      ElectionStatus::Initializing => false,
+      ElectionStatus::Collecting => true,
     }
   }
 
@@ -53,6 +83,129 @@ impl ElectionStatus {
       ElectionStatus::Voting => true,
       ElectionStatus::CollectionFailed => true,
       ElectionStatus::Finished => true,
+      ElectionStatus::Canceled => false,
+      ElectionStatus::Initializing => false,
+      ElectionStatus::Collecting => true,
+    }
+  }
+
+  /// Test if a request to the mediator is holding the election in this status
+  pub fn is_in_progress(&self) -> bool {
+    *self == ElectionStatus::Initializing || *self == ElectionStatus::Collecting
+  }
+}
+
+///
+/// Every change of status that an election can go through
+///
+/// Initialization and collection hold the election in an in-progress status, which they leave on success or failure.
+///   A failed run can only be retried from the failed status, so two runs never overlap.
+///
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ElectionTransition {
+  OpenRegistration,
+  StartInitialization,
+  OpenVoting,
+  FailInitialization,
+  StartCollection,
+  PublishResults,
+  FailCollection,
+  Cancel,
+}
+
+/// Transition table: (Transition, Statuses it can start from, Status it ends in)
+const TRANSITIONS: &[(ElectionTransition, &[ElectionStatus], ElectionStatus)] = &[
+  (
+    ElectionTransition::OpenRegistration,
+    &[ElectionStatus::Draft],
+    ElectionStatus::Registration,
+  ),
+  (
+    ElectionTransition::StartInitialization,
+    &[ElectionStatus::Registration, ElectionStatus::InitFailed],
+    ElectionStatus::Initializing,
+  ),
+  (
+    ElectionTransition::OpenVoting,
+    &[ElectionStatus::Initializing],
+    ElectionStatus::Voting,
+  ),
+  (
+    ElectionTransition::FailInitialization,
+    &[ElectionStatus::Initializing],
+    ElectionStatus::InitFailed,
+  ),
+  (
+    ElectionTransition::StartCollection,
+    &[ElectionStatus::Voting, ElectionStatus::CollectionFailed],
+    ElectionStatus::Collecting,
+  ),
+  (
+    ElectionTransition::PublishResults,
+    &[ElectionStatus::Collecting],
+    ElectionStatus::Finished,
+  ),
+  (
+    ElectionTransition::FailCollection,
+    &[ElectionStatus::Collecting],
+    ElectionStatus::CollectionFailed,
+  ),
+  (
+    ElectionTransition::Cancel,
+    &[
+      ElectionStatus::Registration,
+      ElectionStatus::Initializing,
+      ElectionStatus::InitFailed,
+      ElectionStatus::Voting,
+      ElectionStatus::Collecting,
+      ElectionStatus::CollectionFailed,
+    ],
+    ElectionStatus::Canceled,
+  ),
+];
+
+impl ElectionTransition {
+  fn get_entry(&self) -> &'static (ElectionTransition, &'static [ElectionStatus], ElectionStatus) {
+    TRANSITIONS
+      .iter()
+      .find(|(transition, _, _)| transition == self)
+      .expect("Every transition is in the transition table")
+  }
+
+  /// Statuses that the transition can start from
+  pub fn from_statuses(&self) -> &'static [ElectionStatus] {
+    self.get_entry().1
+  }
+
+  /// Status of the election after the transition
+  pub fn to_status(&self) -> ElectionStatus {
+    self.get_entry().2
+  }
+
+  pub fn can_start_from(&self, status: ElectionStatus) -> bool {
+    self.from_statuses().contains(&status)
+  }
+
+  /// Transition that leaves an in-progress status when the server stopped in the middle of it
+  pub fn fail_from(status: ElectionStatus) -> Option<ElectionTransition> {
+    match status {
+      ElectionStatus::Initializing => Some(ElectionTransition::FailInitialization),
+      ElectionStatus::Collecting => Some(ElectionTransition::FailCollection),
+      _ => None,
+    }
+  }
+
+  /// Action reported in the error when the transition is not allowed
+  pub fn get_action(&self) -> ResourceAction {
+    match self {
+      ElectionTransition::OpenRegistration => ResourceAction::OpenRegistration,
+      ElectionTransition::StartInitialization
+      | ElectionTransition::OpenVoting
+      | ElectionTransition::FailInitialization => ResourceAction::InitVoting,
+      ElectionTransition::StartCollection | ElectionTransition::PublishResults | ElectionTransition::FailCollection => {
+        ResourceAction::CloseVoting
+      }
+      ElectionTransition::Cancel => ResourceAction::Cancel,
     }
   }
 }
@@ -0,0 +1,52 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use uuid_b64::UuidB64 as Uuid;
+
+use crate::models::{Election, ElectionStatus};
+use crate::schema::election_status_history;
+use crate::utils::new_safe_uuid_v4;
+
+///
+/// Single entry in the status history of an election
+///
+/// Saved by Election::transition, so every status change in the transition table is recorded
+///
+#[derive(Debug, Clone, Serialize, Queryable, Insertable, Identifiable, Associations)]
+#[table_name = "election_status_history"]
+#[belongs_to(Election)]
+#[serde(rename_all = "camelCase")]
+pub struct ElectionStatusChange {
+  pub id: Uuid,
+  pub election_id: Uuid,
+  pub from_status: ElectionStatus,
+  pub to_status: ElectionStatus,
+
+  // User that requested the change (None if the scheduler changed the status)
+  pub actor_id: Option<Uuid>,
+  pub reason: String,
+  pub changed_at: DateTime<Utc>,
+}
+
+impl ElectionStatusChange {
+  model_base!(no update);
+
+  belongs_to!(Election);
+
+  pub fn new(
+    election_id: Uuid,
+    from_status: ElectionStatus,
+    to_status: ElectionStatus,
+    actor_id: Option<Uuid>,
+    reason: impl Into<String>,
+  ) -> Self {
+    Self {
+      id: new_safe_uuid_v4(),
+      election_id,
+      from_status,
+      to_status,
+      actor_id,
+      reason: reason.into(),
+      changed_at: Utc::now(),
+    }
+  }
+}
@@ -1,18 +1,36 @@
 //
 // Database tables (ORM models)
 //
+mod ballot_encoding;
+mod cancelation_commitment;
 mod candidate;
+mod collection_status;
 mod commitment;
+mod commitment_group;
 mod election;
 mod election_status;
+mod election_status_change;
+mod pooled_prime;
 mod question;
+mod question_type;
 mod registration;
+mod share_commitment;
 mod user;
+mod verification_mode;
 
+pub use ballot_encoding::BallotEncoding;
+pub use cancelation_commitment::CancelationCommitment;
 pub use candidate::Candidate;
+pub use collection_status::CollectionStatus;
 pub use commitment::Commitment;
+pub use commitment_group::CommitmentGroup;
 pub use election::{Election, HasVotedStatus, ACCESS_CODE_LENGTH};
-pub use election_status::ElectionStatus;
+pub use election_status::{ElectionStatus, ElectionTransition};
+pub use election_status_change::ElectionStatusChange;
+pub use pooled_prime::PooledPrime;
 pub use question::Question;
+pub use question_type::{count_choice_selection_sets, QuestionType, MAX_CHOICE_SELECTION_SETS, MAX_RANKED_CANDIDATES};
 pub use registration::Registration;
+pub use share_commitment::ShareCommitment;
 pub use user::User;
+pub use verification_mode::VerificationMode;
@@ -0,0 +1,73 @@
+use bigdecimal::BigDecimal;
+use curv_kzen::BigInt;
+use diesel::prelude::*;
+use serde::Serialize;
+use uuid_b64::UuidB64 as Uuid;
+
+use crate::db::DbConnection;
+use crate::errors::ServiceError;
+use crate::schema::pooled_primes;
+use crate::utils::{new_safe_uuid_v4, ConvertBigInt};
+
+#[derive(Debug, Clone, Serialize, Queryable, Insertable, Identifiable)]
+#[serde(rename_all = "camelCase")]
+pub struct PooledPrime {
+  pub id: Uuid,
+  pub num_bits: i64,
+
+  // g^x (mod p) is a cyclic group of order p-1
+  pub generator: BigDecimal,
+  pub prime: BigDecimal,
+}
+
+impl PooledPrime {
+  model_base!(no update);
+
+  pub fn new(num_bits: i64, generator: &BigInt, prime: &BigInt) -> Self {
+    Self {
+      id: new_safe_uuid_v4(),
+      num_bits,
+      generator: generator.to_bigdecimal(),
+      prime: prime.to_bigdecimal(),
+    }
+  }
+
+  /// Count the number of primes stored in a single bucket
+  pub fn count_bucket(bucket_bits: i64, conn: &DbConnection) -> Result<i64, ServiceError> {
+    use crate::schema::pooled_primes::dsl::{num_bits, pooled_primes};
+
+    Ok(
+      pooled_primes
+        .filter(num_bits.eq(bucket_bits))
+        .count()
+        .get_result(conn.get())?,
+    )
+  }
+
+  ///
+  /// Remove the smallest prime with at least min_bits bits from the pool
+  ///   Returns None if the pool does not have a prime that is large enough
+  ///
+  /// Rows are locked while being removed, so two elections can never take the same prime
+  ///
+  pub fn take_smallest(min_bits: i64, conn: &DbConnection) -> Result<Option<Self>, ServiceError> {
+    use crate::schema::pooled_primes::dsl::{num_bits, pooled_primes};
+
+    conn.get().transaction::<_, ServiceError, _>(|| {
+      let pooled_prime = pooled_primes
+        .filter(num_bits.ge(min_bits))
+        .order_by(num_bits.asc())
+        .limit(1)
+        .for_update()
+        .skip_locked()
+        .get_result::<Self>(conn.get())
+        .optional()?;
+
+      if let Some(ref pooled_prime) = pooled_prime {
+        pooled_prime.delete(conn)?;
+      }
+
+      Ok(pooled_prime)
+    })
+  }
+}
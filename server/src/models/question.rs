@@ -6,7 +6,9 @@ use uuid_b64::UuidB64 as Uuid;
 
 use crate::db::DbConnection;
 use crate::errors::{NamedResourceType, ServiceError};
-use crate::models::{Commitment, Election, User};
+use crate::models::{
+  count_choice_selection_sets, CollectionStatus, Commitment, CommitmentGroup, Election, QuestionType, User,
+};
 use crate::schema::questions;
 use crate::utils::{new_safe_uuid_v4, ConvertBigInt};
 
@@ -23,6 +25,23 @@ pub struct Question {
   // Set to 0 until after the election has closed
   pub forward_cancelation_shares: BigDecimal,
   pub reverse_cancelation_shares: BigDecimal,
+
+  // Number of candidates a voter must select: min_selections <= k <= max_selections
+  //   (Ignored for ranked questions, as every candidate must be ranked)
+  pub min_selections: i64,
+  pub max_selections: i64,
+
+  pub question_type: QuestionType,
+
+  // g^x (mod p) is a cyclic group of order p-1, or (p-1)/2 for the prime-order subgroup
+  //   Each question has its own group, sized for the voting vector of that question
+  //   Set to 0 until voting is initialized
+  pub generator: BigDecimal,
+  pub prime: BigDecimal,
+
+  // Closing voting can be retried, so only the questions that are not collected are requested again
+  pub collection_status: CollectionStatus,
+  pub collection_error: Option<String>,
 }
 
 impl Question {
@@ -32,7 +51,14 @@ impl Question {
   has_many!(Commitment);
   has_many!(Candidate, order by candidates::candidate_number.asc());
 
-  pub fn new(election_id: Uuid, question: impl Into<String>, question_number: i64) -> Self {
+  pub fn new(
+    election_id: Uuid,
+    question: impl Into<String>,
+    question_number: i64,
+    question_type: QuestionType,
+    min_selections: i64,
+    max_selections: i64,
+  ) -> Self {
     Self {
       id: new_safe_uuid_v4(),
       election_id,
@@ -40,6 +66,13 @@ impl Question {
       question_number,
       forward_cancelation_shares: BigDecimal::default(),
       reverse_cancelation_shares: BigDecimal::default(),
+      min_selections,
+      max_selections,
+      question_type,
+      generator: BigDecimal::default(),
+      prime: BigDecimal::default(),
+      collection_status: CollectionStatus::Pending,
+      collection_error: None,
     }
   }
 
@@ -86,10 +119,14 @@ impl Question {
   }
 
   ///
-  /// Compute the forward and reverse ballot sum for a given question, mod (p - 1)
+  /// Compute the forward and reverse ballot sum for a given question, mod the order of the commitment group
   ///   This method also applies the cancelation shares
   ///
-  pub fn get_ballots_sum(&self, modulo: &BigInt, conn: &DbConnection) -> Result<(BigInt, BigInt), ServiceError> {
+  pub fn get_ballots_sum(
+    &self,
+    commitment_group: CommitmentGroup,
+    conn: &DbConnection,
+  ) -> Result<(BigInt, BigInt), ServiceError> {
     use crate::schema::commitments::dsl::{commitments, election_id, forward_ballot, question_id, reverse_ballot};
     use diesel::dsl::sum;
 
@@ -107,9 +144,10 @@ impl Question {
       .get_result::<Option<BigDecimal>>(conn.get())?
       .unwrap_or_else(BigDecimal::default);
 
+    let modulo = commitment_group.order(&self.prime.to_bigint());
     Ok((
-      (forward + &self.forward_cancelation_shares).to_bigint() % modulo,
-      (reverse + &self.reverse_cancelation_shares).to_bigint() % modulo,
+      (forward + &self.forward_cancelation_shares).to_bigint() % &modulo,
+      (reverse + &self.reverse_cancelation_shares).to_bigint() % &modulo,
     ))
   }
 
@@ -144,6 +182,30 @@ impl Question {
     )
   }
 
+  ///
+  /// Get the registration weights for all users who cast a vote for this question
+  ///
+  pub fn get_voted_weights(&self, conn: &DbConnection) -> Result<Vec<i64>, ServiceError> {
+    use crate::schema::commitments::dsl::{
+      commitments, election_id as c_election_id, question_id as c_question_id, user_id as c_user_id,
+    };
+    use crate::schema::registrations::dsl::{election_id, registrations, user_id, weight};
+    use diesel::dsl::exists;
+
+    Ok(
+      registrations
+        .select(weight)
+        .filter(election_id.eq(&self.election_id))
+        .filter(exists(
+          commitments
+            .filter(c_user_id.eq(user_id))
+            .filter(c_election_id.eq(&self.election_id))
+            .filter(c_question_id.eq(&self.id)),
+        ))
+        .get_results::<i64>(conn.get())?,
+    )
+  }
+
   ///
   /// Get the list of user ids who didn't cast a vote for this question
   ///
@@ -156,4 +218,15 @@ impl Question {
         .collect(),
     )
   }
+
+  ///
+  /// Number of valid ways for a voter to fill in their chunk of the voting vector
+  ///   Choice questions stop counting at MAX_CHOICE_SELECTION_SETS + 1 (See count_choice_selection_sets)
+  ///
+  pub fn count_selection_sets(&self, num_candidates: i64) -> u64 {
+    match self.question_type {
+      QuestionType::Choice => count_choice_selection_sets(num_candidates, self.min_selections, self.max_selections),
+      QuestionType::Ranked => (1..=num_candidates as u64).product(),
+    }
+  }
 }
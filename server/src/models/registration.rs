@@ -5,7 +5,7 @@ use crate::db::ManyToManyConstructor;
 use crate::models::{Election, User};
 use crate::schema::registrations;
 
-#[derive(Debug, Clone, Serialize, Queryable, Insertable, Identifiable, Associations)]
+#[derive(Debug, Clone, Serialize, Queryable, Insertable, Identifiable, AsChangeset, Associations)]
 #[primary_key(user_id, election_id)]
 #[belongs_to(User)]
 #[belongs_to(Election)]
@@ -13,10 +13,13 @@ use crate::schema::registrations;
 pub struct Registration {
   pub user_id: Uuid,
   pub election_id: Uuid,
+
+  // Number of votes each ballot counts for in the final tally
+  pub weight: i64,
 }
 
 impl Registration {
-  model_base!(no update);
+  model_base!();
 
   belongs_to!(User);
   belongs_to!(Election);
@@ -25,7 +28,11 @@ impl Registration {
   // has_many!(Commitment);
 
   pub fn new(user_id: Uuid, election_id: Uuid) -> Self {
-    Self { user_id, election_id }
+    Self {
+      user_id,
+      election_id,
+      weight: 1,
+    }
   }
 }
 
@@ -0,0 +1,53 @@
+use bigdecimal::BigDecimal;
+use curv_kzen::BigInt;
+use serde::Serialize;
+use uuid_b64::UuidB64 as Uuid;
+
+use crate::models::{Election, Question, User};
+use crate::schema::share_commitments;
+use crate::utils::ConvertBigInt;
+
+///
+/// Blinded commitments to the ballot shares of a voter, multiplied across every collector:
+///   D_i = g^(S~_i) * h^(rho_i) and D_i' = g^(S~_i') * h^(rho_i')
+///
+/// Only created for elections that use non-interactive ballot proofs
+///
+#[derive(Debug, Clone, Serialize, Queryable, Insertable, Identifiable, AsChangeset, Associations)]
+#[primary_key(user_id, election_id, question_id)]
+#[belongs_to(User)]
+#[belongs_to(Question)]
+#[belongs_to(Election)]
+#[serde(rename_all = "camelCase")]
+pub struct ShareCommitment {
+  pub user_id: Uuid,
+  pub election_id: Uuid,
+  pub question_id: Uuid,
+
+  pub forward_commitment: BigDecimal,
+  pub reverse_commitment: BigDecimal,
+}
+
+impl ShareCommitment {
+  model_base!();
+
+  belongs_to!(User);
+  belongs_to!(Election);
+  belongs_to!(Question);
+
+  pub fn new(
+    user_id: Uuid,
+    election_id: Uuid,
+    question_id: Uuid,
+    forward_commitment: &BigInt,
+    reverse_commitment: &BigInt,
+  ) -> Self {
+    Self {
+      user_id,
+      election_id,
+      question_id,
+      forward_commitment: forward_commitment.to_bigdecimal(),
+      reverse_commitment: reverse_commitment.to_bigdecimal(),
+    }
+  }
+}
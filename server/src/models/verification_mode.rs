@@ -0,0 +1,27 @@
+// Every election verifies the ballots in one of the following modes:
+//
+//   [Interactive]    - The collectors run sub-protocols 1 and 2 with the server for every ballot
+//
+//   [NonInteractive] - The voter attaches a zero-knowledge proof that the ballot is valid
+//                      (Disjunctive Chaum-Pedersen proof, made non-interactive with Fiat-Shamir)
+//                      The proof uses blinded commitments D = g^(S~ - S) * h^(rho) to the shares of every collector,
+//                      so the server can check the ballot without contacting the collectors
+//
+// Note: The proofs need a second generator h with an unknown discrete log, so they only work in prime-order groups
+sql_enum!(
+  #[derive(Default)]
+  pub VerificationMode {
+    #[default]
+    Interactive = 0,
+    NonInteractive
+  }
+);
+
+impl VerificationMode {
+  pub fn get_name(&self) -> &'static str {
+    match self {
+      VerificationMode::Interactive => "Interactive",
+      VerificationMode::NonInteractive => "Non-Interactive",
+    }
+  }
+}
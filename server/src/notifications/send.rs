@@ -127,13 +127,24 @@ pub async fn notify_registration_closed(election: &Election, jwt_key: &JWTSecret
   .await
 }
 
-pub async fn notify_voting_opened(election: &Election, collectors: Vec<Uuid>, jwt_key: &JWTSecret) {
+pub async fn notify_voting_opened(
+  election: &Election,
+  questions: &[Question],
+  collectors: Vec<Uuid>,
+  jwt_key: &JWTSecret,
+) {
   send_notification(
     &AllServerMessages::VotingOpened(server_types::VotingOpened {
       election_id: election.id,
       collectors,
-      prime: election.prime.to_bigint(),
-      generator: election.generator.to_bigint(),
+      questions: questions
+        .iter()
+        .map(|question| server_types::VotingOpenedQuestion {
+          question_id: question.id,
+          prime: question.prime.to_bigint(),
+          generator: question.generator.to_bigint(),
+        })
+        .collect(),
       location_modulus: election.location_modulus.to_bigint(),
     }),
     jwt_key,
@@ -189,6 +200,34 @@ pub async fn notify_voting_closed(election: &Election, jwt_key: &JWTSecret) {
   send_notification(&AllServerMessages::VotingClosed(election.id.into()), jwt_key).await
 }
 
+pub async fn notify_collection_progress(election: &Election, questions: &[Question], jwt_key: &JWTSecret) {
+  send_notification(
+    &AllServerMessages::CollectionProgress(server_types::CollectionProgress {
+      election_id: election.id,
+      questions: questions
+        .iter()
+        .map(|question| server_types::QuestionCollectionProgress {
+          question_id: question.id,
+          collection_status: question.collection_status,
+        })
+        .collect(),
+    }),
+    jwt_key,
+  )
+  .await
+}
+
 pub async fn notify_results_published(election: &Election, jwt_key: &JWTSecret) {
   send_notification(&AllServerMessages::ResultsPublished(election.id.into()), jwt_key).await
 }
+
+pub async fn notify_election_canceled(election: &Election, reason: &str, jwt_key: &JWTSecret) {
+  send_notification(
+    &AllServerMessages::ElectionCanceled(server_types::ElectionCanceled {
+      election_id: election.id,
+      reason: reason.to_string(),
+    }),
+    jwt_key,
+  )
+  .await
+}
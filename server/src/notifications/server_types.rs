@@ -5,7 +5,7 @@ use curv_kzen::BigInt;
 use serde::Serialize;
 use uuid_b64::UuidB64 as Uuid;
 
-use crate::models::HasVotedStatus;
+use crate::models::{CollectionStatus, HasVotedStatus};
 
 /// List of every message that can be deserialized from the server
 #[derive(Debug, Serialize)]
@@ -24,6 +24,8 @@ pub enum AllServerMessages {
   VoteReceived(VoteReceived),
   VotingClosed(ElectionDetails),
   ResultsPublished(ElectionDetails),
+  CollectionProgress(CollectionProgress),
+  ElectionCanceled(ElectionCanceled),
 }
 
 #[derive(Debug, Serialize)]
@@ -81,13 +83,21 @@ pub struct RegistrationClosed {
 pub struct VotingOpened {
   pub election_id: Uuid,
   pub collectors: Vec<Uuid>,
+  pub questions: Vec<VotingOpenedQuestion>,
+
+  #[serde(with = "kzen_paillier::serialize::bigint")]
+  pub location_modulus: BigInt,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VotingOpenedQuestion {
+  pub question_id: Uuid,
 
   #[serde(with = "kzen_paillier::serialize::bigint")]
   pub prime: BigInt,
   #[serde(with = "kzen_paillier::serialize::bigint")]
   pub generator: BigInt,
-  #[serde(with = "kzen_paillier::serialize::bigint")]
-  pub location_modulus: BigInt,
 }
 
 #[derive(Debug, Serialize)]
@@ -121,3 +131,24 @@ pub struct VoteReceived {
 
   pub num_votes: i64,
 }
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CollectionProgress {
+  pub election_id: Uuid,
+  pub questions: Vec<QuestionCollectionProgress>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuestionCollectionProgress {
+  pub question_id: Uuid,
+  pub collection_status: CollectionStatus,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ElectionCanceled {
+  pub election_id: Uuid,
+  pub reason: String,
+}
@@ -0,0 +1,373 @@
+use curv_kzen::arithmetic::{Converter, Modulo};
+use curv_kzen::BigInt;
+use sha2::{Digest, Sha256};
+use uuid_b64::UuidB64 as Uuid;
+
+use crate::protocol::Group;
+
+/// Domain separator for the Fiat-Shamir challenge
+const CHALLENGE_DOMAIN: &[u8] = b"TruBallot ballot proof";
+
+/// Largest number of branches allowed in a ballot proof
+///   One-hot questions have a branch for every valid selection in every voter's chunk, so the number of branches
+///   grows with the number of voters. Every ballot posts 3 values and the server computes 6 exponentiations
+///   for each branch, so voting cannot be initialized if a proof would need more than 20000 branches.
+pub const MAX_BALLOT_PROOF_BRANCHES: u64 = 20000;
+
+///
+/// Non-interactive proof that a ballot (p_i, p_i') encodes one of the valid voting vectors
+///
+/// Every collector publishes blinded commitments to its shares D_c,i = g^(S~c,i - S_c,i) * h^(rho_c,i),
+///   and the server multiplies them together into D_i = g^(S~i - S_i) * h^(rho_i). Since p_i = v_i - S_i + S~i, we get
+///
+///   C_i = g^(p_i) / D_i = g^(v_i) * h^(-rho_i)
+///
+/// So the ballot is valid if and only if C_i / g^(v_k) is a power of h for one of the valid vectors v_k.
+/// The voter proves this for v_k and v_k' at the same time with a disjunctive Chaum-Pedersen proof:
+///
+///   1. For every branch k, Y_k = C_i / g^(v_k) and Y_k' = C_i' / g^(v_k')
+///   2. The real branch uses random a, a': A_k = h^a, A_k' = h^(a')
+///      Every simulated branch picks c_k, z_k, z_k' first: A_k = h^(z_k) / Y_k^(c_k), A_k' = h^(z_k') / Y_k'^(c_k)
+///   3. c = Hash(election, question, user, p_i, p_i', D_i, D_i', A_1, A_1', ..., A_K, A_K') (mod q)
+///   4. The real branch gets c_k = c - Σ (simulated c_k), z_k = a - c_k * rho_i, z_k' = a' - c_k * rho_i'
+///
+/// The verifier recomputes every A_k and A_k' from (c_k, z_k, z_k'), and checks that Σ c_k = c (mod q)
+///
+#[derive(Debug, Clone)]
+pub struct BallotProof {
+  pub challenges: Vec<BigInt>,        // c_k
+  pub forward_responses: Vec<BigInt>, // z_k
+  pub reverse_responses: Vec<BigInt>, // z_k'
+}
+
+/// Public values that a ballot proof is checked against
+#[derive(Debug, Clone)]
+pub struct BallotStatement<'a> {
+  pub election_id: &'a Uuid,
+  pub question_id: &'a Uuid,
+  pub user_id: &'a Uuid,
+
+  pub forward_ballot: &'a BigInt,           // p_i
+  pub reverse_ballot: &'a BigInt,           // p_i'
+  pub forward_share_commitment: &'a BigInt, // D_i
+  pub reverse_share_commitment: &'a BigInt, // D_i'
+}
+
+impl BallotProof {
+  ///
+  /// Verify the proof for every pair of voting vectors (v_k, v_k')
+  ///
+  /// Returns false if the proof does not have a branch for every pair,
+  ///   or if any of the values are not valid group elements
+  ///
+  pub fn verify(
+    &self,
+    statement: &BallotStatement,
+    group: &Group,
+    group_order: &BigInt,
+    voting_vectors: &[(BigInt, BigInt)],
+  ) -> bool {
+    let num_branches = voting_vectors.len();
+    if num_branches == 0
+      || self.challenges.len() != num_branches
+      || self.forward_responses.len() != num_branches
+      || self.reverse_responses.len() != num_branches
+    {
+      return false;
+    }
+
+    let commitments = self.compute_commitments(statement, group, group_order, voting_vectors);
+    let commitments = match commitments {
+      Some(commitments) => commitments,
+      None => return false,
+    };
+
+    // Every challenge must add up to the Fiat-Shamir challenge
+    let challenge = fiat_shamir_challenge(statement, &commitments, group_order);
+    let challenge_sum = self
+      .challenges
+      .iter()
+      .fold(BigInt::from(0), |sum, c| BigInt::mod_add(&sum, c, group_order));
+
+    challenge_sum == challenge
+  }
+
+  /// Recompute A_k = h^(z_k) / Y_k^(c_k) and A_k' = h^(z_k') / Y_k'^(c_k) for every branch
+  fn compute_commitments(
+    &self,
+    statement: &BallotStatement,
+    group: &Group,
+    group_order: &BigInt,
+    voting_vectors: &[(BigInt, BigInt)],
+  ) -> Option<Vec<(BigInt, BigInt)>> {
+    // C_i = g^(p_i) / D_i and C_i' = g^(p_i') / D_i'
+    let inverse = group_order - 1;
+    let forward_base = group.mul(
+      &group.pow_generator(statement.forward_ballot),
+      &group.pow(statement.forward_share_commitment, &inverse)?,
+    )?;
+    let reverse_base = group.mul(
+      &group.pow_generator(statement.reverse_ballot),
+      &group.pow(statement.reverse_share_commitment, &inverse)?,
+    )?;
+
+    let negate = |x: &BigInt| BigInt::mod_sub(&BigInt::from(0), x, group_order);
+
+    voting_vectors
+      .iter()
+      .enumerate()
+      .map(|(k, (v, v_prime))| {
+        let neg_challenge = negate(&self.challenges[k]);
+
+        // Y_k = C_i / g^(v_k)
+        let y = group.mul(&forward_base, &group.pow_generator(&negate(v)))?;
+        let y_prime = group.mul(&reverse_base, &group.pow_generator(&negate(v_prime)))?;
+
+        // A_k = h^(z_k) / Y_k^(c_k)
+        let a = group.mul(
+          &group.pow_second_generator(&BigInt::modulus(&self.forward_responses[k], group_order)),
+          &group.pow(&y, &neg_challenge)?,
+        )?;
+        let a_prime = group.mul(
+          &group.pow_second_generator(&BigInt::modulus(&self.reverse_responses[k], group_order)),
+          &group.pow(&y_prime, &neg_challenge)?,
+        )?;
+
+        Some((a, a_prime))
+      })
+      .collect()
+  }
+}
+
+///
+/// Compute the Fiat-Shamir challenge c (mod q)
+///
+/// The UUIDs are hashed as their base64 strings, and every integer is hashed as
+///   an 8-byte big-endian length followed by its big-endian bytes
+///
+fn fiat_shamir_challenge(
+  statement: &BallotStatement,
+  commitments: &[(BigInt, BigInt)],
+  group_order: &BigInt,
+) -> BigInt {
+  let mut hasher = Sha256::new();
+  hasher.update(CHALLENGE_DOMAIN);
+
+  for id in [statement.election_id, statement.question_id, statement.user_id].iter() {
+    hasher.update(id.to_string().as_bytes());
+  }
+
+  update_bigint(&mut hasher, statement.forward_ballot);
+  update_bigint(&mut hasher, statement.reverse_ballot);
+  update_bigint(&mut hasher, statement.forward_share_commitment);
+  update_bigint(&mut hasher, statement.reverse_share_commitment);
+  for (a, a_prime) in commitments {
+    update_bigint(&mut hasher, a);
+    update_bigint(&mut hasher, a_prime);
+  }
+
+  BigInt::modulus(&BigInt::from_bytes(&hasher.finalize()), group_order)
+}
+
+/// Hash an integer with its length, so the boundaries between values are not ambiguous
+fn update_bigint(hasher: &mut Sha256, value: &BigInt) {
+  let bytes = value.to_bytes();
+  hasher.update((bytes.len() as u64).to_be_bytes());
+  hasher.update(&bytes);
+}
+
+#[cfg(test)]
+mod tests {
+  use curv_kzen::arithmetic::Samplable;
+
+  use super::*;
+  use crate::models::CommitmentGroup;
+
+  /// Public values and the witness for a ballot that encodes voting_vectors[real]
+  struct TestBallot {
+    election_id: Uuid,
+    question_id: Uuid,
+    user_id: Uuid,
+
+    forward_ballot: BigInt,
+    reverse_ballot: BigInt,
+    forward_share_commitment: BigInt,
+    reverse_share_commitment: BigInt,
+
+    forward_randomness: BigInt, // rho_i
+    reverse_randomness: BigInt, // rho_i'
+  }
+
+  impl TestBallot {
+    fn new(group: &Group, q: &BigInt, vector: &(BigInt, BigInt)) -> Self {
+      let (shares, shares_prime) = (BigInt::sample_below(q), BigInt::sample_below(q));
+      let (rho, rho_prime) = (BigInt::sample_below(q), BigInt::sample_below(q));
+
+      Self {
+        election_id: Uuid::new(),
+        question_id: Uuid::new(),
+        user_id: Uuid::new(),
+
+        forward_ballot: BigInt::mod_add(&vector.0, &shares, q),
+        reverse_ballot: BigInt::mod_add(&vector.1, &shares_prime, q),
+        forward_share_commitment: group
+          .mul(&group.pow_generator(&shares), &group.pow_second_generator(&rho))
+          .unwrap(),
+        reverse_share_commitment: group
+          .mul(
+            &group.pow_generator(&shares_prime),
+            &group.pow_second_generator(&rho_prime),
+          )
+          .unwrap(),
+
+        forward_randomness: rho,
+        reverse_randomness: rho_prime,
+      }
+    }
+
+    fn statement(&self) -> BallotStatement<'_> {
+      BallotStatement {
+        election_id: &self.election_id,
+        question_id: &self.question_id,
+        user_id: &self.user_id,
+        forward_ballot: &self.forward_ballot,
+        reverse_ballot: &self.reverse_ballot,
+        forward_share_commitment: &self.forward_share_commitment,
+        reverse_share_commitment: &self.reverse_share_commitment,
+      }
+    }
+
+    /// Build the disjunctive proof, where the real branch proves that Y_k = h^(-rho_i)
+    fn prove(&self, group: &Group, q: &BigInt, voting_vectors: &[(BigInt, BigInt)], real: usize) -> BallotProof {
+      let negate = |x: &BigInt| BigInt::mod_sub(&BigInt::from(0), x, q);
+
+      // Y_k = g^(p_i) / D_i / g^(v_k)
+      let y = |ballot: &BigInt, commitment: &BigInt, v: &BigInt| {
+        let c = group
+          .mul(&group.pow_generator(ballot), &group.pow(commitment, &(q - 1)).unwrap())
+          .unwrap();
+        group.mul(&c, &group.pow_generator(&negate(v))).unwrap()
+      };
+
+      let (a, a_prime) = (BigInt::sample_below(q), BigInt::sample_below(q));
+      let mut proof = BallotProof {
+        challenges: Vec::new(),
+        forward_responses: Vec::new(),
+        reverse_responses: Vec::new(),
+      };
+
+      let mut commitments = Vec::new();
+      for (k, (v, v_prime)) in voting_vectors.iter().enumerate() {
+        if k == real {
+          proof.challenges.push(BigInt::from(0));
+          proof.forward_responses.push(BigInt::from(0));
+          proof.reverse_responses.push(BigInt::from(0));
+          commitments.push((group.pow_second_generator(&a), group.pow_second_generator(&a_prime)));
+          continue;
+        }
+
+        let (c, z, z_prime) = (
+          BigInt::sample_below(q),
+          BigInt::sample_below(q),
+          BigInt::sample_below(q),
+        );
+        let y_k = y(&self.forward_ballot, &self.forward_share_commitment, v);
+        let y_k_prime = y(&self.reverse_ballot, &self.reverse_share_commitment, v_prime);
+        commitments.push((
+          group
+            .mul(&group.pow_second_generator(&z), &group.pow(&y_k, &negate(&c)).unwrap())
+            .unwrap(),
+          group
+            .mul(
+              &group.pow_second_generator(&z_prime),
+              &group.pow(&y_k_prime, &negate(&c)).unwrap(),
+            )
+            .unwrap(),
+        ));
+
+        proof.challenges.push(c);
+        proof.forward_responses.push(z);
+        proof.reverse_responses.push(z_prime);
+      }
+
+      let challenge = fiat_shamir_challenge(&self.statement(), &commitments, q);
+      let simulated = proof
+        .challenges
+        .iter()
+        .fold(BigInt::from(0), |sum, c| BigInt::mod_add(&sum, c, q));
+      let c_real = BigInt::mod_sub(&challenge, &simulated, q);
+
+      proof.forward_responses[real] = BigInt::mod_sub(&a, &BigInt::mod_mul(&c_real, &self.forward_randomness, q), q);
+      proof.reverse_responses[real] =
+        BigInt::mod_sub(&a_prime, &BigInt::mod_mul(&c_real, &self.reverse_randomness, q), q);
+      proof.challenges[real] = c_real;
+
+      proof
+    }
+  }
+
+  fn setup() -> (Group, BigInt, Vec<(BigInt, BigInt)>) {
+    let group = Group::new(CommitmentGroup::Ristretto, BigInt::from(0), BigInt::from(0));
+    let q = CommitmentGroup::Ristretto.order(&BigInt::from(0));
+    let voting_vectors = vec![
+      (BigInt::from(1), BigInt::from(4)),
+      (BigInt::from(2), BigInt::from(2)),
+      (BigInt::from(4), BigInt::from(1)),
+    ];
+
+    (group, q, voting_vectors)
+  }
+
+  #[test]
+  fn accepts_valid_proof() {
+    let (group, q, voting_vectors) = setup();
+
+    for real in 0..voting_vectors.len() {
+      let ballot = TestBallot::new(&group, &q, &voting_vectors[real]);
+      let proof = ballot.prove(&group, &q, &voting_vectors, real);
+      assert!(proof.verify(&ballot.statement(), &group, &q, &voting_vectors));
+    }
+  }
+
+  #[test]
+  fn rejects_tampered_proof() {
+    let (group, q, voting_vectors) = setup();
+    let ballot = TestBallot::new(&group, &q, &voting_vectors[1]);
+    let proof = ballot.prove(&group, &q, &voting_vectors, 1);
+
+    // Changed challenge or response
+    let mut tampered = proof.clone();
+    tampered.challenges[0] = BigInt::mod_add(&tampered.challenges[0], &BigInt::from(1), &q);
+    assert!(!tampered.verify(&ballot.statement(), &group, &q, &voting_vectors));
+
+    let mut tampered = proof.clone();
+    tampered.reverse_responses[2] = BigInt::mod_add(&tampered.reverse_responses[2], &BigInt::from(1), &q);
+    assert!(!tampered.verify(&ballot.statement(), &group, &q, &voting_vectors));
+
+    // Missing branch
+    let mut tampered = proof.clone();
+    tampered.challenges.pop();
+    tampered.forward_responses.pop();
+    tampered.reverse_responses.pop();
+    assert!(!tampered.verify(&ballot.statement(), &group, &q, &voting_vectors));
+
+    // Changed ballot or a different voter
+    let mut changed = ballot;
+    changed.forward_ballot = BigInt::mod_add(&changed.forward_ballot, &BigInt::from(1), &q);
+    assert!(!proof.verify(&changed.statement(), &group, &q, &voting_vectors));
+
+    changed.forward_ballot = BigInt::mod_sub(&changed.forward_ballot, &BigInt::from(1), &q);
+    changed.user_id = Uuid::new();
+    assert!(!proof.verify(&changed.statement(), &group, &q, &voting_vectors));
+  }
+
+  #[test]
+  fn rejects_invalid_voting_vector() {
+    let (group, q, voting_vectors) = setup();
+
+    // The ballot encodes (3, 3), which is not a valid vector, so the "real" branch cannot be proven
+    let ballot = TestBallot::new(&group, &q, &(BigInt::from(3), BigInt::from(3)));
+    let proof = ballot.prove(&group, &q, &voting_vectors, 1);
+    assert!(!proof.verify(&ballot.statement(), &group, &q, &voting_vectors));
+  }
+}
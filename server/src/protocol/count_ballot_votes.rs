@@ -1,45 +1,145 @@
 use curv_kzen::arithmetic::traits::Converter;
 use curv_kzen::BigInt;
 use std::iter;
+use std::ops::RangeInclusive;
 
 ///
 /// Test the validity of the voting vector, then count the number of votes
 ///
+/// Every candidate uses a single bit in the voting vector, which is set if the voter selected that candidate.
+///   One-hot elections are never weighted, since the chunk of every voter is public once the ballots are summed.
+///
+/// selections = Number of candidates each voter can select (min_selections..=max_selections)
+/// num_voted  = Number of users who cast a ballot for this question
+///
 /// On error, this returns None
 /// On success, it returns the number of votes for each candidate
+///   (For approval questions, this is the number of approvals per candidate)
 ///
 pub fn count_ballot_votes(
   forward_ballot: &BigInt,
   reverse_ballot: &BigInt,
   num_candidates: i64,
+  selections: RangeInclusive<i64>,
   num_voters: i64,
-  no_vote_count: usize,
+  num_voted: usize,
 ) -> Option<Vec<i64>> {
-  let total_bits = (num_candidates * num_voters) as usize;
-  let forward_bits = get_bits(forward_ballot, total_bits);
-  let reverse_bits = get_bits(reverse_ballot, total_bits);
-
   // Step 1: Make sure the forward and reverse bits match
-  for (f, r) in forward_bits.iter().zip(reverse_bits.iter().rev()) {
-    if *f != *r {
-      return None;
+  let num_fields = (num_candidates * num_voters) as usize;
+  let forward_fields = get_verified_fields(forward_ballot, reverse_ballot, num_fields)?;
+
+  // Step 2: Make sure each chunk has either no candidates or between min and max candidates selected
+  let chunks = get_chunks(&forward_fields, num_candidates as usize);
+  let voted = verify_chunks(&chunks, selections)?;
+
+  // Step 3: Make sure the number of voted chunks matches the users who voted
+  if voted != num_voted {
+    return None;
+  }
+
+  // All checks passed!
+  Some(count_votes(&chunks, num_candidates as usize))
+}
+
+///
+/// Test the validity of a ranked voting vector, then decode the rankings
+///
+/// Every voter has a chunk of num_candidates^2 bits. Reading the chunk from the
+///   least-significant bit, bit (c * num_candidates + r) is set if candidate c has rank r.
+///
+/// On error, this returns None
+/// On success, it returns the list of candidates for each voter, ordered from first to last preference
+///
+pub fn count_ranked_ballot_votes(
+  forward_ballot: &BigInt,
+  reverse_ballot: &BigInt,
+  num_candidates: i64,
+  num_voters: i64,
+  num_voted: usize,
+) -> Option<Vec<Vec<usize>>> {
+  let num_candidates = num_candidates as usize;
+  let chunk_size = num_candidates * num_candidates;
+  let num_fields = chunk_size * (num_voters as usize);
+  let forward_fields = get_verified_fields(forward_ballot, reverse_ballot, num_fields)?;
+
+  // Make sure each chunk is either empty, or a complete ranking of the candidates
+  let mut rankings = Vec::new();
+  for chunk in get_chunks(&forward_fields, chunk_size) {
+    match decode_ranking(&chunk, num_candidates) {
+      Some(Some(ranking)) => rankings.push(ranking),
+      Some(None) => {}
+      None => return None,
     }
   }
 
-  // Step 2: Make sure each chunk has, at most, only one candidate selected
-  let chunks = get_chunks(&forward_bits, num_candidates as usize);
-  let (voted, no_vote) = match verify_chunks(&chunks) {
-    Some((voted, no_vote)) => (voted, no_vote),
-    None => return None,
-  };
+  // Make sure the number of voted chunks matches the users who voted
+  if rankings.len() != num_voted {
+    return None;
+  }
+
+  Some(rankings)
+}
+
+///
+/// Test the validity of a counter-encoded voting vector, then count the number of votes
+///
+/// Every candidate is a single base-counter_radix digit shared by all voters, which stores the total
+///   weight of the voters who selected that candidate. Reading from the least-significant digit,
+///   candidate c is digit c in the forward ballot and digit (num_candidates - 1 - c) in the reverse ballot.
+///
+/// The individual ballots are mixed together in the digits, so this can only test the totals
+///   (Sub-protocol 1 already tests that every individual ballot is valid)
+///
+/// On error, this returns None
+/// On success, it returns the (weighted) number of votes for each candidate
+///
+pub fn count_counter_ballot_votes(
+  forward_ballot: &BigInt,
+  reverse_ballot: &BigInt,
+  num_candidates: i64,
+  selections: RangeInclusive<i64>,
+  counter_radix: i64,
+  voted_weights: &[i64],
+) -> Option<Vec<i64>> {
+  // Step 1: Make sure the forward and reverse digits match
+  let votes = get_digits(forward_ballot, num_candidates as usize, counter_radix)?;
+  let reverse_votes = get_digits(reverse_ballot, num_candidates as usize, counter_radix)?;
+  if votes.iter().ne(reverse_votes.iter().rev()) {
+    return None;
+  }
 
-  // Step 3: Make sure the number of voted bits matches the users who voted
-  if voted + no_vote != (num_voters as usize) || no_vote != no_vote_count {
+  // Step 2: Every voter selected between min and max candidates, so the totals must match the voted weights
+  let voted_weight: i64 = voted_weights.iter().sum();
+  let total_votes: i64 = votes.iter().sum();
+  if votes.iter().any(|v| *v > voted_weight)
+    || total_votes < selections.start() * voted_weight
+    || total_votes > selections.end() * voted_weight
+  {
     return None;
   }
 
   // All checks passed!
-  Some(count_votes(&chunks, num_candidates as usize))
+  Some(votes)
+}
+
+/// Convert both ballots into vectors of single-bit fields, making sure the forward and reverse fields match
+///   Returns the forward fields on success, or None on failure
+fn get_verified_fields(forward_ballot: &BigInt, reverse_ballot: &BigInt, num_fields: usize) -> Option<Vec<i64>> {
+  let forward_fields = get_fields(forward_ballot, num_fields);
+  let reverse_fields = get_fields(reverse_ballot, num_fields);
+
+  for (f, r) in forward_fields.iter().zip(reverse_fields.iter().rev()) {
+    if *f != *r {
+      return None;
+    }
+  }
+
+  Some(forward_fields)
+}
+
+/// Convert the integer voting vector into a vector of 0/1 fields, ordered from most-significant to least-significant
+fn get_fields(input: &BigInt, num_fields: usize) -> Vec<i64> {
+  get_bits(input, num_fields).into_iter().map(|bit| bit as i64).collect()
 }
 
 /// Convert the integer voting vector into a vector of bits
@@ -57,50 +157,208 @@ fn get_bits(input: &BigInt, total_bits: usize) -> Vec<bool> {
   reverse_bits.into_iter().rev().collect()
 }
 
-/// Convert the vector of bits into chunks for each candidate
-fn get_chunks(input: &[bool], num_candidates: usize) -> Vec<Vec<bool>> {
+/// Convert the integer voting vector into base-radix digits, ordered from least-significant to most-significant
+///   Returns None if the voting vector has more digits than expected
+fn get_digits(input: &BigInt, num_digits: usize, radix: i64) -> Option<Vec<i64>> {
+  let radix = BigInt::from(radix as u64);
+  let mut remaining = input.clone();
+  let mut digits = Vec::with_capacity(num_digits);
+
+  for _ in 0..num_digits {
+    let digit = &remaining % &radix;
+    digits.push(digit.to_str_radix(10).parse::<i64>().ok()?);
+    remaining = &remaining / &radix;
+  }
+
+  if remaining == BigInt::from(0) {
+    Some(digits)
+  } else {
+    None
+  }
+}
+
+/// Convert the vector of fields into chunks for each voter
+fn get_chunks(input: &[i64], chunk_size: usize) -> Vec<Vec<i64>> {
   input
-    .chunks(num_candidates)
+    .chunks(chunk_size)
     .map(|c| c.into_iter().cloned().collect())
     .collect()
 }
 
-/// Make sure each chunk only has, at most, one candidate
-///    Returns Some((voted, didn't vote)) on success, or None on failure
-fn verify_chunks(input: &Vec<Vec<bool>>) -> Option<(usize, usize)> {
+/// Make sure each chunk has either zero candidates, or a valid number of selected candidates
+///
+/// Returns Some(number of voted chunks) on success, or None on failure
+fn verify_chunks(input: &Vec<Vec<i64>>, selections: RangeInclusive<i64>) -> Option<usize> {
   let mut voted = 0;
-  let mut no_vote = 0;
 
   for chunk in input {
-    let num_votes = chunk.into_iter().filter(|c| **c).count();
-    match num_votes {
-      0 => no_vote += 1,
-      1 => voted += 1,
+    let selected = chunk.iter().filter(|c| **c != 0).count();
+    if selected == 0 {
+      continue; // Voter didn't vote
+    }
+
+    if !selections.contains(&(selected as i64)) {
+      return None;
+    }
+
+    voted += 1;
+  }
+
+  Some(voted)
+}
+
+/// Decode a single ranked chunk
+///    Returns Some(None) if the voter didn't vote, Some(Some(ranking)) for a valid ranking, or None on failure
+fn decode_ranking(chunk: &[i64], num_candidates: usize) -> Option<Option<Vec<usize>>> {
+  if chunk.iter().all(|c| *c == 0) {
+    return Some(None);
+  }
+
+  // Each candidate must have exactly one rank, and each rank must have exactly one candidate
+  let fields: Vec<i64> = chunk.iter().rev().cloned().collect();
+  let mut ranking: Vec<Option<usize>> = vec![None; num_candidates];
+  for (candidate, ranks) in fields.chunks(num_candidates).enumerate() {
+    let mut selected = ranks.iter().enumerate().filter(|(_, c)| **c != 0);
+    match (selected.next(), selected.next()) {
+      (Some((rank, _)), None) if ranking[rank].is_none() => ranking[rank] = Some(candidate),
       _ => return None,
     }
   }
 
-  Some((voted, no_vote))
+  Some(Some(ranking.into_iter().collect::<Option<Vec<usize>>>()?))
 }
 
 /// Total up the votes for each candidate
 ///
 /// This function assumes that the chunk input has already been validated by verify_chunks
-fn count_votes(input: &Vec<Vec<bool>>, num_candidates: usize) -> Vec<i64> {
+fn count_votes(input: &Vec<Vec<i64>>, num_candidates: usize) -> Vec<i64> {
   let mut votes = vec![0; num_candidates];
 
   for chunk in input {
-    // Searches for the first candidate bit set in the chunk
-    if let Some(candidate) = chunk
-      .into_iter()
-      .rev()
-      .enumerate()
-      .filter_map(|(i, c)| (*c).then(|| i))
-      .next()
-    {
-      votes[candidate] += 1;
+    // Add a vote for every candidate selected in the chunk
+    for (candidate, selected) in chunk.iter().rev().enumerate() {
+      votes[candidate] += selected;
     }
   }
 
   votes
 }
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// Sum of the one-hot vectors, where each voter sets the given bits within their chunk
+  ///   Returns (forward, reverse) with the reverse bits mirrored across all L bits
+  fn one_hot_ballots(chunk_size: u32, num_voters: u32, votes: &[(u32, &[u32])]) -> (BigInt, BigInt) {
+    let total_bits = chunk_size * num_voters;
+    votes
+      .iter()
+      .flat_map(|(location, bits)| bits.iter().map(move |bit| location * chunk_size + bit))
+      .fold((BigInt::from(0), BigInt::from(0)), |(forward, reverse), bit| {
+        (
+          forward + (BigInt::from(1) << bit as usize),
+          reverse + (BigInt::from(1) << (total_bits - 1 - bit) as usize),
+        )
+      })
+  }
+
+  /// Counter ballots with the given total for each candidate
+  fn counter_ballots(radix: u64, votes: &[u64]) -> (BigInt, BigInt) {
+    // Fold the digits starting from the most-significant one
+    let number = |digits: &[u64]| {
+      digits
+        .iter()
+        .fold(BigInt::from(0), |acc, v| acc * BigInt::from(radix) + BigInt::from(*v))
+    };
+
+    let reversed: Vec<u64> = votes.iter().rev().cloned().collect();
+    (number(&reversed), number(votes))
+  }
+
+  #[test]
+  fn counts_one_hot_votes() {
+    let (forward, reverse) = one_hot_ballots(3, 4, &[(0, &[1]), (2, &[0]), (3, &[1])]);
+    assert_eq!(
+      count_ballot_votes(&forward, &reverse, 3, 1..=1, 4, 3),
+      Some(vec![1, 2, 0])
+    );
+  }
+
+  #[test]
+  fn counts_one_hot_multiple_selections() {
+    let (forward, reverse) = one_hot_ballots(3, 3, &[(0, &[0, 2]), (1, &[1])]);
+    assert_eq!(
+      count_ballot_votes(&forward, &reverse, 3, 1..=2, 3, 2),
+      Some(vec![1, 1, 1])
+    );
+
+    // Selecting two candidates is not allowed in a single-choice question
+    assert_eq!(count_ballot_votes(&forward, &reverse, 3, 1..=1, 3, 2), None);
+  }
+
+  #[test]
+  fn rejects_invalid_one_hot_ballots() {
+    let (forward, reverse) = one_hot_ballots(3, 4, &[(0, &[1]), (2, &[0])]);
+
+    // Number of voted chunks must match the number of voters
+    assert_eq!(count_ballot_votes(&forward, &reverse, 3, 1..=1, 4, 3), None);
+
+    // Forward and reverse bits must match
+    let (_, other_reverse) = one_hot_ballots(3, 4, &[(0, &[2]), (2, &[0])]);
+    assert_eq!(count_ballot_votes(&forward, &other_reverse, 3, 1..=1, 4, 2), None);
+  }
+
+  #[test]
+  fn decodes_ranked_ballots() {
+    // Voter 0 ranks [2, 0, 1] and voter 1 ranks [1, 2, 0], where candidate c with rank r sets bit (3c + r)
+    let (forward, reverse) = one_hot_ballots(9, 2, &[(0, &[1, 5, 6]), (1, &[2, 3, 7])]);
+    assert_eq!(
+      count_ranked_ballot_votes(&forward, &reverse, 3, 2, 2),
+      Some(vec![vec![1, 2, 0], vec![2, 0, 1]])
+    );
+
+    // Two candidates with the same rank
+    let (forward, reverse) = one_hot_ballots(9, 2, &[(0, &[0, 3, 8])]);
+    assert_eq!(count_ranked_ballot_votes(&forward, &reverse, 3, 2, 1), None);
+  }
+
+  #[test]
+  fn counts_counter_votes() {
+    // Voters with weights 2 and 3 select candidates 0 and 2
+    let (forward, reverse) = counter_ballots(8, &[2, 0, 3]);
+    assert_eq!(
+      count_counter_ballot_votes(&forward, &reverse, 3, 1..=1, 8, &[2, 3]),
+      Some(vec![2, 0, 3])
+    );
+  }
+
+  #[test]
+  fn rejects_invalid_counter_ballots() {
+    let (forward, reverse) = counter_ballots(8, &[2, 0, 3]);
+
+    // Forward and reverse digits must match
+    let (_, other_reverse) = counter_ballots(8, &[3, 0, 2]);
+    assert_eq!(
+      count_counter_ballot_votes(&forward, &other_reverse, 3, 1..=1, 8, &[2, 3]),
+      None
+    );
+
+    // Totals must match the weights of the voters
+    assert_eq!(
+      count_counter_ballot_votes(&forward, &reverse, 3, 1..=1, 8, &[2, 2]),
+      None
+    );
+    assert_eq!(
+      count_counter_ballot_votes(&forward, &reverse, 3, 2..=2, 8, &[2, 3]),
+      None
+    );
+
+    // Extra digits past the last candidate
+    let (forward, reverse) = counter_ballots(8, &[2, 0, 3, 1]);
+    assert_eq!(
+      count_counter_ballot_votes(&forward, &reverse, 3, 1..=1, 8, &[2, 3]),
+      None
+    );
+  }
+}
@@ -46,3 +46,14 @@ pub fn generator_prime_pair(num_bits: usize) -> (BigInt, BigInt) {
 
   (g, p)
 }
+
+/// Returns a generator for the subgroup of Z*p with prime order q = (p-1)/2
+///
+/// The squares of Z*p (quadratic residues) form a subgroup of order (p-1)/2 = q.
+/// Squaring a primitive root g gives g^2, which has order exactly q.
+///
+/// Since q is prime, exponents can be reduced mod q, and the commitments g^s
+/// no longer reveal whether s is even or odd.
+pub fn prime_order_generator(primitive_root: &BigInt, prime: &BigInt) -> BigInt {
+  BigInt::mod_pow(primitive_root, &BigInt::from(2), prime)
+}
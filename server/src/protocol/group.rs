@@ -0,0 +1,137 @@
+//
+// Copied into the server, mediator, and collector crates
+//   All copies must stay byte-identical (pinned by a test in protocol/mod.rs)
+//
+use curv_kzen::arithmetic::{Converter, Modulo};
+use curv_kzen::elliptic::curves::{Curve, Point, Ristretto, Scalar, Secp256k1};
+use curv_kzen::BigInt;
+use sha2::{Digest, Sha256};
+
+use crate::models::CommitmentGroup;
+
+/// Number of bytes in a compressed secp256k1 point
+const SECP256K1_POINT_BYTES: usize = 33;
+
+/// Number of bytes in an encoded Ristretto point
+const RISTRETTO_POINT_BYTES: usize = 32;
+
+/// Domain separator when hashing the group parameters into the second generator h
+const SECOND_GENERATOR_DOMAIN: &[u8] = b"TruBallot second generator";
+
+///
+/// Commitment group shared by the server, mediator, and collectors
+///
+/// Group elements are always passed around as integers, so the messages and database columns
+///   are the same for every group. Curve points are stored as their compressed encoding (Big-endian integer).
+///
+/// The multiplicative notation is kept for every group:
+///   For elliptic curves, g^x is the scalar multiplication x*G and a * b is the point addition A + B
+///
+#[derive(Debug, Clone)]
+pub enum Group {
+  Modular { generator: BigInt, prime: BigInt },
+  Secp256k1,
+  Ristretto,
+}
+
+impl Group {
+  pub fn new(commitment_group: CommitmentGroup, generator: BigInt, prime: BigInt) -> Self {
+    match commitment_group {
+      CommitmentGroup::Full | CommitmentGroup::PrimeOrder => Group::Modular { generator, prime },
+      CommitmentGroup::Secp256k1 => Group::Secp256k1,
+      CommitmentGroup::Ristretto => Group::Ristretto,
+    }
+  }
+
+  /// Compute g^exponent
+  pub fn pow_generator(&self, exponent: &BigInt) -> BigInt {
+    match self {
+      Group::Modular { generator, prime } => BigInt::mod_pow(generator, exponent, prime),
+      Group::Secp256k1 => encode_point(&(Point::<Secp256k1>::generator() * Scalar::from_bigint(exponent))),
+      Group::Ristretto => encode_point(&(Point::<Ristretto>::generator() * Scalar::from_bigint(exponent))),
+    }
+  }
+
+  /// Compute h^exponent, where h is a second generator with an unknown discrete log to the base g
+  ///   h is derived from a hash, so nobody can know log_g(h) and open a commitment g^x * h^r in two ways
+  pub fn pow_second_generator(&self, exponent: &BigInt) -> BigInt {
+    match self {
+      Group::Modular { generator, prime } => {
+        BigInt::mod_pow(&modular_second_generator(generator, prime), exponent, prime)
+      }
+      Group::Secp256k1 => encode_point(&(Point::<Secp256k1>::base_point2() * Scalar::from_bigint(exponent))),
+      Group::Ristretto => encode_point(&(Point::<Ristretto>::base_point2() * Scalar::from_bigint(exponent))),
+    }
+  }
+
+  /// Compute base^exponent
+  ///   Returns None if the base is not a valid group element
+  pub fn pow(&self, base: &BigInt, exponent: &BigInt) -> Option<BigInt> {
+    match self {
+      Group::Modular { prime, .. } => Some(BigInt::mod_pow(base, exponent, prime)),
+      Group::Secp256k1 => {
+        let base = decode_point::<Secp256k1>(base, SECP256K1_POINT_BYTES)?;
+        Some(encode_point(&(base * Scalar::from_bigint(exponent))))
+      }
+      Group::Ristretto => {
+        let base = decode_point::<Ristretto>(base, RISTRETTO_POINT_BYTES)?;
+        Some(encode_point(&(base * Scalar::from_bigint(exponent))))
+      }
+    }
+  }
+
+  /// Compute a * b
+  ///   Returns None if either value is not a valid group element
+  pub fn mul(&self, a: &BigInt, b: &BigInt) -> Option<BigInt> {
+    match self {
+      Group::Modular { prime, .. } => Some(BigInt::mod_mul(a, b, prime)),
+      Group::Secp256k1 => {
+        let a = decode_point::<Secp256k1>(a, SECP256K1_POINT_BYTES)?;
+        let b = decode_point::<Secp256k1>(b, SECP256K1_POINT_BYTES)?;
+        Some(encode_point(&(a + b)))
+      }
+      Group::Ristretto => {
+        let a = decode_point::<Ristretto>(a, RISTRETTO_POINT_BYTES)?;
+        let b = decode_point::<Ristretto>(b, RISTRETTO_POINT_BYTES)?;
+        Some(encode_point(&(a + b)))
+      }
+    }
+  }
+
+  /// Multiply all values together
+  ///   Returns None if the iterator is empty or any value is not a valid group element
+  pub fn product<'a>(&self, mut values: impl Iterator<Item = &'a BigInt>) -> Option<BigInt> {
+    let first = values.next()?.clone();
+    values.try_fold(first, |acc, value| self.mul(&acc, value))
+  }
+}
+
+/// Hash the group parameters into a quadratic residue h = x^2 (mod p), where 2 <= x <= p - 2
+///   Since p is a safe prime, h generates the same subgroup of order (p-1)/2 as g
+fn modular_second_generator(generator: &BigInt, prime: &BigInt) -> BigInt {
+  let mut hasher = Sha256::new();
+  hasher.update(SECOND_GENERATOR_DOMAIN);
+  hasher.update(generator.to_bytes());
+  hasher.update(prime.to_bytes());
+
+  let x = BigInt::modulus(&BigInt::from_bytes(&hasher.finalize()), &(prime - 3)) + 2;
+  BigInt::mod_mul(&x, &x, prime)
+}
+
+/// Convert a curve point into a big-endian integer
+fn encode_point<E: Curve>(point: &Point<E>) -> BigInt {
+  BigInt::from_bytes(&point.to_bytes(true))
+}
+
+/// Convert a big-endian integer back into a curve point
+///   Leading zero bytes are dropped by the integer, so pad them back before decoding
+fn decode_point<E: Curve>(value: &BigInt, num_bytes: usize) -> Option<Point<E>> {
+  let bytes = value.to_bytes();
+  if bytes.len() > num_bytes {
+    return None;
+  }
+
+  let mut padded = vec![0u8; num_bytes - bytes.len()];
+  padded.extend(bytes);
+  Point::from_bytes(&padded).ok()
+}
@@ -0,0 +1,118 @@
+//
+// Copied into the server and collector crates
+//   Both copies must stay byte-identical (pinned by a test in protocol/mod.rs)
+//
+use curv_kzen::arithmetic::Converter;
+use curv_kzen::BigInt;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use uuid_b64::UuidB64 as Uuid;
+
+use crate::models::{BallotEncoding, CommitmentGroup, QuestionType, VerificationMode};
+
+/// Domain separator for the manifest hash
+const MANIFEST_DOMAIN: &[u8] = b"TruBallot election manifest v1";
+
+///
+/// Every election parameter that the collectors depend on, signed by the API server
+///
+/// The mediator only forwards the manifest, so it cannot reorder the collectors,
+///   add a registered user, or swap the group parameters without the signature failing
+///
+/// Signing lives in the server ManifestKey and checking the signature in the collector IdentityKey,
+///   so this file stays the same in both crates
+///
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ElectionManifest {
+  pub election_id: Uuid,
+  pub collectors: Vec<Uuid>,
+  pub registered_users: Vec<Uuid>,
+  pub registered_weights: Vec<i64>,
+  pub ballot_encoding: BallotEncoding,
+  pub counter_radix: i64,
+  pub commitment_group: CommitmentGroup,
+  pub verification_mode: VerificationMode,
+  pub threshold: Option<usize>,
+  pub distributed_locations: bool,
+  pub questions: Vec<ManifestQuestion>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ManifestQuestion {
+  pub id: Uuid,
+  pub num_candidates: i64,
+  pub question_type: QuestionType,
+  pub min_selections: i64,
+  pub max_selections: i64,
+
+  #[serde(with = "kzen_paillier::serialize::bigint")]
+  pub generator: BigInt,
+
+  #[serde(with = "kzen_paillier::serialize::bigint")]
+  pub prime: BigInt,
+}
+
+///
+/// Manifest with the base64 Ed25519 signature over its hash
+///
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignedManifest {
+  pub manifest: ElectionManifest,
+  pub signature: String,
+}
+
+impl ElectionManifest {
+  ///
+  /// Hash every field of the manifest in order
+  ///
+  /// The server and collectors must compute the exact same hash, so the encoding does not depend on JSON:
+  ///   UUIDs are hashed as their base64 strings, enums as their 4-byte discriminant,
+  ///   lists are prefixed with their 8-byte length, and integers are big-endian
+  ///
+  pub fn compute_hash(&self) -> Vec<u8> {
+    let mut hasher = Sha256::new();
+    hasher.update(MANIFEST_DOMAIN);
+    hasher.update(self.election_id.to_string().as_bytes());
+
+    hasher.update((self.collectors.len() as u64).to_be_bytes());
+    for collector_id in self.collectors.iter() {
+      hasher.update(collector_id.to_string().as_bytes());
+    }
+
+    hasher.update((self.registered_users.len() as u64).to_be_bytes());
+    for (user_id, weight) in self.registered_users.iter().zip(self.registered_weights.iter()) {
+      hasher.update(user_id.to_string().as_bytes());
+      hasher.update(weight.to_be_bytes());
+    }
+
+    hasher.update((self.ballot_encoding as i32).to_be_bytes());
+    hasher.update(self.counter_radix.to_be_bytes());
+    hasher.update((self.commitment_group as i32).to_be_bytes());
+    hasher.update((self.verification_mode as i32).to_be_bytes());
+    hasher.update((self.threshold.unwrap_or(0) as u64).to_be_bytes());
+    hasher.update([self.distributed_locations as u8]);
+
+    hasher.update((self.questions.len() as u64).to_be_bytes());
+    for question in self.questions.iter() {
+      hasher.update(question.id.to_string().as_bytes());
+      hasher.update(question.num_candidates.to_be_bytes());
+      hasher.update((question.question_type as i32).to_be_bytes());
+      hasher.update(question.min_selections.to_be_bytes());
+      hasher.update(question.max_selections.to_be_bytes());
+      update_bigint(&mut hasher, &question.generator);
+      update_bigint(&mut hasher, &question.prime);
+    }
+
+    hasher.finalize().to_vec()
+  }
+}
+
+/// Hash an integer with its length, so the boundaries between values are not ambiguous
+fn update_bigint(hasher: &mut Sha256, value: &BigInt) {
+  let bytes = value.to_bytes();
+  hasher.update((bytes.len() as u64).to_be_bytes());
+  hasher.update(&bytes);
+}
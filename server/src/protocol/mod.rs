@@ -1,8 +1,59 @@
 //
 // Functions and structures related to the math behind the e-voting protocol
 //
+mod ballot_proof;
 mod count_ballot_votes;
 mod generator;
+mod group;
+mod manifest;
+mod prime_pool;
+mod ranked_tally;
+pub mod selections;
 
-pub use count_ballot_votes::count_ballot_votes;
-pub use generator::generator_prime_pair;
+pub use ballot_proof::{BallotProof, BallotStatement, MAX_BALLOT_PROOF_BRANCHES};
+pub use count_ballot_votes::{count_ballot_votes, count_counter_ballot_votes, count_ranked_ballot_votes};
+pub use generator::{generator_prime_pair, prime_order_generator};
+pub use group::Group;
+pub use manifest::{ElectionManifest, ManifestQuestion, SignedManifest};
+pub use prime_pool::{prime_pool_buckets, spawn_prime_pool_worker};
+pub use ranked_tally::{borda_count, instant_runoff, RunoffRound};
+
+#[cfg(test)]
+mod copies {
+  #[test]
+  fn group_matches_other_crates() {
+    let group = include_str!("group.rs");
+    assert_eq!(group, include_str!("../../../mediator/src/protocol/group.rs"));
+    assert_eq!(group, include_str!("../../../collector/src/protocol/group.rs"));
+  }
+
+  #[test]
+  fn manifest_matches_collector_copy() {
+    assert_eq!(
+      include_str!("manifest.rs"),
+      include_str!("../../../collector/src/protocol/manifest.rs")
+    );
+  }
+
+  #[test]
+  fn question_limits_match_other_crates() {
+    // Each crate documents the limits for its own use, but the definitions must be identical
+    let limits = |source: &'static str| -> Vec<&'static str> {
+      source
+        .lines()
+        .filter(|line| line.starts_with("pub const MAX_"))
+        .collect()
+    };
+
+    let question_type = limits(include_str!("../models/question_type.rs"));
+    assert_eq!(question_type.len(), 2);
+    assert_eq!(
+      question_type,
+      limits(include_str!("../../../mediator/src/models/question_type.rs"))
+    );
+    assert_eq!(
+      question_type,
+      limits(include_str!("../../../collector/src/models/question_type.rs"))
+    );
+  }
+}
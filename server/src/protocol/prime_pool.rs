@@ -0,0 +1,89 @@
+use std::thread;
+use std::time::Duration;
+
+use crate::config;
+use crate::db::{DbConnection, PgPool};
+use crate::errors::ServiceError;
+use crate::models::PooledPrime;
+use crate::protocol::generator_prime_pair;
+
+/// Smallest prime stored in the pool (initialize_voting never uses primes below 256 bits)
+const MIN_BUCKET_BITS: usize = 256;
+
+/// How long to wait before checking the pool again when every bucket is full
+const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Returns the bit sizes of every bucket in the pool
+///   Buckets are powers of 2, from 256 bits up to the configured maximum
+pub fn prime_pool_buckets() -> Vec<usize> {
+  let max_bits = config::get_prime_pool_max_bits();
+
+  let mut buckets = Vec::new();
+  let mut num_bits = MIN_BUCKET_BITS;
+  while num_bits <= max_bits {
+    buckets.push(num_bits);
+    num_bits *= 2;
+  }
+
+  buckets
+}
+
+///
+/// Start a background thread that keeps every bucket in the prime pool filled
+///
+/// Generating a safe prime can take minutes for large bit sizes, so this runs on its own OS thread
+///   instead of blocking the actix runtime. Setting the pool size to 0 disables the worker.
+///
+pub fn spawn_prime_pool_worker(pool: PgPool) {
+  if config::get_prime_pool_size() <= 0 {
+    log::info!("Prime pool is disabled");
+    return;
+  }
+
+  thread::Builder::new()
+    .name("prime-pool".into())
+    .spawn(move || loop {
+      match fill_next_bucket(&pool) {
+        Ok(true) => {}
+        Ok(false) => thread::sleep(CHECK_INTERVAL),
+        Err(e) => {
+          log::error!("Prime pool worker failed: {}", e);
+          thread::sleep(CHECK_INTERVAL);
+        }
+      }
+    })
+    .expect("Failed to spawn prime pool thread");
+}
+
+/// Generate a single prime for the smallest bucket that is below the target size
+///   Returns false if every bucket is already full
+fn fill_next_bucket(pool: &PgPool) -> Result<bool, ServiceError> {
+  let target_size = config::get_prime_pool_size();
+
+  // Only hold the database connection while counting, since generation can take a long time
+  let next_bucket = {
+    let conn = DbConnection::new_pooled(pool.get()?);
+    let mut next_bucket = None;
+    for num_bits in prime_pool_buckets() {
+      if PooledPrime::count_bucket(num_bits as i64, &conn)? < target_size {
+        next_bucket = Some(num_bits);
+        break;
+      }
+    }
+
+    next_bucket
+  };
+
+  let num_bits = match next_bucket {
+    Some(num_bits) => num_bits,
+    None => return Ok(false),
+  };
+
+  log::debug!("Generating prime with {} bits for the prime pool", num_bits);
+  let (generator, prime) = generator_prime_pair(num_bits);
+
+  let conn = DbConnection::new_pooled(pool.get()?);
+  PooledPrime::new(num_bits as i64, &generator, &prime).insert(&conn)?;
+
+  Ok(true)
+}
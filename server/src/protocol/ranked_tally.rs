@@ -0,0 +1,187 @@
+use std::collections::BTreeMap;
+
+/// Single round of an instant-runoff election
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunoffRound {
+  /// Number of votes for each candidate still in the running
+  pub votes: BTreeMap<usize, i64>,
+
+  /// Candidate eliminated at the end of this round
+  pub eliminated: Option<usize>,
+
+  /// Set if a candidate has a majority of the votes in this round, or is the last one left
+  pub winner: Option<usize>,
+}
+
+///
+/// Count the ballots using instant-runoff voting
+///
+/// Each round, every ballot counts toward the highest-ranked candidate still in the running.
+///   If a candidate has a strict majority, or is the only candidate left, they win.
+///   Otherwise, exactly one candidate with the fewest votes is eliminated and the next round begins.
+///
+/// Ties for the fewest votes are broken in this order, eliminating the candidate with:
+///   1. The fewest votes in the previous round (skipped in the first round)
+///   2. The fewest Borda points across all ballots
+///   3. The lowest candidate index
+///
+/// rankings = List of candidates for each ballot, ordered from first to last preference
+///
+pub fn instant_runoff(rankings: &[Vec<usize>], num_candidates: usize) -> Vec<RunoffRound> {
+  let borda_points = borda_count(rankings, num_candidates);
+  let mut remaining: Vec<usize> = (0..num_candidates).collect();
+  let mut rounds: Vec<RunoffRound> = Vec::new();
+
+  while !remaining.is_empty() {
+    // Count the top remaining choice on every ballot
+    let mut votes: BTreeMap<usize, i64> = remaining.iter().map(|c| (*c, 0)).collect();
+    for ranking in rankings {
+      if let Some(candidate) = ranking.iter().find(|c| votes.contains_key(*c)) {
+        *votes.get_mut(candidate).unwrap() += 1;
+      }
+    }
+
+    // Test for a majority winner
+    let total: i64 = votes.values().sum();
+    let winner = match remaining.as_slice() {
+      [last] => Some(*last),
+      _ => votes.iter().find(|(_, v)| 2 * **v > total).map(|(c, _)| *c),
+    };
+
+    // Otherwise, eliminate a single candidate with the fewest votes
+    let previous_votes = rounds.last().map(|round| &round.votes);
+    let eliminated = match winner {
+      Some(_) => None,
+      None => remaining.iter().cloned().min_by_key(|c| {
+        (
+          votes[c],
+          previous_votes.map(|previous| previous[c]).unwrap_or(0),
+          borda_points[*c],
+          *c,
+        )
+      }),
+    };
+
+    remaining.retain(|c| Some(*c) != eliminated);
+    rounds.push(RunoffRound {
+      votes,
+      eliminated,
+      winner,
+    });
+
+    if winner.is_some() {
+      break;
+    }
+  }
+
+  rounds
+}
+
+///
+/// Count the ballots using a Borda count
+///
+/// With m candidates, the first preference gets (m - 1) points, the second preference
+///   gets (m - 2) points, and so on until the last preference gets 0 points.
+///
+/// Returns the total number of points for each candidate
+///
+pub fn borda_count(rankings: &[Vec<usize>], num_candidates: usize) -> Vec<i64> {
+  let mut points = vec![0; num_candidates];
+
+  for ranking in rankings {
+    for (rank, candidate) in ranking.iter().enumerate() {
+      points[*candidate] += (num_candidates - 1 - rank) as i64;
+    }
+  }
+
+  points
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn ballots(groups: &[(&[usize], usize)]) -> Vec<Vec<usize>> {
+    groups
+      .iter()
+      .flat_map(|(ranking, count)| std::iter::repeat_n(ranking.to_vec(), *count))
+      .collect()
+  }
+
+  fn votes(counts: &[(usize, i64)]) -> BTreeMap<usize, i64> {
+    counts.iter().cloned().collect()
+  }
+
+  #[test]
+  fn majority_in_first_round() {
+    let rankings = ballots(&[(&[0, 1, 2], 3), (&[1, 0, 2], 2)]);
+    let rounds = instant_runoff(&rankings, 3);
+
+    assert_eq!(rounds.len(), 1);
+    assert_eq!(rounds[0].votes, votes(&[(0, 3), (1, 2), (2, 0)]));
+    assert_eq!(rounds[0].eliminated, None);
+    assert_eq!(rounds[0].winner, Some(0));
+  }
+
+  #[test]
+  fn transfers_eliminated_votes() {
+    let rankings = ballots(&[(&[0, 2, 1], 4), (&[1, 2, 0], 3), (&[2, 1, 0], 2)]);
+    let rounds = instant_runoff(&rankings, 3);
+
+    assert_eq!(rounds.len(), 2);
+    assert_eq!(rounds[0].votes, votes(&[(0, 4), (1, 3), (2, 2)]));
+    assert_eq!(rounds[0].eliminated, Some(2));
+    assert_eq!(rounds[1].votes, votes(&[(0, 4), (1, 5)]));
+    assert_eq!(rounds[1].winner, Some(1));
+  }
+
+  #[test]
+  fn tie_broken_by_previous_round() {
+    let rankings = ballots(&[
+      (&[0, 1, 2, 3], 5),
+      (&[1, 0, 2, 3], 3),
+      (&[2, 0, 1, 3], 2),
+      (&[3, 2, 0, 1], 1),
+    ]);
+    let rounds = instant_runoff(&rankings, 4);
+
+    // Candidates 1 and 2 tie in the second round, but 2 had fewer votes in the first round
+    assert_eq!(rounds.len(), 3);
+    assert_eq!(rounds[0].eliminated, Some(3));
+    assert_eq!(rounds[1].votes, votes(&[(0, 5), (1, 3), (2, 3)]));
+    assert_eq!(rounds[1].eliminated, Some(2));
+    assert_eq!(rounds[2].votes, votes(&[(0, 8), (1, 3)]));
+    assert_eq!(rounds[2].winner, Some(0));
+  }
+
+  #[test]
+  fn tie_broken_by_borda_count() {
+    let rankings = ballots(&[(&[2, 0, 1], 2), (&[0, 2, 1], 1), (&[1, 2, 0], 1)]);
+    assert_eq!(borda_count(&rankings, 3), vec![4, 2, 6]);
+
+    // Candidates 0 and 1 tie in the first round, but 1 has fewer Borda points
+    let rounds = instant_runoff(&rankings, 3);
+    assert_eq!(rounds.len(), 2);
+    assert_eq!(rounds[0].votes, votes(&[(0, 1), (1, 1), (2, 2)]));
+    assert_eq!(rounds[0].eliminated, Some(1));
+    assert_eq!(rounds[1].votes, votes(&[(0, 1), (2, 3)]));
+    assert_eq!(rounds[1].winner, Some(2));
+  }
+
+  #[test]
+  fn tie_broken_by_lowest_index() {
+    let rankings = ballots(&[(&[0, 1], 1), (&[1, 0], 1)]);
+    let rounds = instant_runoff(&rankings, 2);
+
+    assert_eq!(rounds.len(), 2);
+    assert_eq!(rounds[0].eliminated, Some(0));
+    assert_eq!(rounds[1].votes, votes(&[(1, 2)]));
+    assert_eq!(rounds[1].winner, Some(1));
+  }
+
+  #[test]
+  fn borda_points() {
+    let rankings = ballots(&[(&[0, 1, 2], 2), (&[2, 1, 0], 1)]);
+    assert_eq!(borda_count(&rankings, 3), vec![4, 3, 2]);
+  }
+}
@@ -0,0 +1,92 @@
+use curv_kzen::BigInt;
+use itertools::Itertools;
+
+///
+/// Compute every pair of voting vectors (v, v') that a non-interactive ballot proof may choose between
+///
+/// The voting vector is read as L digits in base R, where every digit stores the voter weight w.
+///   One-hot questions use R = 2^B (B bits per field), and give every voter their own chunk of m digits.
+///   Counter questions use R = N + 1 (N is the total weight), and share a single chunk of m digits between all voters.
+///
+/// Say a voter sets digits r_1, ..., r_k within their chunk of m digits.
+/// Then the forward and reverse voting vectors are v = w * Σ R^(l + r_a) and v' = w * Σ R^(L - 1 - l - r_b),
+/// where l is the offset of the voter's chunk.
+///
+/// Unlike sub-protocol 1, the proof is checked against v and v' directly, so the server does not learn
+///   the voter location. Every chunk is a separate branch of the proof.
+///
+/// radix      = Base of every digit in the voting vector (R)
+/// num_chunks = Number of chunks in the voting vector (Number of voters for one-hot, 1 for counter)
+/// chunk_size = Number of digits in each chunk (m)
+/// weight     = Weight of the voter (w)
+/// positions  = Every valid set of digit positions within a chunk
+pub fn voting_vectors(
+  radix: &BigInt,
+  num_chunks: i64,
+  chunk_size: i64,
+  weight: i64,
+  positions: impl Iterator<Item = Vec<usize>>,
+) -> Vec<(BigInt, BigInt)> {
+  let m = chunk_size as usize;
+  let num_digits = (num_chunks * chunk_size) as usize;
+  let weight = &BigInt::from(weight as u64);
+  let positions: Vec<Vec<usize>> = positions.collect();
+
+  (0..num_chunks as usize)
+    .flat_map(|chunk| {
+      let offset = chunk * m;
+      positions.iter().map(move |positions| {
+        let (forward, reverse) = positions
+          .iter()
+          .fold((BigInt::from(0), BigInt::from(0)), |(forward, reverse), r| {
+            (
+              forward + pow(radix, offset + *r),
+              reverse + pow(radix, num_digits - 1 - offset - *r),
+            )
+          });
+
+        (weight * forward, weight * reverse)
+      })
+    })
+    .collect()
+}
+
+/// Compute base^exponent without any modulus (Square-and-multiply)
+fn pow(base: &BigInt, exponent: usize) -> BigInt {
+  let mut result = BigInt::from(1);
+  let mut square = base.clone();
+  let mut exponent = exponent;
+
+  while exponent > 0 {
+    if exponent & 1 == 1 {
+      result = &result * &square;
+    }
+    square = &square * &square;
+    exponent >>= 1;
+  }
+
+  result
+}
+
+/// Every valid set of positions for a choice question:
+///   Select between min_selections and max_selections (inclusive) of the candidates
+pub fn choice_positions(
+  num_candidates: i64,
+  min_selections: i64,
+  max_selections: i64,
+) -> impl Iterator<Item = Vec<usize>> {
+  (min_selections as usize..=max_selections as usize).flat_map(move |k| (0..num_candidates as usize).combinations(k))
+}
+
+/// Every valid set of positions for a ranked question:
+///   Candidate c with rank r sets bit (c * num_candidates + r), where the ranks are a permutation
+pub fn ranked_positions(num_candidates: i64) -> impl Iterator<Item = Vec<usize>> {
+  let m = num_candidates as usize;
+  (0..m).permutations(m).map(move |ranks| {
+    ranks
+      .into_iter()
+      .enumerate()
+      .map(|(candidate, rank)| candidate * m + rank)
+      .collect()
+  })
+}
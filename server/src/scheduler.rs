@@ -0,0 +1,195 @@
+//
+// Background scheduler that moves elections through their statuses at the scheduled times
+//
+use actix_web::rt;
+use chrono::{DateTime, Utc};
+use std::time::Duration;
+use uuid_b64::UuidB64 as Uuid;
+
+use crate::auth::{JWTSecret, ManifestKey};
+use crate::db::{DbConnection, PgPool};
+use crate::errors::ServiceError;
+use crate::handlers::registration::open_election_registration;
+use crate::handlers::voting::{
+  close_election_voting, fail_in_progress, initialize_election_voting, InitializeVotingData,
+};
+use crate::models::{Election, ElectionStatus};
+use crate::notifications::notify_election_updated;
+
+/// How long to wait between checks for scheduled transitions
+const CHECK_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Give up on a scheduled transition after this many failed attempts
+const MAX_RETRY_ATTEMPTS: i32 = 10;
+
+///
+/// Start the election scheduler on the actix runtime
+///
+/// Initializing and closing voting send requests to the mediator, so the scheduler cannot run on its own OS thread.
+///   Elections are held in Initializing or Collecting while the request runs, so they are never due twice at once.
+///
+pub fn spawn_election_scheduler(pool: PgPool, jwt_key: JWTSecret, manifest_key: ManifestKey) {
+  rt::spawn(async move {
+    if let Err(e) = fail_interrupted_transitions(&pool, &jwt_key).await {
+      log::error!("Failed to recover interrupted elections: {}", e);
+    }
+
+    loop {
+      if let Err(e) = run_due_transitions(&pool, &jwt_key, &manifest_key).await {
+        log::error!("Election scheduler failed: {}", e);
+      }
+
+      rt::time::delay_for(CHECK_INTERVAL).await;
+    }
+  });
+}
+
+///
+/// Move every election left in Initializing or Collecting back to its failed status
+///
+/// Only this server initializes and closes elections, so nothing can still be running for them when it starts
+///
+async fn fail_interrupted_transitions(pool: &PgPool, jwt_key: &JWTSecret) -> Result<(), ServiceError> {
+  let conn = DbConnection::new_pooled(pool.get()?);
+
+  for election in Election::find_in_progress(&conn)? {
+    log::warn!(
+      "Election <{}> was left in {} status, moving it to the failed status",
+      election.id,
+      election.status.get_name()
+    );
+    fail_in_progress(
+      &election.id,
+      "Server restarted before the transition finished",
+      None,
+      &conn,
+      jwt_key,
+    )
+    .await;
+  }
+
+  Ok(())
+}
+
+/// Run the next transition for every election that is due
+async fn run_due_transitions(
+  pool: &PgPool,
+  jwt_key: &JWTSecret,
+  manifest_key: &ManifestKey,
+) -> Result<(), ServiceError> {
+  let conn = DbConnection::new_pooled(pool.get()?);
+  let now = Utc::now();
+
+  for election in Election::find_scheduled_due(now, &conn)? {
+    let election_id = election.id;
+    let status = election.status;
+
+    if let Err(e) = run_transition(election, &conn, jwt_key, manifest_key).await {
+      log::warn!(
+        "Scheduled transition from {} failed for election <{}>: {}",
+        status.get_name(),
+        election_id,
+        e
+      );
+
+      // Keep going with the other due elections, even if this one cannot be updated
+      if let Err(retry_error) = handle_failed_transition(&election_id, &e, now, &conn, jwt_key).await {
+        log::error!(
+          "Failed to handle the failed transition for election <{}>: {}",
+          election_id,
+          retry_error
+        );
+      }
+    }
+  }
+
+  Ok(())
+}
+
+///
+/// Retry the transition later, or stop retrying after a permanent error or too many attempts
+///
+/// A failed initialization or collection has already moved back to its failed status,
+///   so once the scheduler stops the owner can retry it from there
+///
+async fn handle_failed_transition(
+  election_id: &Uuid,
+  error: &ServiceError,
+  now: DateTime<Utc>,
+  conn: &DbConnection,
+  jwt_key: &JWTSecret,
+) -> Result<(), ServiceError> {
+  // The transition may have changed the status before failing, so load the election again
+  let mut election = Election::find(election_id, conn)?;
+
+  if error.is_transient() && election.retry_attempts < MAX_RETRY_ATTEMPTS {
+    election.schedule_retry(now);
+    election.save_schedule(conn)?;
+
+    log::warn!(
+      "Retrying scheduled transition for election <{}> (Attempt {}, retry at {})",
+      election_id,
+      election.retry_attempts,
+      election.next_retry_at.unwrap_or(now)
+    );
+    return Ok(());
+  }
+
+  // Clear the scheduled time, so the election is no longer due
+  match election.status {
+    ElectionStatus::Draft => election.registration_opens_at = None,
+    ElectionStatus::Registration | ElectionStatus::Initializing | ElectionStatus::InitFailed => {
+      election.voting_opens_at = None
+    }
+    ElectionStatus::Voting | ElectionStatus::Collecting | ElectionStatus::CollectionFailed => {
+      election.voting_closes_at = None
+    }
+    ElectionStatus::Finished | ElectionStatus::Canceled => {}
+  }
+  let attempts = election.retry_attempts + 1;
+  election.clear_retries();
+  let election = election.save_schedule(conn)?;
+
+  notify_election_updated(&election, jwt_key).await;
+  log::error!(
+    "Stopped the scheduled transitions for election <{}> in {} status after {} attempts",
+    election_id,
+    election.status.get_name(),
+    attempts
+  );
+
+  Ok(())
+}
+
+///
+/// Move the election to the next status
+///
+/// Failed initializations and collections are retried, since they start from the failed status again
+///
+async fn run_transition(
+  election: Election,
+  conn: &DbConnection,
+  jwt_key: &JWTSecret,
+  manifest_key: &ManifestKey,
+) -> Result<(), ServiceError> {
+  match election.status {
+    ElectionStatus::Draft => {
+      open_election_registration(election, None, conn, jwt_key).await?;
+    }
+    ElectionStatus::Registration | ElectionStatus::InitFailed => {
+      let data = InitializeVotingData {
+        collectors: election.scheduled_collectors.clone(),
+        threshold: election.scheduled_threshold.map(|threshold| threshold as usize),
+        distributed_locations: election.scheduled_distributed_locations,
+      };
+      initialize_election_voting(election, data, None, conn, jwt_key, manifest_key).await?;
+    }
+    ElectionStatus::Voting | ElectionStatus::CollectionFailed => {
+      close_election_voting(election, None, conn, jwt_key).await?;
+    }
+    ElectionStatus::Initializing | ElectionStatus::Collecting => {}
+    ElectionStatus::Finished | ElectionStatus::Canceled => {}
+  }
+
+  Ok(())
+}
@@ -7,6 +7,17 @@ table! {
     }
 }
 
+table! {
+    cancelation_commitments (user_id, election_id, question_id, collector_id) {
+        user_id -> Uuid,
+        election_id -> Uuid,
+        question_id -> Uuid,
+        collector_id -> Uuid,
+        forward_commitment -> Numeric,
+        reverse_commitment -> Numeric,
+    }
+}
+
 table! {
     commitments (user_id, election_id, question_id) {
         user_id -> Uuid,
@@ -19,6 +30,23 @@ table! {
         g_s_s_prime -> Numeric,
         single_vote_verified -> Bool,
         published_ballots_verified -> Bool,
+        forward_share_commitment -> Nullable<Numeric>,
+        reverse_share_commitment -> Nullable<Numeric>,
+        proof_challenges -> Nullable<Array<Numeric>>,
+        proof_forward_responses -> Nullable<Array<Numeric>>,
+        proof_reverse_responses -> Nullable<Array<Numeric>>,
+    }
+}
+
+table! {
+    election_status_history (id) {
+        id -> Uuid,
+        election_id -> Uuid,
+        from_status -> Int4,
+        to_status -> Int4,
+        actor_id -> Nullable<Uuid>,
+        reason -> Text,
+        changed_at -> Timestamptz,
     }
 }
 
@@ -30,9 +58,28 @@ table! {
         status -> Int4,
         is_public -> Bool,
         access_code -> Nullable<Varchar>,
+        location_modulus -> Numeric,
+        ballot_encoding -> Int4,
+        commitment_group -> Int4,
+        verification_mode -> Int4,
+        registration_opens_at -> Nullable<Timestamptz>,
+        registration_closes_at -> Nullable<Timestamptz>,
+        voting_opens_at -> Nullable<Timestamptz>,
+        voting_closes_at -> Nullable<Timestamptz>,
+        scheduled_collectors -> Array<Uuid>,
+        scheduled_threshold -> Nullable<Int8>,
+        scheduled_distributed_locations -> Bool,
+        retry_attempts -> Int4,
+        next_retry_at -> Nullable<Timestamptz>,
+    }
+}
+
+table! {
+    pooled_primes (id) {
+        id -> Uuid,
+        num_bits -> Int8,
         generator -> Numeric,
         prime -> Numeric,
-        location_modulus -> Numeric,
     }
 }
 
@@ -44,6 +91,13 @@ table! {
         question_number -> Int8,
         forward_cancelation_shares -> Numeric,
         reverse_cancelation_shares -> Numeric,
+        min_selections -> Int8,
+        max_selections -> Int8,
+        question_type -> Int4,
+        generator -> Numeric,
+        prime -> Numeric,
+        collection_status -> Int4,
+        collection_error -> Nullable<Text>,
     }
 }
 
@@ -51,6 +105,17 @@ table! {
     registrations (user_id, election_id) {
         user_id -> Uuid,
         election_id -> Uuid,
+        weight -> Int8,
+    }
+}
+
+table! {
+    share_commitments (user_id, election_id, question_id) {
+        user_id -> Uuid,
+        election_id -> Uuid,
+        question_id -> Uuid,
+        forward_commitment -> Numeric,
+        reverse_commitment -> Numeric,
     }
 }
 
@@ -64,20 +129,32 @@ table! {
     }
 }
 
+joinable!(cancelation_commitments -> elections (election_id));
+joinable!(cancelation_commitments -> questions (question_id));
+joinable!(cancelation_commitments -> users (user_id));
 joinable!(candidates -> questions (question_id));
 joinable!(commitments -> elections (election_id));
 joinable!(commitments -> questions (question_id));
 joinable!(commitments -> users (user_id));
+joinable!(election_status_history -> elections (election_id));
+joinable!(election_status_history -> users (actor_id));
 joinable!(elections -> users (created_by));
 joinable!(questions -> elections (election_id));
 joinable!(registrations -> elections (election_id));
 joinable!(registrations -> users (user_id));
+joinable!(share_commitments -> elections (election_id));
+joinable!(share_commitments -> questions (question_id));
+joinable!(share_commitments -> users (user_id));
 
 allow_tables_to_appear_in_same_query!(
+    cancelation_commitments,
     candidates,
     commitments,
+    election_status_history,
     elections,
+    pooled_primes,
     questions,
     registrations,
+    share_commitments,
     users,
 );
@@ -0,0 +1,15 @@
+use serde::Serialize;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrimePoolStatus {
+  pub target_size: i64,
+  pub buckets: Vec<PrimePoolBucket>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrimePoolBucket {
+  pub num_bits: i64,
+  pub num_primes: i64,
+}
@@ -1,9 +1,15 @@
+use bigdecimal::BigDecimal;
+use chrono::{DateTime, Utc};
 use curv_kzen::BigInt;
 use serde::Serialize;
 use std::collections::HashMap;
 use uuid_b64::UuidB64 as Uuid;
 
-use crate::models::{Candidate, Commitment, Election, ElectionStatus, HasVotedStatus, Question, User};
+use crate::models::{
+  BallotEncoding, Candidate, CollectionStatus, Commitment, CommitmentGroup, Election, ElectionStatus,
+  ElectionStatusChange, HasVotedStatus, Question, QuestionType, User, VerificationMode,
+};
+use crate::protocol::RunoffRound;
 use crate::utils::ConvertBigInt;
 
 #[derive(Debug, Serialize)]
@@ -48,6 +54,11 @@ pub struct PublicElectionDetails {
   pub is_public: bool,
   #[serde(skip_serializing_if = "Option::is_none")]
   pub access_code: Option<String>,
+  pub ballot_encoding: BallotEncoding,
+  pub commitment_group: CommitmentGroup,
+  pub verification_mode: VerificationMode,
+  pub schedule: ElectionSchedule,
+  pub status_history: Vec<StatusChangeDetails>,
 
   pub is_registered: bool,
   pub has_voted_status: HasVotedStatus,
@@ -55,6 +66,42 @@ pub struct PublicElectionDetails {
   pub questions: Vec<PublicElectionQuestion>,
 }
 
+///
+/// Times when the scheduler changes the election status (Not set if the owner changes the status by hand)
+///
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ElectionSchedule {
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub registration_opens_at: Option<DateTime<Utc>>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub registration_closes_at: Option<DateTime<Utc>>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub voting_opens_at: Option<DateTime<Utc>>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub voting_closes_at: Option<DateTime<Utc>>,
+
+  /// Set while waiting to retry a failed transition
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub next_retry_at: Option<DateTime<Utc>>,
+}
+
+///
+/// Single status change of the election (The history is sorted from oldest to newest)
+///
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatusChangeDetails {
+  pub from_status: ElectionStatus,
+  pub to_status: ElectionStatus,
+
+  /// User that changed the status (Not set if the scheduler changed the status)
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub actor_id: Option<Uuid>,
+  pub reason: String,
+  pub changed_at: DateTime<Utc>,
+}
+
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct UserDetails {
@@ -68,6 +115,7 @@ pub struct RegisteredUserDetails {
   pub id: Uuid,
   pub name: String,
   pub has_voted_status: HasVotedStatus,
+  pub weight: i64,
 }
 
 #[derive(Debug, Serialize)]
@@ -78,6 +126,14 @@ pub struct PublicElectionQuestion {
   pub has_voted: bool,
   pub num_votes_received: i64,
   pub candidates: Vec<String>,
+  pub question_type: QuestionType,
+  pub min_selections: i64,
+  pub max_selections: i64,
+
+  pub collection_status: CollectionStatus,
+  // Only shown to the election creator
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub collection_error: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -92,11 +148,9 @@ pub struct PublishElectionResult {
 pub struct ElectionParameters {
   pub num_registered: i64,
   pub questions: Vec<QuestionParameters>,
-
-  #[serde(with = "kzen_paillier::serialize::bigint")]
-  pub generator: BigInt,
-  #[serde(with = "kzen_paillier::serialize::bigint")]
-  pub prime: BigInt,
+  pub counter_radix: i64,
+  pub commitment_group: CommitmentGroup,
+  pub verification_mode: VerificationMode,
 
   #[serde(with = "kzen_paillier::serialize::bigint")]
   pub location_modulus: BigInt,
@@ -106,6 +160,26 @@ pub struct ElectionParameters {
 #[serde(rename_all = "camelCase")]
 pub struct QuestionParameters {
   pub num_candidates: i64,
+  pub question_type: QuestionType,
+  pub ballot_encoding: BallotEncoding,
+  pub min_selections: i64,
+  pub max_selections: i64,
+
+  #[serde(with = "kzen_paillier::serialize::bigint")]
+  pub generator: BigInt,
+  #[serde(with = "kzen_paillier::serialize::bigint")]
+  pub prime: BigInt,
+
+  /// Modulus for all shares and ballots (Order of the commitment group)
+  #[serde(with = "kzen_paillier::serialize::bigint")]
+  pub group_order: BigInt,
+
+  /// Second generator h for the ballot proofs (Only set for questions that require one)
+  #[serde(
+    skip_serializing_if = "Option::is_none",
+    with = "crate::utils::serialize_option_bigint"
+  )]
+  pub second_generator: Option<BigInt>,
 }
 
 #[derive(Debug, Serialize)]
@@ -145,6 +219,12 @@ pub struct QuestionResult {
 
   #[serde(skip_serializing_if = "Option::is_none")]
   pub candidate_votes: Option<HashMap<i64, CandidateResult>>,
+
+  // Only provided for ranked questions
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub instant_runoff: Option<Vec<InstantRunoffRound>>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub borda_count: Option<HashMap<i64, i64>>,
 }
 
 #[derive(Debug, Serialize)]
@@ -164,6 +244,30 @@ pub struct UserBallotResult {
   pub g_s_prime: BigInt,
   #[serde(with = "kzen_paillier::serialize::bigint")]
   pub g_s_s_prime: BigInt,
+
+  // Only provided for questions that require a ballot proof
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub proof: Option<BallotProofResult>,
+}
+
+///
+/// Everything needed to re-verify a non-interactive ballot proof
+///   The valid voting vectors are computed from the public election parameters
+///
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BallotProofResult {
+  #[serde(with = "kzen_paillier::serialize::bigint")]
+  pub forward_share_commitment: BigInt,
+  #[serde(with = "kzen_paillier::serialize::bigint")]
+  pub reverse_share_commitment: BigInt,
+
+  #[serde(with = "kzen_paillier::serialize::vecbigint")]
+  pub challenges: Vec<BigInt>,
+  #[serde(with = "kzen_paillier::serialize::vecbigint")]
+  pub forward_responses: Vec<BigInt>,
+  #[serde(with = "kzen_paillier::serialize::vecbigint")]
+  pub reverse_responses: Vec<BigInt>,
 }
 
 #[derive(Debug, Serialize)]
@@ -172,6 +276,16 @@ pub struct CandidateResult {
   pub num_votes: i64,
 }
 
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InstantRunoffRound {
+  pub candidate_votes: HashMap<i64, CandidateResult>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub eliminated: Option<i64>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub winner: Option<i64>,
+}
+
 impl PublicElectionList {
   pub fn new(
     election: Election,
@@ -203,6 +317,7 @@ impl PublicElectionDetails {
     has_voted_status: HasVotedStatus,
     registered: Vec<RegisteredUserDetails>,
     questions: Vec<PublicElectionQuestion>,
+    status_history: Vec<ElectionStatusChange>,
   ) -> Self {
     Self {
       id: election.id,
@@ -211,6 +326,17 @@ impl PublicElectionDetails {
       status: election.status,
       is_public: election.is_public,
       access_code: election.access_code,
+      ballot_encoding: election.ballot_encoding,
+      commitment_group: election.commitment_group,
+      verification_mode: election.verification_mode,
+      schedule: ElectionSchedule {
+        registration_opens_at: election.registration_opens_at,
+        registration_closes_at: election.registration_closes_at,
+        voting_opens_at: election.voting_opens_at,
+        voting_closes_at: election.voting_closes_at,
+        next_retry_at: election.next_retry_at,
+      },
+      status_history: status_history.into_iter().map(StatusChangeDetails::new).collect(),
       is_registered,
       has_voted_status,
       registered,
@@ -219,6 +345,18 @@ impl PublicElectionDetails {
   }
 }
 
+impl StatusChangeDetails {
+  pub fn new(change: ElectionStatusChange) -> Self {
+    Self {
+      from_status: change.from_status,
+      to_status: change.to_status,
+      actor_id: change.actor_id,
+      reason: change.reason,
+      changed_at: change.changed_at,
+    }
+  }
+}
+
 impl UserDetails {
   pub fn new(user: User) -> Self {
     Self {
@@ -229,23 +367,35 @@ impl UserDetails {
 }
 
 impl RegisteredUserDetails {
-  pub fn new(user: User, has_voted_status: HasVotedStatus) -> Self {
+  pub fn new(user: User, has_voted_status: HasVotedStatus, weight: i64) -> Self {
     Self {
       id: user.id,
       name: user.name,
       has_voted_status,
+      weight,
     }
   }
 }
 
 impl PublicElectionQuestion {
-  pub fn new(question: Question, has_voted: bool, num_votes_received: i64, candidates: Vec<Candidate>) -> Self {
+  pub fn new(
+    question: Question,
+    has_voted: bool,
+    num_votes_received: i64,
+    candidates: Vec<Candidate>,
+    is_owner: bool,
+  ) -> Self {
     Self {
       id: question.id,
       name: question.question,
       has_voted,
       num_votes_received,
       candidates: candidates.into_iter().map(|c| c.candidate).collect(),
+      question_type: question.question_type,
+      min_selections: question.min_selections,
+      max_selections: question.max_selections,
+      collection_status: question.collection_status,
+      collection_error: question.collection_error.filter(|_| is_owner),
     }
   }
 }
@@ -264,6 +414,8 @@ impl QuestionResult {
       user_ballots,
       no_votes,
       candidate_votes: None,
+      instant_runoff: None,
+      borda_count: None,
     }
   }
 
@@ -290,7 +442,45 @@ impl QuestionResult {
       user_ballots,
       no_votes,
       candidate_votes,
+      instant_runoff: None,
+      borda_count: None,
+    }
+  }
+
+  /// Construct a finished election result for a ranked question
+  ///   The candidate votes are from the final runoff round, where eliminated candidates have no votes left
+  pub fn new_ranked(
+    question: Question,
+    forward_ballots: BigInt,
+    reverse_ballots: BigInt,
+    tally: Option<(Vec<RunoffRound>, Vec<i64>)>,
+    user_ballots: Vec<UserBallotResult>,
+    no_votes: Vec<UserDetails>,
+  ) -> Self {
+    let final_round_votes = tally.as_ref().and_then(|(rounds, borda_points)| {
+      let round = rounds.last()?;
+      Some(
+        (0..borda_points.len())
+          .map(|c| round.votes.get(&c).cloned().unwrap_or(0))
+          .collect(),
+      )
+    });
+
+    let mut result = Self::new(
+      question,
+      forward_ballots,
+      reverse_ballots,
+      final_round_votes,
+      user_ballots,
+      no_votes,
+    );
+
+    if let Some((rounds, borda_points)) = tally {
+      result.instant_runoff = Some(rounds.into_iter().map(InstantRunoffRound::new).collect());
+      result.borda_count = Some((0i64..).zip(borda_points).collect());
     }
+
+    result
   }
 }
 
@@ -306,12 +496,43 @@ impl UserBallotResult {
       g_s: commitment.g_s.to_bigint(),
       g_s_prime: commitment.g_s_prime.to_bigint(),
       g_s_s_prime: commitment.g_s_s_prime.to_bigint(),
+
+      proof: BallotProofResult::new(&commitment),
     }
   }
 }
 
+impl BallotProofResult {
+  /// Returns None if the commitment was verified interactively
+  pub fn new(commitment: &Commitment) -> Option<Self> {
+    let to_bigints = |values: &Vec<BigDecimal>| values.iter().map(|v| v.to_bigint()).collect();
+
+    Some(Self {
+      forward_share_commitment: commitment.forward_share_commitment.as_ref()?.to_bigint(),
+      reverse_share_commitment: commitment.reverse_share_commitment.as_ref()?.to_bigint(),
+      challenges: to_bigints(commitment.proof_challenges.as_ref()?),
+      forward_responses: to_bigints(commitment.proof_forward_responses.as_ref()?),
+      reverse_responses: to_bigints(commitment.proof_reverse_responses.as_ref()?),
+    })
+  }
+}
+
 impl CandidateResult {
   pub fn new(num_votes: i64) -> Self {
     Self { num_votes }
   }
 }
+
+impl InstantRunoffRound {
+  pub fn new(round: RunoffRound) -> Self {
+    Self {
+      candidate_votes: round
+        .votes
+        .into_iter()
+        .map(|(candidate, votes)| (candidate as i64, CandidateResult::new(votes)))
+        .collect(),
+      eliminated: round.eliminated.map(|c| c as i64),
+      winner: round.winner.map(|c| c as i64),
+    }
+  }
+}
@@ -1,5 +1,7 @@
 //
 // Views specify the JSON results that are returned from API handlers
 //
+pub mod admin;
 pub mod auth;
 pub mod election;
+pub mod verification;
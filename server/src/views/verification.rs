@@ -0,0 +1,44 @@
+use serde::{Deserialize, Serialize};
+
+///
+/// Explains why the mediator did not accept a ballot
+///
+/// If the cause is a collector, collector_index is None when the mediator cannot tell which collector misbehaved
+///
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VerificationFailure {
+  pub cause: FailureCause,
+  pub check: VerificationCheck,
+  pub collector_index: Option<usize>,
+
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub reported_by: Option<usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FailureCause {
+  Ballot,
+  Collector,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum VerificationCheck {
+  ProductMismatch,
+  CommitmentMismatch,
+  BadSignature,
+  UnknownIdentityKey,
+}
+
+impl VerificationCheck {
+  pub fn description(&self) -> &'static str {
+    match self {
+      Self::ProductMismatch => "sub-protocol 1 product mismatch",
+      Self::CommitmentMismatch => "sub-protocol 2 commitment mismatch",
+      Self::BadSignature => "bad signature",
+      Self::UnknownIdentityKey => "unknown identity key",
+    }
+  }
+}